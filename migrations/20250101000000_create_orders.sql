@@ -0,0 +1,15 @@
+-- Orders are stored as their full JSON document, with the fields we query on
+-- broken out into indexed columns.
+CREATE TABLE IF NOT EXISTS orders (
+    id TEXT PRIMARY KEY NOT NULL,
+    status TEXT NOT NULL,
+    user_address TEXT NOT NULL,
+    nonce TEXT NOT NULL,
+    origin_chain_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orders_status ON orders (status);
+CREATE INDEX IF NOT EXISTS idx_orders_user_nonce ON orders (user_address, nonce, origin_chain_id);
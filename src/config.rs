@@ -1,6 +1,9 @@
 use anyhow::Result;
 use serde::Deserialize;
+use alloy::primitives::{Address, U256};
+use arc_swap::ArcSwap;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct AppConfig {
@@ -10,30 +13,501 @@ pub struct AppConfig {
     pub contracts: ContractConfig,
     pub monitoring: MonitoringConfig,
     pub persistence: PersistenceConfig,
+    #[serde(default)]
+    pub coordinator: CoordinatorConfig,
+    #[serde(default)]
+    pub balance: BalanceConfig,
+    #[serde(default)]
+    pub gas: GasConfig,
+    #[serde(default)]
+    pub backpressure: BackpressureConfig,
+    #[serde(default)]
+    pub encoder: EncoderConfig,
+    #[serde(default)]
+    pub callbacks: CallbackConfig,
+    #[serde(default)]
+    pub allowances: AllowanceConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    #[serde(default)]
+    pub log_format: LogFormat,
+    /// On shutdown, how long to wait for fills and finalizations under way before exiting
+    #[serde(default = "default_shutdown_grace_seconds")]
+    pub shutdown_grace_seconds: u64,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    /// Bearer token the `/api/v1/admin/*` endpoints require; unset leaves them open
+    #[serde(default)]
+    pub admin_token: Option<String>,
+}
+
+fn default_shutdown_grace_seconds() -> u64 {
+    30
+}
+
+/// Token buckets limiting order submissions; each limit is off when unset
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct RateLimitConfig {
+    /// Shared by every client
+    #[serde(default)]
+    pub global: Option<TokenBucketConfig>,
+    /// Separate bucket per client IP
+    #[serde(default)]
+    pub per_ip: Option<TokenBucketConfig>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct TokenBucketConfig {
+    /// Requests regained per second
+    pub per_second: f64,
+    /// Requests allowed at once after an idle period
+    pub burst: u32,
+}
+
+/// How log lines are written to stdout
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, with span and event fields as keys
+    Json,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct SolverConfig {
+    /// The signing key, or a reference to it: `env:NAME` reads an environment
+    /// variable and `file:/path` a file; resolved once by `AppConfig::load`
     pub private_key: String,
     pub finalization_delay_seconds: u64,
+    /// Input tokens the solver accepts; empty (or `"*"`) means any token
+    #[serde(default, alias = "allowed_tokens")]
+    pub allowed_input_tokens: Vec<String>,
+    /// Input tokens the solver refuses, even when allowed above
+    #[serde(default)]
+    pub denied_input_tokens: Vec<String>,
+    /// Users whose orders the solver accepts; empty (or `"*"`) means anyone
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Users whose orders the solver refuses, even when allowed above
+    #[serde(default)]
+    pub denied_users: Vec<String>,
+    /// Oracles (the order's localOracle and each output's remoteOracle) the
+    /// solver can settle through; empty means any oracle
+    #[serde(default)]
+    pub allowed_oracles: Vec<String>,
+    /// Encode and gas-estimate fills/finalizations but never broadcast them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Production deployment: a plaintext `private_key` is refused
+    #[serde(default)]
+    pub production: bool,
+    /// Minimum margin (basis points of the input value) left after outputs and
+    /// gas for an order to be filled; unset fills regardless of profit
+    #[serde(default)]
+    pub min_profit_margin_bps: Option<u32>,
+    /// Value of one base unit of each token in native wei, for the profitability
+    /// check; tokens not listed are valued 1:1
+    #[serde(default)]
+    pub token_prices: HashMap<Address, f64>,
+    /// How far (basis points of the output amount) an encoded fill amount may
+    /// differ from the order's declared amount; 0 requires an exact match
+    #[serde(default)]
+    pub fill_amount_tolerance_bps: u32,
+    /// Failed fill attempts after which a transient failure becomes permanent
+    #[serde(default = "default_max_fill_retries")]
+    pub max_fill_retries: u32,
+    /// Wait before each retry: the nth entry after the nth failure, the last
+    /// entry for every failure beyond
+    #[serde(default = "default_retry_backoff_seconds")]
+    pub retry_backoff_seconds: Vec<u64>,
+    /// Finalize without first asking the order's local oracle whether the fill
+    /// was proven, for setups whose destination chain cannot reorg
+    #[serde(default)]
+    pub skip_fill_proof_check: bool,
+    /// Most inputs an accepted order may carry; unset accepts any number
+    #[serde(default)]
+    pub max_inputs: Option<usize>,
+    /// Most outputs an accepted order may carry; unset accepts any number
+    #[serde(default)]
+    pub max_outputs: Option<usize>,
+    /// How submitted orders get their id
+    #[serde(default)]
+    pub order_id_mode: OrderIdMode,
+    /// Run each finalization as an `eth_call` first and skip the send if it reverts
+    #[serde(default = "default_simulate_finalization")]
+    pub simulate_finalization: bool,
+    /// Run each token fill as an `eth_call` first and skip the send if it reverts
+    #[serde(default = "default_simulate_fill")]
+    pub simulate_fill: bool,
+    /// How order signatures are checked against the order's user
+    #[serde(default)]
+    pub signature_scheme: SignatureScheme,
+    /// Identifies this solver in health reports when several share a deployment;
+    /// defaults to the host name
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+fn default_max_fill_retries() -> u32 {
+    3
+}
+
+fn default_simulate_finalization() -> bool {
+    true
+}
+
+fn default_simulate_fill() -> bool {
+    true
+}
+
+fn default_retry_backoff_seconds() -> Vec<u64> {
+    vec![30, 120, 600]
+}
+
+const DEFAULT_SOLVER_NAME: &str = "oif-solver";
+
+const ENV_KEY_PREFIX: &str = "env:";
+const FILE_KEY_PREFIX: &str = "file:";
+
+impl SolverConfig {
+    /// `name`, or the host name (`HOSTNAME`, then `/etc/hostname`) when unset
+    pub fn name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| DEFAULT_SOLVER_NAME.to_string())
+    }
+
+    /// Replace an `env:` or `file:` private key reference with the key it points at.
+    /// Errors name the reference but never include any part of the key.
+    pub fn resolve_private_key(&mut self) -> Result<()> {
+        let resolved = if let Some(name) = self.private_key.strip_prefix(ENV_KEY_PREFIX) {
+            std::env::var(name)
+                .map_err(|_| anyhow::anyhow!("solver.private_key refers to environment variable {}, which is not set", name))?
+        } else if let Some(path) = self.private_key.strip_prefix(FILE_KEY_PREFIX) {
+            std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Failed to read solver.private_key from {}: {}", path, e))?
+        } else if self.production {
+            anyhow::bail!("Plaintext solver.private_key is not allowed in production; use env:NAME or file:/path");
+        } else {
+            tracing::warn!("solver.private_key is set in plaintext; prefer an env: or file: reference");
+            return Ok(());
+        };
+
+        self.private_key = resolved.trim().to_string();
+        if self.private_key.is_empty() {
+            anyhow::bail!("solver.private_key reference resolved to an empty value");
+        }
+        Ok(())
+    }
+
+    /// Seconds to wait before retrying a fill that has failed `failed_attempts`
+    /// times, or `None` once the retries are used up
+    pub fn fill_retry_delay(&self, failed_attempts: u32) -> Option<u64> {
+        if failed_attempts == 0 || failed_attempts > self.max_fill_retries {
+            return None;
+        }
+        let index = (failed_attempts as usize - 1).min(self.retry_backoff_seconds.len().saturating_sub(1));
+        Some(self.retry_backoff_seconds.get(index).copied().unwrap_or(0))
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ChainConfig {
     pub origin: ChainDetails,
     pub destination: ChainDetails,
+    /// Every chain the solver can reach, keyed by chain id; when absent it is
+    /// derived from `origin` and `destination`, which always take precedence
+    /// over a registry entry with the same id
+    #[serde(default)]
+    pub registry: HashMap<u64, ChainDetails>,
+    /// Only log RPC endpoints whose reported chain id differs from the configured
+    /// one instead of refusing to start, for offline testing
+    #[serde(default)]
+    pub warn_on_chain_id_mismatch: bool,
+    /// Log every JSON-RPC request's method and latency (params and responses at trace level)
+    #[serde(default)]
+    pub log_rpc_requests: bool,
+}
+
+impl ChainConfig {
+    /// Details of the chain with `chain_id`, if the solver is configured for it
+    pub fn get(&self, chain_id: u64) -> Option<&ChainDetails> {
+        [&self.origin, &self.destination]
+            .into_iter()
+            .find(|chain| chain.chain_id == chain_id)
+            .or_else(|| self.registry.get(&chain_id))
+    }
+
+    /// Add `origin` and `destination` to the registry and check that every
+    /// entry's `chain_id` matches its key
+    pub fn build_registry(&mut self) -> Result<()> {
+        for (chain_id, chain) in &self.registry {
+            if chain.chain_id != *chain_id {
+                anyhow::bail!(
+                    "Chain registry entry {} has mismatched chain_id {}",
+                    chain_id, chain.chain_id
+                );
+            }
+        }
+        for chain in [&self.origin, &self.destination] {
+            self.registry.insert(chain.chain_id, chain.clone());
+        }
+        Ok(())
+    }
+}
+
+/// A chain's RPC endpoints in order of preference: a single URL or a list of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcUrls(Vec<String>);
+
+impl RpcUrls {
+    /// The preferred endpoint
+    pub fn primary(&self) -> &str {
+        &self.0[0]
+    }
+
+    /// Every endpoint, preferred first
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    pub fn as_slice(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl From<String> for RpcUrls {
+    fn from(url: String) -> Self {
+        Self(vec![url])
+    }
+}
+
+impl From<&str> for RpcUrls {
+    fn from(url: &str) -> Self {
+        Self(vec![url.to_string()])
+    }
+}
+
+impl TryFrom<Vec<String>> for RpcUrls {
+    type Error = anyhow::Error;
+
+    fn try_from(urls: Vec<String>) -> Result<Self> {
+        if urls.is_empty() {
+            anyhow::bail!("rpc_url needs at least one URL");
+        }
+        Ok(Self(urls))
+    }
+}
+
+impl PartialEq<&str> for RpcUrls {
+    fn eq(&self, url: &&str) -> bool {
+        self.0.len() == 1 && self.0[0] == *url
+    }
+}
+
+impl std::fmt::Display for RpcUrls {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(", "))
+    }
+}
+
+impl<'de> Deserialize<'de> for RpcUrls {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum OneOrMany {
+            One(String),
+            Many(Vec<String>),
+        }
+
+        match OneOrMany::deserialize(deserializer)? {
+            OneOrMany::One(url) => Ok(url.into()),
+            OneOrMany::Many(urls) => urls.try_into().map_err(serde::de::Error::custom),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ChainDetails {
-    pub rpc_url: String,
+    /// Endpoints for sending transactions, tried in order until one answers
+    pub rpc_url: RpcUrls,
+    /// Endpoint for static calls, gas estimates and receipts; `rpc_url` when unset
+    #[serde(default)]
+    pub read_rpc_url: Option<String>,
     pub chain_id: u64,
+    /// Highest gas price (wei) the solver pays on this chain; above it work is deferred
+    #[serde(default)]
+    pub max_gas_price_wei: Option<u128>,
+    /// Per-chain overrides of the global `[gas]` profile
+    #[serde(default)]
+    pub gas: GasProfileOverride,
+    /// Blocks mined on top of a transaction's block before it counts as final; 0 = the receipt alone
+    #[serde(default)]
+    pub confirmations: u64,
+    /// How long an HTTP RPC request may take before it fails
+    #[serde(default = "default_rpc_timeout_seconds")]
+    pub rpc_timeout_seconds: u64,
+    /// How long a fetched block height is reused before the chain is asked again; 0 = never reused
+    #[serde(default = "default_block_height_ttl_ms")]
+    pub block_height_ttl_ms: u64,
+    /// CoinFiller deployed on this chain; `contracts.coin_filler` when unset
+    #[serde(default)]
+    pub coin_filler: Option<String>,
+    /// SettlerCompact orders opened on this chain are finalized through; `contracts.settler_compact` when unset
+    #[serde(default)]
+    pub settler_compact: Option<String>,
+}
+
+fn default_rpc_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_block_height_ttl_ms() -> u64 {
+    2000
+}
+
+/// How transaction gas parameters are derived from the solver's base values
+///
+/// The gas limit is scaled only by `buffer_pct`, the price only by `strategy`.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct GasConfig {
+    /// Headroom (percent) added to a transaction's gas estimate to get its gas limit
+    #[serde(default = "default_gas_buffer_pct")]
+    pub buffer_pct: u64,
+    /// Floor (wei) for the bid gas price
+    #[serde(default)]
+    pub min_gas_price: Option<u64>,
+    /// Ceiling (wei) for the bid gas price
+    #[serde(default)]
+    pub max_gas_price: Option<u64>,
+    /// Where the base gas price comes from; the multiplier and bounds above still apply
+    #[serde(default)]
+    pub strategy: GasStrategy,
+    /// Resubmit a transaction with a bumped gas price after this long without a
+    /// receipt; unset waits for the first submission indefinitely
+    #[serde(default)]
+    pub stuck_tx_timeout_seconds: Option<u64>,
+    /// Replacements sent for one transaction before giving up on it
+    #[serde(default = "default_max_gas_bumps")]
+    pub max_gas_bumps: u32,
+}
+
+/// Source of the base gas price for a chain's transactions and estimates
+///
+/// Written `strategy = "oracle"`, `strategy = { fixed = 50000000000 }` or
+/// `strategy = { oracle_multiplier = 1.25 }`.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GasStrategy {
+    /// Always this price (wei)
+    Fixed(u64),
+    /// The node's `eth_gasPrice`
+    #[default]
+    Oracle,
+    /// The node's `eth_gasPrice` times this factor, for faster inclusion
+    OracleMultiplier(f64),
+}
+
+/// Any field set here replaces the global `GasConfig` value for one chain
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct GasProfileOverride {
+    pub buffer_pct: Option<u64>,
+    pub min_gas_price: Option<u64>,
+    pub max_gas_price: Option<u64>,
+    pub strategy: Option<GasStrategy>,
+    pub stuck_tx_timeout_seconds: Option<u64>,
+    pub max_gas_bumps: Option<u32>,
+}
+
+fn default_gas_buffer_pct() -> u64 {
+    20
+}
+
+fn default_max_gas_bumps() -> u32 {
+    3
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self {
+            buffer_pct: default_gas_buffer_pct(),
+            min_gas_price: None,
+            max_gas_price: None,
+            strategy: GasStrategy::default(),
+            stuck_tx_timeout_seconds: None,
+            max_gas_bumps: default_max_gas_bumps(),
+        }
+    }
+}
+
+impl GasConfig {
+    /// The effective profile for `chain`: its overrides on top of these defaults
+    pub fn for_chain(&self, chain: &ChainDetails) -> GasConfig {
+        let overrides = &chain.gas;
+        GasConfig {
+            buffer_pct: overrides.buffer_pct.unwrap_or(self.buffer_pct),
+            min_gas_price: overrides.min_gas_price.or(self.min_gas_price),
+            max_gas_price: overrides.max_gas_price.or(self.max_gas_price),
+            strategy: overrides.strategy.unwrap_or(self.strategy),
+            stuck_tx_timeout_seconds: overrides.stuck_tx_timeout_seconds.or(self.stuck_tx_timeout_seconds),
+            max_gas_bumps: overrides.max_gas_bumps.unwrap_or(self.max_gas_bumps),
+        }
+    }
+
+    /// The price actually bid for a `strategy` price: clamped to `min_gas_price`/`max_gas_price`
+    pub fn bound_price(&self, gas_price: u128) -> u128 {
+        let mut gas_price = gas_price;
+        if let Some(min_gas_price) = self.min_gas_price {
+            gas_price = gas_price.max(min_gas_price as u128);
+        }
+        if let Some(max_gas_price) = self.max_gas_price {
+            gas_price = gas_price.min(max_gas_price as u128);
+        }
+        gas_price
+    }
+}
+
+impl ChainDetails {
+    /// Endpoint serving read-only calls
+    pub fn read_rpc_url(&self) -> &str {
+        self.read_rpc_url.as_deref().unwrap_or(self.rpc_url.primary())
+    }
+
+    /// Endpoints serving read-only calls, preferred first
+    pub fn read_rpc_urls(&self) -> Vec<&str> {
+        match &self.read_rpc_url {
+            Some(url) => vec![url.as_str()],
+            None => self.rpc_url.iter().collect(),
+        }
+    }
+
+    pub fn rpc_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.rpc_timeout_seconds)
+    }
+
+    pub fn block_height_ttl(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.block_height_ttl_ms)
+    }
+
+    /// Whether `other` reaches a different chain or contracts, which the RPC clients
+    /// and executors built at startup would not pick up
+    fn endpoint_differs(&self, other: &ChainDetails) -> bool {
+        self.chain_id != other.chain_id
+            || self.rpc_url != other.rpc_url
+            || self.read_rpc_url != other.read_rpc_url
+            || self.coin_filler != other.coin_filler
+            || self.settler_compact != other.settler_compact
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -41,18 +515,329 @@ pub struct ContractConfig {
     pub the_compact: String,
     pub settler_compact: String,
     pub coin_filler: String,
+    /// Hex selector (e.g. "0xdd1ff485") used instead of the computed `CoinFiller.fill` selector
+    #[serde(default, deserialize_with = "deserialize_selector")]
+    pub fill_selector: Option<[u8; 4]>,
+    /// Hex selector used instead of the computed `SettlerCompact.finalise` selector
+    #[serde(default, alias = "finalize_selector_override", deserialize_with = "deserialize_selector")]
+    pub finalise_selector: Option<[u8; 4]>,
+    /// Multicall3-compatible aggregator; when origin and destination are the same
+    /// chain, fill and finalization can be sent to it as one transaction
+    #[serde(default)]
+    pub multicall: Option<String>,
+    /// Directory of `<Contract>.json` ABI files overriding the built-in function signatures
+    #[serde(default)]
+    pub abi_dir: Option<String>,
+    /// Compare TheCompact's `DOMAIN_SEPARATOR()` with the locally computed one at startup
+    #[serde(default)]
+    pub verify_domain_separator: DomainSeparatorCheck,
+}
+
+/// What a startup mismatch between the on-chain and local EIP-712 domain separator does
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DomainSeparatorCheck {
+    /// Not checked
+    #[default]
+    Off,
+    /// Logged as a warning
+    Warn,
+    /// Startup fails
+    Error,
+}
+
+/// Parse a 4-byte function selector from hex, with or without `0x`
+pub fn parse_selector(value: &str) -> Result<[u8; 4]> {
+    let bytes = hex::decode(value.trim().strip_prefix("0x").unwrap_or(value.trim()))
+        .map_err(|e| anyhow::anyhow!("Invalid selector {}: {}", value, e))?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| anyhow::anyhow!("Selector {} must be 4 bytes, got {}", value, bytes.len()))
+}
+
+fn deserialize_selector<'de, D>(deserializer: D) -> std::result::Result<Option<[u8; 4]>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| parse_selector(&value).map_err(serde::de::Error::custom))
+        .transpose()
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct MonitoringConfig {
     pub enabled: bool,
     pub check_interval_seconds: u64,
+    /// Leave orders queued while a chain they need fails its health probe
+    #[serde(default = "default_pause_unhealthy_chains")]
+    pub pause_unhealthy_chains: bool,
+    /// Fills and finalizations allowed to run at the same time, to stay within RPC rate limits
+    #[serde(default = "default_max_concurrent_operations")]
+    pub max_concurrent_operations: usize,
+    /// Most order ids one `POST /api/v1/orders/finalize/batch` accepts
+    #[serde(default = "default_max_batch_finalize")]
+    pub max_batch_finalize: usize,
+    /// Orders of a batch finalization started together before the next ones are taken
+    #[serde(default = "default_batch_chunk_size")]
+    pub batch_chunk_size: usize,
+}
+
+fn default_pause_unhealthy_chains() -> bool {
+    true
+}
+
+fn default_max_concurrent_operations() -> usize {
+    4
+}
+
+fn default_max_batch_finalize() -> usize {
+    500
+}
+
+fn default_batch_chunk_size() -> usize {
+    50
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct PersistenceConfig {
     pub enabled: bool,
     pub data_file: String,
+    #[serde(default)]
+    pub backend: PersistenceBackend,
+    /// SQLite database used by the `sqlite` backend (default `sqlite://data/orders.db`)
+    #[serde(default)]
+    pub database_url: Option<String>,
+    /// Append every transaction the solver broadcasts to this JSONL file (off when unset)
+    #[serde(default)]
+    pub tx_journal_file: Option<String>,
+    /// Orders the `json` backend keeps in memory before evicting finished ones (unbounded when unset)
+    #[serde(default)]
+    pub max_orders_in_memory: Option<usize>,
+    /// JSONL file evicted orders are appended to (default `data/orders-archive.jsonl`)
+    #[serde(default)]
+    pub archive_file: Option<String>,
+    /// Seconds a finished order is kept after its last update before it is appended
+    /// to `archive_file` and deleted; kept forever when unset
+    #[serde(default)]
+    pub retention_seconds: Option<u64>,
+}
+
+impl PersistenceConfig {
+    pub fn database_url(&self) -> &str {
+        self.database_url.as_deref().unwrap_or(DEFAULT_DATABASE_URL)
+    }
+
+    pub fn archive_file(&self) -> &str {
+        self.archive_file.as_deref().unwrap_or(DEFAULT_ARCHIVE_FILE)
+    }
+}
+
+const DEFAULT_DATABASE_URL: &str = "sqlite://data/orders.db";
+const DEFAULT_ARCHIVE_FILE: &str = "data/orders-archive.jsonl";
+
+/// How ids are assigned to submitted orders
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderIdMode {
+    /// A random v4 UUID per submission
+    #[default]
+    Random,
+    /// Derived from the order's user, nonce and origin chain, so resubmissions share
+    /// an id and submitters can compute it in advance
+    Deterministic,
+}
+
+/// How a submitted order's signature is checked
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    /// ERC-1271 `isValidSignature` when the user has code on the origin chain, ECDSA otherwise
+    #[default]
+    Auto,
+    /// ECDSA recovery only, without looking the user up on-chain
+    Ecdsa,
+}
+
+/// Where orders are kept between restarts
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PersistenceBackend {
+    /// In memory, loaded from and saved to `data_file` at startup and shutdown
+    #[default]
+    Json,
+    /// SQLite at `database_url`; every write is durable immediately
+    Sqlite,
+}
+
+/// Optional registration with an external solver coordinator.
+/// No heartbeats are sent unless `url` is set.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CoordinatorConfig {
+    pub url: Option<String>,
+    #[serde(default = "default_heartbeat_interval_seconds")]
+    pub heartbeat_interval_seconds: u64,
+    /// Number of orders the solver advertises it can handle concurrently
+    #[serde(default = "default_max_concurrent_orders")]
+    pub max_concurrent_orders: usize,
+}
+
+fn default_heartbeat_interval_seconds() -> u64 {
+    30
+}
+
+fn default_max_concurrent_orders() -> usize {
+    10
+}
+
+impl Default for CoordinatorConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            heartbeat_interval_seconds: default_heartbeat_interval_seconds(),
+            max_concurrent_orders: default_max_concurrent_orders(),
+        }
+    }
+}
+
+/// Solver wallet balance monitoring
+#[derive(Debug, Deserialize, Clone)]
+pub struct BalanceConfig {
+    /// Native balance (wei) below which a critical alert is raised on a chain
+    pub min_balance_alert: Option<String>,
+    /// Stop accepting new orders while any chain is below `min_balance_alert`
+    #[serde(default)]
+    pub block_orders_when_low: bool,
+    #[serde(default = "default_balance_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+    /// Native balance (wei) a chain must hold to be reported funded by the health
+    /// check; `min_balance_alert` when unset
+    #[serde(default)]
+    pub min_reserve: Option<String>,
+}
+
+impl BalanceConfig {
+    /// Balance below which a chain is reported unfunded; zero when neither
+    /// `min_reserve` nor `min_balance_alert` is set
+    pub fn reserve(&self) -> Result<U256> {
+        let Some(value) = self.min_reserve.as_deref().or(self.min_balance_alert.as_deref()) else {
+            return Ok(U256::ZERO);
+        };
+        <U256 as std::str::FromStr>::from_str(value)
+            .map_err(|e| anyhow::anyhow!("Invalid minimum reserve {}: {}", value, e))
+    }
+}
+
+fn default_balance_check_interval_seconds() -> u64 {
+    60
+}
+
+impl Default for BalanceConfig {
+    fn default() -> Self {
+        Self {
+            min_balance_alert: None,
+            block_orders_when_low: false,
+            check_interval_seconds: default_balance_check_interval_seconds(),
+            min_reserve: None,
+        }
+    }
+}
+
+/// Approval of CoinFiller to spend the solver's output tokens
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AllowanceConfig {
+    /// Before a token fill, send an `approve` when CoinFiller's allowance does not cover it
+    #[serde(default)]
+    pub auto_approve: bool,
+    /// Amount (base units) approved at a time; the maximum uint256 when unset
+    #[serde(default)]
+    pub approve_amount: Option<String>,
+}
+
+impl AllowanceConfig {
+    /// Amount each `approve` grants
+    pub fn approve_amount(&self) -> Result<U256> {
+        let Some(value) = self.approve_amount.as_deref() else {
+            return Ok(U256::MAX);
+        };
+        <U256 as std::str::FromStr>::from_str(value)
+            .map_err(|e| anyhow::anyhow!("Invalid approve amount {}: {}", value, e))
+    }
+}
+
+/// Checks on the calldata encoders
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct EncoderConfig {
+    /// Also encode each finalization with the alternate encoder and log an error if the bytes differ
+    #[serde(default)]
+    pub verify_parity: bool,
+    /// Longest calldata (bytes) an encoder hands out; unset applies no ceiling
+    #[serde(default)]
+    pub max_calldata_bytes: Option<usize>,
+}
+
+/// Delivery of terminal order states to the submitter's `callback_url`
+#[derive(Debug, Deserialize, Clone)]
+pub struct CallbackConfig {
+    /// POSTs attempted per notification before it is dropped
+    #[serde(default = "default_callback_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each further one
+    #[serde(default = "default_callback_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    #[serde(default = "default_callback_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+fn default_callback_max_attempts() -> u32 {
+    3
+}
+
+fn default_callback_retry_delay_ms() -> u64 {
+    1000
+}
+
+fn default_callback_timeout_seconds() -> u64 {
+    5
+}
+
+impl Default for CallbackConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_callback_max_attempts(),
+            retry_delay_ms: default_callback_retry_delay_ms(),
+            timeout_seconds: default_callback_timeout_seconds(),
+        }
+    }
+}
+
+/// Load shedding for order submissions, based on pending and in-flight orders
+#[derive(Debug, Deserialize, Clone)]
+pub struct BackpressureConfig {
+    /// Pending + in-flight orders at which new submissions get a 503; unset never rejects
+    pub max_queue_depth: Option<usize>,
+    /// Share of `max_queue_depth` above which accepted submissions carry a Retry-After hint
+    #[serde(default = "default_high_water_ratio")]
+    pub high_water_ratio: f64,
+    #[serde(default = "default_retry_after_seconds")]
+    pub retry_after_seconds: u64,
+}
+
+fn default_high_water_ratio() -> f64 {
+    0.8
+}
+
+fn default_retry_after_seconds() -> u64 {
+    30
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            max_queue_depth: None,
+            high_water_ratio: default_high_water_ratio(),
+            retry_after_seconds: default_retry_after_seconds(),
+        }
+    }
 }
 
 impl AppConfig {
@@ -76,22 +861,30 @@ impl AppConfig {
         };
 
         // Override with environment variables if present
-        if let Ok(private_key) = std::env::var("SOLVER_PRIVATE_KEY") {
-            config.solver.private_key = private_key;
+        if std::env::var_os("SOLVER_PRIVATE_KEY").is_some() {
+            config.solver.private_key = format!("{}SOLVER_PRIVATE_KEY", ENV_KEY_PREFIX);
         }
+        config.solver.resolve_private_key()?;
 
         if let Ok(origin_rpc) = std::env::var("ORIGIN_RPC_URL") {
-            config.chains.origin.rpc_url = origin_rpc;
+            config.chains.origin.rpc_url = origin_rpc.into();
         }
 
         if let Ok(dest_rpc) = std::env::var("DESTINATION_RPC_URL") {
-            config.chains.destination.rpc_url = dest_rpc;
+            config.chains.destination.rpc_url = dest_rpc.into();
         }
 
+        config.chains.build_registry()?;
+
         tracing::info!("Final configuration:");
         tracing::info!("  Server: {}:{}", config.server.host, config.server.port);
         tracing::info!("  Origin chain: {}", config.chains.origin.rpc_url);
         tracing::info!("  Destination chain: {}", config.chains.destination.rpc_url);
+        tracing::info!("  Registered chains: {:?}", {
+            let mut chain_ids: Vec<_> = config.chains.registry.keys().collect();
+            chain_ids.sort();
+            chain_ids
+        });
 
         Ok(config)
     }
@@ -103,34 +896,411 @@ impl Default for AppConfig {
             server: ServerConfig {
                 host: "0.0.0.0".to_string(),
                 port: 3000,
+                log_format: Default::default(),
+                shutdown_grace_seconds: 30,
+                rate_limit: Default::default(),
+                admin_token: None,
             },
             solver: SolverConfig {
                 private_key: "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string(),
                 finalization_delay_seconds: 30,
+                allowed_input_tokens: Vec::new(),
+                denied_input_tokens: Vec::new(),
+                allowed_users: Vec::new(),
+                denied_users: Vec::new(),
+                allowed_oracles: Vec::new(),
+                dry_run: false,
+                production: false,
+                min_profit_margin_bps: None,
+                token_prices: HashMap::new(),
+                fill_amount_tolerance_bps: 0,
+                max_fill_retries: 3,
+                retry_backoff_seconds: vec![30, 120, 600],
+                skip_fill_proof_check: false,
+                max_inputs: None,
+                max_outputs: None,
+                order_id_mode: Default::default(),
+                simulate_finalization: true,
+                simulate_fill: true,
+                signature_scheme: Default::default(),
+                name: None,
             },
             chains: ChainConfig {
                 origin: ChainDetails {
-                    rpc_url: "http://localhost:8545".to_string(),
+                    rpc_url: "http://localhost:8545".into(),
+                    read_rpc_url: None,
                     chain_id: 31337,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
                 destination: ChainDetails {
-                    rpc_url: "http://localhost:8546".to_string(),
+                    rpc_url: "http://localhost:8546".into(),
+                    read_rpc_url: None,
                     chain_id: 31338,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
+                registry: Default::default(),
+                warn_on_chain_id_mismatch: false,
+                log_rpc_requests: false,
             },
             contracts: ContractConfig {
                 the_compact: "0x0000000000000000000000000000000000000000".to_string(),
                 settler_compact: "0x0000000000000000000000000000000000000000".to_string(),
                 coin_filler: "0x0000000000000000000000000000000000000000".to_string(),
+                fill_selector: None,
+                finalise_selector: None,
+                multicall: None,
+                abi_dir: None,
+                verify_domain_separator: Default::default(),
             },
             monitoring: MonitoringConfig {
                 enabled: true,
                 check_interval_seconds: 60,
+                pause_unhealthy_chains: true,
+                max_concurrent_operations: 4,
+                max_batch_finalize: 500,
+                batch_chunk_size: 50,
             },
             persistence: PersistenceConfig {
                 enabled: true,
                 data_file: "data/orders.json".to_string(),
+                backend: Default::default(),
+                database_url: None,
+                tx_journal_file: None,
+                max_orders_in_memory: None,
+                archive_file: None,
+                retention_seconds: None,
             },
+            coordinator: CoordinatorConfig::default(),
+            balance: BalanceConfig::default(),
+            gas: GasConfig::default(),
+            backpressure: BackpressureConfig::default(),
+            encoder: EncoderConfig::default(),
+            callbacks: CallbackConfig::default(),
+            allowances: Default::default(),
         }
     }
-} 
\ No newline at end of file
+} 
+
+impl AppConfig {
+    /// Fields of `other` that differ from this config but are only read at startup
+    /// (keys, chain endpoints, contracts, RPC logging, bind address, storage), so cannot be reloaded
+    pub fn immutable_changes(&self, other: &AppConfig) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        let mut check = |field: &'static str, differs: bool| {
+            if differs {
+                changed.push(field);
+            }
+        };
+        check("server.host", self.server.host != other.server.host);
+        check("server.port", self.server.port != other.server.port);
+        check("solver.private_key", self.solver.private_key != other.solver.private_key);
+        check("chains.origin", self.chains.origin.endpoint_differs(&other.chains.origin));
+        check("chains.destination", self.chains.destination.endpoint_differs(&other.chains.destination));
+        check(
+            "chains.registry",
+            self.chains.registry.len() != other.chains.registry.len()
+                || self.chains.registry.iter().any(|(chain_id, ours)| {
+                    other.chains.registry.get(chain_id).is_none_or(|theirs| ours.endpoint_differs(theirs))
+                }),
+        );
+        check("chains.log_rpc_requests", self.chains.log_rpc_requests != other.chains.log_rpc_requests);
+        check("contracts.the_compact", self.contracts.the_compact != other.contracts.the_compact);
+        check("contracts.settler_compact", self.contracts.settler_compact != other.contracts.settler_compact);
+        check("contracts.coin_filler", self.contracts.coin_filler != other.contracts.coin_filler);
+        check(
+            "persistence",
+            self.persistence.enabled != other.persistence.enabled
+                || self.persistence.backend != other.persistence.backend
+                || self.persistence.data_file != other.persistence.data_file
+                || self.persistence.database_url != other.persistence.database_url,
+        );
+        changed
+    }
+}
+
+/// A reloadable configuration was changed in a field that needs a restart
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Cannot change {} without a restart", fields.join(", "))]
+pub struct ImmutableConfigChange {
+    pub fields: Vec<&'static str>,
+}
+
+/// Configuration shared with the services that pick up changes without a restart
+///
+/// Clones see the same config; `replace` swaps it atomically for all of them.
+#[derive(Clone)]
+pub struct SharedConfig(Arc<ArcSwap<AppConfig>>);
+
+impl SharedConfig {
+    pub fn new(config: AppConfig) -> Self {
+        Self(Arc::new(ArcSwap::from_pointee(config)))
+    }
+
+    /// The configuration in effect now
+    pub fn current(&self) -> Arc<AppConfig> {
+        self.0.load_full()
+    }
+
+    /// Make `config` current, unless it changes a field that is only read at startup
+    pub fn replace(&self, config: AppConfig) -> Result<(), ImmutableConfigChange> {
+        let fields = self.current().immutable_changes(&config);
+        if !fields.is_empty() {
+            return Err(ImmutableConfigChange { fields });
+        }
+        self.0.store(Arc::new(config));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THREE_CHAIN_CONFIG: &str = r#"
+        [server]
+        host = "127.0.0.1"
+        port = 3000
+
+        [solver]
+        private_key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        finalization_delay_seconds = 30
+
+        [chains.origin]
+        rpc_url = "http://localhost:8545"
+        chain_id = 31337
+
+        [chains.destination]
+        rpc_url = "http://localhost:8546"
+        chain_id = 31338
+
+        [chains.registry.31339]
+        rpc_url = "http://localhost:8547"
+        chain_id = 31339
+
+        [contracts]
+        the_compact = "0x0000000000000000000000000000000000000000"
+        settler_compact = "0x0000000000000000000000000000000000000000"
+        coin_filler = "0x0000000000000000000000000000000000000000"
+
+        [monitoring]
+        enabled = false
+        check_interval_seconds = 60
+
+        [persistence]
+        enabled = false
+        data_file = "data/orders.json"
+    "#;
+
+    fn parse(toml: &str) -> AppConfig {
+        config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_three_chain_registry() {
+        let mut config = parse(THREE_CHAIN_CONFIG);
+        config.chains.build_registry().unwrap();
+
+        let mut chain_ids: Vec<_> = config.chains.registry.keys().copied().collect();
+        chain_ids.sort();
+        assert_eq!(chain_ids, vec![31337, 31338, 31339]);
+        assert_eq!(config.chains.get(31339).unwrap().rpc_url, "http://localhost:8547");
+        assert_eq!(config.chains.get(31337).unwrap().rpc_url, "http://localhost:8545");
+        assert!(config.chains.get(1).is_none());
+    }
+
+    #[test]
+    fn test_token_prices_keyed_by_address() {
+        let toml = THREE_CHAIN_CONFIG.replace(
+            "finalization_delay_seconds = 30",
+            "finalization_delay_seconds = 30\n        token_prices = { \"0x5FbDB2315678afecb367f032d93F642f64180aa3\" = 0.5 }",
+        );
+        let config = parse(&toml);
+
+        let token: Address = "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse().unwrap();
+        assert_eq!(config.solver.token_prices.get(&token), Some(&0.5));
+        assert_eq!(config.solver.min_profit_margin_bps, None);
+    }
+
+    #[test]
+    fn test_registry_derived_from_origin_and_destination() {
+        let legacy = THREE_CHAIN_CONFIG.replace(
+            "[chains.registry.31339]\n        rpc_url = \"http://localhost:8547\"\n        chain_id = 31339\n",
+            "",
+        );
+        let mut config = parse(&legacy);
+        assert!(config.chains.registry.is_empty());
+        // Lookups work even before the registry is built
+        assert_eq!(config.chains.get(31338).unwrap().rpc_url, "http://localhost:8546");
+
+        config.chains.build_registry().unwrap();
+        assert_eq!(config.chains.registry.len(), 2);
+    }
+
+    #[test]
+    fn test_rpc_url_accepts_a_list() {
+        let toml = THREE_CHAIN_CONFIG.replace(
+            "rpc_url = \"http://localhost:8546\"",
+            "rpc_url = [\"http://localhost:8546\", \"http://localhost:9546\"]",
+        );
+        let config = parse(&toml);
+
+        let urls: Vec<_> = config.chains.destination.rpc_url.iter().collect();
+        assert_eq!(urls, vec!["http://localhost:8546", "http://localhost:9546"]);
+        assert_eq!(config.chains.destination.read_rpc_url(), "http://localhost:8546");
+        // The single-string form still parses
+        assert_eq!(config.chains.origin.rpc_url, "http://localhost:8545");
+        assert!(RpcUrls::try_from(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_finalize_selector_override_name() {
+        let config = parse(&THREE_CHAIN_CONFIG.replace(
+            "coin_filler = \"0x0000000000000000000000000000000000000000\"",
+            "coin_filler = \"0x0000000000000000000000000000000000000000\"\nfinalize_selector_override = \"0xdd1ff485\"",
+        ));
+        assert_eq!(config.contracts.finalise_selector, Some([0xdd, 0x1f, 0xf4, 0x85]));
+        assert_eq!(parse(THREE_CHAIN_CONFIG).contracts.finalise_selector, None);
+    }
+
+    #[test]
+    fn test_gas_strategy_per_chain() {
+        let toml = THREE_CHAIN_CONFIG
+            .replace(
+                "chain_id = 31338\n",
+                "chain_id = 31338\n        gas = { strategy = { oracle_multiplier = 1.25 } }\n",
+            )
+            .replace("[monitoring]", "[gas]\n        strategy = { fixed = 50000000000 }\n\n        [monitoring]");
+        let config = parse(&toml);
+
+        assert_eq!(config.gas.strategy, GasStrategy::Fixed(50_000_000_000));
+        assert_eq!(config.gas.for_chain(&config.chains.origin).strategy, GasStrategy::Fixed(50_000_000_000));
+        assert_eq!(config.gas.for_chain(&config.chains.destination).strategy, GasStrategy::OracleMultiplier(1.25));
+
+        let toml = THREE_CHAIN_CONFIG.replace("[monitoring]", "[gas]\n        strategy = \"oracle\"\n\n        [monitoring]");
+        assert_eq!(parse(&toml).gas.strategy, GasStrategy::Oracle);
+        assert_eq!(parse(THREE_CHAIN_CONFIG).gas.strategy, GasStrategy::Oracle);
+        assert_eq!(config.gas.stuck_tx_timeout_seconds, None);
+        assert_eq!(config.gas.max_gas_bumps, 3);
+    }
+
+    #[test]
+    fn test_registry_rejects_mismatched_chain_id() {
+        let mut config = parse(&THREE_CHAIN_CONFIG.replace("chain_id = 31339", "chain_id = 1"));
+        assert!(config.chains.build_registry().is_err());
+    }
+
+    const TEST_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    #[test]
+    fn test_resolve_private_key_references() {
+        let mut solver = AppConfig::default().solver;
+
+        // env:NAME reads the variable
+        std::env::set_var("OIF_TEST_RESOLVE_KEY", TEST_KEY);
+        solver.private_key = "env:OIF_TEST_RESOLVE_KEY".to_string();
+        solver.resolve_private_key().unwrap();
+        assert_eq!(solver.private_key, TEST_KEY);
+
+        // file:/path reads the file, ignoring the trailing newline secrets usually carry
+        let path = std::env::temp_dir().join(format!("oif-test-key-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, format!("{}\n", TEST_KEY)).unwrap();
+        solver.private_key = format!("file:{}", path.display());
+        solver.resolve_private_key().unwrap();
+        assert_eq!(solver.private_key, TEST_KEY);
+        std::fs::remove_file(&path).unwrap();
+
+        // A plaintext key is kept as is outside production
+        solver.resolve_private_key().unwrap();
+        assert_eq!(solver.private_key, TEST_KEY);
+    }
+
+    #[test]
+    fn test_resolve_private_key_errors() {
+        let mut solver = AppConfig::default().solver;
+
+        solver.private_key = "env:OIF_TEST_UNSET_KEY".to_string();
+        let error = solver.resolve_private_key().unwrap_err().to_string();
+        assert!(error.contains("OIF_TEST_UNSET_KEY"), "{}", error);
+
+        solver.private_key = "file:/nonexistent/oif-solver-key".to_string();
+        assert!(solver.resolve_private_key().is_err());
+
+        // Production refuses plaintext keys without echoing them
+        solver.production = true;
+        solver.private_key = TEST_KEY.to_string();
+        let error = solver.resolve_private_key().unwrap_err().to_string();
+        assert!(!error.contains(&TEST_KEY[2..12]), "{}", error);
+
+        std::env::set_var("OIF_TEST_PRODUCTION_KEY", TEST_KEY);
+        solver.private_key = "env:OIF_TEST_PRODUCTION_KEY".to_string();
+        solver.resolve_private_key().unwrap();
+        assert_eq!(solver.private_key, TEST_KEY);
+    }
+
+    #[test]
+    fn test_fill_retry_schedule() {
+        let mut solver = parse(THREE_CHAIN_CONFIG).solver;
+        assert_eq!(solver.max_fill_retries, 3);
+        assert_eq!(solver.retry_backoff_seconds, vec![30, 120, 600]);
+
+        assert_eq!(solver.fill_retry_delay(0), None);
+        assert_eq!(solver.fill_retry_delay(1), Some(30));
+        assert_eq!(solver.fill_retry_delay(3), Some(600));
+        assert_eq!(solver.fill_retry_delay(4), None);
+
+        // Failures beyond the schedule reuse its last entry
+        solver.max_fill_retries = 5;
+        assert_eq!(solver.fill_retry_delay(5), Some(600));
+    }
+
+    #[test]
+    fn test_reload_refuses_startup_only_fields() {
+        let shared = SharedConfig::new(AppConfig::default());
+
+        let mut reloaded = AppConfig::default();
+        reloaded.chains.destination.max_gas_price_wei = Some(1_000_000_000);
+        reloaded.server.rate_limit.global = Some(TokenBucketConfig { per_second: 1.0, burst: 5 });
+        shared.replace(reloaded.clone()).unwrap();
+        assert_eq!(shared.current().chains.destination.max_gas_price_wei, Some(1_000_000_000));
+
+        reloaded.solver.private_key = format!("0x{}", "11".repeat(32));
+        reloaded.chains.origin.chain_id = 1;
+        let err = shared.replace(reloaded).unwrap_err();
+        assert_eq!(err.fields, vec!["solver.private_key", "chains.origin"]);
+        assert_eq!(shared.current().chains.origin.chain_id, 31337, "nothing applied");
+
+        // Registry chains and RPC logging are wired into clients at startup as well
+        let mut reloaded = (*shared.current()).clone();
+        let mut other_chain = reloaded.chains.destination.clone();
+        other_chain.chain_id = 31339;
+        reloaded.chains.registry.insert(31339, other_chain);
+        reloaded.chains.log_rpc_requests = true;
+        let err = shared.replace(reloaded.clone()).unwrap_err();
+        assert_eq!(err.fields, vec!["chains.registry", "chains.log_rpc_requests"]);
+
+        // ... but gas caps on a registry chain can still be changed
+        reloaded.chains.log_rpc_requests = false;
+        let shared = SharedConfig::new(reloaded.clone());
+        reloaded.chains.registry.get_mut(&31339).unwrap().max_gas_price_wei = Some(1);
+        shared.replace(reloaded.clone()).unwrap();
+        reloaded.chains.registry.get_mut(&31339).unwrap().coin_filler = Some(format!("{}", alloy::primitives::Address::ZERO));
+        assert_eq!(shared.replace(reloaded).unwrap_err().fields, vec!["chains.registry"]);
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
-use anyhow::Result;
+use std::path::Path;
+use alloy::json_abi::JsonAbi;
+use anyhow::{Context, Result};
 
 #[derive(Debug, Clone)]
 pub struct ContractAbi {
@@ -14,6 +16,8 @@ pub trait AbiProvider: Send + Sync {
 
 pub struct AbiRegistry {
     abis: HashMap<String, ContractAbi>,
+    /// ABIs read from disk, consulted before the built-in definitions
+    loaded: HashMap<String, ContractAbi>,
 }
 
 impl AbiRegistry {
@@ -55,21 +59,126 @@ impl AbiRegistry {
             ],
         });
         
-        Self { abis }
+        Self { abis, loaded: HashMap::new() }
     }
+
+    /// Built-in definitions overlaid with `<Contract>.json` ABIs from `dir`, when set
+    pub fn from_config(dir: Option<&str>) -> Result<Self> {
+        match dir {
+            Some(dir) => Self::new().with_abi_dir(dir),
+            None => Ok(Self::new()),
+        }
+    }
+
+    /// Load every `<Contract>.json` in `dir`, either a plain ABI array or a
+    /// Foundry/Hardhat artifact with an `abi` field
+    pub fn with_abi_dir(mut self, dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read ABI directory {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let abi = load_abi_file(&path)
+                .with_context(|| format!("Failed to load ABI {}", path.display()))?;
+            tracing::info!("Loaded {} ABI from {}", name, path.display());
+            self.loaded.insert(name.to_string(), abi);
+        }
+        Ok(self)
+    }
+}
+
+fn load_abi_file(path: &Path) -> Result<ContractAbi> {
+    let json: serde_json::Value = serde_json::from_slice(&std::fs::read(path)?)?;
+    let json = match json {
+        serde_json::Value::Object(mut artifact) => artifact
+            .remove("abi")
+            .ok_or_else(|| anyhow::anyhow!("Artifact has no `abi` field"))?,
+        abi => abi,
+    };
+    let abi: JsonAbi = serde_json::from_value(json)?;
+    Ok(ContractAbi {
+        functions: abi.functions().map(|function| function.signature()).collect(),
+        events: abi.events().map(|event| event.signature()).collect(),
+    })
+}
+
+/// Match on the whole function name, so `fill` doesn't resolve to e.g. `fillOrderOutputs`
+fn find_function(abi: &ContractAbi, function: &str) -> Option<String> {
+    abi.functions.iter()
+        .find(|f| f.split('(').next() == Some(function))
+        .cloned()
 }
 
 impl AbiProvider for AbiRegistry {
     fn get_contract_abi(&self, contract_name: &str) -> Result<&ContractAbi> {
-        self.abis.get(contract_name)
+        self.loaded.get(contract_name)
+            .or_else(|| self.abis.get(contract_name))
             .ok_or_else(|| anyhow::anyhow!("Contract ABI not found: {}", contract_name))
     }
     
     fn get_function_signature(&self, contract: &str, function: &str) -> Result<String> {
-        let abi = self.get_contract_abi(contract)?;
-        abi.functions.iter()
-            .find(|f| f.starts_with(function))
-            .cloned()
+        [&self.loaded, &self.abis].into_iter()
+            .filter_map(|abis| abis.get(contract))
+            .find_map(|abi| find_function(abi, function))
             .ok_or_else(|| anyhow::anyhow!("Function not found: {}::{}", contract, function))
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loaded_abis_take_precedence_over_builtins() {
+        let dir = std::env::temp_dir().join(format!("oif-abis-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let abi = serde_json::json!([
+            {
+                "type": "function",
+                "name": "fillOrderOutputs",
+                "inputs": [{ "name": "orderId", "type": "bytes32" }],
+                "outputs": [],
+                "stateMutability": "nonpayable"
+            },
+            {
+                "type": "function",
+                "name": "fill",
+                "inputs": [
+                    { "name": "orderId", "type": "bytes32" },
+                    {
+                        "name": "output",
+                        "type": "tuple",
+                        "components": [
+                            { "name": "token", "type": "bytes32" },
+                            { "name": "amount", "type": "uint256" }
+                        ]
+                    }
+                ],
+                "outputs": [],
+                "stateMutability": "nonpayable"
+            }
+        ]);
+        // Artifacts wrap the ABI in an object
+        std::fs::write(dir.join("CoinFiller.json"), serde_json::json!({ "abi": abi }).to_string()).unwrap();
+        std::fs::write(dir.join("README.md"), "not an abi").unwrap();
+
+        let registry = AbiRegistry::new().with_abi_dir(&dir).unwrap();
+        let default = AbiRegistry::new().get_function_signature("CoinFiller", "fill").unwrap();
+        let loaded = registry.get_function_signature("CoinFiller", "fill").unwrap();
+        assert_eq!(loaded, "fill(bytes32,(bytes32,uint256))");
+        assert_ne!(loaded, default);
+
+        // Contracts without a file keep the built-in signatures
+        assert_eq!(
+            registry.get_function_signature("SettlerCompact", "finalise").unwrap(),
+            AbiRegistry::new().get_function_signature("SettlerCompact", "finalise").unwrap()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,7 +3,7 @@ use tracing::info;
 use std::sync::Arc;
 use std::str::FromStr;
 
-use crate::contracts::encoding::{CallDataEncoder, traits::{FinaliseParams, FillRequest, StandardOrderParams, MandateOutputParams}};
+use crate::contracts::encoding::{CallDataEncoder, SelectorOverrides, selectors::apply_selector, traits::{allocator_signature, check_calldata_ceiling, FinaliseParams, FillRequest, StandardOrderParams, MandateOutputParams, OutputField}};
 use crate::contracts::abi::AbiProvider;
 use alloy::primitives::{Address, FixedBytes, Bytes, U256};
 use alloy::sol;
@@ -66,6 +66,8 @@ sol! {
 #[derive(Clone)]
 pub struct AlloyEncoder {
     abi_provider: Arc<dyn AbiProvider>,
+    selectors: SelectorOverrides,
+    max_calldata_bytes: Option<usize>,
 }
 
 impl CallDataEncoder for AlloyEncoder {
@@ -76,12 +78,12 @@ impl CallDataEncoder for AlloyEncoder {
         let params = self.order_to_finalize_params(order)?;
         
         // Use the detailed internal implementation
-        self.encode_finalise_call_internal(&params)
+        check_calldata_ceiling(self.encode_finalise_call_internal(&params)?, self.max_calldata_bytes)
     }
 
     fn get_finalize_selector(&self) -> [u8; 4] {
         // Get the selector using Alloy's built-in selector computation
-        SettlerCompact::finaliseCall::SELECTOR
+        self.selectors.finalise.unwrap_or(SettlerCompact::finaliseCall::SELECTOR)
     }
 
     fn description(&self) -> &str {
@@ -110,18 +112,19 @@ impl CallDataEncoder for AlloyEncoder {
             output,
             proposedSolver: proposed_solver,
         }.abi_encode();
+        let call_data = apply_selector(call_data, self.selectors.fill);
         
         info!("✅ Alloy fill call encoded successfully");
         info!("  Call data length: {} bytes", call_data.len());
         info!("  Function selector: 0x{}", hex::encode(&call_data[..4]));
         info!("⚠️  REMEMBER: Orchestrator must update remoteFiller, chainId, proposedSolver");
         
-        Ok(call_data)
+        check_calldata_ceiling(call_data, self.max_calldata_bytes)
     }
     
     fn get_fill_selector(&self) -> [u8; 4] {
         // Get the selector using Alloy's built-in selector computation
-        CoinFiller::fillCall::SELECTOR
+        self.selectors.fill.unwrap_or(CoinFiller::fillCall::SELECTOR)
     }
     
     fn encode_complete_fill_call(
@@ -132,14 +135,27 @@ impl CallDataEncoder for AlloyEncoder {
         solver_address: Address,
     ) -> Result<Vec<u8>> {
         // Use our internal complete implementation method
-        self.encode_complete_fill_call_internal(request, coin_filler_address, destination_chain_id, solver_address)
+        let call_data = self.encode_complete_fill_call_internal(request, coin_filler_address, destination_chain_id, solver_address)?;
+        check_calldata_ceiling(call_data, self.max_calldata_bytes)
     }
 }
 
 impl AlloyEncoder {
     pub fn new(abi_provider: Arc<dyn AbiProvider>) -> Self {
         info!("🏗️ Creating AlloyEncoder with sol! macro support");
-        Self { abi_provider }
+        Self { abi_provider, selectors: SelectorOverrides::default(), max_calldata_bytes: None }
+    }
+    
+    /// Use configured selectors instead of the ones computed from the sol! definitions
+    pub fn with_selector_overrides(mut self, selectors: SelectorOverrides) -> Self {
+        self.selectors = selectors;
+        self
+    }
+    
+    /// Refuse to produce calldata longer than `max_calldata_bytes`, when set
+    pub fn with_max_calldata_bytes(mut self, max_calldata_bytes: Option<usize>) -> Self {
+        self.max_calldata_bytes = max_calldata_bytes;
+        self
     }
     
     /// Convert high-level FillRequest to Alloy struct parameters
@@ -156,12 +172,12 @@ impl AlloyEncoder {
         
         // Create MandateOutput using Alloy struct - TEMPLATE VERSION
         let mandate_output = MandateOutput {
-            remoteOracle: self.address_to_bytes32(request.remote_oracle),
+            remoteOracle: request.address_encoding.encode(OutputField::RemoteOracle, request.remote_oracle),
             remoteFiller: FixedBytes::ZERO, // Will be set by the orchestrator
             chainId: U256::ZERO, // Will be set by the orchestrator  
-            token: self.address_to_bytes32(request.token),
+            token: request.address_encoding.encode(OutputField::Token, request.token),
             amount: request.amount,
-            recipient: self.address_to_bytes32(request.recipient),
+            recipient: request.address_encoding.encode(OutputField::Recipient, request.recipient),
             remoteCall: Bytes::default(),
             fulfillmentContext: Bytes::default(),
         };
@@ -194,19 +210,16 @@ impl AlloyEncoder {
         info!("  Solver address: {:?}", solver_address);
         
         // Convert order_id string to bytes32 - hash the string like TypeScript
-        let order_id_bytes32 = {
-            use alloy::primitives::keccak256;
-            keccak256(request.order_id.as_bytes())
-        };
+        let order_id_bytes32 = self.string_to_order_id(&request.order_id);
         
         // Create COMPLETE MandateOutput using Alloy struct (matches factory-bkp.rs)
         let mandate_output = MandateOutput {
-            remoteOracle: self.address_to_bytes32(request.remote_oracle),
-            remoteFiller: self.address_to_bytes32(coin_filler_address),  // ✅ FIXED
+            remoteOracle: request.address_encoding.encode(OutputField::RemoteOracle, request.remote_oracle),
+            remoteFiller: request.address_encoding.encode(OutputField::RemoteFiller, coin_filler_address),  // ✅ FIXED
             chainId: U256::from(destination_chain_id),                    // ✅ FIXED  
-            token: self.address_to_bytes32(request.token),
+            token: request.address_encoding.encode(OutputField::Token, request.token),
             amount: request.amount,
-            recipient: self.address_to_bytes32(request.recipient),
+            recipient: request.address_encoding.encode(OutputField::Recipient, request.recipient),
             remoteCall: Bytes::default(),
             fulfillmentContext: Bytes::default(),
         };
@@ -240,32 +253,20 @@ impl AlloyEncoder {
         };
         
         // Convert inputs
-        let inputs: Result<Vec<(U256, U256)>, anyhow::Error> = standard_order.inputs.iter()
-            .map(|(token_id, amount)| {
-                let token_id_u256 = U256::from_str(token_id)?;
-                let amount_u256 = U256::from_str(amount)?;
-                Ok((token_id_u256, amount_u256))
-            })
-            .collect();
-        let inputs = inputs?;
+        let inputs = standard_order.inputs.clone();
         
         // Convert outputs  
         let outputs: Result<Vec<MandateOutputParams>, anyhow::Error> = standard_order.outputs.iter()
             .map(|output| {
-                let amount_u256 = U256::from_str(&output.amount)?;
                 Ok(MandateOutputParams {
-                    remote_oracle: address_to_bytes32(output.remote_oracle),
-                    remote_filler: address_to_bytes32(output.remote_filler),
+                    remote_oracle: output.address_encoding.encode(OutputField::RemoteOracle, output.remote_oracle),
+                    remote_filler: output.address_encoding.encode(OutputField::RemoteFiller, output.remote_filler),
                     chain_id: U256::from(output.chain_id),
-                    token: address_to_bytes32(output.token),
-                    amount: amount_u256,
-                    recipient: address_to_bytes32(output.recipient),
-                    remote_call: output.remote_call.as_ref()
-                        .map(|s| Bytes::from(hex::decode(s.strip_prefix("0x").unwrap_or(s)).unwrap_or_default()))
-                        .unwrap_or_default(),
-                    fulfillment_context: output.fulfillment_context.as_ref()
-                        .map(|s| Bytes::from(hex::decode(s.strip_prefix("0x").unwrap_or(s)).unwrap_or_default()))
-                        .unwrap_or_default(),
+                    token: output.address_encoding.encode(OutputField::Token, output.token),
+                    amount: output.amount,
+                    recipient: output.address_encoding.encode(OutputField::Recipient, output.recipient),
+                    remote_call: output.remote_call_bytes()?,
+                    fulfillment_context: output.fulfillment_context_bytes()?,
                 })
             })
             .collect();
@@ -296,7 +297,7 @@ impl AlloyEncoder {
         Ok(FinaliseParams {
             order: order_params,
             sponsor_sig,
-            allocator_sig: Bytes::new(),
+            allocator_sig: allocator_signature(order)?,
             timestamps: vec![current_timestamp],
             solvers: vec![address_to_bytes32(solver_address)],
             destination: address_to_bytes32(solver_address),
@@ -311,11 +312,8 @@ impl AlloyEncoder {
         // Convert our internal params to Alloy structs
         let order = self.params_to_alloy_order(&params.order)?;
         
-        // Process signatures - concatenate them for the contract
-        let mut signatures_bytes = Vec::new();
-        signatures_bytes.extend_from_slice(&params.sponsor_sig);
-        signatures_bytes.extend_from_slice(&params.allocator_sig);
-        let signatures = Bytes::from(signatures_bytes);
+        // Process signatures - ABI tuple (bytes,bytes) of sponsor and allocator signatures
+        let signatures = params.encoded_signatures();
         
         // Use Alloy's sol! macro for encoding
         let call_data = SettlerCompact::finaliseCall {
@@ -326,6 +324,7 @@ impl AlloyEncoder {
             destination: params.destination,
             calls: params.calls.clone(),
         }.abi_encode();
+        let call_data = apply_selector(call_data, self.selectors.finalise);
         
         info!("✅ Alloy finalise call encoded successfully:");
         info!("  Function selector: 0x{}", hex::encode(&call_data[..4]));
@@ -390,12 +389,8 @@ impl AlloyEncoder {
             }
             FixedBytes::from(bytes)
         } else {
-            // Treat as string, convert to bytes32
-            let mut bytes = [0u8; 32];
-            let string_bytes = order_id.as_bytes();
-            let copy_len = string_bytes.len().min(32);
-            bytes[..copy_len].copy_from_slice(&string_bytes[..copy_len]);
-            FixedBytes::from(bytes)
+            // Hash the id string, matching the order id checked on-chain
+            crate::models::onchain_order_id(order_id)
         }
     }
     
@@ -434,6 +429,7 @@ impl AlloyEncoder {
             output,
             proposedSolver: proposed_solver,
         }.abi_encode();
+        let call_data = apply_selector(call_data, self.selectors.fill);
         
         info!("✅ COMPLETE fill call encoded successfully");
         info!("  Call data length: {} bytes", call_data.len());
@@ -448,7 +444,8 @@ impl AlloyEncoder {
 mod tests {
     use super::*;
     use crate::contracts::abi::{AbiRegistry, AbiProvider};
-    use crate::contracts::encoding::traits::{StandardOrderParams, MandateOutputParams};
+    use crate::contracts::encoding::traits::StandardOrderParams;
+    use crate::contracts::encoding::AddressEncoding;
     use alloy::primitives::U256;
     use std::str::FromStr;
 
@@ -487,6 +484,8 @@ mod tests {
             token: Address::from_str("0x9fe46736679d2d9a65f0992f2272de9f3c7fa6e0").unwrap(),
             amount: U256::from_str("99000000000000000000").unwrap(),
             recipient: Address::from_str("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266").unwrap(),
+            address_encoding: Default::default(),
+            chain_id: None,
         };
         
         let result = encoder.encode_fill_call(&request);
@@ -507,4 +506,148 @@ mod tests {
         println!("✅ Generated fill call data: {} bytes", calldata.len());
         println!("   Selector: 0x{}", hex::encode(&calldata[..4]));
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_alloy_encoder_uses_configured_selectors() {
+        let selectors = SelectorOverrides {
+            fill: Some([0x12, 0x34, 0x56, 0x78]),
+            finalise: Some([0xdd, 0x1f, 0xf4, 0x85]),
+        };
+        let encoder = create_test_alloy_encoder().with_selector_overrides(selectors);
+        
+        let request = FillRequest {
+            order_id: "test_order_123".to_string(),
+            fill_deadline: u32::MAX,
+            remote_oracle: Address::from([0x11; 20]),
+            token: Address::from([0x22; 20]),
+            amount: U256::from(99),
+            recipient: Address::from([0x33; 20]),
+            address_encoding: Default::default(),
+            chain_id: None,
+        };
+        let fill_calldata = encoder
+            .encode_complete_fill_call(&request, Address::from([0x44; 20]), 31338, Address::from([0x55; 20]))
+            .unwrap();
+        assert_eq!(&fill_calldata[..4], &[0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(encoder.get_fill_selector(), [0x12, 0x34, 0x56, 0x78]);
+        
+        let params = FinaliseParams {
+            order: StandardOrderParams {
+                user: Address::from([0x11; 20]),
+                nonce: U256::from(1),
+                origin_chain_id: U256::from(31337),
+                expires: u32::MAX,
+                fill_deadline: u32::MAX,
+                local_oracle: Address::from([0x22; 20]),
+                inputs: vec![(U256::from(1), U256::from(100))],
+                outputs: vec![],
+            },
+            sponsor_sig: Bytes::from(vec![0xab; 65]),
+            allocator_sig: Bytes::new(),
+            timestamps: vec![1],
+            solvers: vec![FixedBytes::ZERO],
+            destination: FixedBytes::ZERO,
+            calls: Bytes::new(),
+        };
+        let finalise_calldata = encoder.encode_finalise_call_internal(&params).unwrap();
+        assert_eq!(&finalise_calldata[..4], &[0xdd, 0x1f, 0xf4, 0x85]);
+        assert_eq!(encoder.get_finalize_selector(), [0xdd, 0x1f, 0xf4, 0x85]);
+        
+        // Parameters are untouched by the override
+        let default_calldata = create_test_alloy_encoder().encode_finalise_call_internal(&params).unwrap();
+        assert_eq!(finalise_calldata[4..], default_calldata[4..]);
+    }
+
+    #[test]
+    fn test_calldata_ceiling_is_enforced() {
+        let request = FillRequest {
+            order_id: "test_order_123".to_string(),
+            fill_deadline: u32::MAX,
+            remote_oracle: Address::from([0x11; 20]),
+            token: Address::from([0x22; 20]),
+            amount: U256::from(99),
+            recipient: Address::from([0x33; 20]),
+            address_encoding: Default::default(),
+            chain_id: None,
+        };
+        let encode = |encoder: AlloyEncoder| {
+            encoder.encode_complete_fill_call(&request, Address::from([0x44; 20]), 31338, Address::from([0x55; 20]))
+        };
+
+        let len = encode(create_test_alloy_encoder()).unwrap().len();
+        assert!(encode(create_test_alloy_encoder().with_max_calldata_bytes(Some(len))).is_ok());
+        let error = encode(create_test_alloy_encoder().with_max_calldata_bytes(Some(len - 1))).unwrap_err();
+        assert!(error.to_string().contains("Calldata too large"), "{}", error);
+    }
+
+    #[test]
+    fn test_fill_uses_the_output_address_encoding() {
+        let encoder = create_test_alloy_encoder();
+        let request = FillRequest {
+            order_id: "test_order_123".to_string(),
+            fill_deadline: u32::MAX,
+            remote_oracle: Address::from([0x11; 20]),
+            token: Address::from([0x22; 20]),
+            amount: U256::from(99),
+            recipient: Address::from([0x33; 20]),
+            address_encoding: AddressEncoding::RightPad20,
+            chain_id: None,
+        };
+        let calldata = encoder
+            .encode_complete_fill_call(&request, Address::from([0x44; 20]), 31338, Address::from([0x55; 20]))
+            .unwrap();
+        let fill = CoinFiller::fillCall::abi_decode_raw(&calldata[4..]).unwrap();
+
+        let right_padded = |byte: u8| {
+            let mut bytes = [0u8; 32];
+            bytes[..20].copy_from_slice(&[byte; 20]);
+            FixedBytes::from(bytes)
+        };
+        assert_eq!(fill.output.remoteOracle, right_padded(0x11));
+        assert_eq!(fill.output.token, right_padded(0x22));
+        assert_eq!(fill.output.recipient, right_padded(0x33));
+        assert_eq!(fill.output.remoteFiller, right_padded(0x44));
+        // The solver is an EVM account on the destination chain either way
+        assert_eq!(fill.proposedSolver, Address::from([0x55; 20]).into_word());
+    }
+
+    #[test]
+    fn test_allocator_signature_is_encoded_next_to_sponsor_signature() {
+        use crate::models::{MandateOutput, Order, StandardOrder};
+        use alloy::sol_types::SolValue;
+
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce: 1,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        };
+        let order = Order::new(standard_order, format!("0x{}", "ab".repeat(65)))
+            .with_allocator_signature(Some(format!("0x{}", "cd".repeat(65))));
+
+        let calldata = create_test_alloy_encoder().encode_finalize_call(&order).unwrap();
+        let finalise = SettlerCompact::finaliseCall::abi_decode_raw(&calldata[4..]).unwrap();
+        let (sponsor_sig, allocator_sig) = <(Bytes, Bytes)>::abi_decode_params(&finalise.signatures).unwrap();
+        assert_eq!(sponsor_sig, Bytes::from(vec![0xab; 65]));
+        assert_eq!(allocator_sig, Bytes::from(vec![0xcd; 65]));
+
+        // Without one the allocator half of the tuple is empty
+        let calldata = create_test_alloy_encoder()
+            .encode_finalize_call(&order.clone().with_allocator_signature(None))
+            .unwrap();
+        let finalise = SettlerCompact::finaliseCall::abi_decode_raw(&calldata[4..]).unwrap();
+        let (_, allocator_sig) = <(Bytes, Bytes)>::abi_decode_params(&finalise.signatures).unwrap();
+        assert!(allocator_sig.is_empty());
+    }
+}
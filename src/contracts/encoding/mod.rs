@@ -2,8 +2,11 @@
 pub mod traits;
 pub mod foundry_encoder;
 pub mod alloy_encoder;
+pub mod selectors;
+pub mod replay;
 
 // Re-export everything for easy access
 pub use traits::*;
 pub use foundry_encoder::FoundryEncoder;
-pub use alloy_encoder::AlloyEncoder; 
\ No newline at end of file
+pub use alloy_encoder::AlloyEncoder;
+pub use selectors::SelectorOverrides; 
\ No newline at end of file
@@ -0,0 +1,202 @@
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::Result;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::config::AppConfig;
+use crate::contracts::abi::AbiRegistry;
+use crate::contracts::encoding::{AlloyEncoder, CallDataEncoder, FillRequest, FoundryEncoder, SelectorOverrides};
+use crate::models::Order;
+
+/// Which call a stored order is encoded as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayOp {
+    Fill,
+    Finalize,
+}
+
+impl FromStr for ReplayOp {
+    type Err = anyhow::Error;
+
+    fn from_str(op: &str) -> Result<Self> {
+        match op {
+            "fill" => Ok(Self::Fill),
+            "finalize" | "finalise" => Ok(Self::Finalize),
+            other => Err(anyhow::anyhow!("Unknown operation '{}', expected 'fill' or 'finalize'", other)),
+        }
+    }
+}
+
+/// Calldata both encoders produce for the same order; an encoder error is kept as its message
+#[derive(Debug, Clone)]
+pub struct EncodingComparison {
+    pub alloy: Result<Vec<u8>, String>,
+    pub foundry: Result<Vec<u8>, String>,
+}
+
+impl EncodingComparison {
+    pub fn is_identical(&self) -> bool {
+        matches!((&self.alloy, &self.foundry), (Ok(alloy), Ok(foundry)) if alloy == foundry)
+    }
+
+    /// Human-readable report: both calldatas in hex, then the differing 32-byte words
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        for (name, calldata) in [("AlloyEncoder", &self.alloy), ("FoundryEncoder", &self.foundry)] {
+            match calldata {
+                Ok(calldata) => {
+                    let _ = writeln!(report, "{} ({} bytes):\n0x{}", name, calldata.len(), hex::encode(calldata));
+                }
+                Err(e) => {
+                    let _ = writeln!(report, "{}: failed: {}", name, e);
+                }
+            }
+        }
+
+        let (Ok(alloy), Ok(foundry)) = (&self.alloy, &self.foundry) else {
+            return report;
+        };
+        if alloy == foundry {
+            report.push_str("Identical\n");
+            return report;
+        }
+        if alloy.len() != foundry.len() {
+            let _ = writeln!(report, "Lengths differ: {} vs {} bytes", alloy.len(), foundry.len());
+        }
+        for (word, offset) in word_offsets(alloy.len().max(foundry.len())).enumerate() {
+            let alloy_word = chunk(alloy, offset);
+            let foundry_word = chunk(foundry, offset);
+            if alloy_word != foundry_word {
+                let _ = writeln!(
+                    report,
+                    "{} (offset 0x{:x}):\n  alloy   0x{}\n  foundry 0x{}",
+                    if word == 0 { "selector".to_string() } else { format!("word {}", word - 1) },
+                    offset,
+                    hex::encode(alloy_word),
+                    hex::encode(foundry_word),
+                );
+            }
+        }
+        report
+    }
+}
+
+/// Start of the selector, then of each 32-byte word after it
+fn word_offsets(len: usize) -> impl Iterator<Item = usize> {
+    std::iter::once(0).chain((4..len).step_by(32))
+}
+
+fn chunk(calldata: &[u8], offset: usize) -> &[u8] {
+    let end = if offset == 0 { 4 } else { offset + 32 };
+    &calldata[offset.min(calldata.len())..end.min(calldata.len())]
+}
+
+/// Encode `order` as `op` with both `AlloyEncoder` and `FoundryEncoder`
+pub fn compare_encoders(order: &Order, op: ReplayOp, config: &AppConfig) -> Result<EncodingComparison> {
+    let abi_registry = Arc::new(AbiRegistry::new());
+    let selectors = SelectorOverrides::from_config(&config.contracts);
+    let alloy = AlloyEncoder::new(abi_registry.clone()).with_selector_overrides(selectors);
+    let foundry = FoundryEncoder::new(abi_registry).with_selector_overrides(selectors);
+
+    let encode = |encoder: &dyn CallDataEncoder| -> Result<Result<Vec<u8>, String>> {
+        let calldata = match op {
+            ReplayOp::Finalize => encoder.encode_finalize_call(order),
+            ReplayOp::Fill => {
+                let output = order.standard_order.outputs.first()
+                    .ok_or_else(|| anyhow::anyhow!("Order {} has no outputs", order.id))?;
+                let request = FillRequest {
+                    order_id: order.id.to_string(),
+                    fill_deadline: order.standard_order.fill_deadline as u32,
+                    remote_oracle: output.remote_oracle,
+                    token: output.token,
+                    amount: output.amount,
+                    recipient: output.recipient,
+                    address_encoding: output.address_encoding.clone(),
+                    chain_id: None,
+                };
+                let coin_filler = config.contracts.coin_filler.parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid CoinFiller address in config: {}", e))?;
+                let solver = PrivateKeySigner::from_str(&config.solver.private_key)?.address();
+                encoder.encode_complete_fill_call(&request, coin_filler, config.chains.destination.chain_id, solver)
+            }
+        };
+        Ok(calldata.map_err(|e| e.to_string()))
+    };
+
+    Ok(EncodingComparison {
+        alloy: encode(&alloy)?,
+        foundry: encode(&foundry)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MandateOutput, StandardOrder};
+    use alloy::primitives::{Address, U256};
+    use std::process::Command;
+
+    fn fixture_order() -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from_str("0x70997970C51812dc3A010C7d01b50e0d17dc79C8").unwrap(),
+            nonce: 1,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from_str("0x0165878A594ca255338adfa4d48449f69242Eb8F").unwrap(),
+            inputs: vec![(
+                U256::from_str("232173931049414487598928205764542517475099722052565410375093941968804628563").unwrap(),
+                U256::from_str("100000000000000000000").unwrap(),
+            )],
+            outputs: vec![MandateOutput::new(
+                Address::from_str("0x0165878A594ca255338adfa4d48449f69242Eb8F").unwrap(),
+                Address::from_str("0x5FbDB2315678afecb367f032d93F642f64180aa3").unwrap(),
+                31338,
+                Address::from_str("0x5FbDB2315678afecb367f032d93F642f64180aa3").unwrap(),
+                U256::from_str("99000000000000000000").unwrap(),
+                Address::from_str("0x70997970C51812dc3A010C7d01b50e0d17dc79C8").unwrap(),
+            )],
+        };
+        Order::new(standard_order, format!("0x{}1b", "ab".repeat(64)))
+    }
+
+    #[test]
+    fn test_replayed_finalize_is_identical_across_encoders() {
+        // Skip if foundry not available in CI
+        if !Command::new("cast").arg("--version").output().map(|o| o.status.success()).unwrap_or(false) {
+            println!("⚠️  Skipping test - Foundry cast not available");
+            return;
+        }
+
+        let comparison = compare_encoders(&fixture_order(), ReplayOp::Finalize, &AppConfig::default()).unwrap();
+        assert!(!comparison.alloy.as_ref().unwrap().is_empty());
+        assert!(comparison.is_identical(), "{}", comparison.report());
+    }
+
+    #[test]
+    fn test_report_points_at_differing_words() {
+        let mut foundry = vec![0xdd, 0x1f, 0xf4, 0x85];
+        foundry.extend([0u8; 64]);
+        let mut alloy = foundry.clone();
+        alloy[4 + 32 + 31] = 0x01;
+
+        let comparison = EncodingComparison { alloy: Ok(alloy), foundry: Ok(foundry.clone()) };
+        assert!(!comparison.is_identical());
+        let report = comparison.report();
+        assert!(report.contains("word 1 (offset 0x24)"), "{}", report);
+        assert!(!report.contains("word 0 "), "{}", report);
+
+        let comparison = EncodingComparison { alloy: Ok(foundry.clone()), foundry: Ok(foundry) };
+        assert!(comparison.is_identical());
+        assert!(comparison.report().ends_with("Identical\n"));
+    }
+
+    #[test]
+    fn test_replayed_fill_reports_unsupported_encoder() {
+        let comparison = compare_encoders(&fixture_order(), ReplayOp::Fill, &AppConfig::default()).unwrap();
+        assert!(!comparison.alloy.as_ref().unwrap().is_empty());
+        assert!(comparison.foundry.is_err());
+        assert!(comparison.report().contains("FoundryEncoder: failed"));
+    }
+}
@@ -0,0 +1,87 @@
+use alloy::primitives::keccak256;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::config::{AppConfig, ContractConfig};
+use crate::contracts::abi::AbiRegistry;
+use crate::contracts::encoding::{AlloyEncoder, CallDataEncoder, FoundryEncoder};
+
+/// Function selectors that replace the computed ones in every encoder when set
+///
+/// Configured through `contracts.fill_selector` / `contracts.finalise_selector`,
+/// for deployments whose contract version differs from the ABI the solver was built with.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SelectorOverrides {
+    pub fill: Option<[u8; 4]>,
+    pub finalise: Option<[u8; 4]>,
+}
+
+impl SelectorOverrides {
+    pub fn from_config(contracts: &ContractConfig) -> Self {
+        Self {
+            fill: contracts.fill_selector,
+            finalise: contracts.finalise_selector,
+        }
+    }
+}
+
+/// Replace the selector of already encoded `calldata` with `selector`, if set
+pub fn apply_selector(mut calldata: Vec<u8>, selector: Option<[u8; 4]>) -> Vec<u8> {
+    if let Some(selector) = selector {
+        if calldata.len() >= 4 {
+            calldata[..4].copy_from_slice(&selector);
+        }
+    }
+    calldata
+}
+
+/// First four bytes of the keccak256 hash of a canonical function signature
+pub fn selector_from_signature(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Log the selectors the fill (AlloyEncoder) and finalise (FoundryEncoder) paths will use
+pub fn log_selectors_in_effect(config: &AppConfig) {
+    let overrides = SelectorOverrides::from_config(&config.contracts);
+    let abi_registry = Arc::new(AbiRegistry::new());
+    let fill_encoder = AlloyEncoder::new(abi_registry.clone()).with_selector_overrides(overrides);
+    let finalise_encoder = FoundryEncoder::new(abi_registry).with_selector_overrides(overrides);
+
+    log_selector("fill", fill_encoder.get_fill_selector(), overrides.fill.is_some());
+    log_selector("finalise", finalise_encoder.get_finalize_selector(), overrides.finalise.is_some());
+}
+
+/// Overrides bypass the ABI, so a stale one only shows up as reverts: make it stand out
+fn log_selector(name: &str, selector: [u8; 4], overridden: bool) {
+    if overridden {
+        warn!(
+            "⚠️ {} selector OVERRIDDEN by config: 0x{} (not computed from ABI; remove the override once contracts match)",
+            name,
+            hex::encode(selector)
+        );
+    } else {
+        info!("🔑 {} selector: 0x{} (computed from ABI)", name, hex::encode(selector));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_selector_replaces_prefix_only() {
+        let calldata = vec![0x01, 0x02, 0x03, 0x04, 0xaa, 0xbb];
+
+        assert_eq!(apply_selector(calldata.clone(), None), calldata);
+        assert_eq!(
+            apply_selector(calldata, Some([0xdd, 0x1f, 0xf4, 0x85])),
+            vec![0xdd, 0x1f, 0xf4, 0x85, 0xaa, 0xbb]
+        );
+    }
+
+    #[test]
+    fn test_selector_from_signature() {
+        assert_eq!(selector_from_signature("transfer(address,uint256)"), [0xa9, 0x05, 0x9c, 0xbb]);
+    }
+}
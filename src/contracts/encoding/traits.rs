@@ -1,6 +1,9 @@
 use alloy::primitives::{Address, U256, FixedBytes, Bytes};
+use alloy::sol_types::SolValue;
 use anyhow::Result;
 
+pub use crate::models::{AddressEncoding, OutputField};
+
 #[derive(Debug, Clone)]
 pub struct FinaliseParams {
     pub order: StandardOrderParams,
@@ -44,6 +47,33 @@ pub struct FillParams {
     pub proposed_solver: FixedBytes<32>,
 }
 
+impl FinaliseParams {
+    /// The `signatures` argument of `finalise`: `abi.encode(sponsorSig, allocatorSig)`
+    pub fn encoded_signatures(&self) -> Bytes {
+        Bytes::from((self.sponsor_sig.clone(), self.allocator_sig.clone()).abi_encode_params())
+    }
+}
+
+/// The order's allocator signature as bytes; empty when it has none
+pub fn allocator_signature(order: &crate::models::Order) -> Result<Bytes> {
+    match order.allocator_signature.as_deref().map(str::trim) {
+        None | Some("") | Some("0x") => Ok(Bytes::new()),
+        Some(signature) => hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+            .map(Bytes::from)
+            .map_err(|e| anyhow::anyhow!("Invalid hex in allocator signature: {}", e)),
+    }
+}
+
+/// Refuse `calldata` longer than `max_bytes`, when a ceiling is configured
+pub fn check_calldata_ceiling(calldata: Vec<u8>, max_bytes: Option<usize>) -> Result<Vec<u8>> {
+    match max_bytes {
+        Some(max) if calldata.len() > max => {
+            Err(anyhow::anyhow!("Calldata too large: {} bytes, ceiling is {}", calldata.len(), max))
+        }
+        _ => Ok(calldata),
+    }
+}
+
 /// High-level fill request parameters
 #[derive(Debug, Clone)]
 pub struct FillRequest {
@@ -53,6 +83,10 @@ pub struct FillRequest {
     pub token: Address,
     pub amount: U256,
     pub recipient: Address,
+    /// How the output's addresses are laid out as bytes32
+    pub address_encoding: AddressEncoding,
+    /// Chain the output is paid on; the configured destination when `None`
+    pub chain_id: Option<u64>,
 }
 
 /// Abstract trait for call data encoding
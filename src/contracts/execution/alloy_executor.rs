@@ -1,21 +1,270 @@
-use crate::contracts::execution::traits::{ExecutionEngine, GasParams, ChainType};
-use crate::config::AppConfig;
+use crate::contracts::execution::traits::{ExecutionEngine, GasParams, ChainType, SignedTransaction, TransactionReceiptInfo, ReceiptLog};
+use crate::contracts::execution::error::{revert_reason, ExecutionError};
+use crate::contracts::execution::nonce::NonceManager;
+use crate::contracts::execution::block_height::BlockHeightCache;
+use crate::contracts::execution::rpc_log::RpcLogLayer;
+use crate::config::{AppConfig, ChainDetails, GasConfig};
 use alloy::{
-    providers::{Provider, ProviderBuilder},
-    network::EthereumWallet,
-    primitives::{Address, U256, TxHash},
-    rpc::types::{TransactionRequest, TransactionInput},
+    eips::eip2718::Encodable2718,
+    providers::{PendingTransactionBuilder, Provider, ProviderBuilder, WsConnect},
+    network::{EthereumWallet, TransactionBuilder},
+    primitives::{Address, TxHash, U256},
+    pubsub::PubSubFrontend,
+    rpc::client::{ClientBuilder, RpcClient},
+    rpc::types::{TransactionReceipt, TransactionRequest, TransactionInput},
     signers::local::PrivateKeySigner,
+    transports::{http::Http, IntoBoxTransport},
 };
 use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{info, error, warn};
 use hex;
+use tower::Layer;
+
+/// How to reach an RPC endpoint, chosen from the URL scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RpcTransport {
+    /// `http://` / `https://`: receipts are found by polling
+    Http,
+    /// `ws://` / `wss://`: receipts are found through new-block subscriptions
+    Ws,
+}
+
+impl RpcTransport {
+    pub(crate) fn for_url(rpc_url: &str) -> Result<Self> {
+        let url: reqwest::Url = rpc_url.parse()
+            .map_err(|e| anyhow::anyhow!("Invalid RPC URL '{}': {}", rpc_url, e))?;
+        match url.scheme() {
+            "http" | "https" => Ok(Self::Http),
+            "ws" | "wss" => Ok(Self::Ws),
+            scheme => Err(anyhow::anyhow!("Unsupported RPC URL scheme '{}' in '{}'", scheme, rpc_url)),
+        }
+    }
+}
+
+/// Idle connections kept open per RPC host
+const RPC_POOL_MAX_IDLE_PER_HOST: usize = 8;
+/// How long an idle pooled connection is kept before it is closed
+const RPC_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// JSON-RPC client for the first of `rpc_urls` that answers, over WebSocket for `ws(s)://` URLs
+///
+/// Every endpoint but the last must answer `eth_blockNumber` within `timeout`
+/// to be chosen; the last is used without probing, as there is nothing left to
+/// fail over to. HTTP requests share a connection pool and fail after `timeout`
+/// instead of blocking on a node that stopped answering. With `log_requests` every
+/// request is logged through `RpcLogLayer`.
+pub(crate) async fn connect_rpc_client<S: AsRef<str>>(rpc_urls: &[S], timeout: Duration, log_requests: bool) -> Result<RpcClient> {
+    let Some((last, preferred)) = rpc_urls.split_last() else {
+        return Err(anyhow::anyhow!("No RPC endpoints configured"));
+    };
+    for (i, rpc_url) in preferred.iter().map(AsRef::as_ref).enumerate() {
+        let probe = async {
+            let client = connect_endpoint(rpc_url, timeout, log_requests).await?;
+            client.request_noparams::<alloy::primitives::U64>("eth_blockNumber").await
+                .map_err(ExecutionError::from)?;
+            Ok::<_, anyhow::Error>(client)
+        };
+        match tokio::time::timeout(timeout, probe).await {
+            Ok(Ok(client)) => {
+                if i > 0 {
+                    info!("🔀 Using fallback RPC endpoint {} ({} of {})", rpc_url, i + 1, rpc_urls.len());
+                }
+                return Ok(client);
+            }
+            Ok(Err(e)) => warn!("RPC endpoint {} is unavailable, trying the next one: {}", rpc_url, e),
+            Err(_) => warn!("RPC endpoint {} did not answer within {:?}, trying the next one", rpc_url, timeout),
+        }
+    }
+    if !preferred.is_empty() {
+        info!("🔀 Using fallback RPC endpoint {} ({} of {})", last.as_ref(), rpc_urls.len(), rpc_urls.len());
+    }
+    connect_endpoint(last.as_ref(), timeout, log_requests).await
+}
+
+/// JSON-RPC client for a single endpoint, logging its requests if `log_requests`
+async fn connect_endpoint(rpc_url: &str, timeout: Duration, log_requests: bool) -> Result<RpcClient> {
+    if log_requests {
+        connect_endpoint_with(ClientBuilder::default().layer(RpcLogLayer), rpc_url, timeout).await
+    } else {
+        connect_endpoint_with(ClientBuilder::default(), rpc_url, timeout).await
+    }
+}
+
+async fn connect_endpoint_with<L>(builder: ClientBuilder<L>, rpc_url: &str, timeout: Duration) -> Result<RpcClient>
+where
+    L: Layer<Http<reqwest::Client>> + Layer<PubSubFrontend>,
+    <L as Layer<Http<reqwest::Client>>>::Service: IntoBoxTransport,
+    <L as Layer<PubSubFrontend>>::Service: IntoBoxTransport,
+{
+    match RpcTransport::for_url(rpc_url)? {
+        RpcTransport::Http => {
+            let http = reqwest::Client::builder()
+                .timeout(timeout)
+                .connect_timeout(timeout)
+                .pool_max_idle_per_host(RPC_POOL_MAX_IDLE_PER_HOST)
+                .pool_idle_timeout(RPC_POOL_IDLE_TIMEOUT)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to build HTTP client for '{}': {}", rpc_url, e))?;
+            Ok(builder.http_with_client(http, rpc_url.parse()?))
+        }
+        RpcTransport::Ws => Ok(builder
+            .ws(WsConnect::new(rpc_url))
+            .await
+            .map_err(ExecutionError::from)?),
+    }
+}
+
+/// Chain id reported by the endpoint at `rpc_url`
+async fn rpc_chain_id(rpc_url: &str, timeout: Duration, log_requests: bool) -> Result<u64> {
+    let client = connect_endpoint(rpc_url, timeout, log_requests).await?;
+    let chain_id = client.request_noparams::<alloy::primitives::U64>("eth_chainId").await
+        .map_err(ExecutionError::from)?;
+    Ok(chain_id.to::<u64>())
+}
+
+/// Why the mined transaction `tx_hash` reverted, found by replaying it as a call
+/// on the state its block started from; `None` when the replay yields no revert data
+async fn replay_revert_reason(provider: &dyn Provider, tx_hash: TxHash, block: Option<u64>) -> Option<String> {
+    let tx = match provider.get_transaction_by_hash(tx_hash).await {
+        Ok(Some(tx)) => tx,
+        Ok(None) => return None,
+        Err(e) => {
+            warn!("Could not fetch reverted transaction {} for replay: {}", tx_hash, e);
+            return None;
+        }
+    };
+    let mut call = provider.call(tx.into_request());
+    if let Some(block) = block {
+        call = call.block(block.saturating_sub(1).into());
+    }
+    match call.await {
+        Ok(_) => None,
+        Err(e) => match ExecutionError::from(&e) {
+            ExecutionError::CallReverted { data } => Some(revert_reason(&data)),
+            _ => {
+                warn!("Replaying reverted transaction {} gave no revert data: {}", tx_hash, e);
+                None
+            }
+        },
+    }
+}
+
+/// How often receipts are polled while a stuck transaction may be replaced
+const REPLACEMENT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lowest gas price a node accepts for replacing a transaction priced `gas_price`:
+/// at least 10% more, rounded up
+pub(crate) fn bumped_gas_price(gas_price: u128) -> u128 {
+    gas_price.saturating_add(gas_price.div_ceil(10).max(1))
+}
+
+/// When and how far a transaction without a receipt is resubmitted
+#[derive(Debug, Clone, Copy)]
+struct ReplacementPolicy {
+    stuck_timeout: Duration,
+    max_bumps: u32,
+    /// Replacements are never priced above this (wei)
+    max_gas_price: Option<u128>,
+    poll_interval: Duration,
+}
+
+impl ReplacementPolicy {
+    /// The policy `chain`'s gas profile asks for, `None` when stuck transactions are not replaced
+    fn for_chain(gas: &GasConfig, chain: &ChainDetails) -> Option<Self> {
+        let profile = gas.for_chain(chain);
+        Some(Self {
+            stuck_timeout: Duration::from_secs(profile.stuck_tx_timeout_seconds?),
+            max_bumps: profile.max_gas_bumps,
+            max_gas_price: chain.max_gas_price_wei,
+            poll_interval: REPLACEMENT_POLL_INTERVAL,
+        })
+    }
+}
+
+/// Wait for the receipt of `first_hash`, the broadcast of `tx_request`; each time
+/// the policy's timeout passes without one, resubmit the same nonce with a bumped
+/// gas price until `max_bumps` replacements have been sent
+///
+/// Any submission may be the one that gets mined, so all of them are watched.
+async fn wait_or_replace(
+    provider: &dyn Provider,
+    mut tx_request: TransactionRequest,
+    first_hash: TxHash,
+    policy: ReplacementPolicy,
+) -> Result<TransactionReceipt> {
+    let mut sent = vec![first_hash];
+    let mut bumps = 0;
+    loop {
+        let deadline = tokio::time::Instant::now() + policy.stuck_timeout;
+        loop {
+            for hash in &sent {
+                if let Some(receipt) = provider.get_transaction_receipt(*hash).await.map_err(ExecutionError::from)? {
+                    return Ok(receipt);
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(policy.poll_interval).await;
+        }
+
+        let gas_price = bumped_gas_price(tx_request.gas_price.unwrap_or_default());
+        if bumps >= policy.max_bumps || policy.max_gas_price.is_some_and(|max| gas_price > max) {
+            return Err(ExecutionError::Timeout(format!(
+                "no receipt for nonce {:?} after {} replacement(s)", tx_request.nonce, bumps
+            )).into());
+        }
+        bumps += 1;
+        warn!("⛽ No receipt for {} after {:?}; replacing nonce {:?} at gas price {} ({}/{})",
+              sent[sent.len() - 1], policy.stuck_timeout, tx_request.nonce, gas_price, bumps, policy.max_bumps);
+        tx_request.gas_price = Some(gas_price);
+        match provider.send_transaction(tx_request.clone()).await {
+            Ok(pending_tx) => sent.push(*pending_tx.tx_hash()),
+            // The original may have been mined meanwhile, so keep watching what was sent
+            Err(e) => warn!("Replacement for nonce {:?} was not accepted: {}", tx_request.nonce, e),
+        }
+    }
+}
+
+/// How often the chain head is checked while waiting for confirmations
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Wait until the chain head is `confirmations` blocks past `block`, returning the head seen
+///
+/// The head is read through `heights`, so waits on the same chain share polls.
+pub(crate) async fn wait_for_confirmations(
+    provider: &dyn Provider,
+    heights: &BlockHeightCache,
+    chain: &ChainDetails,
+    block: u64,
+    confirmations: u64,
+    poll_interval: Duration,
+) -> Result<u64> {
+    if confirmations == 0 {
+        return Ok(block);
+    }
+    let target = block.saturating_add(confirmations);
+    info!("⏳ Waiting for {} confirmations (block {})", confirmations, target);
+    loop {
+        let head = heights.height(chain.chain_id, chain.block_height_ttl(), || async {
+            Ok(provider.get_block_number().await.map_err(ExecutionError::from)?)
+        }).await?;
+        if head >= target {
+            info!("✅ {} confirmations reached at block {}", confirmations, head);
+            return Ok(head);
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
 
 pub struct AlloyExecutor {
     config: Arc<AppConfig>,
     wallet: EthereumWallet,
+    nonces: Arc<NonceManager>,
+    block_heights: Arc<BlockHeightCache>,
 }
 
 impl AlloyExecutor {
@@ -37,35 +286,95 @@ impl AlloyExecutor {
         Ok(Self {
             config,
             wallet,
+            nonces: NonceManager::global(),
+            block_heights: BlockHeightCache::global(),
         })
     }
     
+    /// Assign nonces from `nonces` instead of the process-wide manager
+    pub fn with_nonce_manager(mut self, nonces: Arc<NonceManager>) -> Self {
+        self.nonces = nonces;
+        self
+    }
+    
+    /// Read chain heads through `block_heights` instead of the process-wide cache
+    pub fn with_block_height_cache(mut self, block_heights: Arc<BlockHeightCache>) -> Self {
+        self.block_heights = block_heights;
+        self
+    }
+    
+    /// Reserve the wallet's next nonce on `chain_id`, asking the chain only when none is cached
+    async fn reserve_nonce(&self, provider: &(dyn Provider + Send + Sync), chain_id: u64) -> Result<u64> {
+        let from = self.wallet.default_signer().address();
+        self.nonces.next_nonce(chain_id, from, || async move {
+            Ok(provider.get_transaction_count(from).pending().await
+                .map_err(ExecutionError::from)?)
+        }).await
+    }
+    
+    /// Drop the cached nonce after a send that never reached the mempool
+    async fn reset_nonce(&self, chain_id: u64) {
+        warn!("Resetting cached nonce for chain {}", chain_id);
+        self.nonces.reset(chain_id, self.wallet.default_signer().address()).await;
+    }
+    
+    /// Create a wallet-backed provider for the first of `rpc_urls` that answers, with requests failing after `timeout`
+    async fn create_provider<S: AsRef<str>>(&self, rpc_urls: &[S], timeout: Duration) -> Result<Box<dyn Provider + Send + Sync>> {
+        let client = connect_rpc_client(rpc_urls, timeout, self.config.chains.log_rpc_requests).await?;
+        Ok(Box::new(ProviderBuilder::new().wallet(self.wallet.clone()).connect_client(client)))
+    }
+    
     /// Create provider for origin chain
-    fn create_origin_provider(&self) -> Result<Box<dyn Provider + Send + Sync>> {
-        let provider = ProviderBuilder::new()
-            .wallet(self.wallet.clone())
-            .on_http(self.config.chains.origin.rpc_url.parse()?);
-            
-        Ok(Box::new(provider))
+    async fn create_origin_provider(&self) -> Result<Box<dyn Provider + Send + Sync>> {
+        let origin = &self.config.chains.origin;
+        self.create_provider(origin.rpc_url.as_slice(), origin.rpc_timeout()).await
     }
     
     /// Create provider for destination chain  
-    fn create_destination_provider(&self) -> Result<Box<dyn Provider + Send + Sync>> {
-        let provider = ProviderBuilder::new()
-            .wallet(self.wallet.clone())
-            .on_http(self.config.chains.destination.rpc_url.parse()?);
-            
-        Ok(Box::new(provider))
+    async fn create_destination_provider(&self) -> Result<Box<dyn Provider + Send + Sync>> {
+        let destination = &self.config.chains.destination;
+        self.create_provider(destination.rpc_url.as_slice(), destination.rpc_timeout()).await
     }
     
-    /// Create provider for specific chain by ID
-    fn create_provider_for_chain(&self, chain_id: u64) -> Result<Box<dyn Provider + Send + Sync>> {
-        if chain_id == self.config.chains.origin.chain_id {
-            self.create_origin_provider()
-        } else if chain_id == self.config.chains.destination.chain_id {
-            self.create_destination_provider()
-        } else {
-            Err(anyhow::anyhow!("Unsupported chain ID: {}", chain_id))
+    /// Resolve a `ChainType` to its configured chain
+    fn chain_details(&self, chain: ChainType) -> Result<&ChainDetails> {
+        match chain {
+            ChainType::Origin => Ok(&self.config.chains.origin),
+            ChainType::Destination => Ok(&self.config.chains.destination),
+            ChainType::ById(chain_id) => self.config.chains.get(chain_id)
+                .ok_or_else(|| anyhow::anyhow!("Unsupported chain ID: {}", chain_id)),
+        }
+    }
+    
+    /// Create provider for the chain `chain` refers to
+    async fn create_chain_provider(&self, chain: ChainType) -> Result<Box<dyn Provider + Send + Sync>> {
+        let details = self.chain_details(chain)?;
+        self.create_provider(details.rpc_url.as_slice(), details.rpc_timeout()).await
+    }
+    
+    /// Create provider for read-only calls on the chain `chain` refers to
+    async fn create_chain_read_provider(&self, chain: ChainType) -> Result<Box<dyn Provider + Send + Sync>> {
+        let details = self.chain_details(chain)?;
+        self.create_provider(&details.read_rpc_urls(), details.rpc_timeout()).await
+    }
+    
+    /// Create provider for specific chain by ID, looked up in the chain registry
+    async fn create_provider_for_chain(&self, chain_id: u64) -> Result<Box<dyn Provider + Send + Sync>> {
+        self.create_chain_provider(ChainType::ById(chain_id)).await
+    }
+    
+    /// Wait for the receipt of `pending_tx`, the broadcast of `tx_request`, replacing it
+    /// while stuck when `chain`'s gas profile sets a timeout
+    async fn await_receipt(
+        &self,
+        provider: &(dyn Provider + Send + Sync),
+        chain: &ChainDetails,
+        tx_request: TransactionRequest,
+        pending_tx: PendingTransactionBuilder<alloy::network::Ethereum>,
+    ) -> Result<TransactionReceipt> {
+        match ReplacementPolicy::for_chain(&self.config.gas, chain) {
+            Some(policy) => wait_or_replace(provider, tx_request, *pending_tx.tx_hash(), policy).await,
+            None => Ok(pending_tx.get_receipt().await.map_err(ExecutionError::from)?),
         }
     }
     
@@ -83,6 +392,9 @@ impl AlloyExecutor {
         // Set gas parameters explicitly
         tx_request.gas = Some(gas.gas_limit.into());
         tx_request.gas_price = Some(gas.gas_price.into());
+        if !gas.value.is_zero() {
+            tx_request.value = Some(gas.value);
+        }
         
         tx_request
     }
@@ -117,36 +429,40 @@ impl ExecutionEngine for AlloyExecutor {
         info!("  Call data: {} bytes", call_data.len());
         info!("  Gas limit: {}", gas.gas_limit);
         info!("  Gas price: {}", gas.gas_price);
+        if !gas.value.is_zero() {
+            info!("  Value: {} wei", gas.value);
+        }
         
         // Create provider based on specified chain
-        let provider = match chain {
-            ChainType::Origin => self.create_origin_provider()?,
-            ChainType::Destination => self.create_destination_provider()?,
-        };
+        let provider = self.create_chain_provider(chain).await?;
+        let chain_id = self.chain_details(chain)?.chain_id;
         
         // Build transaction request
-        let tx_request = self.build_transaction_request(call_data.clone(), to, gas);
+        let mut tx_request = self.build_transaction_request(call_data.clone(), to, gas);
+        tx_request.nonce = Some(self.reserve_nonce(&*provider, chain_id).await?);
         
         // Log debug information
         self.log_transaction_debug_info(&tx_request, &call_data);
         
         // Send transaction
-        let pending_tx = provider.send_transaction(tx_request.clone()).await
-            .map_err(|e| {
+        let pending_tx = match provider.send_transaction(tx_request.clone()).await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
                 // Enhanced error logging
                 error!("❌ ALLOY EXECUTOR TRANSACTION FAILED:");
                 error!("  Error: {}", e);
                 error!("  Contract address: {:?}", to);
                 error!("  Wallet address: {:?}", self.wallet.default_signer().address());
                 error!("  Call data: 0x{}", hex::encode(&call_data));
-                anyhow::anyhow!("Failed to send transaction: {}", e)
-            })?;
+                self.reset_nonce(chain_id).await;
+                return Err(ExecutionError::from(e).into());
+            }
+        };
         
         info!("⏳ Transaction sent, waiting for confirmation...");
         
         // Wait for transaction receipt
-        let receipt = pending_tx.get_receipt().await
-            .map_err(|e| anyhow::anyhow!("Failed to get transaction receipt: {}", e))?;
+        let receipt = self.await_receipt(&*provider, self.chain_details(chain)?, tx_request, pending_tx).await?;
         
         let tx_hash = format!("0x{}", hex::encode(receipt.transaction_hash));
         
@@ -158,10 +474,17 @@ impl ExecutionEngine for AlloyExecutor {
         
         // Check if transaction was successful
         if !receipt.status() {
+            let reason = replay_revert_reason(&*provider, receipt.transaction_hash, receipt.block_number).await;
             error!("❌ Transaction failed (reverted)");
             error!("  Transaction hash: {}", tx_hash);
             error!("  Block number: {:?}", receipt.block_number);
-            return Err(anyhow::anyhow!("Transaction reverted: {}", tx_hash));
+            error!("  Reason: {}", reason.as_deref().unwrap_or("unknown"));
+            return Err(ExecutionError::Reverted { tx_hash, reason }.into());
+        }
+        
+        if let Some(block) = receipt.block_number {
+            let details = self.chain_details(chain)?;
+            wait_for_confirmations(&*provider, &self.block_heights, details, block, details.confirmations, CONFIRMATION_POLL_INTERVAL).await?;
         }
         
         Ok(tx_hash)
@@ -174,11 +497,8 @@ impl ExecutionEngine for AlloyExecutor {
         info!("  From: {}", from);
         info!("  Call data: {} bytes", call_data.len());
         
-        // Create provider based on specified chain
-        let provider = match chain {
-            ChainType::Origin => self.create_origin_provider()?,
-            ChainType::Destination => self.create_destination_provider()?,
-        };
+        // Reads go to the chain's read endpoint
+        let provider = self.create_chain_read_provider(chain).await?;
         
         // Build call request
         let call_request = TransactionRequest::default()
@@ -193,7 +513,7 @@ impl ExecutionEngine for AlloyExecutor {
                 error!("  Error: {}", e);
                 error!("  To: {}", to);
                 error!("  From: {}", from);
-                anyhow::anyhow!("Static call failed: {}", e)
+                ExecutionError::from(e)
             })?;
         
         info!("✅ Static call successful: {} bytes returned", result.len());
@@ -208,11 +528,8 @@ impl ExecutionEngine for AlloyExecutor {
         info!("  From: {}", from);
         info!("  Call data: {} bytes", call_data.len());
         
-        // Create provider based on specified chain
-        let provider = match chain {
-            ChainType::Origin => self.create_origin_provider()?,
-            ChainType::Destination => self.create_destination_provider()?,
-        };
+        // Reads go to the chain's read endpoint
+        let provider = self.create_chain_read_provider(chain).await?;
         
         // Build estimation request
         let estimation_request = TransactionRequest::default()
@@ -237,6 +554,130 @@ impl ExecutionEngine for AlloyExecutor {
         Ok(gas_u64)
     }
     
+    async fn sign_transaction(&self, chain: ChainType, call_data: Vec<u8>, to: Address, gas: GasParams) -> Result<SignedTransaction> {
+        let provider = self.create_chain_provider(chain).await?;
+        let chain_id = self.chain_details(chain)?.chain_id;
+        
+        let from = self.wallet.default_signer().address();
+        let nonce = self.reserve_nonce(&*provider, chain_id).await?;
+        
+        let tx_request = self.build_transaction_request(call_data, to, gas)
+            .with_from(from)
+            .with_nonce(nonce)
+            .with_chain_id(chain_id);
+        
+        let envelope = tx_request.build(&self.wallet).await
+            .map_err(|e| anyhow::anyhow!("Failed to sign transaction: {}", e))?;
+        
+        let tx_hash = format!("0x{}", hex::encode(envelope.tx_hash()));
+        info!("✍️ Signed transaction {} with nonce {}", tx_hash, nonce);
+        
+        Ok(SignedTransaction {
+            nonce,
+            tx_hash,
+            raw: envelope.encoded_2718(),
+        })
+    }
+    
+    async fn broadcast_signed(&self, chain: ChainType, tx: &SignedTransaction) -> Result<String> {
+        info!("🚀 AlloyExecutor: Broadcasting signed transaction {}", tx.tx_hash);
+        
+        let provider = self.create_chain_provider(chain).await?;
+        
+        let pending_tx = match provider.send_raw_transaction(&tx.raw).await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                self.reset_nonce(self.chain_details(chain)?.chain_id).await;
+                warn!("Failed to broadcast transaction {}: {}", tx.tx_hash, e);
+                return Err(ExecutionError::from(e).into());
+            }
+        };
+        
+        info!("⏳ Transaction sent, waiting for confirmation...");
+        
+        let receipt = pending_tx.get_receipt().await
+            .map_err(ExecutionError::from)?;
+        
+        let tx_hash = format!("0x{}", hex::encode(receipt.transaction_hash));
+        if !receipt.status() {
+            let reason = replay_revert_reason(&*provider, receipt.transaction_hash, receipt.block_number).await;
+            error!("❌ Transaction failed (reverted): {} ({})", tx_hash, reason.as_deref().unwrap_or("unknown reason"));
+            return Err(ExecutionError::Reverted { tx_hash, reason }.into());
+        }
+        
+        info!("✅ Transaction confirmed: {}", tx_hash);
+        Ok(tx_hash)
+    }
+    
+    async fn find_transaction(&self, chain: ChainType, tx_hash: &str) -> Result<Option<String>> {
+        let provider = self.create_chain_provider(chain).await?;
+        
+        let hash: TxHash = tx_hash.parse()
+            .map_err(|e| anyhow::anyhow!("Invalid transaction hash {}: {}", tx_hash, e))?;
+        
+        if provider.get_transaction_by_hash(hash).await?.is_none() {
+            return Ok(None);
+        }
+        
+        info!("⏳ Transaction {} already known to the node, waiting for confirmation...", tx_hash);
+        let receipt = PendingTransactionBuilder::new(provider.root().clone(), hash).get_receipt().await
+            .map_err(|e| anyhow::anyhow!("Failed to get transaction receipt: {}", e))?;
+        
+        if !receipt.status() {
+            return Err(anyhow::anyhow!("Transaction reverted: {}", tx_hash));
+        }
+        
+        Ok(Some(tx_hash.to_string()))
+    }
+    
+    async fn get_receipt(&self, chain: ChainType, tx_hash: &str) -> Result<TransactionReceiptInfo> {
+        let provider = self.create_chain_read_provider(chain).await?;
+        
+        let hash: TxHash = tx_hash.parse()
+            .map_err(|e| anyhow::anyhow!("Invalid transaction hash {}: {}", tx_hash, e))?;
+        
+        let receipt = provider.get_transaction_receipt(hash).await?
+            .ok_or_else(|| anyhow::anyhow!("No receipt for transaction {}", tx_hash))?;
+        
+        let logs = receipt.inner.logs().iter()
+            .map(|log| ReceiptLog {
+                address: log.address(),
+                topics: log.topics().to_vec(),
+                data: log.data().data.to_vec(),
+            })
+            .collect();
+        
+        Ok(TransactionReceiptInfo {
+            tx_hash: tx_hash.to_string(),
+            status: receipt.status(),
+            gas_used: receipt.gas_used,
+            effective_gas_price: receipt.effective_gas_price,
+            logs,
+        })
+    }
+    
+    async fn get_gas_price(&self, chain: ChainType) -> Result<u128> {
+        let provider = self.create_chain_provider(chain).await?;
+        provider.get_gas_price().await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch gas price on {:?} chain: {}", chain, e))
+    }
+
+    async fn get_balance(&self, chain: ChainType) -> Result<U256> {
+        let provider = self.create_chain_provider(chain).await?;
+        
+        let balance = provider.get_balance(self.wallet.default_signer().address()).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch balance on {:?} chain: {}", chain, e))?;
+        
+        Ok(balance)
+    }
+
+    async fn get_code(&self, chain: ChainType, address: Address) -> Result<Vec<u8>> {
+        let provider = self.create_chain_read_provider(chain).await?;
+        let code = provider.get_code_at(address).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch code of {} on {:?} chain: {}", address, chain, e))?;
+        Ok(code.to_vec())
+    }
+    
     fn wallet_address(&self) -> Address {
         self.wallet.default_signer().address()
     }
@@ -258,21 +699,27 @@ impl AlloyExecutor {
         info!("🚀 AlloyExecutor: Sending transaction to chain {}", chain_id);
         
         // Create provider for specific chain
-        let provider = self.create_provider_for_chain(chain_id)?;
+        let provider = self.create_provider_for_chain(chain_id).await?;
         
         // Build transaction request
-        let tx_request = self.build_transaction_request(call_data.clone(), to, gas);
+        let mut tx_request = self.build_transaction_request(call_data.clone(), to, gas);
+        tx_request.nonce = Some(self.reserve_nonce(&*provider, chain_id).await?);
         
         // Log debug information
         self.log_transaction_debug_info(&tx_request, &call_data);
         
         // Send transaction
-        let pending_tx = provider.send_transaction(tx_request).await
-            .map_err(|e| anyhow::anyhow!("Failed to send transaction to chain {}: {}", chain_id, e))?;
+        let pending_tx = match provider.send_transaction(tx_request.clone()).await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                self.reset_nonce(chain_id).await;
+                warn!("Failed to send transaction to chain {}: {}", chain_id, e);
+                return Err(ExecutionError::from(e).into());
+            }
+        };
         
         // Wait for confirmation
-        let receipt = pending_tx.get_receipt().await
-            .map_err(|e| anyhow::anyhow!("Failed to get receipt on chain {}: {}", chain_id, e))?;
+        let receipt = self.await_receipt(&*provider, self.chain_details(ChainType::ById(chain_id))?, tx_request, pending_tx).await?;
         
         let tx_hash = format!("0x{}", hex::encode(receipt.transaction_hash));
         
@@ -281,7 +728,14 @@ impl AlloyExecutor {
         info!("  Status: {:?}", receipt.status());
         
         if !receipt.status() {
-            return Err(anyhow::anyhow!("Transaction reverted on chain {}: {}", chain_id, tx_hash));
+            let reason = replay_revert_reason(&*provider, receipt.transaction_hash, receipt.block_number).await;
+            error!("❌ Transaction reverted on chain {}: {} ({})", chain_id, tx_hash, reason.as_deref().unwrap_or("unknown reason"));
+            return Err(ExecutionError::Reverted { tx_hash, reason }.into());
+        }
+        
+        if let Some(block) = receipt.block_number {
+            let details = self.chain_details(ChainType::ById(chain_id))?;
+            wait_for_confirmations(&*provider, &self.block_heights, details, block, details.confirmations, CONFIRMATION_POLL_INTERVAL).await?;
         }
         
         Ok(tx_hash)
@@ -294,31 +748,77 @@ impl AlloyExecutor {
     
     /// Get current chain ID for origin chain
     pub async fn get_origin_chain_id(&self) -> Result<u64> {
-        let provider = self.create_origin_provider()?;
+        let provider = self.create_origin_provider().await?;
         let chain_id = provider.get_chain_id().await?;
         Ok(chain_id)
     }
     
     /// Get current chain ID for destination chain
     pub async fn get_destination_chain_id(&self) -> Result<u64> {
-        let provider = self.create_destination_provider()?;
+        let provider = self.create_destination_provider().await?;
         let chain_id = provider.get_chain_id().await?;
         Ok(chain_id)
     }
     
+    /// Check that every configured RPC endpoint reports the `chain_id` its chain is
+    /// configured with, so transactions are never signed for the wrong chain
+    ///
+    /// A mismatch is an error unless `chains.warn_on_chain_id_mismatch` is set;
+    /// endpoints that do not answer are only logged, as failover may cover them.
+    pub async fn verify_chain_ids(&self) -> Result<()> {
+        let chains = &self.config.chains;
+        let mut configured: std::collections::BTreeMap<u64, &ChainDetails> =
+            chains.registry.iter().map(|(chain_id, chain)| (*chain_id, chain)).collect();
+        for chain in [&chains.origin, &chains.destination] {
+            configured.insert(chain.chain_id, chain);
+        }
+        
+        let mut mismatches = Vec::new();
+        for chain in configured.values() {
+            let endpoints = chain.rpc_url.iter().chain(chain.read_rpc_url.as_deref());
+            for rpc_url in endpoints {
+                match rpc_chain_id(rpc_url, chain.rpc_timeout(), chains.log_rpc_requests).await {
+                    Ok(chain_id) if chain_id == chain.chain_id => {}
+                    Ok(chain_id) => mismatches.push(format!(
+                        "{} reports chain id {} but is configured as chain {}", rpc_url, chain_id, chain.chain_id
+                    )),
+                    Err(e) => warn!("Could not check the chain id of {}: {}", rpc_url, e),
+                }
+            }
+        }
+        
+        if mismatches.is_empty() {
+            info!("✅ RPC chain ids match the configuration");
+            return Ok(());
+        }
+        if chains.warn_on_chain_id_mismatch {
+            for mismatch in &mismatches {
+                warn!("⚠️ Chain id mismatch: {}", mismatch);
+            }
+            return Ok(());
+        }
+        Err(anyhow::anyhow!("Chain id mismatch: {}", mismatches.join("; ")))
+    }
+    
     /// Verify chain connectivity
     pub async fn verify_chain_connectivity(&self) -> Result<()> {
         info!("🔗 Verifying chain connectivity...");
         
         // Test origin chain
-        let origin_provider = self.create_origin_provider()?;
-        let origin_block = origin_provider.get_block_number().await
-            .map_err(|e| anyhow::anyhow!("Failed to connect to origin chain: {}", e))?;
+        let origin = &self.config.chains.origin;
+        let origin_provider = self.create_origin_provider().await?;
+        let origin_block = self.block_heights.height(origin.chain_id, origin.block_height_ttl(), || async {
+            origin_provider.get_block_number().await
+                .map_err(|e| anyhow::anyhow!("Failed to connect to origin chain: {}", e))
+        }).await?;
         
         // Test destination chain  
-        let dest_provider = self.create_destination_provider()?;
-        let dest_block = dest_provider.get_block_number().await
-            .map_err(|e| anyhow::anyhow!("Failed to connect to destination chain: {}", e))?;
+        let destination = &self.config.chains.destination;
+        let dest_provider = self.create_destination_provider().await?;
+        let dest_block = self.block_heights.height(destination.chain_id, destination.block_height_ttl(), || async {
+            dest_provider.get_block_number().await
+                .map_err(|e| anyhow::anyhow!("Failed to connect to destination chain: {}", e))
+        }).await?;
         
         info!("✅ Chain connectivity verified:");
         info!("  Origin chain {} block: {}", self.config.chains.origin.chain_id, origin_block);
@@ -340,34 +840,99 @@ mod tests {
             server: ServerConfig {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
+                log_format: Default::default(),
+                shutdown_grace_seconds: 30,
+                rate_limit: Default::default(),
+                admin_token: None,
             },
             chains: ChainConfig {
                 origin: ChainDetails {
-                    rpc_url: "http://localhost:8545".to_string(),
+                    rpc_url: "http://localhost:8545".into(),
+                    read_rpc_url: None,
                     chain_id: 31337,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
                 destination: ChainDetails {
-                    rpc_url: "http://localhost:8546".to_string(),
+                    rpc_url: "http://localhost:8546".into(),
+                    read_rpc_url: None,
                     chain_id: 31338,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
+                registry: Default::default(),
+                warn_on_chain_id_mismatch: false,
+                log_rpc_requests: false,
             },
             solver: SolverConfig {
                 private_key: "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string(),
                 finalization_delay_seconds: 30,
+                allowed_input_tokens: Vec::new(),
+                denied_input_tokens: Vec::new(),
+                allowed_users: Vec::new(),
+                denied_users: Vec::new(),
+                allowed_oracles: Vec::new(),
+                dry_run: false,
+                production: false,
+                min_profit_margin_bps: None,
+                token_prices: std::collections::HashMap::new(),
+                fill_amount_tolerance_bps: 0,
+                max_fill_retries: 3,
+                retry_backoff_seconds: vec![30, 120, 600],
+                skip_fill_proof_check: false,
+                max_inputs: None,
+                max_outputs: None,
+                order_id_mode: Default::default(),
+                simulate_finalization: true,
+                simulate_fill: true,
+                signature_scheme: Default::default(),
+                name: None,
             },
             contracts: ContractConfig {
                 the_compact: "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0".to_string(),
                 settler_compact: "0x5FC8d32690cc91D4c39d9d3abcBD16989F875707".to_string(),
                 coin_filler: "0xCf7Ed3AccA5a467e9e704C703E8D87F634fB0Fc9".to_string(),
+                fill_selector: None,
+                finalise_selector: None,
+                multicall: None,
+                abi_dir: None,
+                verify_domain_separator: Default::default(),
             },
             monitoring: MonitoringConfig {
                 enabled: true,
                 check_interval_seconds: 60,
+                pause_unhealthy_chains: true,
+                max_concurrent_operations: 4,
+                max_batch_finalize: 500,
+                batch_chunk_size: 50,
             },
             persistence: PersistenceConfig {
                 enabled: true,
                 data_file: "data/orders.json".to_string(),
+                backend: Default::default(),
+                database_url: None,
+                tx_journal_file: None,
+                max_orders_in_memory: None,
+                archive_file: None,
+                retention_seconds: None,
             },
+            coordinator: crate::config::CoordinatorConfig::default(),
+            balance: crate::config::BalanceConfig::default(),
+            gas: crate::config::GasConfig::default(),
+            backpressure: crate::config::BackpressureConfig::default(),
+            encoder: crate::config::EncoderConfig::default(),
+            callbacks: crate::config::CallbackConfig::default(),
+            allowances: Default::default(),
         })
     }
 
@@ -389,11 +954,53 @@ mod tests {
         println!("   Wallet address: {}", executor.wallet_address());
     }
 
+    #[tokio::test]
+    async fn test_chain_lookup_uses_registry() {
+        let mut config = (*create_test_config()).clone();
+        config.chains.registry.insert(31339, ChainDetails {
+            rpc_url: "http://localhost:8547".into(),
+            read_rpc_url: None,
+            chain_id: 31339,
+            max_gas_price_wei: None,
+            gas: Default::default(),
+            confirmations: 0,
+            rpc_timeout_seconds: 30,
+            block_height_ttl_ms: 2000,
+            coin_filler: None,
+            settler_compact: None,
+        });
+        config.chains.build_registry().unwrap();
+        let executor = AlloyExecutor::new(Arc::new(config)).expect("Executor creation");
+
+        assert_eq!(executor.chain_details(ChainType::ById(31339)).unwrap().rpc_url, "http://localhost:8547");
+        assert_eq!(executor.chain_details(ChainType::ById(31338)).unwrap().rpc_url, "http://localhost:8546");
+        assert_eq!(executor.chain_details(ChainType::Origin).unwrap().chain_id, 31337);
+        assert!(executor.create_provider_for_chain(31339).await.is_ok());
+        assert!(executor.create_provider_for_chain(1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_provider_transport_follows_url_scheme() {
+        assert_eq!(RpcTransport::for_url("http://localhost:8545").unwrap(), RpcTransport::Http);
+        assert_eq!(RpcTransport::for_url("https://rpc.example.org").unwrap(), RpcTransport::Http);
+        assert_eq!(RpcTransport::for_url("ws://localhost:8545").unwrap(), RpcTransport::Ws);
+        assert_eq!(RpcTransport::for_url("wss://rpc.example.org/ws").unwrap(), RpcTransport::Ws);
+        assert!(RpcTransport::for_url("ftp://localhost").is_err());
+        assert!(RpcTransport::for_url("not a url").is_err());
+
+        // The WebSocket branch dials the endpoint, so a dead ws:// URL fails where HTTP stays lazy
+        let executor = AlloyExecutor::new(create_test_config()).expect("Executor creation");
+        assert!(executor.create_provider(&["http://127.0.0.1:1"], Duration::from_secs(30)).await.is_ok());
+        let error = executor.create_provider(&["ws://127.0.0.1:1"], Duration::from_secs(30)).await.err().expect("ws connect fails");
+        assert!(matches!(error.downcast_ref::<ExecutionError>(), Some(ExecutionError::RpcUnreachable(_))), "{}", error);
+    }
+
     #[test]
     fn test_gas_params_creation() {
         let gas_params = GasParams {
             gas_limit: 650000,
             gas_price: 1178761408,
+            value: U256::ZERO,
         };
         
         assert_eq!(gas_params.gas_limit, 650000);
@@ -413,6 +1020,7 @@ mod tests {
         let gas_params = GasParams {
             gas_limit: 650000,
             gas_price: 1178761408,
+            value: U256::ZERO,
         };
         
         let tx_request = executor.build_transaction_request(call_data.clone(), to_address, gas_params);
@@ -431,6 +1039,12 @@ mod tests {
         
         assert_eq!(tx_request.gas, Some(650000u64.into()));
         assert_eq!(tx_request.gas_price, Some(1178761408u64.into()));
+        assert_eq!(tx_request.value, None);
+        
+        let value = U256::from(10u64).pow(U256::from(18u64));
+        let gas_params = GasParams { gas_limit: 650000, gas_price: 1178761408, value };
+        let tx_request = executor.build_transaction_request(call_data.clone(), to_address, gas_params);
+        assert_eq!(tx_request.value, Some(value));
         
         // Verify input data is set (structure validation)
         // Note: TransactionInput structure verification - actual data comparison would require more complex logic
@@ -440,4 +1054,270 @@ mod tests {
 
     // Note: Integration tests that actually connect to blockchain would require running test nodes
     // These basic tests verify the structure and configuration without network calls
-} 
\ No newline at end of file
+
+    /// JSON-RPC node recording the methods it is asked for; rejects raw
+    /// transactions so sends fail fast instead of waiting for a receipt
+    fn spawn_recording_rpc() -> (String, Arc<std::sync::Mutex<Vec<String>>>, actix_web::dev::ServerHandle) {
+        use actix_web::{web, App, HttpResponse, HttpServer};
+
+        let methods = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = methods.clone();
+        let server = HttpServer::new(move || {
+            let recorded = recorded.clone();
+            App::new().default_service(web::to(move |body: web::Json<serde_json::Value>| {
+                let recorded = recorded.clone();
+                async move {
+                    let method = body["method"].as_str().unwrap_or_default().to_string();
+                    recorded.lock().unwrap().push(method.clone());
+                    let reply = match method.as_str() {
+                        "eth_sendRawTransaction" => serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "id": body["id"],
+                            "error": { "code": -32000, "message": "rejected by test node" },
+                        }),
+                        "eth_call" => serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "result": "0x" }),
+                        _ => serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "result": "0x1" }),
+                    };
+                    HttpResponse::Ok().json(reply)
+                }
+            }))
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let url = format!("http://127.0.0.1:{}", server.addrs()[0].port());
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+        (url, methods, handle)
+    }
+
+    #[actix_web::test]
+    async fn test_reads_and_writes_use_separate_endpoints() {
+        let (write_url, write_methods, write_handle) = spawn_recording_rpc();
+        let (read_url, read_methods, read_handle) = spawn_recording_rpc();
+
+        let mut config = (*create_test_config()).clone();
+        config.chains.destination.rpc_url = write_url.into();
+        config.chains.destination.read_rpc_url = Some(read_url);
+        let executor = AlloyExecutor::new(Arc::new(config))
+            .unwrap()
+            .with_nonce_manager(Arc::new(NonceManager::new()));
+        let to = Address::from([0x44; 20]);
+
+        executor.static_call(ChainType::Destination, vec![0x01], to, executor.wallet_address()).await.unwrap();
+        assert!(read_methods.lock().unwrap().contains(&"eth_call".to_string()));
+        assert!(write_methods.lock().unwrap().is_empty());
+
+        let gas = GasParams { gas_limit: 100_000, gas_price: 1_000_000_000, value: U256::ZERO };
+        assert!(executor.send_transaction(ChainType::Destination, vec![0x01], to, gas).await.is_err());
+        assert!(write_methods.lock().unwrap().contains(&"eth_sendRawTransaction".to_string()));
+        assert!(!read_methods.lock().unwrap().contains(&"eth_sendRawTransaction".to_string()));
+
+        // Without a read endpoint everything goes to rpc_url
+        assert_eq!(create_test_config().chains.origin.read_rpc_url(), "http://localhost:8545");
+
+        write_handle.stop(true).await;
+        read_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_fails_over_to_next_rpc_endpoint() {
+        let (live_url, live_methods, live_handle) = spawn_recording_rpc();
+        let (backup_url, backup_methods, backup_handle) = spawn_recording_rpc();
+
+        // Nothing listens on port 1, so the first endpoint refuses the connection
+        let mut config = (*create_test_config()).clone();
+        config.chains.destination.rpc_url = vec!["http://127.0.0.1:1".to_string(), live_url, backup_url].try_into().unwrap();
+        let executor = AlloyExecutor::new(Arc::new(config)).unwrap();
+
+        executor.static_call(ChainType::Destination, vec![0x01], Address::from([0x44; 20]), executor.wallet_address()).await.unwrap();
+        let live_methods = live_methods.lock().unwrap().clone();
+        assert_eq!(live_methods, vec!["eth_blockNumber".to_string(), "eth_call".to_string()]);
+        assert!(backup_methods.lock().unwrap().is_empty(), "stopped at the first endpoint that answered");
+
+        live_handle.stop(true).await;
+        backup_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_rpc_requests_are_logged_when_enabled() {
+        use crate::config::LogFormat;
+        use crate::logging::{capture::Buffer, format_layer};
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(format_layer(LogFormat::Json, move || writer.clone()));
+        let _guard = tracing::subscriber::set_default(subscriber);
+        let (url, _, handle) = spawn_recording_rpc();
+        let logged_calls = || buffer.json_lines().into_iter().filter(|line| line["message"] == "RPC call").collect::<Vec<_>>();
+
+        let client = connect_rpc_client(&[url.as_str()], Duration::from_secs(5), false).await.unwrap();
+        client.request_noparams::<alloy::primitives::U64>("eth_chainId").await.unwrap();
+        assert!(logged_calls().is_empty(), "logging is off by default");
+
+        let client = connect_rpc_client(&[url.as_str()], Duration::from_secs(5), true).await.unwrap();
+        client.request_noparams::<alloy::primitives::U64>("eth_chainId").await.unwrap();
+        let methods = logged_calls().iter().map(|line| line.to_string()).collect::<Vec<_>>();
+        assert!(methods.iter().any(|line| line.contains("eth_chainId")), "{:?}", methods);
+
+        handle.stop(true).await;
+    }
+
+    /// JSON-RPC node that accepts raw transactions but only ever mines the
+    /// second one it is sent; returns the raw transactions it received
+    fn spawn_stuck_rpc() -> (String, Arc<std::sync::Mutex<Vec<Vec<u8>>>>, actix_web::dev::ServerHandle) {
+        use actix_web::{web, App, HttpResponse, HttpServer};
+
+        let raw_txs = Arc::new(std::sync::Mutex::new(Vec::<Vec<u8>>::new()));
+        let received = raw_txs.clone();
+        let server = HttpServer::new(move || {
+            let received = received.clone();
+            App::new().default_service(web::to(move |body: web::Json<serde_json::Value>| {
+                let received = received.clone();
+                async move {
+                    let result = match body["method"].as_str().unwrap_or_default() {
+                        "eth_sendRawTransaction" => {
+                            let raw = hex::decode(body["params"][0].as_str().unwrap().trim_start_matches("0x")).unwrap();
+                            let hash = alloy::primitives::keccak256(&raw);
+                            received.lock().unwrap().push(raw);
+                            serde_json::json!(hash)
+                        }
+                        "eth_getTransactionReceipt" => {
+                            let mined = received.lock().unwrap().get(1).map(alloy::primitives::keccak256);
+                            let hash: TxHash = body["params"][0].as_str().unwrap().parse().unwrap();
+                            match mined {
+                                Some(mined) if mined == hash => serde_json::json!({
+                                    "transactionHash": hash,
+                                    "transactionIndex": "0x0",
+                                    "blockHash": format!("0x{}", "11".repeat(32)),
+                                    "blockNumber": "0x10",
+                                    "from": Address::from([0x11; 20]),
+                                    "to": Address::from([0x44; 20]),
+                                    "cumulativeGasUsed": "0x5208",
+                                    "gasUsed": "0x5208",
+                                    "effectiveGasPrice": "0x1",
+                                    "contractAddress": null,
+                                    "logs": [],
+                                    "logsBloom": format!("0x{}", "00".repeat(256)),
+                                    "status": "0x1",
+                                    "type": "0x0",
+                                }),
+                                _ => serde_json::Value::Null,
+                            }
+                        }
+                        _ => serde_json::json!("0x1"),
+                    };
+                    HttpResponse::Ok().json(serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+                }
+            }))
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let url = format!("http://127.0.0.1:{}", server.addrs()[0].port());
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+        (url, raw_txs, handle)
+    }
+
+    #[actix_web::test]
+    async fn test_stuck_transaction_is_replaced_with_same_nonce() {
+        use alloy::consensus::{Transaction, TxEnvelope};
+        use alloy::eips::eip2718::Decodable2718;
+
+        let (url, raw_txs, handle) = spawn_stuck_rpc();
+        let mut config = (*create_test_config()).clone();
+        config.chains.destination.rpc_url = url.into();
+        config.gas.stuck_tx_timeout_seconds = Some(1);
+        config.gas.max_gas_bumps = 2;
+        let executor = AlloyExecutor::new(Arc::new(config))
+            .unwrap()
+            .with_nonce_manager(Arc::new(NonceManager::new()));
+
+        let gas = GasParams { gas_limit: 100_000, gas_price: 1_000_000_000, value: U256::ZERO };
+        let tx_hash = executor.send_transaction(ChainType::Destination, vec![0x01], Address::from([0x44; 20]), gas).await.unwrap();
+
+        let sent: Vec<TxEnvelope> = raw_txs.lock().unwrap().iter()
+            .map(|raw| TxEnvelope::decode_2718(&mut raw.as_slice()).unwrap())
+            .collect();
+        assert_eq!(sent.len(), 2, "one replacement was enough");
+        assert_eq!(sent[1].nonce(), sent[0].nonce());
+        assert_eq!(sent[0].gas_price(), Some(1_000_000_000));
+        assert_eq!(sent[1].gas_price(), Some(1_100_000_000));
+        assert_eq!(tx_hash, sent[1].tx_hash().to_string());
+
+        assert_eq!(bumped_gas_price(15), 17);
+        assert_eq!(bumped_gas_price(0), 1);
+
+        handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_chain_id_mismatch_is_caught() {
+        // The test node reports chain id 1
+        let (url, _, handle) = spawn_recording_rpc();
+        let mut config = (*create_test_config()).clone();
+        config.chains.origin.rpc_url = url.clone().into();
+        config.chains.origin.chain_id = 1;
+        config.chains.destination.rpc_url = url.into();
+
+        let executor = AlloyExecutor::new(Arc::new(config.clone())).unwrap();
+        let error = executor.verify_chain_ids().await.unwrap_err().to_string();
+        assert!(error.contains("reports chain id 1 but is configured as chain 31338"), "{}", error);
+
+        config.chains.warn_on_chain_id_mismatch = true;
+        assert!(AlloyExecutor::new(Arc::new(config.clone())).unwrap().verify_chain_ids().await.is_ok());
+
+        config.chains.warn_on_chain_id_mismatch = false;
+        config.chains.destination.chain_id = 1;
+        assert!(AlloyExecutor::new(Arc::new(config)).unwrap().verify_chain_ids().await.is_ok());
+
+        handle.stop(true).await;
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_rpc_times_out() {
+        // Nothing answers from this non-routable address, so without a timeout the call would hang
+        let mut config = create_test_config().as_ref().clone();
+        config.chains.origin.rpc_url = "http://10.255.255.1:8545".into();
+        config.chains.origin.rpc_timeout_seconds = 1;
+        let executor = AlloyExecutor::new(Arc::new(config)).expect("Executor creation");
+
+        let started = std::time::Instant::now();
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            executor.static_call(ChainType::Origin, Vec::new(), Address::ZERO, Address::ZERO),
+        ).await.expect("the RPC timeout fires before the test's own");
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_configured_confirmation_depth() {
+        use alloy::transports::mock::Asserter;
+
+        // Each poll of the chain head sees one more block
+        let asserter = Asserter::new();
+        for head in [100u64, 101, 102, 103] {
+            asserter.push_success(&format!("0x{:x}", head));
+        }
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+        // Every poll goes to the chain when heights are never reused
+        let mut chain = create_test_config().chains.origin.clone();
+        chain.block_height_ttl_ms = 0;
+        let heights = BlockHeightCache::new();
+
+        let head = wait_for_confirmations(&provider, &heights, &chain, 100, 3, Duration::from_millis(1)).await.unwrap();
+        assert_eq!(head, 103);
+        assert!(asserter.pop_response().is_none(), "stopped polling once deep enough");
+
+        // No confirmations: the receipt is final and the head is not queried
+        let head = wait_for_confirmations(&provider, &heights, &chain, 100, 0, Duration::from_millis(1)).await.unwrap();
+        assert_eq!(head, 100);
+    }
+}
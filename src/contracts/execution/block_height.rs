@@ -0,0 +1,99 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Last block height read from one chain and when, `None` until first fetched
+type CachedHeight = Arc<RwLock<Option<(u64, Instant)>>>;
+
+/// Short-lived per-chain cache of the chain head, so connectivity checks and
+/// confirmation waits running side by side share one `eth_blockNumber` call
+///
+/// A height younger than the caller's TTL is reused; otherwise the first caller
+/// refetches it while the others wait on the chain's lock and take its result.
+#[derive(Debug, Default)]
+pub struct BlockHeightCache {
+    chains: Mutex<HashMap<u64, CachedHeight>>,
+}
+
+impl BlockHeightCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cache shared by every factory and executor in the process
+    pub fn global() -> Arc<Self> {
+        static GLOBAL: OnceLock<Arc<BlockHeightCache>> = OnceLock::new();
+        GLOBAL.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    fn chain(&self, chain_id: u64) -> CachedHeight {
+        self.chains.lock().unwrap().entry(chain_id).or_default().clone()
+    }
+
+    /// Block height of `chain_id`, calling `fetch` only when the cached one is older than `ttl`
+    pub async fn height<F, Fut>(&self, chain_id: u64, ttl: Duration, fetch: F) -> Result<u64>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<u64>>,
+    {
+        let chain = self.chain(chain_id);
+        if let Some((height, fetched_at)) = *chain.read().await {
+            if fetched_at.elapsed() < ttl {
+                return Ok(height);
+            }
+        }
+
+        let mut cached = chain.write().await;
+        // Another caller may have refreshed it while we waited for the lock
+        if let Some((height, fetched_at)) = *cached {
+            if fetched_at.elapsed() < ttl {
+                return Ok(height);
+            }
+        }
+        let height = fetch().await?;
+        *cached = Some((height, Instant::now()));
+        Ok(height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_rapid_calls_share_one_fetch() {
+        let cache = Arc::new(BlockHeightCache::new());
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let ttl = Duration::from_secs(2);
+
+        let calls: Vec<_> = (0..2).map(|_| {
+            let cache = cache.clone();
+            let fetches = fetches.clone();
+            tokio::spawn(async move {
+                cache
+                    .height(31337, ttl, || async move {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        tokio::task::yield_now().await;
+                        Ok(100)
+                    })
+                    .await
+                    .unwrap()
+            })
+        }).collect();
+        for call in calls {
+            assert_eq!(call.await.unwrap(), 100);
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1, "block height is fetched once");
+
+        // Other chains are cached separately
+        assert_eq!(cache.height(31338, ttl, || async { Ok(7) }).await.unwrap(), 7);
+
+        // Once the height is older than the TTL it is fetched again
+        assert_eq!(cache.height(31337, Duration::ZERO, || async { Ok(101) }).await.unwrap(), 101);
+        assert_eq!(cache.height(31337, ttl, || async { Ok(0) }).await.unwrap(), 101);
+    }
+}
@@ -0,0 +1,195 @@
+use alloy::providers::{PendingTransactionError, WatchTxError};
+use alloy::transports::{RpcError, TransportError, TransportErrorKind};
+
+/// Why a transaction could not be executed, so callers can tell transient
+/// failures (worth retrying) from final ones without matching on messages
+///
+/// Executors return it inside `anyhow::Error`; recover it with
+/// `error.downcast_ref::<ExecutionError>()` or use `is_retryable`.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ExecutionError {
+    /// Mined, but the transaction reverted; `reason` is decoded from a replay when available
+    #[error("Transaction reverted: {tx_hash}{}", reason.as_ref().map(|r| format!(" ({})", r)).unwrap_or_default())]
+    Reverted { tx_hash: String, reason: Option<String> },
+
+    /// The RPC endpoint could not be reached or answered with an HTTP error
+    #[error("RPC endpoint unreachable: {0}")]
+    RpcUnreachable(String),
+
+    /// The node already has a transaction with this nonce
+    #[error("Nonce too low: {0}")]
+    NonceTooLow(String),
+
+    /// The wallet cannot pay for value plus gas
+    #[error("Insufficient funds: {0}")]
+    InsufficientFunds(String),
+
+    /// No answer or receipt within the allowed time
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    /// A call or gas estimate reverted; `data` is the raw revert data
+    #[error("Call reverted: {}", revert_reason(data))]
+    CallReverted { data: Vec<u8> },
+
+    /// The node refused the transaction with a JSON-RPC error
+    #[error("Transaction rejected ({code}): {message}")]
+    Rejected { code: i64, message: String },
+
+    /// Anything else, e.g. local signing or (de)serialization failures
+    #[error("Execution failed: {0}")]
+    Other(String),
+}
+
+impl ExecutionError {
+    /// Whether the same transaction may succeed if attempted again later
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::RpcUnreachable(_) | Self::Timeout(_) | Self::NonceTooLow(_))
+    }
+
+    /// Classify a JSON-RPC error response by its message
+    fn from_error_response(code: i64, message: &str) -> Self {
+        let lowered = message.to_lowercase();
+        if lowered.contains("nonce too low") {
+            Self::NonceTooLow(message.to_string())
+        } else if lowered.contains("insufficient funds") {
+            Self::InsufficientFunds(message.to_string())
+        } else {
+            Self::Rejected { code, message: message.to_string() }
+        }
+    }
+}
+
+impl From<&TransportError> for ExecutionError {
+    fn from(error: &TransportError) -> Self {
+        match error {
+            RpcError::ErrorResp(payload) => match payload.as_revert_data() {
+                Some(data) => Self::CallReverted { data: data.to_vec() },
+                None => Self::from_error_response(payload.code, &payload.message),
+            },
+            RpcError::Transport(TransportErrorKind::Custom(inner)) => match inner.downcast_ref::<reqwest::Error>() {
+                Some(e) if e.is_timeout() => Self::Timeout(e.to_string()),
+                _ => Self::RpcUnreachable(inner.to_string()),
+            },
+            RpcError::Transport(kind) => Self::RpcUnreachable(kind.to_string()),
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<TransportError> for ExecutionError {
+    fn from(error: TransportError) -> Self {
+        Self::from(&error)
+    }
+}
+
+impl From<PendingTransactionError> for ExecutionError {
+    fn from(error: PendingTransactionError) -> Self {
+        match error {
+            PendingTransactionError::TransportError(e) => Self::from(e),
+            PendingTransactionError::TxWatcher(WatchTxError::Timeout) => {
+                Self::Timeout("transaction was not confirmed in time".to_string())
+            }
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+/// Human-readable revert reason: the message of an `Error(string)` or `Panic(uint256)`
+/// revert, otherwise the raw data in hex
+pub fn revert_reason(data: &[u8]) -> String {
+    if data.is_empty() {
+        return "no revert data".to_string();
+    }
+    use alloy::sol_types::{Revert, SolError};
+    Revert::abi_decode(data)
+        .map(|revert| revert.reason)
+        .ok()
+        .or_else(|| alloy::sol_types::decode_revert_reason(data))
+        .unwrap_or_else(|| format!("0x{}", alloy::hex::encode(data)))
+}
+
+/// Whether `error` carries an `ExecutionError` that is worth retrying
+pub fn is_retryable(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<ExecutionError>().is_some_and(ExecutionError::is_retryable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_response(code: i64, message: &str) -> TransportError {
+        let payload = serde_json::json!({ "code": code, "message": message });
+        RpcError::ErrorResp(serde_json::from_value(payload).unwrap())
+    }
+
+    #[test]
+    fn test_node_error_responses() {
+        assert!(matches!(
+            ExecutionError::from(error_response(-32000, "nonce too low: next nonce 7, tx nonce 6")),
+            ExecutionError::NonceTooLow(_)
+        ));
+        assert!(matches!(
+            ExecutionError::from(error_response(-32000, "insufficient funds for gas * price + value")),
+            ExecutionError::InsufficientFunds(_)
+        ));
+        assert_eq!(
+            ExecutionError::from(error_response(3, "execution reverted")),
+            ExecutionError::Rejected { code: 3, message: "execution reverted".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_revert_data_is_decoded() {
+        use alloy::sol_types::{Revert, SolError};
+
+        let data = Revert::from("Order already finalised").abi_encode();
+        let payload = serde_json::json!({
+            "code": 3,
+            "message": "execution reverted: Order already finalised",
+            "data": format!("0x{}", alloy::hex::encode(&data))
+        });
+        let error = ExecutionError::from(RpcError::ErrorResp(serde_json::from_value(payload).unwrap()));
+        assert_eq!(error, ExecutionError::CallReverted { data: data.clone() });
+        assert_eq!(error.to_string(), "Call reverted: Order already finalised");
+
+        assert_eq!(revert_reason(&[0xde, 0xad, 0xbe, 0xef]), "0xdeadbeef");
+        assert_eq!(revert_reason(&[]), "no revert data");
+    }
+
+    #[test]
+    fn test_revert_reasons_are_human_readable() {
+        use alloy::sol_types::{Panic, Revert, SolError};
+
+        assert_eq!(revert_reason(&Revert::from("Invalid signature").abi_encode()), "Invalid signature");
+        assert_eq!(revert_reason(&Panic::from(0x11).abi_encode()), "panic: arithmetic underflow or overflow (0x11)");
+        assert_eq!(revert_reason(&Panic::from(0x32).abi_encode()), "panic: array out-of-bounds access (0x32)");
+
+        let reverted = ExecutionError::Reverted { tx_hash: "0xabc".to_string(), reason: Some("Invalid signature".to_string()) };
+        assert_eq!(reverted.to_string(), "Transaction reverted: 0xabc (Invalid signature)");
+        let reverted = ExecutionError::Reverted { tx_hash: "0xabc".to_string(), reason: None };
+        assert_eq!(reverted.to_string(), "Transaction reverted: 0xabc");
+    }
+
+    #[tokio::test]
+    async fn test_transport_failures() {
+        // Nothing listens on port 1: reqwest fails to connect
+        let connect_error = reqwest::Client::new().post("http://127.0.0.1:1").send().await.unwrap_err();
+        let error = ExecutionError::from(TransportErrorKind::custom(connect_error));
+        assert!(matches!(error, ExecutionError::RpcUnreachable(_)), "{:?}", error);
+        assert!(error.is_retryable());
+
+        let error = ExecutionError::from(RpcError::Transport(TransportErrorKind::BackendGone));
+        assert!(matches!(error, ExecutionError::RpcUnreachable(_)));
+
+        let error = ExecutionError::from(PendingTransactionError::TxWatcher(WatchTxError::Timeout));
+        assert!(matches!(error, ExecutionError::Timeout(_)));
+        assert!(error.is_retryable());
+
+        // Reverts are final; the typed error survives the trip through anyhow
+        let reverted: anyhow::Error = ExecutionError::Reverted { tx_hash: "0xabc".to_string(), reason: None }.into();
+        assert!(!is_retryable(&reverted));
+        assert!(is_retryable(&ExecutionError::RpcUnreachable("down".to_string()).into()));
+        assert!(!is_retryable(&anyhow::anyhow!("plain error")));
+    }
+}
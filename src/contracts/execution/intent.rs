@@ -0,0 +1,268 @@
+use crate::contracts::execution::traits::{ChainType, ExecutionEngine, GasParams};
+use crate::storage::memory::temp_path_for;
+use alloy::primitives::{keccak256, Address};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Serializes read-modify-write cycles on journal files. Several
+/// `ContractFactory` instances live in one process, so the lock is global.
+static JOURNAL_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// A transaction the solver is about to broadcast, recorded before it leaves the process
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxIntent {
+    pub order_id: String,
+    pub chain: ChainType,
+    pub nonce: u64,
+    pub calldata_hash: String,
+    /// Hash of the signed transaction; it commits to nonce and calldata
+    pub tx_hash: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Durable record of in-flight transactions, keyed by order id and chain
+///
+/// An intent is written (and fsynced) before broadcasting and removed once the
+/// transaction is confirmed. An intent found on a later attempt means a
+/// previous run may already have broadcast that transaction.
+#[derive(Debug, Clone)]
+pub struct IntentJournal {
+    path: PathBuf,
+}
+
+impl IntentJournal {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Journal stored next to the orders data file, e.g. `data/orders.intents.json`
+    pub fn for_data_file<P: AsRef<Path>>(data_file: P) -> Self {
+        Self::new(data_file.as_ref().with_extension("intents.json"))
+    }
+
+    pub async fn get(&self, order_id: &str, chain: ChainType) -> Result<Option<TxIntent>> {
+        let _guard = JOURNAL_LOCK.lock().await;
+        let intents = self.read().await?;
+        Ok(intents.get(&intent_key(order_id, chain)).cloned())
+    }
+
+    pub async fn record(&self, intent: TxIntent) -> Result<()> {
+        let _guard = JOURNAL_LOCK.lock().await;
+        let mut intents = self.read().await?;
+        intents.insert(intent_key(&intent.order_id, intent.chain), intent);
+        self.write(&intents).await
+    }
+
+    pub async fn clear(&self, order_id: &str, chain: ChainType) -> Result<()> {
+        let _guard = JOURNAL_LOCK.lock().await;
+        let mut intents = self.read().await?;
+        if intents.remove(&intent_key(order_id, chain)).is_some() {
+            self.write(&intents).await?;
+        }
+        Ok(())
+    }
+
+    async fn read(&self) -> Result<HashMap<String, TxIntent>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+        let content = fs::read_to_string(&self.path).await?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    async fn write(&self, intents: &HashMap<String, TxIntent>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let json_data = serde_json::to_string_pretty(intents)?;
+        let temp_path = temp_path_for(&self.path);
+
+        let mut file = fs::File::create(&temp_path).await?;
+        file.write_all(json_data.as_bytes()).await?;
+        file.sync_all().await?;
+        drop(file);
+
+        fs::rename(&temp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+fn intent_key(order_id: &str, chain: ChainType) -> String {
+    format!("{}:{:?}", order_id, chain)
+}
+
+/// Send a transaction for `order_id` at most once across restarts
+///
+/// Without a journal this is a plain `send_transaction`. With one, a recorded
+/// intent is checked on-chain first; only if the node has never seen that
+/// transaction is a new one signed, recorded and broadcast.
+pub async fn send_once(
+    executor: &dyn ExecutionEngine,
+    journal: Option<&IntentJournal>,
+    order_id: &str,
+    chain: ChainType,
+    call_data: Vec<u8>,
+    to: Address,
+    gas: GasParams,
+) -> Result<String> {
+    let Some(journal) = journal else {
+        return executor.send_transaction(chain, call_data, to, gas).await;
+    };
+
+    if let Some(intent) = journal.get(order_id, chain).await? {
+        info!("🔁 Found recorded intent for order {} on {:?}: {}", order_id, chain, intent.tx_hash);
+        if let Some(tx_hash) = executor.find_transaction(chain, &intent.tx_hash).await? {
+            info!("✅ Transaction from a previous attempt already broadcast: {}", tx_hash);
+            journal.clear(order_id, chain).await?;
+            return Ok(tx_hash);
+        }
+        warn!("Recorded transaction {} was never broadcast, sending a new one", intent.tx_hash);
+    }
+
+    let calldata_hash = format!("{:?}", keccak256(&call_data));
+    let signed = executor.sign_transaction(chain, call_data, to, gas).await?;
+
+    journal
+        .record(TxIntent {
+            order_id: order_id.to_string(),
+            chain,
+            nonce: signed.nonce,
+            calldata_hash,
+            tx_hash: signed.tx_hash.clone(),
+            recorded_at: Utc::now(),
+        })
+        .await?;
+
+    let tx_hash = executor.broadcast_signed(chain, &signed).await?;
+    journal.clear(order_id, chain).await?;
+    Ok(tx_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::execution::traits::SignedTransaction;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex as StdMutex;
+    use uuid::Uuid;
+
+    /// Executor that remembers which transactions it has "broadcast"
+    #[derive(Default)]
+    struct MockExecutor {
+        on_chain: StdMutex<Vec<String>>,
+        broadcasts: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ExecutionEngine for MockExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<String> {
+            unreachable!("journaled sends must sign and broadcast separately")
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        async fn sign_transaction(&self, _chain: ChainType, call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<SignedTransaction> {
+            Ok(SignedTransaction {
+                nonce: 7,
+                tx_hash: format!("{:?}", keccak256(&call_data)),
+                raw: call_data,
+            })
+        }
+
+        async fn broadcast_signed(&self, _chain: ChainType, tx: &SignedTransaction) -> Result<String> {
+            self.broadcasts.fetch_add(1, Ordering::SeqCst);
+            self.on_chain.lock().unwrap().push(tx.tx_hash.clone());
+            Ok(tx.tx_hash.clone())
+        }
+
+        async fn find_transaction(&self, _chain: ChainType, tx_hash: &str) -> Result<Option<String>> {
+            let known = self.on_chain.lock().unwrap().iter().any(|hash| hash == tx_hash);
+            Ok(known.then(|| tx_hash.to_string()))
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "MockExecutor"
+        }
+    }
+
+    fn test_journal() -> IntentJournal {
+        IntentJournal::new(std::env::temp_dir().join(format!("oif-intents-test-{}.json", Uuid::new_v4())))
+    }
+
+    fn gas() -> GasParams {
+        GasParams { gas_limit: 100000, gas_price: 1, value: alloy::primitives::U256::ZERO }
+    }
+
+    #[tokio::test]
+    async fn test_recorded_intent_is_not_rebroadcast() {
+        let executor = MockExecutor::default();
+        let journal = test_journal();
+        let call_data = vec![0xde, 0xad, 0xbe, 0xef];
+        let to = Address::from([0x22; 20]);
+
+        // A previous run broadcast the fill and crashed before confirming it
+        let previous = executor.sign_transaction(ChainType::Destination, call_data.clone(), to, gas()).await.unwrap();
+        executor.on_chain.lock().unwrap().push(previous.tx_hash.clone());
+        journal.record(TxIntent {
+            order_id: "order-1".to_string(),
+            chain: ChainType::Destination,
+            nonce: previous.nonce,
+            calldata_hash: format!("{:?}", keccak256(&call_data)),
+            tx_hash: previous.tx_hash.clone(),
+            recorded_at: Utc::now(),
+        }).await.unwrap();
+
+        let tx_hash = send_once(&executor, Some(&journal), "order-1", ChainType::Destination, call_data, to, gas())
+            .await
+            .unwrap();
+
+        assert_eq!(tx_hash, previous.tx_hash);
+        assert_eq!(executor.broadcasts.load(Ordering::SeqCst), 0);
+        assert!(journal.get("order-1", ChainType::Destination).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unbroadcast_intent_is_sent() {
+        let executor = MockExecutor::default();
+        let journal = test_journal();
+
+        // Intent recorded, but the process died before the broadcast reached the node
+        journal.record(TxIntent {
+            order_id: "order-2".to_string(),
+            chain: ChainType::Origin,
+            nonce: 7,
+            calldata_hash: "0x00".to_string(),
+            tx_hash: "0x1234".to_string(),
+            recorded_at: Utc::now(),
+        }).await.unwrap();
+
+        send_once(&executor, Some(&journal), "order-2", ChainType::Origin, vec![0x01], Address::ZERO, gas())
+            .await
+            .unwrap();
+
+        assert_eq!(executor.broadcasts.load(Ordering::SeqCst), 1);
+        assert!(journal.get("order-2", ChainType::Origin).await.unwrap().is_none());
+    }
+}
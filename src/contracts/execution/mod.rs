@@ -1,7 +1,18 @@
 // Re-export existing traits and implementation
 pub mod traits;
 pub mod alloy_executor;
+pub mod block_height;
+pub mod error;
+pub mod intent;
+pub mod nonce;
+pub mod rpc_log;
+pub mod tx_journal;
 
 // Re-export everything for easy access
 pub use traits::*;
-pub use alloy_executor::AlloyExecutor; 
\ No newline at end of file
+pub use alloy_executor::AlloyExecutor;
+pub use block_height::BlockHeightCache;
+pub use error::ExecutionError;
+pub use intent::{IntentJournal, TxIntent};
+pub use nonce::NonceManager;
+pub use tx_journal::{TxJournal, TxOp, TxRecord}; 
\ No newline at end of file
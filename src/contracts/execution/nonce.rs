@@ -0,0 +1,102 @@
+use alloy::primitives::Address;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::Mutex as AsyncMutex;
+
+/// Next nonce to hand out for one account, `None` until fetched from the chain
+type AccountNonce = Arc<AsyncMutex<Option<u64>>>;
+
+/// Assigns transaction nonces per (chain, sender) so concurrent sends don't reuse one
+///
+/// The pending nonce is fetched from the chain once and then incremented locally;
+/// assignment for an account is serialized behind its own mutex.
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    accounts: Mutex<HashMap<(u64, Address), AccountNonce>>,
+}
+
+impl NonceManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Manager shared by every executor in the process. Orchestrators each build
+    /// their own `AlloyExecutor`, so per-executor state would not serialize anything.
+    pub fn global() -> Arc<Self> {
+        static GLOBAL: OnceLock<Arc<NonceManager>> = OnceLock::new();
+        GLOBAL.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    fn account(&self, chain_id: u64, address: Address) -> AccountNonce {
+        self.accounts.lock().unwrap().entry((chain_id, address)).or_default().clone()
+    }
+
+    /// Reserve the next nonce for `address` on `chain_id`; `fetch_pending` is only
+    /// called when no nonce is cached yet (or after `reset`)
+    pub async fn next_nonce<F, Fut>(&self, chain_id: u64, address: Address, fetch_pending: F) -> Result<u64>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<u64>>,
+    {
+        let account = self.account(chain_id, address);
+        let mut next = account.lock().await;
+        let nonce = match *next {
+            Some(nonce) => nonce,
+            None => fetch_pending().await?,
+        };
+        *next = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Forget the cached nonce so the next send re-reads it from the chain,
+    /// e.g. after a transaction was rejected and its nonce never used
+    pub async fn reset(&self, chain_id: u64, address: Address) {
+        *self.account(chain_id, address).lock().await = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_concurrent_sends_get_sequential_nonces() {
+        let manager = Arc::new(NonceManager::new());
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let solver = Address::from([0x11; 20]);
+
+        let sends: Vec<_> = (0..3).map(|_| {
+            let manager = manager.clone();
+            let fetches = fetches.clone();
+            tokio::spawn(async move {
+                manager
+                    .next_nonce(31337, solver, || async move {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        tokio::task::yield_now().await;
+                        Ok(7)
+                    })
+                    .await
+                    .unwrap()
+            })
+        }).collect();
+        let mut nonces = Vec::new();
+        for send in sends {
+            nonces.push(send.await.unwrap());
+        }
+        nonces.sort();
+
+        assert_eq!(nonces, vec![7, 8, 9]);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1, "pending nonce is fetched once");
+
+        // Other chains are tracked separately
+        assert_eq!(manager.next_nonce(31338, solver, || async { Ok(0) }).await.unwrap(), 0);
+
+        // After a failed send the nonce is re-read from the chain
+        manager.reset(31337, solver).await;
+        assert_eq!(manager.next_nonce(31337, solver, || async { Ok(8) }).await.unwrap(), 8);
+        assert_eq!(manager.next_nonce(31337, solver, || async { Ok(0) }).await.unwrap(), 9);
+    }
+}
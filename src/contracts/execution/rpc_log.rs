@@ -0,0 +1,62 @@
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::{TransportError, TransportFut};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+
+/// Transport layer logging every JSON-RPC request sent through it: method names and
+/// latency at info, params and responses at trace (target `rpc`)
+///
+/// Installed on RPC clients when `chains.log_rpc_requests` is set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcLogLayer;
+
+impl<S> Layer<S> for RpcLogLayer {
+    type Service = RpcLogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcLogService { inner }
+    }
+}
+
+/// Transport wrapped by `RpcLogLayer`
+#[derive(Debug, Clone)]
+pub struct RpcLogService<S> {
+    inner: S,
+}
+
+impl<S> Service<RequestPacket> for RpcLogService<S>
+where
+    S: Service<RequestPacket, Response = ResponsePacket, Error = TransportError> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let methods = request.method_names().collect::<Vec<_>>().join(",");
+        for req in request.requests() {
+            tracing::trace!(target: "rpc", method = req.method(), params = req.params().map(|p| p.get()), "RPC request");
+        }
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let response = inner.call(request).await;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            match &response {
+                Ok(packet) => {
+                    tracing::info!(target: "rpc", method = %methods, elapsed_ms, "RPC call");
+                    tracing::trace!(target: "rpc", method = %methods, response = ?packet, "RPC response");
+                }
+                Err(e) => tracing::info!(target: "rpc", method = %methods, elapsed_ms, error = %e, "RPC call failed"),
+            }
+            response
+        })
+    }
+}
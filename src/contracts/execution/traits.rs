@@ -1,20 +1,155 @@
-use alloy::primitives::Address;
+use alloy::primitives::{Address, B256, U256};
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GasStrategy;
 
 /// Enum to specify which blockchain to execute transactions on
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ChainType {
     /// Origin chain (where SettlerCompact is deployed - for finalize operations)
     Origin,
     /// Destination chain (where CoinFiller is deployed - for fill operations)
     Destination,
+    /// Any chain in the configured chain registry
+    ById(u64),
 }
 
 #[derive(Debug, Clone)]
 pub struct GasParams {
     pub gas_limit: u64,
     pub gas_price: u64,
+    /// Native value (wei) sent along with the call
+    pub value: U256,
+}
+
+impl GasParams {
+    /// Gas for a transaction: `gas_limit` as estimated (buffer included) and the
+    /// strategy's `gas_price` clamped to the profile's bounds
+    pub fn from_profile(gas_limit: u64, gas_price: u64, profile: &crate::config::GasConfig) -> Self {
+        Self {
+            gas_limit,
+            gas_price: profile.bound_price(gas_price as u128) as u64,
+            value: U256::ZERO,
+        }
+    }
+
+    /// Send `value` wei with the transaction
+    pub fn with_value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Most the transaction can spend: `gas_limit * gas_price + value`
+    pub fn max_cost(&self) -> U256 {
+        U256::from(self.gas_limit) * U256::from(self.gas_price) + self.value
+    }
+}
+
+/// Refuse to send when the executor's wallet on `chain` cannot cover `gas.max_cost()`
+///
+/// Executors that cannot report balances are let through with a warning; the
+/// node will still reject an underfunded transaction.
+pub async fn ensure_affordable(executor: &dyn ExecutionEngine, chain: ChainType, gas: &GasParams) -> Result<()> {
+    let required = gas.max_cost();
+    match executor.get_balance(chain).await {
+        Ok(balance) if balance < required => Err(crate::contracts::execution::ExecutionError::InsufficientFunds(format!(
+            "wallet {} holds {} wei on {:?} chain, {} wei needed for gas",
+            executor.wallet_address(), balance, chain, required
+        )).into()),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::warn!("Could not check wallet balance on {:?} chain before sending: {}", chain, e);
+            Ok(())
+        }
+    }
+}
+
+/// Gas limit for sending `call_data` to `to`: the executor's estimate plus `buffer_pct`
+/// percent, or `fallback` when the node cannot estimate it
+pub async fn estimate_gas_limit(
+    executor: &dyn ExecutionEngine,
+    chain: ChainType,
+    call_data: Vec<u8>,
+    to: Address,
+    fallback: u64,
+    buffer_pct: u64,
+) -> u64 {
+    match executor.estimate_gas(chain, call_data, to, executor.wallet_address()).await {
+        Ok(estimate) => {
+            let gas_limit = estimate.saturating_mul(100 + buffer_pct) / 100;
+            tracing::info!("⛽ Estimated {} gas on {:?} chain, using {} ({}% buffer)", estimate, chain, gas_limit, buffer_pct);
+            gas_limit
+        }
+        Err(e) => {
+            tracing::warn!("Gas estimation failed on {:?} chain, using default limit {}: {}", chain, fallback, e);
+            fallback
+        }
+    }
+}
+
+/// Gas price (wei) to bid under `strategy`; `oracle` fetches the network's current
+/// price and is only called by the strategies that need it
+pub async fn resolve_gas_price<F, Fut>(strategy: GasStrategy, oracle: F) -> Result<u128>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<u128>>,
+{
+    match strategy {
+        GasStrategy::Fixed(gas_price) => Ok(gas_price as u128),
+        GasStrategy::Oracle => oracle().await,
+        GasStrategy::OracleMultiplier(multiplier) => Ok((oracle().await? as f64 * multiplier) as u128),
+    }
+}
+
+/// Base gas price for a transaction on `chain` under `strategy`, from the executor's
+/// view of the network, or `fallback` when it cannot report one
+pub async fn bid_gas_price(executor: &dyn ExecutionEngine, chain: ChainType, strategy: GasStrategy, fallback: u64) -> u64 {
+    match resolve_gas_price(strategy, || executor.get_gas_price(chain)).await {
+        Ok(gas_price) => {
+            let gas_price = u64::try_from(gas_price).unwrap_or(u64::MAX);
+            tracing::info!("⛽ Gas price on {:?} chain: {} wei ({:?})", chain, gas_price, strategy);
+            gas_price
+        }
+        Err(e) => {
+            tracing::warn!("Gas price lookup failed on {:?} chain, using default price {}: {}", chain, fallback, e);
+            fallback
+        }
+    }
+}
+
+/// A transaction signed locally but not yet broadcast
+#[derive(Debug, Clone)]
+pub struct SignedTransaction {
+    pub nonce: u64,
+    pub tx_hash: String,
+    /// EIP-2718 encoded transaction, ready for `eth_sendRawTransaction`
+    pub raw: Vec<u8>,
+}
+
+/// Outcome of a mined transaction, reduced to what the solver inspects
+#[derive(Debug, Clone)]
+pub struct TransactionReceiptInfo {
+    pub tx_hash: String,
+    pub status: bool,
+    pub gas_used: u64,
+    /// Price (wei) paid per unit of gas
+    pub effective_gas_price: u128,
+    pub logs: Vec<ReceiptLog>,
+}
+
+impl TransactionReceiptInfo {
+    pub fn gas_usage(&self) -> crate::models::GasUsage {
+        crate::models::GasUsage::new(self.gas_used, self.effective_gas_price)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReceiptLog {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Vec<u8>,
 }
 
 #[async_trait]
@@ -46,6 +181,43 @@ pub trait ExecutionEngine: Send + Sync {
     /// * `from` - The address to call from
     async fn estimate_gas(&self, chain: ChainType, call_data: Vec<u8>, to: Address, from: Address) -> Result<u64>;
     
+    /// Sign a transaction without broadcasting it, so its hash can be recorded first
+    async fn sign_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<SignedTransaction> {
+        Err(anyhow::anyhow!("{} cannot sign without broadcasting", self.description()))
+    }
+
+    /// Broadcast a transaction from `sign_transaction` and wait for its receipt
+    async fn broadcast_signed(&self, _chain: ChainType, _tx: &SignedTransaction) -> Result<String> {
+        Err(anyhow::anyhow!("{} cannot broadcast signed transactions", self.description()))
+    }
+
+    /// Look up a previously broadcast transaction and wait for it to confirm
+    ///
+    /// Returns `Ok(None)` if the node has never seen `tx_hash`.
+    async fn find_transaction(&self, _chain: ChainType, _tx_hash: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Fetch the receipt of a mined transaction, including its logs
+    async fn get_receipt(&self, _chain: ChainType, _tx_hash: &str) -> Result<TransactionReceiptInfo> {
+        Err(anyhow::anyhow!("{} cannot fetch receipts", self.description()))
+    }
+
+    /// The network's current gas price (wei) on the specified blockchain
+    async fn get_gas_price(&self, _chain: ChainType) -> Result<u128> {
+        Err(anyhow::anyhow!("{} cannot query gas prices", self.description()))
+    }
+
+    /// Native token balance of the executor's wallet on the specified blockchain
+    async fn get_balance(&self, _chain: ChainType) -> Result<U256> {
+        Err(anyhow::anyhow!("{} cannot query balances", self.description()))
+    }
+
+    /// Code deployed at `address` on the specified blockchain; empty for an EOA
+    async fn get_code(&self, _chain: ChainType, _address: Address) -> Result<Vec<u8>> {
+        Err(anyhow::anyhow!("{} cannot query contract code", self.description()))
+    }
+
     /// Get the wallet address used by this executor
     fn wallet_address(&self) -> Address;
     
@@ -88,4 +260,57 @@ pub trait OrderExecutor: Send + Sync {
     /// # Returns
     /// * `&str` - Description of the executor implementation  
     fn description(&self) -> &str;
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Executor whose node reports `gas_price`
+    struct PricedExecutor {
+        gas_price: Option<u128>,
+    }
+
+    #[async_trait]
+    impl ExecutionEngine for PricedExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<String> {
+            Ok("0xsent".to_string())
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        async fn get_gas_price(&self, _chain: ChainType) -> Result<u128> {
+            self.gas_price.ok_or_else(|| anyhow::anyhow!("node unreachable"))
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::ZERO
+        }
+
+        fn description(&self) -> &str {
+            "PricedExecutor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gas_strategies() {
+        let executor = PricedExecutor { gas_price: Some(2_000_000_000) };
+        let chain = ChainType::Destination;
+
+        assert_eq!(bid_gas_price(&executor, chain, GasStrategy::Oracle, 1).await, 2_000_000_000);
+        assert_eq!(bid_gas_price(&executor, chain, GasStrategy::OracleMultiplier(1.5), 1).await, 3_000_000_000);
+        assert_eq!(bid_gas_price(&executor, chain, GasStrategy::Fixed(7_000_000_000), 1).await, 7_000_000_000);
+
+        // Without an oracle only the fixed strategy still has a price of its own
+        let offline = PricedExecutor { gas_price: None };
+        assert_eq!(bid_gas_price(&offline, chain, GasStrategy::Oracle, 50).await, 50);
+        assert_eq!(bid_gas_price(&offline, chain, GasStrategy::OracleMultiplier(1.5), 50).await, 50);
+        assert_eq!(bid_gas_price(&offline, chain, GasStrategy::Fixed(7), 50).await, 7);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::config::PersistenceConfig;
+use crate::contracts::execution::traits::{ChainType, ExecutionEngine};
+use alloy::primitives::Address;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// Serializes appends so concurrent sends never interleave within a line
+static TX_JOURNAL_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// What a broadcast transaction did for its order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxOp {
+    Fill,
+    Finalize,
+    /// Fill and finalization in one multicall transaction
+    Bundle,
+    /// ERC20 approval letting CoinFiller spend an output token
+    Approve,
+}
+
+/// One line of the transaction journal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxRecord {
+    pub order_id: String,
+    pub op_type: TxOp,
+    pub chain_id: u64,
+    pub to: Address,
+    pub tx_hash: String,
+    /// `None` when the receipt could not be fetched after sending
+    pub gas_used: Option<u64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TxRecord {
+    /// Record of a transaction sent just now, gas used not yet known
+    pub fn sent(order_id: &str, op_type: TxOp, chain_id: u64, to: Address, tx_hash: &str) -> Self {
+        Self {
+            order_id: order_id.to_string(),
+            op_type,
+            chain_id,
+            to,
+            tx_hash: tx_hash.to_string(),
+            gas_used: None,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Append-only JSONL log of every transaction the solver broadcast, for
+/// accounting and post-mortems
+///
+/// Unlike the `IntentJournal`, entries are never removed.
+#[derive(Debug, Clone)]
+pub struct TxJournal {
+    path: PathBuf,
+}
+
+impl TxJournal {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Journal at `persistence.tx_journal_file`, if one is configured
+    pub fn from_config(persistence: &PersistenceConfig) -> Option<Self> {
+        persistence.tx_journal_file.as_ref().map(Self::new)
+    }
+
+    pub async fn append(&self, record: &TxRecord) -> Result<()> {
+        let _guard = TX_JOURNAL_LOCK.lock().await;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&self.path).await?;
+        file.write_all(line.as_bytes()).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+
+    /// Every record in the journal, oldest first
+    pub async fn read_all(&self) -> Result<Vec<TxRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path).await?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+
+    /// Append `record` for a transaction `executor` sent on `chain`, filling in
+    /// the gas used from its receipt
+    ///
+    /// The transaction is already out, so a failed write is logged rather than returned.
+    pub async fn record_sent(&self, executor: &dyn ExecutionEngine, chain: ChainType, mut record: TxRecord) {
+        record.gas_used = executor
+            .get_receipt(chain, &record.tx_hash)
+            .await
+            .map(|receipt| receipt.gas_used)
+            .ok();
+        if let Err(e) = self.append(&record).await {
+            warn!("Failed to journal transaction {} for order {}: {}", record.tx_hash, record.order_id, e);
+        }
+    }
+}
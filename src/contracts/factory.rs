@@ -1,15 +1,13 @@
 use alloy::{
-    primitives::{Address, U256, FixedBytes, Bytes},
+    primitives::{Address, FixedBytes},
     providers::{ProviderBuilder, Provider},
     sol,
     signers::local::PrivateKeySigner,
     network::{EthereumWallet},
-    primitives::keccak256,
 };
 use anyhow::Result;
 use std::str::FromStr;
-use tracing::{info, error, warn};
-use hex;
+use tracing::info;
 
 // CRITICAL FIX: Add ethers-rs for proper contract interface
 use ethers::prelude::*;
@@ -64,7 +62,11 @@ abigen!(
 use crate::config::AppConfig;
 use crate::contracts::operations::{FinalizationOrchestrator, FillOrchestrator};
 use crate::contracts::abi::AbiRegistry;
-use std::sync::Arc;
+use crate::contracts::encoding::{AlloyEncoder, FillRequest, FoundryEncoder, SelectorOverrides};
+use crate::contracts::execution::{AlloyExecutor, BlockHeightCache, ExecutionEngine, GasParams};
+use crate::contracts::execution::alloy_executor::connect_rpc_client;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 // Contract interfaces using Alloy sol! macro - shared across modules  
 sol! {
@@ -103,6 +105,12 @@ sol! {
             MandateOutput memory output,
             bytes32 proposedSolver
         ) external returns (bool);
+
+        /// Fill record for an output; a zero solver means it has not been filled
+        function filledOutputs(
+            bytes32 orderId,
+            bytes32 outputHash
+        ) external view returns (bytes32 solver, uint32 timestamp);
     }
 
     interface SettlerCompact {
@@ -125,22 +133,53 @@ sol! {
 
         function DOMAIN_SEPARATOR() external view returns (bytes32);
     }
+
+    /// Output token, approved for CoinFiller to pull from the solver
+    interface IERC20 {
+        function allowance(address owner, address spender) external view returns (uint256);
+        function approve(address spender, uint256 amount) external returns (bool);
+    }
+
+    /// Contract wallet validating signatures made on its behalf
+    interface IERC1271 {
+        function isValidSignature(bytes32 hash, bytes signature) external view returns (bytes4 magicValue);
+    }
+
+    /// Local oracle on the origin chain, attesting to fills proven on a remote chain
+    interface IOracle {
+        function isProven(
+            uint256 remoteChainId,
+            bytes32 remoteOracle,
+            bytes32 application,
+            bytes32 dataHash
+        ) external view returns (bool);
+    }
 }
 
 pub struct ContractFactory {
     pub config: AppConfig,
-    origin_provider: Option<Box<dyn Provider + Send + Sync>>,
-    destination_provider: Option<Box<dyn Provider + Send + Sync>>,
+    origin_provider: Option<Arc<dyn Provider + Send + Sync>>,
+    destination_provider: Option<Arc<dyn Provider + Send + Sync>>,
+    /// Read-only providers of the other registry chains, connected on first use
+    registry_providers: Mutex<HashMap<u64, Arc<dyn Provider + Send + Sync>>>,
     wallet: Option<EthereumWallet>,
+    executor: Option<Arc<dyn ExecutionEngine>>,
+    block_heights: Arc<BlockHeightCache>,
+    abi_registry: Arc<AbiRegistry>,
 }
 
 impl ContractFactory {
     pub async fn new(config: AppConfig) -> Result<Self> {
+        let abi_registry = Arc::new(AbiRegistry::from_config(config.contracts.abi_dir.as_deref())?);
         let mut factory = Self {
             config,
             origin_provider: None,
             destination_provider: None,
+            registry_providers: Mutex::new(HashMap::new()),
             wallet: None,
+            executor: None,
+            block_heights: BlockHeightCache::global(),
+            abi_registry,
         };
 
         // Initialize providers
@@ -151,32 +190,44 @@ impl ContractFactory {
         Ok(factory)
     }
 
+    /// Execute fills and finalizations through `executor` instead of a fresh AlloyExecutor
+    ///
+    /// Orchestrators built this way keep no intent journal.
+    pub fn with_executor(mut self, executor: Arc<dyn ExecutionEngine>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Read chain heads through `block_heights` instead of the process-wide cache
+    pub fn with_block_height_cache(mut self, block_heights: Arc<BlockHeightCache>) -> Self {
+        self.block_heights = block_heights;
+        self
+    }
+
     async fn init_providers(&mut self) -> Result<()> {
         info!("Initializing blockchain providers");
         info!("Origin RPC: {}", self.config.chains.origin.rpc_url);
         info!("Destination RPC: {}", self.config.chains.destination.rpc_url);
 
-        // Create origin chain provider
-        let origin_provider = ProviderBuilder::new()
-            .on_http(self.config.chains.origin.rpc_url.parse()
-                .map_err(|e| anyhow::anyhow!("Invalid origin RPC URL '{}': {}", self.config.chains.origin.rpc_url, e))?);
-        self.origin_provider = Some(Box::new(origin_provider));
-
-        // Create destination chain provider  
-        let destination_provider = ProviderBuilder::new()
-            .on_http(self.config.chains.destination.rpc_url.parse()
-                .map_err(|e| anyhow::anyhow!("Invalid destination RPC URL '{}': {}", self.config.chains.destination.rpc_url, e))?);
-        self.destination_provider = Some(Box::new(destination_provider));
+        let log_requests = self.config.chains.log_rpc_requests;
+        self.origin_provider = Some(Self::connect_read_provider("origin", &self.config.chains.origin, log_requests).await?);
+        self.destination_provider = Some(Self::connect_read_provider("destination", &self.config.chains.destination, log_requests).await?);
 
         info!("Blockchain providers initialized successfully");
         Ok(())
     }
 
+    /// Read-only provider for the first of `chain`'s endpoints that answers
+    async fn connect_read_provider(label: &str, chain: &crate::config::ChainDetails, log_requests: bool) -> Result<Arc<dyn Provider + Send + Sync>> {
+        let client = connect_rpc_client(chain.rpc_url.as_slice(), chain.rpc_timeout(), log_requests).await
+            .map_err(|e| anyhow::anyhow!("Failed to connect to {} RPC '{}': {}", label, chain.rpc_url, e))?;
+        Ok(Arc::new(ProviderBuilder::new().connect_client(client)))
+    }
+
     async fn init_wallet(&mut self) -> Result<()> {
         info!("Initializing solver wallet");
 
         let private_key = &self.config.solver.private_key;
-        info!("Using private key: {}...", &private_key[..10]);
         let signer = PrivateKeySigner::from_str(private_key)?;
         let wallet = EthereumWallet::from(signer);
         
@@ -186,34 +237,33 @@ impl ContractFactory {
         Ok(())
     }
 
-    pub async fn fill_order(
-        &self,
-        order_id: &str,
-        fill_deadline: u32,
-        remote_oracle: Address,
-        token: Address,
-        amount: U256,
-        recipient: Address,
-    ) -> Result<String> {
+    /// Fill one output as described by `fill_request`, on the chain it is paid on
+    pub async fn fill_order(&self, fill_request: &FillRequest) -> Result<String> {
         info!("🚀 MODULAR FILL: Using FillOrchestrator architecture");
         
         // Create FillOrchestrator with modular components
         let orchestrator = self.create_fill_orchestrator()?;
         
         // Execute fill using the new modular approach
-        let tx_hash = orchestrator.execute_fill(
-            order_id,
-            fill_deadline,
-            remote_oracle,
-            token,
-            amount,
-            recipient,
-        ).await?;
+        let tx_hash = orchestrator.execute_fill_request(fill_request).await?;
         
         info!("✅ Modular fill completed successfully: {}", tx_hash);
         Ok(tx_hash)
     }
 
+    /// Check a fill transaction's receipt; `Ok(Some(reason))` means the fill is flagged
+    pub async fn verify_fill(
+        &self,
+        tx_hash: &str,
+        order_id: &str,
+        chain_id: u64,
+        recipient: Address,
+        has_remote_call: bool,
+    ) -> Result<crate::contracts::operations::fill::FillCheck> {
+        let orchestrator = self.create_fill_orchestrator()?;
+        orchestrator.verify_fill(tx_hash, order_id, Some(chain_id), recipient, has_remote_call).await
+    }
+
     pub async fn finalize_order(
         &self,
         order: &crate::models::Order,
@@ -230,18 +280,33 @@ impl ContractFactory {
         Ok(tx_hash)
     }
 
+    /// Whether the order's local oracle holds a proof for every output's fill
+    pub async fn fill_is_proven(&self, order: &crate::models::Order) -> Result<bool> {
+        let orchestrator = self.create_finalization_orchestrator()?;
+        orchestrator.fill_is_proven(order).await
+    }
+
     /// Create FinalizationOrchestrator with the current factory configuration
     fn create_finalization_orchestrator(&self) -> Result<FinalizationOrchestrator> {
         info!("🏗️ Creating FinalizationOrchestrator from ContractFactory");
         
-        // Create ABI provider
-        let abi_provider = Arc::new(AbiRegistry::new());
+        let abi_provider = self.abi_registry.clone();
         
         // Create config Arc from current config
         let config = Arc::new(self.config.clone());
         
         // Create FinalizationOrchestrator
-        let orchestrator = FinalizationOrchestrator::new(abi_provider, config)?;
+        let orchestrator = match &self.executor {
+            Some(executor) => {
+                let encoder = Arc::new(
+                    FoundryEncoder::new(abi_provider)
+                        .with_selector_overrides(SelectorOverrides::from_config(&config.contracts))
+                        .with_max_calldata_bytes(config.encoder.max_calldata_bytes),
+                );
+                FinalizationOrchestrator::new_with_traits(encoder, executor.clone(), config)
+            }
+            None => FinalizationOrchestrator::new(abi_provider, config)?,
+        };
         
         info!("✅ FinalizationOrchestrator created with factory configuration");
         info!("  Wallet address: {}", orchestrator.wallet_address());
@@ -253,14 +318,23 @@ impl ContractFactory {
     fn create_fill_orchestrator(&self) -> Result<FillOrchestrator> {
         info!("🏗️ Creating FillOrchestrator from ContractFactory");
         
-        // Create ABI provider
-        let abi_provider = Arc::new(AbiRegistry::new());
+        let abi_provider = self.abi_registry.clone();
         
         // Create config Arc from current config
         let config = Arc::new(self.config.clone());
         
         // Create FillOrchestrator
-        let orchestrator = FillOrchestrator::new(abi_provider, config)?;
+        let orchestrator = match &self.executor {
+            Some(executor) => {
+                let encoder = Arc::new(
+                    AlloyEncoder::new(abi_provider)
+                        .with_selector_overrides(SelectorOverrides::from_config(&config.contracts))
+                        .with_max_calldata_bytes(config.encoder.max_calldata_bytes),
+                );
+                FillOrchestrator::new_with_traits(encoder, executor.clone(), config)?
+            }
+            None => FillOrchestrator::new(abi_provider, config)?,
+        };
         
         info!("✅ FillOrchestrator created with factory configuration");
         info!("  Wallet address: {}", orchestrator.wallet_address());
@@ -279,32 +353,25 @@ impl ContractFactory {
         Ok(gas_estimate)
     }
 
-    /// Estimate gas for fill using FillOrchestrator
-    pub async fn estimate_fill_gas(
-        &self,
-        order_id: &str,
-        fill_deadline: u32,
-        remote_oracle: Address,
-        token: Address,
-        amount: U256,
-        recipient: Address,
-    ) -> Result<u64> {
+    /// Gas limit and price the fill described by `fill_request` would be sent with, using FillOrchestrator
+    pub async fn estimate_fill_gas(&self, fill_request: &FillRequest) -> Result<GasParams> {
         info!("⛽ Estimating fill gas using FillOrchestrator");
         
         let orchestrator = self.create_fill_orchestrator()?;
-        let gas_estimate = orchestrator.estimate_fill_gas(
-            order_id,
-            fill_deadline,
-            remote_oracle,
-            token,
-            amount,
-            recipient,
-        ).await?;
-        
-        info!("✅ Fill gas estimation completed: {} gas", gas_estimate);
+        let gas_estimate = orchestrator.estimate_fill_request_gas(fill_request).await?;
+        
+        info!("✅ Fill gas estimation completed: {} gas", gas_estimate.gas_limit);
         Ok(gas_estimate)
     }
 
+    /// Executor used for fills and finalizations: the injected one, or a fresh AlloyExecutor
+    pub fn executor(&self) -> Result<Arc<dyn ExecutionEngine>> {
+        match &self.executor {
+            Some(executor) => Ok(executor.clone()),
+            None => Ok(Arc::new(AlloyExecutor::new(Arc::new(self.config.clone()))?)),
+        }
+    }
+
     /// Get wallet address from the factory
     pub fn get_wallet_address(&self) -> Result<Address> {
         Ok(self.get_wallet()?.default_signer().address())
@@ -322,272 +389,34 @@ impl ContractFactory {
             .ok_or_else(|| anyhow::anyhow!("Destination provider not initialized"))
     }
 
-    pub fn get_wallet(&self) -> Result<&EthereumWallet> {
-        self.wallet.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Wallet not initialized"))
-    }
-
-    // ⚠️ REMOVED: get_the_compact_domain_separator function - not needed since we don't 
-    // manually verify EIP-712 signatures anymore
-
-    // CRITICAL FIX: Use EXACT TypeScript selector 0xdd1ff485
-    fn encode_finalize_call_with_typescript_selector(
-        &self,
-        order: &StandardOrder,
-        sponsor_sig: &Bytes,
-        allocator_sig: &Bytes,
-        timestamps: &[u32],
-        solvers: &[FixedBytes<32>],
-        destination: &FixedBytes<32>,
-        calls: &Bytes,
-    ) -> Result<Vec<u8>> {
-        info!("🔧 Using EXACT TypeScript selector 0xdd1ff485 to match working version");
-        
-        // Use the working TypeScript calldata as a template
-        // From TypeScript logs: 1349 bytes with selector 0xdd1ff485
-        
-        // First try to find what function signature produces 0xdd1ff485
-        // Since we can't reverse it, we'll use the raw parameter encoding from cast
-        // but with the correct TypeScript selector
-        
-        // Call the original manual function to get parameter encoding
-        let manual_result = self.encode_finalize_call_manual(
-            order, sponsor_sig, allocator_sig, timestamps, solvers, destination, calls
-        )?;
-        
-        // Replace the selector with TypeScript's working selector
-        let typescript_selector = [0xdd, 0x1f, 0xf4, 0x85]; // 0xdd1ff485
-        let parameters = &manual_result[4..]; // Skip original selector
-        
-        let typescript_calldata = [typescript_selector.as_slice(), parameters].concat();
-        
-        info!("✅ Using TypeScript selector: 0x{}", hex::encode(&typescript_selector));
-        info!("✅ Parameters from cast: {} bytes", parameters.len());
-        info!("✅ Total calldata: {} bytes", typescript_calldata.len());
-        
-        Ok(typescript_calldata)
-    }
-
-    // Original Foundry cast abi-encode implementation 
-    fn encode_finalize_call_manual(
-        &self,
-        order: &StandardOrder,
-        sponsor_sig: &Bytes,
-        allocator_sig: &Bytes,
-        timestamps: &[u32],
-        solvers: &[FixedBytes<32>],
-        destination: &FixedBytes<32>,
-        calls: &Bytes,
-    ) -> Result<Vec<u8>> {
-        use std::process::Command;
-
-        info!("🔧 Using Foundry cast abi-encode to fix nested tuple arrays bug");
-        
-        // Check if cast is available
-        let cast_available = Command::new("cast")
-            .arg("--version")
-            .output()
-            .map(|out| out.status.success())
-            .unwrap_or(false);
-            
-        if !cast_available {
-            error!("⚠️  Foundry cast not available - falling back to ethabi (may have nested tuple bugs)");
-            warn!("Install Foundry with: curl -L https://foundry.paradigm.xyz | bash");
-            // TODO: Implement fallback to ethabi here if needed
-            return Err(anyhow::anyhow!("Foundry cast not available and ethabi has nested tuple arrays bug"));
+    /// Read-only provider of any configured chain: the origin or destination one, or for
+    /// another registry chain one connected on first use and kept for later calls
+    pub async fn get_chain_provider(&self, chain_id: u64) -> Result<Arc<dyn Provider + Send + Sync>> {
+        let chains = &self.config.chains;
+        if chain_id == chains.origin.chain_id {
+            return self.origin_provider.clone().ok_or_else(|| anyhow::anyhow!("Origin provider not initialized"));
         }
-        
-        info!("✅ Foundry cast is available");
-
-        // Helper function to format Address as hex string
-        let addr = |a: &Address| -> String {
-            format!("0x{}", hex::encode(a.as_slice()))
-        };
-
-        // Helper for FixedBytes<32> to proper bytes32 hex (full 32 bytes)
-        let bytes32_hex = |b: &FixedBytes<32>| -> String {
-            format!("0x{}", hex::encode(b.as_slice())) // Full 32 bytes, not just last 20
-        };
-        
-        // Helper function to format bytes fields 
-        let bytes_hex = |b: &[u8]| -> String {
-            if b.is_empty() { "0x".to_string() } else { format!("0x{}", hex::encode(b)) }
-        };
-
-        // Prepare the function signature with CORRECT types: uint32[] for timestamps, bytes32[] for solvers  
-        let function_sig = "finalise((address,uint256,uint256,uint256,uint256,address,(uint256,uint256)[],(bytes32,bytes32,uint256,bytes32,uint256,bytes32,bytes,bytes)[]),bytes,uint32[],bytes32[],bytes32,bytes)";
-
-        // EIP-712 type definitions must match EXACTLY the TypeScript definitions
-        let standard_order_type_string = [
-            "StandardOrder(address user,uint256 nonce,uint256 originChainId,uint256 expires,uint256 fillDeadline,address localOracle,Input[] inputs,MandateOutput[] outputs)",
-            "Input(uint256 tokenId,uint256 amount)",  // ⚠️ CRITICAL: tokenId FIRST (matches TypeScript)
-            "MandateOutput(bytes32 remoteOracle,bytes32 remoteFiller,uint256 chainId,bytes32 token,uint256 amount,bytes32 recipient,bytes remoteCall,bytes fulfillmentContext)"  // ⚠️ CRITICAL: bytes32 types (matches TypeScript)
-        ].concat();
-
-        // Build order argument without quotes (Command doesn't use shell)
-        let order_arg = format!(
-            "({},{},{},{},{},{},{},{})",
-            format!("0x{}", hex::encode(order.user)),
-            order.nonce,
-            order.originChainId,
-            order.expires,
-            order.fillDeadline,
-            format!("0x{}", hex::encode(order.localOracle)),
-            format!("[{}]", 
-                order.inputs.iter()
-                    .map(|i| format!("({},{})", i.tokenId, i.amount))
-                    .collect::<Vec<_>>()
-                    .join(",")
-            ),
-            format!("[{}]",
-                order.outputs.iter()
-                    .map(|o| format!(
-                        "({},{},{},{},{},{},{},{})",
-                        bytes32_hex(&o.remoteOracle),
-                        bytes32_hex(&o.remoteFiller),
-                        o.chainId,
-                        bytes32_hex(&o.token),
-                        o.amount,
-                        bytes32_hex(&o.recipient),
-                        bytes_hex(&o.remoteCall),
-                        bytes_hex(&o.fulfillmentContext)
-                    ))
-                    .collect::<Vec<_>>()
-                    .join(",")
-            )
-        );
-
-        // Build signatures as concatenated bytes (sponsorSig + allocatorSig)
-        // CRITICAL: TypeScript uses ABI.encode(['bytes','bytes'], [sponsor, allocator])
-        // which creates a bytes containing the encoded tuple. We need to replicate this.
-        
-        // CRITICAL FIX: Don't pad sponsor signature - ECDSA signatures are already 65 bytes
-        // The sig_to_65_bytes function was corrupting the 'v' component (recovery id)
-        info!("🔍 Sponsor signature length before processing: {} bytes", sponsor_sig.len());
-        
-        let sponsor_fixed = sponsor_sig.to_vec(); // Use signature as-is, no padding
-        let allocator_fixed = allocator_sig.to_vec(); // Keep allocator empty as intended
-        
-        info!("🔍 Signature lengths after processing: sponsor={} bytes, allocator={} bytes", 
-              sponsor_fixed.len(), allocator_fixed.len());
-        
-        // TypeScript uses ABI.encode(['bytes','bytes'], [sponsor, allocator])
-        // We need to replicate this by encoding the tuple first, then passing it
-        let sponsor_hex = format!("0x{}", hex::encode(&sponsor_fixed));
-        let allocator_hex = "0x"; // Empty bytes - exactly like TypeScript
-        
-        // Use cast abi-encode with function signature (without selector)
-        let tuple_encode_output = Command::new("cast")
-            .arg("abi-encode")
-            .arg("f(bytes,bytes)")  // Function signature - cast abi-encode doesn't add selector
-            .arg(&sponsor_hex)
-            .arg(&allocator_hex) // allocator empty bytes
-            .output()
-            .map_err(|e| anyhow::anyhow!("Failed to encode signatures tuple: {}", e))?;
-
-        if !tuple_encode_output.status.success() {
-            let stderr = String::from_utf8_lossy(&tuple_encode_output.stderr);
-            return Err(anyhow::anyhow!("Failed to encode signatures tuple: {}", stderr));
-        }
-
-        let signatures_arg = String::from_utf8(tuple_encode_output.stdout)?.trim().to_string(); // Already 0x... without selector
-
-        info!("len(signatures_arg) = {} chars ⇒ {} bytes", signatures_arg.len(), (signatures_arg.len() - 2) / 2);
-        
-        // Build other arguments without quotes - u32 for uint32[] type
-        let timestamps_arg = format!("[{}]", timestamps.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(","));
-        let solvers_arg = format!("[{}]", solvers.iter().map(|s| bytes32_hex(s)).collect::<Vec<_>>().join(","));
-        let destination_arg = bytes32_hex(destination);
-        let calls_arg = bytes_hex(calls);
-
-        // Log critical parameters that the contract validates
-        info!("🔍 CRITICAL VALIDATION PARAMETERS:");
-        info!("  Order nonce: {}", order.nonce);
-        info!("  Order user: 0x{}", hex::encode(order.user));
-        info!("  Order expires: {} (current time: {})", order.expires, std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs());
-        info!("  Fill deadline: {}", order.fillDeadline);
-        info!("  Timestamps[0]: {}", timestamps[0]);
-        info!("  Destination: {}", destination_arg);
-
-        info!("🔧 Cast arguments (no quotes, correct types):");
-        info!("  Function sig: {}", function_sig);
-        info!("  Order: {}", &order_arg[..200.min(order_arg.len())]);
-        info!("  Signatures: {} (length: {} chars = {} bytes)", signatures_arg, signatures_arg.len(), signatures_arg.len()/2 - 1);
-        info!("  Signatures breakdown: sponsor={} bytes, allocator={} bytes (empty)", sponsor_fixed.len(), allocator_fixed.len());
-        info!("  Timestamps: {}", timestamps_arg);
-        info!("  Solvers: {}", solvers_arg);
-
-        // Call cast abi-encode to obtain the parameter payload (without selector)
-        let output = Command::new("cast")
-            .arg("abi-encode")
-            .arg(function_sig)
-            .arg(&order_arg)
-            .arg(&signatures_arg)
-            .arg(&timestamps_arg)
-            .arg(&solvers_arg)
-            .arg(&destination_arg)
-            .arg(&calls_arg)
-            .output()
-            .map_err(|e| anyhow::anyhow!("Failed to run cast abi-encode: {}", e))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            return Err(anyhow::anyhow!("cast abi-encode failed:\nSTDERR: {}\nSTDOUT: {}", stderr, stdout));
-        }
-
-        let encoded_hex = String::from_utf8(output.stdout)?.trim().to_string();
-        
-        // Convert the full (selector + params) hex string to bytes
-        let encoded_hex = encoded_hex.strip_prefix("0x").unwrap_or(&encoded_hex);
-        // Parameters only (cast doesn't include selector when --selector isn't used)
-        let encoded_bytes = hex::decode(encoded_hex)
-            .map_err(|e| anyhow::anyhow!("Failed to decode hex from cast: {}", e))?;
-
-        // Prepend the correct selector constant (0xa80b6640)
-        const SELECTOR: [u8; 4] = [0xa8, 0x0b, 0x66, 0x40];
-        let calldata = [SELECTOR.as_slice(), &encoded_bytes[..]].concat();
-
-        info!("✅ Foundry cast abi-encode completed: {} bytes", calldata.len());
-
-        // DETAILED COMPARISON OUTPUT FOR TYPESCRIPT DEBUGGING
-        info!("🔬 RUST CALL DATA FOR TYPESCRIPT COMPARISON:");
-        info!("🔬 Rust CallData ({} chars = {} bytes):", calldata.len() * 2, calldata.len());
-        info!("🔬 0x{}", hex::encode(&calldata));
-        info!("🔬 END RUST CALL DATA");
-
-        // Critical runtime checks with asserts
-        info!("🔍 cast payload len = {}", calldata.len());  // Should be ~1348
-        info!("🔍 selector = 0x{}", hex::encode(&calldata[..4]));
-
-        // Assert payload is not absurdly short. For a single-output order the
-        // correct size is ~1349 bytes (matching TypeScript).
-        if calldata.len() < 1200 {
-            error!("❌ Calldata unexpectedly small: {} bytes (expected ≈1349).", calldata.len());
-            return Err(anyhow::anyhow!("Calldata too small: {} bytes, expected ≈1349", calldata.len()));
+        if chain_id == chains.destination.chain_id {
+            return self.destination_provider.clone().ok_or_else(|| anyhow::anyhow!("Destination provider not initialized"));
         }
-
-        // Sanity check on selector
-        if &calldata[..4] != SELECTOR {
-            return Err(anyhow::anyhow!("Unexpected selector after prefixing: 0x{}", hex::encode(&calldata[..4])));
+        if let Some(provider) = self.registry_providers.lock().unwrap().get(&chain_id) {
+            return Ok(provider.clone());
         }
 
-        // Informative check for expected size range (allows small variations
-        // depending on number of inputs/outputs). Should match TypeScript at 1349 bytes.
-        if (1345..=1355).contains(&calldata.len()) {
-            info!("🎉 SUCCESS! Cast payload = {} bytes (matches expected TypeScript ≈1349)", calldata.len());
-        } else {
-            warn!("⚠️  Cast payload = {} bytes (outside expected 1345-1355 range – verify vs TypeScript)", calldata.len());
-        }
-
-        // Debug: print first 64 bytes to check offset structure
-        if calldata.len() >= 64 {
-            info!("🔍 First 64 bytes: {}", hex::encode(&calldata[..64]));
-        }
+        let chain = chains.get(chain_id)
+            .ok_or_else(|| anyhow::anyhow!("Chain {} is not configured", chain_id))?;
+        let provider = Self::connect_read_provider(&format!("chain {}", chain_id), chain, chains.log_rpc_requests).await?;
+        self.registry_providers.lock().unwrap().insert(chain_id, provider.clone());
+        Ok(provider)
+    }
 
-        Ok(calldata)
+    pub fn get_wallet(&self) -> Result<&EthereumWallet> {
+        self.wallet.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Wallet not initialized"))
     }
 
+    // ⚠️ MOVED: EIP-712 domain and order signature checks live in contracts::signature
+
     // ⚠️ REMOVED: calculate_order_hash function - not needed since signature verification 
     // happens in the smart contract via TheCompact.batchClaim()
     
@@ -600,12 +429,21 @@ impl ContractFactory {
     }
 
     pub fn string_to_order_id(&self, order_id: &str) -> FixedBytes<32> {
-        keccak256(order_id.as_bytes())
+        crate::models::onchain_order_id(order_id)
     }
 
+    /// Current block of the origin and destination chains, reusing a height
+    /// fetched within the chain's `block_height_ttl_ms`
     pub async fn check_chain_connectivity(&self) -> Result<(u64, u64)> {
-        let origin_block = self.get_origin_provider()?.get_block_number().await?;
-        let dest_block = self.get_destination_provider()?.get_block_number().await?;
+        let (origin, destination) = (&self.config.chains.origin, &self.config.chains.destination);
+        let origin_provider = self.get_origin_provider()?;
+        let origin_block = self.block_heights.height(origin.chain_id, origin.block_height_ttl(), || async {
+            Ok(origin_provider.get_block_number().await?)
+        }).await?;
+        let dest_provider = self.get_destination_provider()?;
+        let dest_block = self.block_heights.height(destination.chain_id, destination.block_height_ttl(), || async {
+            Ok(dest_provider.get_block_number().await?)
+        }).await?;
         
         Ok((origin_block, dest_block))
     }
@@ -614,41 +452,107 @@ impl ContractFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{Order, StandardOrder};
+    use crate::models::{ExecutionPriority, Order, StandardOrder};
+    use alloy::primitives::U256;
     
     fn create_test_config() -> AppConfig {
         AppConfig {
             server: crate::config::ServerConfig {
                 host: "localhost".to_string(),
                 port: 8080,
+                log_format: Default::default(),
+                shutdown_grace_seconds: 30,
+                rate_limit: Default::default(),
+                admin_token: None,
             },
             solver: crate::config::SolverConfig {
                 private_key: "0x1111111111111111111111111111111111111111111111111111111111111111".to_string(),
                 finalization_delay_seconds: 30,
+                allowed_input_tokens: Vec::new(),
+                denied_input_tokens: Vec::new(),
+                allowed_users: Vec::new(),
+                denied_users: Vec::new(),
+                allowed_oracles: Vec::new(),
+                dry_run: false,
+                production: false,
+                min_profit_margin_bps: None,
+                token_prices: std::collections::HashMap::new(),
+                fill_amount_tolerance_bps: 0,
+                max_fill_retries: 3,
+                retry_backoff_seconds: vec![30, 120, 600],
+                skip_fill_proof_check: false,
+                max_inputs: None,
+                max_outputs: None,
+                order_id_mode: Default::default(),
+                simulate_finalization: true,
+                simulate_fill: true,
+                signature_scheme: Default::default(),
+                name: None,
             },
             chains: crate::config::ChainConfig {
                 origin: crate::config::ChainDetails {
                     chain_id: 1,
-                    rpc_url: "https://eth.llamarpc.com".to_string(),
+                    rpc_url: "https://eth.llamarpc.com".into(),
+                    read_rpc_url: None,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
                 destination: crate::config::ChainDetails {
                     chain_id: 137,
-                    rpc_url: "https://polygon.llamarpc.com".to_string(),
+                    rpc_url: "https://polygon.llamarpc.com".into(),
+                    read_rpc_url: None,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
+                registry: Default::default(),
+                warn_on_chain_id_mismatch: false,
+                log_rpc_requests: false,
             },
             contracts: crate::config::ContractConfig {
                 settler_compact: "0x1234567890123456789012345678901234567890".to_string(),
                 the_compact: "0x2345678901234567890123456789012345678901".to_string(),
                 coin_filler: "0x3456789012345678901234567890123456789012".to_string(),
+                fill_selector: None,
+                finalise_selector: None,
+                multicall: None,
+                abi_dir: None,
+                verify_domain_separator: Default::default(),
             },
             monitoring: crate::config::MonitoringConfig {
                 enabled: false,
                 check_interval_seconds: 60,
+                pause_unhealthy_chains: true,
+                max_concurrent_operations: 4,
+                max_batch_finalize: 500,
+                batch_chunk_size: 50,
             },
             persistence: crate::config::PersistenceConfig {
                 enabled: false,
                 data_file: "test_orders.json".to_string(),
+                backend: Default::default(),
+                database_url: None,
+                tx_journal_file: None,
+                max_orders_in_memory: None,
+                archive_file: None,
+                retention_seconds: None,
             },
+            coordinator: crate::config::CoordinatorConfig::default(),
+            balance: crate::config::BalanceConfig::default(),
+            gas: crate::config::GasConfig::default(),
+            backpressure: crate::config::BackpressureConfig::default(),
+            encoder: crate::config::EncoderConfig::default(),
+            callbacks: crate::config::CallbackConfig::default(),
+            allowances: Default::default(),
         }
     }
     
@@ -665,6 +569,18 @@ mod tests {
             fill_tx_hash: None,
             finalize_tx_hash: None,
             error_message: None,
+            filled_at: None,
+            fill_warning: None,
+            status_history: Vec::new(),
+            priority: ExecutionPriority::Normal,
+            fill_attempts: 0,
+            fill_retry_at: None,
+            correlation_id: "test".to_string(),
+            allocator_signature: None,
+            callback_url: None,
+            fill_gas: None,
+            output_fill_txs: Vec::new(),
+            fill_sending: false,
             standard_order: StandardOrder {
                 user: "0x1111111111111111111111111111111111111111".parse().unwrap(),
                 nonce: 123,
@@ -672,17 +588,18 @@ mod tests {
                 expires: 1752062605,
                 fill_deadline: 1752062605,
                 local_oracle: "0x2222222222222222222222222222222222222222".parse().unwrap(),
-                inputs: vec![("100".to_string(), "1000000000000000000".to_string())],
+                inputs: vec![(U256::from(100), U256::from(1000000000000000000u64))],
                 outputs: vec![
                     crate::models::MandateOutput {
                         remote_oracle: "0x3333333333333333333333333333333333333333".parse().unwrap(),
                         remote_filler: "0x4444444444444444444444444444444444444444".parse().unwrap(),
                         chain_id: 137,
                         token: "0x5555555555555555555555555555555555555555".parse().unwrap(),
-                        amount: "500000000000000000".to_string(),
+                        amount: U256::from(500000000000000000u64),
                         recipient: "0x6666666666666666666666666666666666666666".parse().unwrap(),
                         remote_call: None,
                         fulfillment_context: None,
+                        address_encoding: Default::default(),
                     }
                 ],
             },
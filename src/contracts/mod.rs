@@ -3,6 +3,7 @@ pub mod abi;
 pub mod encoding;
 pub mod execution;
 pub mod operations;
+pub mod signature;
 
 // Re-export key types for convenience
 pub use abi::*;
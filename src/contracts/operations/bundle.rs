@@ -0,0 +1,386 @@
+use anyhow::Result;
+use alloy::primitives::{Address, Bytes};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::config::AppConfig;
+use crate::contracts::encoding::{CallDataEncoder, SelectorOverrides, traits::FillRequest};
+use crate::contracts::execution::{ExecutionEngine, GasParams, TxJournal, TxOp, TxRecord, traits::{bid_gas_price, estimate_gas_limit, ChainType}};
+use crate::contracts::operations::{FillOrchestrator, FinalizationOrchestrator};
+use crate::models::Order;
+
+sol! {
+    interface Multicall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Call3Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Call3Result[] memory returnData);
+    }
+}
+
+/// How a fill + finalization pair was sent
+#[derive(Debug, Clone, PartialEq)]
+pub enum BundleOutcome {
+    /// Both calls went out atomically in one multicall transaction
+    Bundled { tx_hash: String },
+    /// The calls were sent as two transactions, fill first
+    Separate { fill_tx_hash: String, finalize_tx_hash: String },
+}
+
+/// Orchestrator that fills and finalizes an order in one multicall transaction
+/// when both calls target the same chain and a multicall contract is configured,
+/// and falls back to the regular fill and finalization orchestrators otherwise
+pub struct BundleOrchestrator {
+    fill_encoder: Arc<dyn CallDataEncoder>,
+    finalize_encoder: Arc<dyn CallDataEncoder>,
+    executor: Arc<dyn ExecutionEngine>,
+    config: Arc<AppConfig>,
+}
+
+impl BundleOrchestrator {
+    /// Create a BundleOrchestrator with dependency injection
+    pub fn new_with_traits(
+        fill_encoder: Arc<dyn CallDataEncoder>,
+        finalize_encoder: Arc<dyn CallDataEncoder>,
+        executor: Arc<dyn ExecutionEngine>,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        info!("🏗️ Creating BundleOrchestrator with injected traits");
+        info!("  Executor: {}", executor.description());
+
+        Self {
+            fill_encoder,
+            finalize_encoder,
+            executor,
+            config,
+        }
+    }
+
+    /// Create a BundleOrchestrator with the same defaults as the fill and
+    /// finalization orchestrators (AlloyEncoder, FoundryEncoder, AlloyExecutor)
+    pub fn new(
+        abi_provider: Arc<dyn crate::contracts::abi::AbiProvider>,
+        config: Arc<AppConfig>,
+    ) -> Result<Self> {
+        let selectors = SelectorOverrides::from_config(&config.contracts);
+        let fill_encoder = Arc::new(
+            crate::contracts::encoding::AlloyEncoder::new(abi_provider.clone())
+                .with_selector_overrides(selectors)
+                .with_max_calldata_bytes(config.encoder.max_calldata_bytes),
+        );
+        let finalize_encoder = Arc::new(
+            crate::contracts::encoding::FoundryEncoder::new(abi_provider)
+                .with_selector_overrides(selectors)
+                .with_max_calldata_bytes(config.encoder.max_calldata_bytes),
+        );
+        let executor = Arc::new(crate::contracts::execution::AlloyExecutor::new(config.clone())?);
+
+        Ok(Self::new_with_traits(fill_encoder, finalize_encoder, executor, config))
+    }
+
+    /// The multicall contract to bundle through, if bundling applies to this configuration
+    pub fn multicall_address(&self) -> Result<Option<Address>> {
+        let Some(multicall) = &self.config.contracts.multicall else {
+            return Ok(None);
+        };
+        if self.config.chains.origin.chain_id != self.config.chains.destination.chain_id {
+            return Ok(None);
+        }
+        let address = multicall.parse::<Address>()
+            .map_err(|e| anyhow::anyhow!("Invalid multicall address in config: {}", e))?;
+        Ok(Some(address))
+    }
+
+    /// Encode `Multicall3.aggregate3` calldata running the fill and then the finalization,
+    /// neither allowed to fail on its own
+    pub fn encode_bundle(&self, fill_request: &FillRequest, order: &Order) -> Result<Vec<u8>> {
+        let coin_filler_address: Address = self.config.contracts.coin_filler.parse()
+            .map_err(|e| anyhow::anyhow!("Invalid CoinFiller address in config: {}", e))?;
+        let settler_compact_address: Address = self.config.contracts.settler_compact.parse()
+            .map_err(|e| anyhow::anyhow!("Invalid SettlerCompact address in config: {}", e))?;
+
+        let fill_call = self.fill_encoder.encode_complete_fill_call(
+            fill_request,
+            coin_filler_address,
+            self.config.chains.destination.chain_id,
+            self.executor.wallet_address(),
+        )?;
+        let finalize_call = self.finalize_encoder.encode_finalize_call(order)?;
+
+        let calls = vec![
+            Multicall3::Call3 {
+                target: coin_filler_address,
+                allowFailure: false,
+                callData: Bytes::from(fill_call),
+            },
+            Multicall3::Call3 {
+                target: settler_compact_address,
+                allowFailure: false,
+                callData: Bytes::from(finalize_call),
+            },
+        ];
+        Ok(Multicall3::aggregate3Call { calls }.abi_encode())
+    }
+
+    /// Fill and finalize `order`, atomically when a multicall applies
+    pub async fn execute_fill_and_finalize(&self, fill_request: &FillRequest, order: &Order) -> Result<BundleOutcome> {
+        // The multicall runs on the configured origin, so orders opened elsewhere are never bundled
+        let multicall_address = match self.multicall_address()? {
+            Some(address) if order.standard_order.origin_chain_id == self.config.chains.origin.chain_id => Some(address),
+            _ => None,
+        };
+        let Some(multicall_address) = multicall_address else {
+            info!("📦 Bundling not available for order {}, sending fill and finalization separately", order.id);
+            return self.execute_separately(fill_request, order).await;
+        };
+
+        info!("📦 Bundling fill and finalization for order {} through multicall {}", order.id, multicall_address);
+        let call_data = self.encode_bundle(fill_request, order)?;
+
+        if self.config.solver.dry_run {
+            let gas_estimate = self.executor
+                .estimate_gas(ChainType::Origin, call_data, multicall_address, self.executor.wallet_address())
+                .await?;
+            info!("🧪 DRY RUN: bundle for order {} encoded and estimated at {} gas, not broadcast", order.id, gas_estimate);
+            return Ok(BundleOutcome::Bundled {
+                tx_hash: format!("{}{}", crate::models::DRY_RUN_TX_PREFIX, gas_estimate),
+            });
+        }
+
+        // The estimate covers both legs; without one, leave room for the fill (360k)
+        // and the finalization (650k)
+        let profile = self.config.gas.for_chain(&self.config.chains.origin);
+        let gas_limit = estimate_gas_limit(
+            self.executor.as_ref(),
+            ChainType::Origin,
+            call_data.clone(),
+            multicall_address,
+            360000 + 650000,
+            profile.buffer_pct,
+        ).await;
+        let gas_price = bid_gas_price(self.executor.as_ref(), ChainType::Origin, profile.strategy, 50_000_000_000u64).await;
+        let gas_params = GasParams::from_profile(gas_limit, gas_price, &profile);
+        let tx_hash = self.executor
+            .send_transaction(ChainType::Origin, call_data, multicall_address, gas_params)
+            .await?;
+        if let Some(journal) = TxJournal::from_config(&self.config.persistence) {
+            let record = TxRecord::sent(&order.id.to_string(), TxOp::Bundle, self.config.chains.origin.chain_id, multicall_address, &tx_hash);
+            journal.record_sent(self.executor.as_ref(), ChainType::Origin, record).await;
+        }
+
+        info!("✅ Bundled fill and finalization completed: {}", tx_hash);
+        Ok(BundleOutcome::Bundled { tx_hash })
+    }
+
+    async fn execute_separately(&self, fill_request: &FillRequest, order: &Order) -> Result<BundleOutcome> {
+        let fill_orchestrator = FillOrchestrator::new_with_traits(
+            self.fill_encoder.clone(),
+            self.executor.clone(),
+            self.config.clone(),
+        )?;
+        let fill_tx_hash = fill_orchestrator.execute_fill_request(fill_request).await?;
+
+        let finalization_orchestrator = FinalizationOrchestrator::new_with_traits(
+            self.finalize_encoder.clone(),
+            self.executor.clone(),
+            self.config.clone(),
+        );
+        let finalize_tx_hash = finalization_orchestrator.execute_finalization(order).await?;
+
+        Ok(BundleOutcome::Separate { fill_tx_hash, finalize_tx_hash })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::AbiRegistry;
+    use crate::contracts::encoding::AlloyEncoder;
+    use crate::contracts::execution::TransactionReceiptInfo;
+    use crate::models::{MandateOutput, StandardOrder};
+    use alloy::primitives::U256;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const MULTICALL: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+    /// Executor that counts transactions and the contracts they were sent to
+    #[derive(Default)]
+    struct MockExecutor {
+        sent: std::sync::Mutex<Vec<Address>>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for MockExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, to: Address, _gas: GasParams) -> Result<String> {
+            self.sent.lock().unwrap().push(to);
+            Ok(format!("0x{:02x}", self.calls.fetch_add(1, Ordering::SeqCst)))
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        async fn get_receipt(&self, _chain: ChainType, tx_hash: &str) -> Result<TransactionReceiptInfo> {
+            Ok(TransactionReceiptInfo { tx_hash: tx_hash.to_string(), status: true, gas_used: 21000, effective_gas_price: 1_000_000_000, logs: Vec::new() })
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "MockExecutor"
+        }
+    }
+
+    fn create_test_config(same_chain: bool) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.contracts.coin_filler = "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string();
+        config.contracts.settler_compact = "0x5FC8d32690cc91D4c39d9d3abcBD16989F875707".to_string();
+        config.contracts.multicall = Some(MULTICALL.to_string());
+        config.persistence.enabled = false;
+        if same_chain {
+            config.chains.destination.chain_id = config.chains.origin.chain_id;
+        }
+        config
+    }
+
+    fn create_test_order() -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce: 1,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31337,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        };
+        Order::new(standard_order, format!("0x{}", "ab".repeat(65)))
+    }
+
+    fn fill_request(order: &Order) -> FillRequest {
+        let output = &order.standard_order.outputs[0];
+        FillRequest {
+            order_id: order.id.to_string(),
+            fill_deadline: order.standard_order.fill_deadline as u32,
+            remote_oracle: output.remote_oracle,
+            token: output.token,
+            amount: U256::from(99u64),
+            recipient: output.recipient,
+            address_encoding: Default::default(),
+            chain_id: None,
+        }
+    }
+
+    fn create_orchestrator(config: AppConfig, executor: Arc<MockExecutor>) -> BundleOrchestrator {
+        let encoder = Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new())));
+        BundleOrchestrator::new_with_traits(encoder.clone(), encoder, executor, Arc::new(config))
+    }
+
+    #[tokio::test]
+    async fn test_bundle_contains_fill_and_finalize_calls() {
+        let config = create_test_config(true);
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = create_orchestrator(config.clone(), executor.clone());
+        let order = create_test_order();
+        let request = fill_request(&order);
+
+        let encoder = AlloyEncoder::new(Arc::new(AbiRegistry::new()));
+        let coin_filler: Address = config.contracts.coin_filler.parse().unwrap();
+        let settler_compact: Address = config.contracts.settler_compact.parse().unwrap();
+        let fill_call = encoder
+            .encode_complete_fill_call(&request, coin_filler, config.chains.destination.chain_id, executor.wallet_address())
+            .unwrap();
+        let finalize_call = encoder.encode_finalize_call(&order).unwrap();
+
+        let bundle = orchestrator.encode_bundle(&request, &order).unwrap();
+        assert_eq!(bundle[..4], Multicall3::aggregate3Call::SELECTOR);
+        let decoded = Multicall3::aggregate3Call::abi_decode(&bundle).unwrap();
+        assert_eq!(decoded.calls.len(), 2);
+        assert_eq!(decoded.calls[0].target, coin_filler);
+        assert_eq!(decoded.calls[0].callData.as_ref(), fill_call.as_slice());
+        assert_eq!(decoded.calls[1].target, settler_compact);
+        assert_eq!(decoded.calls[1].callData.as_ref(), finalize_call.as_slice());
+        assert!(decoded.calls.iter().all(|call| !call.allowFailure));
+
+        // One transaction, sent to the multicall contract
+        let outcome = orchestrator.execute_fill_and_finalize(&request, &order).await.unwrap();
+        assert_eq!(outcome, BundleOutcome::Bundled { tx_hash: "0x00".to_string() });
+        assert_eq!(*executor.sent.lock().unwrap(), vec![MULTICALL.parse::<Address>().unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn test_cross_chain_order_falls_back_to_separate_transactions() {
+        let config = create_test_config(false);
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = create_orchestrator(config.clone(), executor.clone());
+        let order = create_test_order();
+
+        assert_eq!(orchestrator.multicall_address().unwrap(), None);
+        let outcome = orchestrator.execute_fill_and_finalize(&fill_request(&order), &order).await.unwrap();
+
+        assert_eq!(outcome, BundleOutcome::Separate {
+            fill_tx_hash: "0x00".to_string(),
+            finalize_tx_hash: "0x01".to_string(),
+        });
+        let sent = executor.sent.lock().unwrap();
+        assert_eq!(sent[0], config.contracts.coin_filler.parse::<Address>().unwrap());
+        assert_eq!(sent[1], config.contracts.settler_compact.parse::<Address>().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sent_transactions_are_journaled() {
+        let mut config = create_test_config(false);
+        let journal_path = std::env::temp_dir().join(format!("oif-txs-test-{}.jsonl", uuid::Uuid::new_v4()));
+        config.persistence.tx_journal_file = Some(journal_path.to_string_lossy().into_owned());
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = create_orchestrator(config.clone(), executor);
+        let order = create_test_order();
+
+        orchestrator.execute_fill_and_finalize(&fill_request(&order), &order).await.unwrap();
+
+        let content = std::fs::read_to_string(&journal_path).unwrap();
+        assert_eq!(content.lines().count(), 2, "one line per transaction");
+        let records = TxJournal::new(&journal_path).read_all().await.unwrap();
+        let (fill, finalize) = (&records[0], &records[1]);
+
+        assert_eq!(fill.order_id, order.id.to_string());
+        assert_eq!(fill.op_type, TxOp::Fill);
+        assert_eq!(fill.chain_id, config.chains.destination.chain_id);
+        assert_eq!(fill.to, config.contracts.coin_filler.parse::<Address>().unwrap());
+        assert_eq!(fill.tx_hash, "0x00");
+        assert_eq!(fill.gas_used, Some(21000));
+
+        assert_eq!(finalize.order_id, order.id.to_string());
+        assert_eq!(finalize.op_type, TxOp::Finalize);
+        assert_eq!(finalize.chain_id, config.chains.origin.chain_id);
+        assert_eq!(finalize.to, config.contracts.settler_compact.parse::<Address>().unwrap());
+        assert_eq!(finalize.tx_hash, "0x01");
+        assert!(finalize.timestamp >= fill.timestamp);
+
+        std::fs::remove_file(&journal_path).ok();
+    }
+}
@@ -2,10 +2,55 @@ use anyhow::Result;
 use tracing::info;
 use std::sync::Arc;
 
-use crate::contracts::encoding::{CallDataEncoder, traits::FillRequest};
-use crate::contracts::execution::{ExecutionEngine, traits::ChainType};
-use crate::config::AppConfig;
-use alloy::primitives::{Address, FixedBytes, U256};
+use crate::contracts::encoding::{CallDataEncoder, traits::{AddressEncoding, FillRequest}};
+use crate::contracts::execution::{ExecutionEngine, IntentJournal, TxJournal, TxOp, TxRecord, intent, traits::{bid_gas_price, ensure_affordable, estimate_gas_limit, ChainType, GasParams, TransactionReceiptInfo}};
+use crate::config::{AppConfig, ChainDetails};
+use alloy::primitives::{keccak256, Address, FixedBytes, U256};
+use alloy::sol_types::{SolCall, SolValue};
+use crate::contracts::factory::{CoinFiller, IERC20};
+use crate::models::GasUsage;
+
+/// Canonical `CoinFiller.OutputFilled` signature, matching the AbiRegistry definition
+const OUTPUT_FILLED_EVENT: &str =
+    "OutputFilled(bytes32,bytes32,uint32,(bytes32,bytes32,uint256,bytes32,uint256,bytes32,bytes,bytes))";
+
+/// CoinFiller already holds a fill record for the output, e.g. from a send made
+/// before a restart whose tx hash was never persisted
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Output for order {order_id} was already filled on-chain by solver {solver} at {timestamp}")]
+pub struct AlreadyFilled {
+    pub order_id: String,
+    pub solver: FixedBytes<32>,
+    pub timestamp: u32,
+}
+
+/// Refuse a fill whose encoded `output.amount` differs from `expected` by more than
+/// `tolerance_bps` basis points, so an encoding regression never reaches the chain
+pub fn check_fill_amount(call_data: &[u8], expected: U256, tolerance_bps: u32) -> Result<()> {
+    if call_data.len() < 4 {
+        return Err(anyhow::anyhow!("Fill call data is too short to hold a selector"));
+    }
+    // Decode past the selector, which may be overridden in config
+    let encoded = CoinFiller::fillCall::abi_decode_raw(&call_data[4..])
+        .map_err(|e| anyhow::anyhow!("Could not decode fill call data: {}", e))?
+        .output
+        .amount;
+
+    let difference = if encoded > expected { encoded - expected } else { expected - encoded };
+    let tolerance = expected * U256::from(tolerance_bps) / U256::from(10_000u64);
+    if difference > tolerance {
+        return Err(anyhow::anyhow!(
+            "Encoded fill amount {} does not match the order's output amount {} (tolerance {} bps)",
+            encoded, expected, tolerance_bps
+        ));
+    }
+    Ok(())
+}
+
+/// `IERC20.approve(spender, amount)` calldata
+pub fn approve_call_data(spender: Address, amount: U256) -> Vec<u8> {
+    IERC20::approveCall { spender, amount }.abi_encode()
+}
 
 /// High-level orchestrator for fill order operations
 /// 
@@ -16,6 +61,8 @@ pub struct FillOrchestrator {
     encoder: Arc<dyn CallDataEncoder>,
     executor: Arc<dyn ExecutionEngine>,
     config: Arc<AppConfig>,
+    intent_journal: Option<IntentJournal>,
+    tx_journal: Option<TxJournal>,
 }
 
 impl FillOrchestrator {
@@ -37,10 +84,24 @@ impl FillOrchestrator {
         Ok(Self {
             encoder,
             executor,
+            tx_journal: TxJournal::from_config(&config.persistence),
             config,
+            intent_journal: None,
         })
     }
     
+    /// Record transaction intents in `journal` so a fill is never broadcast twice
+    pub fn with_intent_journal(mut self, journal: IntentJournal) -> Self {
+        self.intent_journal = Some(journal);
+        self
+    }
+    
+    /// Log every fill sent to `journal`
+    pub fn with_tx_journal(mut self, journal: TxJournal) -> Self {
+        self.tx_journal = Some(journal);
+        self
+    }
+    
     /// Create a FillOrchestrator with default implementations
     /// 
     /// For convenience, this creates a FillOrchestrator with:
@@ -53,10 +114,18 @@ impl FillOrchestrator {
         info!("🏗️ Creating FillOrchestrator with default AlloyEncoder + AlloyExecutor");
         
         // Create default encoder and executor
-        let encoder = Arc::new(crate::contracts::encoding::AlloyEncoder::new(abi_provider));
+        let encoder = Arc::new(
+            crate::contracts::encoding::AlloyEncoder::new(abi_provider)
+                .with_selector_overrides(crate::contracts::encoding::SelectorOverrides::from_config(&config.contracts))
+                .with_max_calldata_bytes(config.encoder.max_calldata_bytes),
+        );
         let executor = Arc::new(crate::contracts::execution::AlloyExecutor::new(config.clone())?);
         
-        Self::new_with_traits(encoder, executor, config)
+        let orchestrator = Self::new_with_traits(encoder, executor, config.clone())?;
+        if config.persistence.enabled {
+            return Ok(orchestrator.with_intent_journal(IntentJournal::for_data_file(&config.persistence.data_file)));
+        }
+        Ok(orchestrator)
     }
     
     /// Execute a fill order using the modular architecture
@@ -75,50 +144,98 @@ impl FillOrchestrator {
         amount: U256,
         recipient: Address,
     ) -> Result<String> {
-        info!("🚀 MODULAR FILL: Executing fill order with abstract architecture");
-        info!("  Order ID: {}", order_id);
-        info!("  Remote Oracle: {:?}", remote_oracle);
-        info!("  Token: {:?}", token);
-        info!("  Amount: {}", amount);
-        info!("  Recipient: {:?}", recipient);
-        
-        // Step 1: Create high-level fill request
-        let fill_request = FillRequest {
+        self.execute_fill_request(&FillRequest {
             order_id: order_id.to_string(),
             fill_deadline,
             remote_oracle,
             token,
             amount,
             recipient,
-        };
+            address_encoding: AddressEncoding::LeftPad20,
+            chain_id: None,
+        }).await
+    }
+
+    /// Execute a fill described by a complete `FillRequest`
+    pub async fn execute_fill_request(&self, fill_request: &FillRequest) -> Result<String> {
+        let order_id = fill_request.order_id.as_str();
+        let amount = fill_request.amount;
+        info!("🚀 MODULAR FILL: Executing fill order with abstract architecture");
+        info!("  Order ID: {}", order_id);
+        info!("  Remote Oracle: {:?}", fill_request.remote_oracle);
+        info!("  Token: {:?}", fill_request.token);
+        info!("  Amount: {}", amount);
+        info!("  Recipient: {:?}", fill_request.recipient);
+        info!("  Address encoding: {:?}", fill_request.address_encoding);
         
-        // Step 2: Get destination chain contract address and parameters
-        let coin_filler_address: Address = self.config.contracts.coin_filler.parse()
-            .map_err(|e| anyhow::anyhow!("Invalid CoinFiller address in config: {}", e))?;
-        let destination_chain_id = self.config.chains.destination.chain_id;
+        // Step 2: Get the output chain's contract address and parameters
+        let (chain, chain_details, coin_filler_address) = self.output_chain(fill_request.chain_id)?;
+        let destination_chain_id = chain_details.chain_id;
+        info!("  Chain: {} ({:?})", destination_chain_id, chain);
         let solver_address = self.executor.wallet_address();
         
         // Step 3: Generate COMPLETE call data using the trait method (matches factory-bkp.rs)
         info!("🔧 Encoding COMPLETE fill call data...");
         let call_data = self.encoder.encode_complete_fill_call(
-            &fill_request,
+            fill_request,
             coin_filler_address,
             destination_chain_id,
             solver_address,
         )?;
         
-        // Step 5: Execute transaction using the executor
+        // Step 4: Make sure the encoded amount is the one the order declared
+        check_fill_amount(&call_data, amount, self.config.solver.fill_amount_tolerance_bps)?;
+        
+        // Step 5: Never fill an output CoinFiller has already recorded as filled
+        if let Some(filled) = self.existing_fill(order_id, chain, &call_data, coin_filler_address).await {
+            info!("⏭️ {}, not broadcasting", filled);
+            return Err(filled.into());
+        }
+        
+        // Step 6: In dry-run mode, stop after gas estimation
+        if self.config.solver.dry_run {
+            let gas_estimate = self.executor
+                .estimate_gas(chain, call_data, coin_filler_address, solver_address)
+                .await?;
+            info!("🧪 DRY RUN: fill for order {} encoded and estimated at {} gas, not broadcast", order_id, gas_estimate);
+            return Ok(format!("{}{}", crate::models::DRY_RUN_TX_PREFIX, gas_estimate));
+        }
+        
+        // Step 7: Let CoinFiller pull the output token, if configured to
+        if self.config.allowances.auto_approve && fill_request.token != crate::models::NATIVE_TOKEN {
+            self.ensure_allowance(order_id, chain, destination_chain_id, fill_request.token, coin_filler_address, amount).await?;
+        }
+        
+        // Step 8: Make sure the fill would not revert, e.g. for a missing allowance or a passed deadline
+        if self.config.solver.simulate_fill {
+            self.simulate_fill(fill_request, chain, &call_data, coin_filler_address).await?;
+        }
+        
+        // Step 9: Execute transaction using the executor
         info!("📡 Sending fill transaction...");
-        let gas_params = crate::contracts::execution::traits::GasParams {
-            gas_limit: 360000u64, // Gas limit matching TypeScript
-            gas_price: 50_000_000_000u64, // Gas price (50 gwei)
-        };
-        let tx_hash = self.executor.send_transaction(
-            ChainType::Destination, // Fill operations execute on destination chain
+        let mut gas_params = self.fill_gas_params(chain, chain_details, call_data.clone(), coin_filler_address).await;
+        crate::services::gas::check_gas_price_cap(chain_details, gas_params.gas_price as u128)?;
+        // Native outputs are paid with the transaction's value rather than a token transfer
+        if fill_request.token == crate::models::NATIVE_TOKEN {
+            gas_params = gas_params.with_value(amount);
+        }
+        info!("  Gas limit: {} (sent)", gas_params.gas_limit);
+        ensure_affordable(self.executor.as_ref(), chain, &gas_params).await?;
+        // Journaled per output, so another output of the order filled on this chain
+        // never adopts this fill's transaction
+        let tx_hash = intent::send_once(
+            self.executor.as_ref(),
+            self.intent_journal.as_ref(),
+            &format!("{}:{:?}", order_id, keccak256(&call_data)),
+            chain, // Fill operations execute on the output's chain
             call_data,
             coin_filler_address,
             gas_params,
         ).await?;
+        if let Some(journal) = &self.tx_journal {
+            let record = TxRecord::sent(order_id, TxOp::Fill, destination_chain_id, coin_filler_address, &tx_hash);
+            journal.record_sent(self.executor.as_ref(), chain, record).await;
+        }
         
         info!("✅ Modular fill completed successfully: {}", tx_hash);
         Ok(tx_hash)
@@ -134,45 +251,209 @@ impl FillOrchestrator {
         amount: U256,
         recipient: Address,
     ) -> Result<u64> {
-        info!("⛽ Estimating fill gas using modular architecture");
-        
-        // Create fill request
-        let fill_request = FillRequest {
+        let gas_params = self.estimate_fill_request_gas(&FillRequest {
             order_id: order_id.to_string(),
             fill_deadline,
             remote_oracle,
             token,
             amount,
             recipient,
-        };
+            address_encoding: AddressEncoding::LeftPad20,
+            chain_id: None,
+        }).await?;
+        Ok(gas_params.gas_limit)
+    }
+
+    /// Gas limit and price the fill described by `fill_request` would be sent with
+    pub async fn estimate_fill_request_gas(&self, fill_request: &FillRequest) -> Result<GasParams> {
+        info!("⛽ Estimating fill gas using modular architecture");
         
         // Generate COMPLETE call data with proper configuration
-        let coin_filler_address: Address = self.config.contracts.coin_filler.parse()
-            .map_err(|e| anyhow::anyhow!("Invalid CoinFiller address in config: {}", e))?;
-        let destination_chain_id = self.config.chains.destination.chain_id;
+        let (chain, chain_details, coin_filler_address) = self.output_chain(fill_request.chain_id)?;
         let solver_address = self.executor.wallet_address();
         
         let call_data = self.encoder.encode_complete_fill_call(
-            &fill_request,
+            fill_request,
             coin_filler_address,
-            destination_chain_id,
+            chain_details.chain_id,
             solver_address,
         )?;
         
-        // Get contract address
-        let coin_filler_address: Address = self.config.contracts.coin_filler.parse()
-            .map_err(|e| anyhow::anyhow!("Invalid CoinFiller address in config: {}", e))?;
+        // Estimate gas on the output's chain, exactly as the fill is sent
+        let gas_params = self.fill_gas_params(chain, chain_details, call_data, coin_filler_address).await;
         
-        // Estimate gas
-        let gas_estimate = self.executor.estimate_gas(
-            ChainType::Destination, // Fill operations estimate on destination chain
+        info!("✅ Fill gas estimation completed: {} gas at {} wei", gas_params.gas_limit, gas_params.gas_price);
+        Ok(gas_params)
+    }
+
+    /// Gas for sending the fill `call_data` on `chain`: the node's estimate plus the
+    /// chain's `buffer_pct` (or the default fill limit), at the bid price within its bounds
+    async fn fill_gas_params(&self, chain: ChainType, chain_details: &ChainDetails, call_data: Vec<u8>, coin_filler: Address) -> GasParams {
+        let profile = self.config.gas.for_chain(chain_details);
+        let gas_limit = estimate_gas_limit(
+            self.executor.as_ref(),
+            chain,
             call_data,
+            coin_filler,
+            360000u64, // Gas limit matching TypeScript
+            profile.buffer_pct,
+        ).await;
+        let gas_price = bid_gas_price(self.executor.as_ref(), chain, profile.strategy, 50_000_000_000u64).await;
+        GasParams::from_profile(gas_limit, gas_price, &profile)
+    }
+    
+    /// Chain an output on `chain_id` is filled on, and the CoinFiller deployed there
+    ///
+    /// The configured destination (or no chain id) keeps `ChainType::Destination`;
+    /// any other chain is looked up in the registry.
+    fn output_chain(&self, chain_id: Option<u64>) -> Result<(ChainType, &ChainDetails, Address)> {
+        let destination = &self.config.chains.destination;
+        let (chain, details) = match chain_id {
+            None => (ChainType::Destination, destination),
+            Some(id) if id == destination.chain_id => (ChainType::Destination, destination),
+            Some(id) => {
+                let details = self.config.chains.get(id).ok_or_else(|| {
+                    anyhow::anyhow!("Output is paid on chain {}, which is not configured", id)
+                })?;
+                (ChainType::ById(id), details)
+            }
+        };
+        let coin_filler = details.coin_filler.as_deref().unwrap_or(&self.config.contracts.coin_filler);
+        let coin_filler_address: Address = coin_filler.parse()
+            .map_err(|e| anyhow::anyhow!("Invalid CoinFiller address for chain {}: {}", details.chain_id, e))?;
+        Ok((chain, details, coin_filler_address))
+    }
+    
+    /// Inspect a mined fill transaction beyond its status flag
+    ///
+    /// The check's `warning` is set when the fill should be flagged; `Err` means
+    /// the receipt can't be fetched.
+    pub async fn verify_fill(
+        &self,
+        tx_hash: &str,
+        order_id: &str,
+        chain_id: Option<u64>,
+        recipient: Address,
+        has_remote_call: bool,
+    ) -> Result<FillCheck> {
+        let (chain, _, coin_filler_address) = self.output_chain(chain_id)?;
+        let receipt = self.executor.get_receipt(chain, tx_hash).await?;
+        
+        let warning = check_fill_receipt(
+            &receipt,
             coin_filler_address,
-            self.executor.wallet_address(), // Use executor's wallet as from address
-        ).await?;
+            crate::models::onchain_order_id(order_id),
+            recipient,
+            has_remote_call,
+        ).err();
+        Ok(FillCheck { warning, gas: receipt.gas_usage() })
+    }
+    
+    /// Ask CoinFiller for the fill record of the output encoded in `call_data`.
+    /// A failed lookup is logged and treated as "not filled" so fills still go ahead.
+    async fn existing_fill(&self, order_id: &str, chain: ChainType, call_data: &[u8], coin_filler: Address) -> Option<AlreadyFilled> {
+        let lookup = async {
+            let fill = CoinFiller::fillCall::abi_decode_raw(&call_data[4..])?;
+            let query = CoinFiller::filledOutputsCall {
+                orderId: fill.orderId,
+                outputHash: keccak256(fill.output.abi_encode()),
+            };
+            let result = self.executor
+                .static_call(chain, query.abi_encode(), coin_filler, self.executor.wallet_address())
+                .await?;
+            if result.is_empty() {
+                return Ok(None);
+            }
+            Ok::<_, anyhow::Error>(Some(CoinFiller::filledOutputsCall::abi_decode_returns(&result)?))
+        };
         
-        info!("✅ Fill gas estimation completed: {} gas", gas_estimate);
-        Ok(gas_estimate)
+        match lookup.await {
+            Ok(Some(record)) if record.solver != FixedBytes::ZERO => Some(AlreadyFilled {
+                order_id: order_id.to_string(),
+                solver: record.solver,
+                timestamp: record.timestamp,
+            }),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Could not check on-chain fill status for order {}: {}", order_id, e);
+                None
+            }
+        }
+    }
+    
+    /// The solver's allowance for `spender` on `token`
+    pub async fn allowance(&self, chain: ChainType, token: Address, spender: Address) -> Result<U256> {
+        let owner = self.executor.wallet_address();
+        let result = self.executor
+            .static_call(chain, IERC20::allowanceCall { owner, spender }.abi_encode(), token, owner)
+            .await?;
+        IERC20::allowanceCall::abi_decode_returns(&result)
+            .map_err(|e| anyhow::anyhow!("Unexpected allowance response from token {}: {}", token, e))
+    }
+
+    /// Approve `spender` for `allowances.approve_amount` (at least `required`) of `token`
+    /// unless the current allowance already covers `required`; returns the approval's tx hash if one was sent
+    async fn ensure_allowance(
+        &self,
+        order_id: &str,
+        chain: ChainType,
+        chain_id: u64,
+        token: Address,
+        spender: Address,
+        required: U256,
+    ) -> Result<Option<String>> {
+        let allowance = self.allowance(chain, token, spender).await?;
+        if allowance >= required {
+            return Ok(None);
+        }
+
+        let amount = self.config.allowances.approve_amount()?.max(required);
+        info!("🔓 Allowance {} of token {} for {} does not cover {}, approving {}", allowance, token, spender, required, amount);
+        let call_data = approve_call_data(spender, amount);
+        let (_, chain_details, _) = self.output_chain(Some(chain_id))?;
+        let profile = self.config.gas.for_chain(chain_details);
+        let gas_limit = estimate_gas_limit(self.executor.as_ref(), chain, call_data.clone(), token, 100000u64, profile.buffer_pct).await;
+        let gas_price = bid_gas_price(self.executor.as_ref(), chain, profile.strategy, 50_000_000_000u64).await;
+        let gas_params = GasParams::from_profile(gas_limit, gas_price, &profile);
+        crate::services::gas::check_gas_price_cap(chain_details, gas_params.gas_price as u128)?;
+        ensure_affordable(self.executor.as_ref(), chain, &gas_params).await?;
+        // Journaled under its own key so a recorded approve is never mistaken for the fill
+        let tx_hash = intent::send_once(
+            self.executor.as_ref(),
+            self.intent_journal.as_ref(),
+            &format!("{}:approve", order_id),
+            chain,
+            call_data,
+            token,
+            gas_params,
+        ).await?;
+        if let Some(journal) = &self.tx_journal {
+            let record = TxRecord::sent(order_id, TxOp::Approve, chain_id, token, &tx_hash);
+            journal.record_sent(self.executor.as_ref(), chain, record).await;
+        }
+        info!("✅ Approved {} of token {} for {}: {}", amount, token, spender, tx_hash);
+        Ok(Some(tx_hash))
+    }
+
+    /// Run the fill call as an `eth_call` from the solver's address, failing with the
+    /// revert reason instead of broadcasting a transaction that would revert
+    ///
+    /// Native outputs are skipped: the call carries no value, so it would always revert.
+    async fn simulate_fill(&self, fill_request: &FillRequest, chain: ChainType, call_data: &[u8], to: Address) -> Result<()> {
+        let order_id = &fill_request.order_id;
+        if fill_request.token == crate::models::NATIVE_TOKEN {
+            info!("Not simulating native fill for order {}", order_id);
+            return Ok(());
+        }
+        info!("🔍 Simulating fill for order {}", order_id);
+        match self.executor.static_call(chain, call_data.to_vec(), to, self.executor.wallet_address()).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                tracing::error!("❌ Fill of order {} failed in simulation, not sending: {}", order_id, e);
+                let message = format!("Fill simulation failed for order {}: {}", order_id, e);
+                Err(e.context(message))
+            }
+        }
     }
     
     /// Get the wallet address used by this orchestrator
@@ -206,47 +487,179 @@ impl FillOrchestrator {
     }
 }
 
+/// What a mined fill's receipt shows
+#[derive(Debug, Clone)]
+pub struct FillCheck {
+    /// Why the fill needs attention, if it does
+    pub warning: Option<String>,
+    pub gas: GasUsage,
+}
+
+/// Check that a fill receipt shows the output was actually filled
+///
+/// A successful status alone is not enough: CoinFiller emits `OutputFilled`
+/// for the order only when this transaction performed the fill, and when the
+/// output carries a `remoteCall` the recipient must have emitted at least one
+/// event, showing that the callback ran.
+pub fn check_fill_receipt(
+    receipt: &TransactionReceiptInfo,
+    coin_filler: Address,
+    order_id: FixedBytes<32>,
+    recipient: Address,
+    has_remote_call: bool,
+) -> Result<(), String> {
+    if !receipt.status {
+        return Err(format!("Fill transaction {} reverted", receipt.tx_hash));
+    }
+
+    let output_filled = keccak256(OUTPUT_FILLED_EVENT.as_bytes());
+    let filled = receipt.logs.iter().any(|log| {
+        log.address == coin_filler
+            && log.topics.first() == Some(&output_filled)
+            && log.topics.get(1) == Some(&order_id)
+    });
+    if !filled {
+        return Err(format!(
+            "Fill transaction {} emitted no OutputFilled event for order 0x{}",
+            receipt.tx_hash, hex::encode(order_id)
+        ));
+    }
+
+    if has_remote_call && !receipt.logs.iter().any(|log| log.address == recipient) {
+        return Err(format!(
+            "Fill transaction {} succeeded but remoteCall target {} emitted no events; the remote call did not execute as expected",
+            receipt.tx_hash, recipient
+        ));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::contracts::abi::AbiRegistry;
     use crate::contracts::encoding::AlloyEncoder;
     use crate::contracts::execution::AlloyExecutor;
+    use crate::contracts::execution::traits::ReceiptLog;
     use std::str::FromStr;
 
+    fn fill_receipt(logs: Vec<ReceiptLog>) -> TransactionReceiptInfo {
+        TransactionReceiptInfo {
+            tx_hash: "0xfill".to_string(),
+            status: true,
+            gas_used: 120000,
+            effective_gas_price: 1_000_000_000,
+            logs,
+        }
+    }
+
+    fn output_filled_log(coin_filler: Address, order_id: FixedBytes<32>) -> ReceiptLog {
+        ReceiptLog {
+            address: coin_filler,
+            topics: vec![keccak256(OUTPUT_FILLED_EVENT.as_bytes()), order_id],
+            data: Vec::new(),
+        }
+    }
+
     fn create_test_config() -> AppConfig {
         crate::config::AppConfig {
             server: crate::config::ServerConfig {
                 host: "localhost".to_string(),
                 port: 8080,
+                log_format: Default::default(),
+                shutdown_grace_seconds: 30,
+                rate_limit: Default::default(),
+                admin_token: None,
             },
             solver: crate::config::SolverConfig {
                 private_key: "0x1111111111111111111111111111111111111111111111111111111111111111".to_string(),
                 finalization_delay_seconds: 30,
+                allowed_input_tokens: Vec::new(),
+                denied_input_tokens: Vec::new(),
+                allowed_users: Vec::new(),
+                denied_users: Vec::new(),
+                allowed_oracles: Vec::new(),
+                dry_run: false,
+                production: false,
+                min_profit_margin_bps: None,
+                token_prices: std::collections::HashMap::new(),
+                fill_amount_tolerance_bps: 0,
+                max_fill_retries: 3,
+                retry_backoff_seconds: vec![30, 120, 600],
+                skip_fill_proof_check: false,
+                max_inputs: None,
+                max_outputs: None,
+                order_id_mode: Default::default(),
+                simulate_finalization: true,
+                simulate_fill: true,
+                signature_scheme: Default::default(),
+                name: None,
             },
             chains: crate::config::ChainConfig {
                 origin: crate::config::ChainDetails {
                     chain_id: 31337,
-                    rpc_url: "http://localhost:8545".to_string(),
+                    rpc_url: "http://localhost:8545".into(),
+                    read_rpc_url: None,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
                 destination: crate::config::ChainDetails {
                     chain_id: 31338,
-                    rpc_url: "http://localhost:8546".to_string(),
+                    rpc_url: "http://localhost:8546".into(),
+                    read_rpc_url: None,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
+                registry: Default::default(),
+                warn_on_chain_id_mismatch: false,
+                log_rpc_requests: false,
             },
             contracts: crate::config::ContractConfig {
                 settler_compact: "0x1234567890123456789012345678901234567890".to_string(),
                 the_compact: "0x2345678901234567890123456789012345678901".to_string(),
                 coin_filler: "0x3456789012345678901234567890123456789012".to_string(),
+                fill_selector: None,
+                finalise_selector: None,
+                multicall: None,
+                abi_dir: None,
+                verify_domain_separator: Default::default(),
             },
             monitoring: crate::config::MonitoringConfig {
                 enabled: false,
                 check_interval_seconds: 60,
+                pause_unhealthy_chains: true,
+                max_concurrent_operations: 4,
+                max_batch_finalize: 500,
+                batch_chunk_size: 50,
             },
             persistence: crate::config::PersistenceConfig {
                 enabled: false,
                 data_file: "test_orders.json".to_string(),
+                backend: Default::default(),
+                database_url: None,
+                tx_journal_file: None,
+                max_orders_in_memory: None,
+                archive_file: None,
+                retention_seconds: None,
             },
+            coordinator: crate::config::CoordinatorConfig::default(),
+            balance: crate::config::BalanceConfig::default(),
+            gas: crate::config::GasConfig::default(),
+            backpressure: crate::config::BackpressureConfig::default(),
+            encoder: crate::config::EncoderConfig::default(),
+            callbacks: crate::config::CallbackConfig::default(),
+            allowances: Default::default(),
         }
     }
 
@@ -317,10 +730,518 @@ mod tests {
             token: Address::from_str("0x9fe46736679d2d9a65f0992f2272de9f3c7fa6e0").unwrap(),
             amount: U256::from_str("99000000000000000000").unwrap(),
             recipient: Address::from_str("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266").unwrap(),
+            address_encoding: Default::default(),
+            chain_id: None,
         };
         
         assert_eq!(fill_request.order_id, "test_order_123");
         assert_eq!(fill_request.fill_deadline, u32::MAX);
         assert!(!fill_request.amount.is_zero());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_fill_receipt_flags_remote_call_failure() {
+        let coin_filler = Address::from([0xcf; 20]);
+        let recipient = Address::from([0xaa; 20]);
+        let order_id = keccak256("order-1".as_bytes());
+
+        // Overall success and the fill event, but the callback target stayed silent
+        let receipt = fill_receipt(vec![output_filled_log(coin_filler, order_id)]);
+        let flagged = check_fill_receipt(&receipt, coin_filler, order_id, recipient, true);
+        assert!(flagged.unwrap_err().contains("remote call did not execute"));
+
+        // Same receipt is fine for an output without a remoteCall
+        assert!(check_fill_receipt(&receipt, coin_filler, order_id, recipient, false).is_ok());
+
+        // A log from the recipient shows the callback ran
+        let receipt = fill_receipt(vec![
+            output_filled_log(coin_filler, order_id),
+            ReceiptLog { address: recipient, topics: vec![FixedBytes::ZERO], data: Vec::new() },
+        ]);
+        assert!(check_fill_receipt(&receipt, coin_filler, order_id, recipient, true).is_ok());
+    }
+
+    #[test]
+    fn test_fill_receipt_requires_output_filled_event() {
+        let coin_filler = Address::from([0xcf; 20]);
+        let order_id = keccak256("order-1".as_bytes());
+
+        // Status 1 without OutputFilled for this order, e.g. the output was already filled
+        let receipt = fill_receipt(vec![output_filled_log(coin_filler, keccak256("other".as_bytes()))]);
+        let flagged = check_fill_receipt(&receipt, coin_filler, order_id, Address::ZERO, false);
+        assert!(flagged.unwrap_err().contains("no OutputFilled event"));
+    }
+
+    /// Executor that records the gas parameters of every transaction it is asked to send
+    #[derive(Default)]
+    struct MockExecutor {
+        sent: std::sync::Mutex<Vec<(ChainType, crate::contracts::execution::GasParams)>>,
+        /// Contract each sent transaction was addressed to
+        sent_to: std::sync::Mutex<Vec<Address>>,
+        /// Returned by every static call
+        static_result: Vec<u8>,
+        /// Revert data every static call fails with instead, when set
+        static_revert: Option<Vec<u8>>,
+        /// Wallet balance reported to the affordability check; unsupported when `None`
+        balance: Option<U256>,
+        /// Returned by gas estimation; 21000 when `None`
+        gas_estimate: Option<u64>,
+        /// Fail gas estimation as a node would for a call it cannot simulate
+        estimate_fails: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for MockExecutor {
+        async fn send_transaction(&self, chain: ChainType, _call_data: Vec<u8>, to: Address, gas: crate::contracts::execution::GasParams) -> Result<String> {
+            self.sent.lock().unwrap().push((chain, gas));
+            self.sent_to.lock().unwrap().push(to);
+            Ok("0xfill".to_string())
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            if let Some(data) = &self.static_revert {
+                return Err(crate::contracts::execution::ExecutionError::CallReverted { data: data.clone() }.into());
+            }
+            Ok(self.static_result.clone())
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            if self.estimate_fails {
+                return Err(anyhow::anyhow!("execution reverted"));
+            }
+            Ok(self.gas_estimate.unwrap_or(21000))
+        }
+
+        async fn get_balance(&self, _chain: ChainType) -> Result<U256> {
+            self.balance.ok_or_else(|| anyhow::anyhow!("MockExecutor cannot query balances"))
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "MockExecutor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_already_filled_output_is_not_sent_again() {
+        let solver = FixedBytes::<32>::from([0x11; 32]);
+        let record = CoinFiller::filledOutputsReturn { solver, timestamp: 1_700_000_000 };
+        let executor = Arc::new(MockExecutor {
+            static_result: CoinFiller::filledOutputsCall::abi_encode_returns(&record),
+            ..Default::default()
+        });
+        let orchestrator = FillOrchestrator::new_with_traits(
+            Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(create_test_config()),
+        ).unwrap();
+
+        let err = orchestrator.execute_fill(
+            "test_order_123",
+            u32::MAX,
+            Address::from([0x22; 20]),
+            Address::from([0x33; 20]),
+            U256::from(99),
+            Address::from([0x44; 20]),
+        ).await.unwrap_err();
+
+        let filled = err.downcast_ref::<AlreadyFilled>().expect("should report the existing fill");
+        assert_eq!(filled.solver, solver);
+        assert_eq!(filled.timestamp, 1_700_000_000);
+        assert!(executor.sent.lock().unwrap().is_empty(), "an existing fill must not be broadcast again");
+    }
+
+    #[tokio::test]
+    async fn test_reverting_fill_simulation_aborts_send() {
+        use alloy::sol_types::{Revert, SolError};
+
+        let executor = Arc::new(MockExecutor {
+            static_revert: Some(Revert::from("ERC20: insufficient allowance").abi_encode()),
+            ..Default::default()
+        });
+        let orchestrator = FillOrchestrator::new_with_traits(
+            Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(create_test_config()),
+        ).unwrap();
+        let err = orchestrator.execute_fill(
+            "test_order_123",
+            u32::MAX,
+            Address::from([0x22; 20]),
+            Address::from([0x33; 20]),
+            U256::from(99),
+            Address::from([0x44; 20]),
+        ).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("insufficient allowance"), "{:#}", err);
+        assert!(executor.sent.lock().unwrap().is_empty(), "a fill that reverts in simulation must not be sent");
+
+        // With the preflight turned off the fill goes out regardless
+        let mut config = create_test_config();
+        config.solver.simulate_fill = false;
+        let orchestrator = FillOrchestrator::new_with_traits(
+            Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(config),
+        ).unwrap();
+        orchestrator.execute_fill(
+            "test_order_123",
+            u32::MAX,
+            Address::from([0x22; 20]),
+            Address::from([0x33; 20]),
+            U256::from(99),
+            Address::from([0x44; 20]),
+        ).await.unwrap();
+        assert_eq!(executor.sent.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_approve_call_data() {
+        let spender = Address::from([0x55; 20]);
+        let call_data = approve_call_data(spender, U256::MAX);
+
+        assert_eq!(&call_data[..4], &[0x09, 0x5e, 0xa7, 0xb3], "approve(address,uint256) selector");
+        let decoded = IERC20::approveCall::abi_decode(&call_data).unwrap();
+        assert_eq!(decoded.spender, spender);
+        assert_eq!(decoded.amount, U256::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_allowance_is_approved_before_fill() {
+        let token = Address::from([0x33; 20]);
+        let mut config = create_test_config();
+        config.allowances.auto_approve = true;
+        config.allowances.approve_amount = Some("1000".to_string());
+        let coin_filler: Address = config.contracts.coin_filler.parse().unwrap();
+
+        for (allowance, expected_sends) in [(U256::from(50), vec![token, coin_filler]), (U256::from(1000), vec![coin_filler])] {
+            let executor = Arc::new(MockExecutor {
+                // Every static call reads as this allowance
+                static_result: allowance.abi_encode(),
+                ..Default::default()
+            });
+            let orchestrator = FillOrchestrator::new_with_traits(
+                Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+                executor.clone(),
+                Arc::new(config.clone()),
+            ).unwrap();
+            assert_eq!(orchestrator.allowance(ChainType::Destination, token, coin_filler).await.unwrap(), allowance);
+
+            orchestrator.execute_fill(
+                "test_order_123",
+                u32::MAX,
+                Address::from([0x22; 20]),
+                token,
+                U256::from(99),
+                Address::from([0x44; 20]),
+            ).await.unwrap();
+            assert_eq!(*executor.sent_to.lock().unwrap(), expected_sends, "allowance {}", allowance);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_underfunded_wallet_sends_no_approve() {
+        let mut config = create_test_config();
+        config.allowances.auto_approve = true;
+        let executor = Arc::new(MockExecutor {
+            static_result: U256::ZERO.abi_encode(),
+            balance: Some(U256::from(1)),
+            ..Default::default()
+        });
+        let orchestrator = FillOrchestrator::new_with_traits(
+            Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(config),
+        ).unwrap();
+
+        let err = orchestrator.execute_fill(
+            "test_order_123",
+            u32::MAX,
+            Address::from([0x22; 20]),
+            Address::from([0x33; 20]),
+            U256::from(99),
+            Address::from([0x44; 20]),
+        ).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<crate::contracts::execution::ExecutionError>(),
+            Some(crate::contracts::execution::ExecutionError::InsufficientFunds(_))
+        ), "{}", err);
+        assert!(executor.sent_to.lock().unwrap().is_empty(), "an unaffordable approve must not be sent");
+    }
+
+    #[tokio::test]
+    async fn test_native_output_is_sent_as_value() {
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = FillOrchestrator::new_with_traits(
+            Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(create_test_config()),
+        ).unwrap();
+
+        for token in [crate::models::NATIVE_TOKEN, Address::from([0x33; 20])] {
+            orchestrator.execute_fill(
+                "test_order_123",
+                u32::MAX,
+                Address::from([0x22; 20]),
+                token,
+                U256::from(99),
+                Address::from([0x44; 20]),
+            ).await.unwrap();
+        }
+
+        let sent = executor.sent.lock().unwrap();
+        assert_eq!(sent[0].1.value, U256::from(99), "native output amount goes out as value");
+        assert_eq!(sent[1].1.value, U256::ZERO, "token outputs send no value");
+    }
+
+    #[tokio::test]
+    async fn test_fill_is_routed_to_the_output_chain() {
+        let mut config = create_test_config();
+        let other_filler = Address::from([0x77; 20]);
+        let mut other_chain = config.chains.destination.clone();
+        other_chain.chain_id = 31339;
+        other_chain.coin_filler = Some(other_filler.to_string());
+        config.chains.registry.insert(31339, other_chain);
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = FillOrchestrator::new_with_traits(
+            Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(config.clone()),
+        ).unwrap();
+        let request = |chain_id| FillRequest {
+            order_id: "test_order_123".to_string(),
+            fill_deadline: u32::MAX,
+            remote_oracle: Address::from([0x22; 20]),
+            token: Address::from([0x33; 20]),
+            amount: U256::from(99),
+            recipient: Address::from([0x44; 20]),
+            address_encoding: AddressEncoding::LeftPad20,
+            chain_id,
+        };
+
+        orchestrator.execute_fill_request(&request(Some(31339))).await.unwrap();
+        orchestrator.execute_fill_request(&request(Some(config.chains.destination.chain_id))).await.unwrap();
+
+        let chains: Vec<ChainType> = executor.sent.lock().unwrap().iter().map(|(chain, _)| *chain).collect();
+        let sent_to = executor.sent_to.lock().unwrap().clone();
+        assert_eq!(chains, vec![ChainType::ById(31339), ChainType::Destination]);
+        assert_eq!(sent_to[0], other_filler, "the output chain's CoinFiller is called");
+        assert_eq!(sent_to[1], config.contracts.coin_filler.parse::<Address>().unwrap());
+
+        // Chains missing from the registry are refused rather than filled on the destination
+        let err = orchestrator.execute_fill_request(&request(Some(424242))).await.unwrap_err();
+        assert!(err.to_string().contains("424242"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_underfunded_wallet_aborts_fill() {
+        // The fill is sent with the 21000 estimate plus the default 20% buffer, at 50 gwei
+        let required = U256::from(25_200u64) * U256::from(50_000_000_000u64);
+        let config = Arc::new(create_test_config());
+
+        for (balance, affordable) in [(required - U256::from(1), false), (required, true)] {
+            let executor = Arc::new(MockExecutor { balance: Some(balance), ..Default::default() });
+            let orchestrator = FillOrchestrator::new_with_traits(
+                Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+                executor.clone(),
+                config.clone(),
+            ).unwrap();
+
+            let result = orchestrator.execute_fill(
+                "test_order_123",
+                u32::MAX,
+                Address::from([0x22; 20]),
+                Address::from([0x33; 20]),
+                U256::from(99),
+                Address::from([0x44; 20]),
+            ).await;
+
+            if affordable {
+                assert_eq!(result.unwrap(), "0xfill");
+                assert_eq!(executor.sent.lock().unwrap().len(), 1);
+            } else {
+                let err = result.unwrap_err();
+                assert!(matches!(
+                    err.downcast_ref::<crate::contracts::execution::ExecutionError>(),
+                    Some(crate::contracts::execution::ExecutionError::InsufficientFunds(_))
+                ), "{}", err);
+                assert!(executor.sent.lock().unwrap().is_empty(), "an underfunded fill must not be sent");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fill_uses_destination_gas_profile() {
+        let mut config = create_test_config();
+        config.gas.buffer_pct = 100;
+        config.gas.max_gas_price = Some(40_000_000_000);
+        config.chains.destination.gas.buffer_pct = Some(50);
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = FillOrchestrator::new_with_traits(
+            Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(config),
+        ).unwrap();
+
+        orchestrator.execute_fill(
+            "test_order_123",
+            u32::MAX,
+            Address::from([0x22; 20]),
+            Address::from([0x33; 20]),
+            U256::from(99),
+            Address::from([0x44; 20]),
+        ).await.unwrap();
+
+        {
+            let sent = executor.sent.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            assert_eq!(sent[0].0, ChainType::Destination);
+            // The destination buffer replaces the global one and is applied once; the global price bound still applies
+            assert_eq!(sent[0].1.gas_limit, 31500);
+            assert_eq!(sent[0].1.gas_price, 40_000_000_000);
+        }
+
+        // The chain's cap is checked against the price actually bid, after the floor
+        let mut config = create_test_config();
+        config.gas.min_gas_price = Some(60_000_000_000);
+        config.chains.destination.max_gas_price_wei = Some(55_000_000_000);
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = FillOrchestrator::new_with_traits(
+            Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(config),
+        ).unwrap();
+        let err = orchestrator.execute_fill(
+            "test_order_123",
+            u32::MAX,
+            Address::from([0x22; 20]),
+            Address::from([0x33; 20]),
+            U256::from(99),
+            Address::from([0x44; 20]),
+        ).await.unwrap_err();
+        assert!(crate::services::gas::is_gas_price_too_high(&err), "{}", err);
+        assert!(executor.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fill_gas_limit_scales_from_estimate() {
+        let mut config = create_test_config();
+        config.gas.buffer_pct = 25;
+        let config = Arc::new(config);
+
+        for (executor, expected) in [
+            (MockExecutor { gas_estimate: Some(100_000), ..Default::default() }, 125_000),
+            (MockExecutor { gas_estimate: Some(800_000), ..Default::default() }, 1_000_000),
+            // Only a failed estimate falls back to the fixed limit
+            (MockExecutor { estimate_fails: true, ..Default::default() }, 360_000),
+        ] {
+            let executor = Arc::new(executor);
+            let orchestrator = FillOrchestrator::new_with_traits(
+                Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+                executor.clone(),
+                config.clone(),
+            ).unwrap();
+
+            orchestrator.execute_fill(
+                "test_order_123",
+                u32::MAX,
+                Address::from([0x22; 20]),
+                Address::from([0x33; 20]),
+                U256::from(99),
+                Address::from([0x44; 20]),
+            ).await.unwrap();
+
+            assert_eq!(executor.sent.lock().unwrap()[0].1.gas_limit, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_fill_does_not_send_transaction() {
+        let mut config = create_test_config();
+        config.solver.dry_run = true;
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = FillOrchestrator::new_with_traits(
+            Arc::new(AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(config),
+        ).unwrap();
+
+        let result = orchestrator.execute_fill(
+            "test_order_123",
+            u32::MAX,
+            Address::from([0x22; 20]),
+            Address::from([0x33; 20]),
+            U256::from(99),
+            Address::from([0x44; 20]),
+        ).await.unwrap();
+
+        assert_eq!(result, "dry-run:21000");
+        assert!(executor.sent.lock().unwrap().is_empty(), "dry run must not call send_transaction");
+    }
+
+    /// Encoder that encodes a different amount than it was asked to, like an encoding regression would
+    struct AmountSkewingEncoder {
+        inner: AlloyEncoder,
+        skew: u64,
+    }
+
+    impl CallDataEncoder for AmountSkewingEncoder {
+        fn encode_finalize_call(&self, order: &crate::models::Order) -> Result<Vec<u8>> {
+            self.inner.encode_finalize_call(order)
+        }
+
+        fn get_finalize_selector(&self) -> [u8; 4] {
+            self.inner.get_finalize_selector()
+        }
+
+        fn description(&self) -> &str {
+            "AmountSkewingEncoder"
+        }
+
+        fn encode_complete_fill_call(
+            &self,
+            request: &FillRequest,
+            coin_filler_address: Address,
+            destination_chain_id: u64,
+            solver_address: Address,
+        ) -> Result<Vec<u8>> {
+            let mut skewed = request.clone();
+            skewed.amount += U256::from(self.skew);
+            self.inner.encode_complete_fill_call(&skewed, coin_filler_address, destination_chain_id, solver_address)
+        }
+    }
+
+    async fn fill_with_skew(skew: u64, tolerance_bps: u32) -> (Result<String>, Arc<MockExecutor>) {
+        let mut config = create_test_config();
+        config.solver.fill_amount_tolerance_bps = tolerance_bps;
+        let executor = Arc::new(MockExecutor::default());
+        let encoder = AmountSkewingEncoder { inner: AlloyEncoder::new(Arc::new(AbiRegistry::new())), skew };
+        let orchestrator = FillOrchestrator::new_with_traits(Arc::new(encoder), executor.clone(), Arc::new(config)).unwrap();
+
+        let result = orchestrator.execute_fill(
+            "test_order_123",
+            u32::MAX,
+            Address::from([0x22; 20]),
+            Address::from([0x33; 20]),
+            U256::from(10_000),
+            Address::from([0x44; 20]),
+        ).await;
+        (result, executor)
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_fill_amount_aborts_send() {
+        let (result, executor) = fill_with_skew(1, 0).await;
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("does not match the order's output amount"), "{}", err);
+        assert!(executor.sent.lock().unwrap().is_empty(), "guard must stop the send");
+
+        // Within the configured tolerance (1 of 10000 at 1 bps) the fill goes ahead
+        let (result, executor) = fill_with_skew(1, 1).await;
+        assert_eq!(result.unwrap(), "0xfill");
+        assert_eq!(executor.sent.lock().unwrap().len(), 1);
+    }
+}
@@ -1,5 +1,7 @@
 pub mod settlement;
 pub mod fill;
+pub mod bundle;
 
 pub use settlement::*;
-pub use fill::*; 
\ No newline at end of file
+pub use fill::*;
+pub use bundle::*; 
\ No newline at end of file
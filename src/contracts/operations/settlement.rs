@@ -1,12 +1,15 @@
-use crate::contracts::encoding::traits::{CallDataEncoder, FinaliseParams, StandardOrderParams, MandateOutputParams};
-use crate::contracts::execution::traits::{ExecutionEngine, GasParams};
-use crate::contracts::abi::AbiProvider;
-use crate::contracts::encoding::FoundryEncoder;
+use crate::contracts::encoding::traits::{allocator_signature, CallDataEncoder, FinaliseParams, StandardOrderParams, MandateOutputParams};
+use crate::contracts::execution::traits::{bid_gas_price, ensure_affordable, estimate_gas_limit, ExecutionEngine, GasParams};
+use crate::contracts::abi::{AbiProvider, AbiRegistry};
+use crate::contracts::encoding::{AlloyEncoder, FoundryEncoder, SelectorOverrides};
 use crate::contracts::execution::AlloyExecutor;
 use crate::contracts::execution::traits::ChainType;
-use crate::models::Order;
-use crate::config::AppConfig;
+use crate::contracts::execution::{IntentJournal, TxJournal, TxOp, TxRecord, intent};
+use crate::contracts::factory::IOracle;
+use crate::models::{Order, OutputField};
+use crate::config::{AppConfig, ChainDetails};
 use alloy::primitives::{Address, U256, FixedBytes, Bytes};
+use alloy::sol_types::SolCall;
 use anyhow::Result;
 use std::sync::Arc;
 use tracing::{info, error, warn};
@@ -17,6 +20,10 @@ pub struct FinalizationOrchestrator {
     encoder: Arc<dyn crate::contracts::encoding::CallDataEncoder>,
     executor: Arc<dyn crate::contracts::execution::ExecutionEngine>,
     config: Arc<AppConfig>,
+    intent_journal: Option<IntentJournal>,
+    tx_journal: Option<TxJournal>,
+    /// Second encoder whose output must match `encoder`'s, when `encoder.verify_parity` is on
+    parity_encoder: Option<Arc<dyn CallDataEncoder>>,
 }
 
 impl FinalizationOrchestrator {
@@ -28,7 +35,11 @@ impl FinalizationOrchestrator {
         info!("🏗️ Creating FinalizationOrchestrator with modular architecture");
         
         // Create encoder with ABI provider
-        let encoder = Arc::new(FoundryEncoder::new(abi_provider));
+        let encoder = Arc::new(
+            FoundryEncoder::new(abi_provider)
+                .with_selector_overrides(SelectorOverrides::from_config(&config.contracts))
+                .with_max_calldata_bytes(config.encoder.max_calldata_bytes),
+        );
         
         // Create executor with config - AlloyExecutor implements ExecutionEngine
         let executor = Arc::new(AlloyExecutor::new(config.clone())?);
@@ -38,10 +49,16 @@ impl FinalizationOrchestrator {
         info!("  Executor: AlloyExecutor (Alloy providers) - Abstract trait");
         info!("  Wallet: {}", executor.wallet_address());
         
+        let intent_journal = config.persistence.enabled
+            .then(|| IntentJournal::for_data_file(&config.persistence.data_file));
+        
         Ok(Self {
             encoder,
             executor,
+            parity_encoder: default_parity_encoder(&config),
+            tx_journal: TxJournal::from_config(&config.persistence),
             config,
+            intent_journal,
         })
     }
     
@@ -56,17 +73,40 @@ impl FinalizationOrchestrator {
         Self {
             encoder,
             executor,
+            parity_encoder: default_parity_encoder(&config),
+            tx_journal: TxJournal::from_config(&config.persistence),
             config,
+            intent_journal: None,
         }
     }
     
+    /// Compare every finalization's calldata against `encoder`'s
+    pub fn with_parity_encoder(mut self, encoder: Arc<dyn CallDataEncoder>) -> Self {
+        self.parity_encoder = Some(encoder);
+        self
+    }
+    
+    /// Record transaction intents in `journal` so a finalization is never broadcast twice
+    pub fn with_intent_journal(mut self, journal: IntentJournal) -> Self {
+        self.intent_journal = Some(journal);
+        self
+    }
+    
+    /// Log every finalization sent to `journal`
+    pub fn with_tx_journal(mut self, journal: TxJournal) -> Self {
+        self.tx_journal = Some(journal);
+        self
+    }
+    
     /// Execute complete finalization process
     pub async fn execute_finalization(&self, order: &Order) -> Result<String> {
         info!("🚀 MODULAR FINALIZATION: Starting finalization for order: {}", order.id);
         info!("🔧 Using FoundryEncoder + AlloyExecutor architecture");
         
         // Step 1: Validate chain connectivity
-        self.validate_prerequisites().await?;
+        let (origin, origin_details) = self.origin_chain(order)?;
+        let settler_compact_address = self.settler_compact(origin_details)?;
+        self.validate_prerequisites(settler_compact_address).await?;
         
         // Step 2: Prepare finalization parameters from order
         let finalize_params = self.prepare_finalization_params(order).await?;
@@ -75,16 +115,52 @@ impl FinalizationOrchestrator {
         info!("📦 Step 3: Generating call data with abstract encoder...");
         info!("  Encoder: {}", self.encoder.description());
         let call_data = self.encoder.encode_finalize_call(order)?;
+        self.encoders_agree(order, &call_data);
         
         // Step 4: Execute transaction using abstract executor
         info!("🚀 Step 4: Executing transaction with abstract executor...");
-        let settler_compact_address = self.config.contracts.settler_compact.parse::<Address>()?;
-        let gas_params = GasParams {
-            gas_limit: 650000,
-            gas_price: 1178761408,
-        };
         
-        let tx_hash = self.executor.send_transaction(ChainType::Origin, call_data, settler_compact_address, gas_params).await?;
+        // In dry-run mode, stop after gas estimation
+        if self.config.solver.dry_run {
+            let gas_estimate = self.executor
+                .estimate_gas(origin, call_data, settler_compact_address, self.executor.wallet_address())
+                .await?;
+            info!("🧪 DRY RUN: finalization for order {} encoded and estimated at {} gas, not broadcast", order.id, gas_estimate);
+            return Ok(format!("{}{}", crate::models::DRY_RUN_TX_PREFIX, gas_estimate));
+        }
+        
+        if self.config.solver.simulate_finalization {
+            self.simulate_finalization(order, origin, &call_data, settler_compact_address).await?;
+        }
+        
+        let profile = self.config.gas.for_chain(origin_details);
+        let gas_limit = estimate_gas_limit(
+            self.executor.as_ref(),
+            origin,
+            call_data.clone(),
+            settler_compact_address,
+            650000,
+            profile.buffer_pct,
+        ).await;
+        let gas_price = bid_gas_price(self.executor.as_ref(), origin, profile.strategy, 1178761408).await;
+        let gas_params = GasParams::from_profile(gas_limit, gas_price, &profile);
+        crate::services::gas::check_gas_price_cap(origin_details, gas_params.gas_price as u128)?;
+        info!("  Gas limit: {} (sent)", gas_params.gas_limit);
+        ensure_affordable(self.executor.as_ref(), origin, &gas_params).await?;
+        
+        let tx_hash = intent::send_once(
+            self.executor.as_ref(),
+            self.intent_journal.as_ref(),
+            &order.id.to_string(),
+            origin,
+            call_data,
+            settler_compact_address,
+            gas_params,
+        ).await?;
+        if let Some(journal) = &self.tx_journal {
+            let record = TxRecord::sent(&order.id.to_string(), TxOp::Finalize, origin_details.chain_id, settler_compact_address, &tx_hash);
+            journal.record_sent(self.executor.as_ref(), origin, record).await;
+        }
         
         info!("🎉 MODULAR FINALIZATION COMPLETED:");
         info!("  Order ID: {}", order.id);
@@ -95,8 +171,102 @@ impl FinalizationOrchestrator {
         Ok(tx_hash)
     }
     
+    /// Run the finalise call as an `eth_call` from the solver's address, failing with
+    /// the revert reason instead of broadcasting a transaction that would revert
+    async fn simulate_finalization(&self, order: &Order, chain: ChainType, call_data: &[u8], to: Address) -> Result<()> {
+        info!("🔍 Simulating finalization for order {}", order.id);
+        match self.executor.static_call(chain, call_data.to_vec(), to, self.executor.wallet_address()).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("❌ Finalization of order {} failed in simulation, not sending: {}", order.id, e);
+                let message = format!("Finalization simulation failed for order {}: {}", order.id, e);
+                Err(e.context(message))
+            }
+        }
+    }
+
+    /// Ask the order's local oracle whether the fill of every output has been proven
+    ///
+    /// The fill descriptions use the same solvers and timestamps `finalise` will
+    /// present, so a fill the destination chain reorged away reads as unproven.
+    pub async fn fill_is_proven(&self, order: &Order) -> Result<bool> {
+        let (origin, _) = self.origin_chain(order)?;
+        let params = self.prepare_finalization_params(order).await?;
+        let order_id = order.onchain_id();
+        let local_oracle = params.order.local_oracle;
+
+        for (i, output) in params.order.outputs.iter().enumerate() {
+            let solver = params.solvers.get(i).or(params.solvers.first()).copied().unwrap_or_default();
+            let timestamp = params.timestamps.get(i).or(params.timestamps.first()).copied().unwrap_or_default();
+            let query = IOracle::isProvenCall {
+                remoteChainId: output.chain_id,
+                remoteOracle: output.remote_oracle,
+                application: output.remote_filler,
+                dataHash: alloy::primitives::keccak256(fill_description(solver, order_id, timestamp, output)),
+            };
+            let result = self.executor
+                .static_call(origin, query.abi_encode(), local_oracle, self.executor.wallet_address())
+                .await?;
+            let proven = IOracle::isProvenCall::abi_decode_returns(&result)
+                .map_err(|e| anyhow::anyhow!("Unexpected isProven response from oracle {}: {}", local_oracle, e))?;
+            if !proven {
+                info!("Oracle {} has no proof for output {} of order {}", local_oracle, i, order.id);
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Whether the parity encoder, if any, produces the same calldata for `order`
+    ///
+    /// A divergence is logged with both encodings; the primary encoder's output is used regardless.
+    fn encoders_agree(&self, order: &Order, call_data: &[u8]) -> bool {
+        let Some(parity_encoder) = &self.parity_encoder else {
+            return true;
+        };
+        match parity_encoder.encode_finalize_call(order) {
+            Ok(parity_call_data) if parity_call_data == call_data => true,
+            Ok(parity_call_data) => {
+                error!(
+                    "❌ Encoder divergence for order {}: {} produced 0x{}, {} produced 0x{}",
+                    order.id,
+                    self.encoder.description(),
+                    hex::encode(call_data),
+                    parity_encoder.description(),
+                    hex::encode(parity_call_data),
+                );
+                false
+            }
+            Err(e) => {
+                warn!("⚠️ Parity encoder failed for order {}, comparison skipped: {}", order.id, e);
+                true
+            }
+        }
+    }
+    
+    /// The chain `order` was opened on, where it is finalized
+    ///
+    /// The configured origin keeps `ChainType::Origin`; other chains are looked up in the registry.
+    fn origin_chain(&self, order: &Order) -> Result<(ChainType, &ChainDetails)> {
+        let chain_id = order.standard_order.origin_chain_id;
+        if chain_id == self.config.chains.origin.chain_id {
+            return Ok((ChainType::Origin, &self.config.chains.origin));
+        }
+        let details = self.config.chains.get(chain_id).ok_or_else(|| {
+            anyhow::anyhow!("Order {} originates on chain {}, which is not configured", order.id, chain_id)
+        })?;
+        Ok((ChainType::ById(chain_id), details))
+    }
+
+    /// The SettlerCompact on `chain`, falling back to `contracts.settler_compact`
+    fn settler_compact(&self, chain: &ChainDetails) -> Result<Address> {
+        let settler = chain.settler_compact.as_deref().unwrap_or(&self.config.contracts.settler_compact);
+        settler.parse()
+            .map_err(|e| anyhow::anyhow!("Invalid SettlerCompact address for chain {}: {}", chain.chain_id, e))
+    }
+    
     /// Validate prerequisites before finalization
-    async fn validate_prerequisites(&self) -> Result<()> {
+    async fn validate_prerequisites(&self, settler_compact_address: Address) -> Result<()> {
         info!("🔍 Validating finalization prerequisites...");
         
         // Check chain connectivity using wallet address (basic validation)
@@ -106,7 +276,7 @@ impl FinalizationOrchestrator {
         }
         
         // Verify contracts are properly configured
-        if self.config.contracts.settler_compact == "0x0000000000000000000000000000000000000000" {
+        if settler_compact_address.is_zero() {
             return Err(anyhow::anyhow!("SettlerCompact contract address not configured"));
         }
         
@@ -125,7 +295,7 @@ impl FinalizationOrchestrator {
         // 1. The order's sponsor signature 
         // 2. The allocator signature (if any)
         let sponsor_sig = self.prepare_sponsor_signature(order)?;
-        let allocator_sig = Bytes::new(); // Empty allocator signature
+        let allocator_sig = self.prepare_allocator_signature(order)?;
         
         // Prepare timestamps - use exact timestamp that worked in TypeScript
         let timestamps = vec![1752062605u32]; // Use the working timestamp from our previous testing
@@ -162,6 +332,13 @@ impl FinalizationOrchestrator {
         })
     }
     
+    /// Prepare allocator signature from order; empty for an always-OK allocator
+    fn prepare_allocator_signature(&self, order: &Order) -> Result<Bytes> {
+        let allocator_sig = allocator_signature(order)?;
+        info!("🔏 Allocator signature: {} bytes", allocator_sig.len());
+        Ok(allocator_sig)
+    }
+    
     /// Prepare sponsor signature from order
     fn prepare_sponsor_signature(&self, order: &Order) -> Result<Bytes> {
         // In a real implementation, this would validate and extract the signature
@@ -195,53 +372,28 @@ impl FinalizationOrchestrator {
         let standard_order = &order.standard_order;
         
         // Convert inputs to proper format
-        let inputs: Result<Vec<(U256, U256)>, anyhow::Error> = standard_order.inputs.iter()
-            .enumerate()
-            .map(|(i, (token_id, amount))| {
-                let token_id_u256 = token_id.parse::<U256>()
-                    .map_err(|e| anyhow::anyhow!("Failed to parse token_id at input[{}]: {}", i, e))?;
-                let amount_u256 = amount.parse::<U256>()
-                    .map_err(|e| anyhow::anyhow!("Failed to parse amount at input[{}]: {}", i, e))?;
-                
-                info!("🔢 Input[{}]: tokenId={}, amount={}", i, token_id_u256, amount_u256);
-                Ok((token_id_u256, amount_u256))
-            })
-            .collect();
-        
-        let inputs = inputs?;
+        for (i, (token_id, amount)) in standard_order.inputs.iter().enumerate() {
+            info!("🔢 Input[{}]: tokenId={}, amount={}", i, token_id, amount);
+        }
+        let inputs = standard_order.inputs.clone();
         
         // Convert outputs to proper format
         let outputs: Result<Vec<MandateOutputParams>, anyhow::Error> = standard_order.outputs.iter()
             .enumerate()
             .map(|(i, output)| {
-                let amount_u256 = output.amount.parse::<U256>()
-                    .map_err(|e| anyhow::anyhow!("Failed to parse output amount at output[{}]: {}", i, e))?;
-                
-                // Handle optional bytes fields
-                let remote_call = match &output.remote_call {
-                    Some(s) if !s.is_empty() && s != "null" => {
-                        Bytes::from(hex::decode(s.strip_prefix("0x").unwrap_or(s)).unwrap_or_default())
-                    }
-                    _ => Bytes::from(vec![]),
-                };
-                
-                let fulfillment_context = match &output.fulfillment_context {
-                    Some(s) if !s.is_empty() && s != "null" => {
-                        Bytes::from(hex::decode(s.strip_prefix("0x").unwrap_or(s)).unwrap_or_default())
-                    }
-                    _ => Bytes::new(),
-                };
+                let remote_call = output.remote_call_bytes()?;
+                let fulfillment_context = output.fulfillment_context_bytes()?;
                 
                 info!("🔢 Output[{}]: amount={}, remoteCall={} bytes, fulfillmentContext={} bytes", 
-                      i, amount_u256, remote_call.len(), fulfillment_context.len());
+                      i, output.amount, remote_call.len(), fulfillment_context.len());
                 
                 Ok(MandateOutputParams {
-                    remote_oracle: address_to_bytes32(output.remote_oracle),
-                    remote_filler: address_to_bytes32(output.remote_filler),
+                    remote_oracle: output.address_encoding.encode(OutputField::RemoteOracle, output.remote_oracle),
+                    remote_filler: output.address_encoding.encode(OutputField::RemoteFiller, output.remote_filler),
                     chain_id: U256::from(output.chain_id),
-                    token: address_to_bytes32(output.token),
-                    amount: amount_u256,
-                    recipient: address_to_bytes32(output.recipient),
+                    token: output.address_encoding.encode(OutputField::Token, output.token),
+                    amount: output.amount,
+                    recipient: output.address_encoding.encode(OutputField::Recipient, output.recipient),
                     remote_call,
                     fulfillment_context,
                 })
@@ -281,6 +433,7 @@ impl FinalizationOrchestrator {
     /// Estimate gas for finalization
     pub async fn estimate_finalization_gas(&self, order: &Order) -> Result<u64> {
         info!("⛽ Estimating gas for finalization of order: {}", order.id);
+        let (origin, origin_details) = self.origin_chain(order)?;
         
         // Prepare parameters
         let finalize_params = self.prepare_finalization_params(order).await?;
@@ -289,10 +442,10 @@ impl FinalizationOrchestrator {
         let call_data = self.encoder.encode_finalize_call(order)?;
         
         // Estimate gas
-        let settler_compact_address = self.config.contracts.settler_compact.parse::<Address>()?;
+        let settler_compact_address = self.settler_compact(origin_details)?;
         let from_address = self.executor.wallet_address();
         
-        let gas_estimate = self.executor.estimate_gas(ChainType::Origin, call_data, settler_compact_address, from_address).await?;
+        let gas_estimate = self.executor.estimate_gas(origin, call_data, settler_compact_address, from_address).await?;
         
         info!("✅ Gas estimation completed: {} gas", gas_estimate);
         
@@ -312,49 +465,21 @@ async fn prepare_finalization_params_legacy(
     let standard_order = &order.standard_order;
     
     // Convert inputs to proper format
-    let inputs: Result<Vec<(U256, U256)>, anyhow::Error> = standard_order.inputs.iter()
-        .enumerate()
-        .map(|(i, (token_id, amount))| {
-            let token_id_u256 = token_id.parse::<U256>()
-                .map_err(|e| anyhow::anyhow!("Failed to parse token_id at input[{}]: {}", i, e))?;
-            let amount_u256 = amount.parse::<U256>()
-                .map_err(|e| anyhow::anyhow!("Failed to parse amount at input[{}]: {}", i, e))?;
-            
-            Ok((token_id_u256, amount_u256))
-        })
-        .collect();
-    
-    let inputs = inputs?;
+    let inputs = standard_order.inputs.clone();
     
     // Convert outputs to proper format
     let outputs: Result<Vec<MandateOutputParams>, anyhow::Error> = standard_order.outputs.iter()
-        .enumerate()
-        .map(|(i, output)| {
-            let amount_u256 = output.amount.parse::<U256>()
-                .map_err(|e| anyhow::anyhow!("Failed to parse output amount at output[{}]: {}", i, e))?;
-            
-            // Handle optional bytes fields
-            let remote_call = match &output.remote_call {
-                Some(s) if !s.is_empty() && s != "null" => {
-                    Bytes::from(hex::decode(s.strip_prefix("0x").unwrap_or(s)).unwrap_or_default())
-                }
-                _ => Bytes::from(vec![]),
-            };
-            
-            let fulfillment_context = match &output.fulfillment_context {
-                Some(s) if !s.is_empty() && s != "null" => {
-                    Bytes::from(hex::decode(s.strip_prefix("0x").unwrap_or(s)).unwrap_or_default())
-                }
-                _ => Bytes::new(),
-            };
+        .map(|output| {
+            let remote_call = output.remote_call_bytes()?;
+            let fulfillment_context = output.fulfillment_context_bytes()?;
             
             Ok(MandateOutputParams {
-                remote_oracle: address_to_bytes32(output.remote_oracle),
-                remote_filler: address_to_bytes32(output.remote_filler),
+                remote_oracle: output.address_encoding.encode(OutputField::RemoteOracle, output.remote_oracle),
+                remote_filler: output.address_encoding.encode(OutputField::RemoteFiller, output.remote_filler),
                 chain_id: U256::from(output.chain_id),
-                token: address_to_bytes32(output.token),
-                amount: amount_u256,
-                recipient: address_to_bytes32(output.recipient),
+                token: output.address_encoding.encode(OutputField::Token, output.token),
+                amount: output.amount,
+                recipient: output.address_encoding.encode(OutputField::Recipient, output.recipient),
                 remote_call,
                 fulfillment_context,
             })
@@ -377,7 +502,7 @@ async fn prepare_finalization_params_legacy(
     
     // Process signatures
     let sponsor_sig = validate_and_parse_signature(&order.signature)?;
-    let allocator_sig = Bytes::new(); // Empty for AlwaysOKAllocator
+    let allocator_sig = allocator_signature(order)?;
     
     // Create timestamps
     let current_timestamp = std::time::SystemTime::now()
@@ -422,17 +547,52 @@ fn validate_and_parse_signature(signature: &str) -> Result<Bytes> {
         return Err(anyhow::anyhow!("Odd-length hex signature: '{}'", signature));
     }
     
-    // Validate signature is exactly 65 bytes (130 hex chars) for ECDSA
-    if sig_str.len() != 130 {
-        return Err(anyhow::anyhow!("Invalid signature length: {} chars, expected 130 for ECDSA", sig_str.len()));
+    // ECDSA signatures are 65 bytes (130 hex chars), or 64 (128 hex chars) in EIP-2098 compact form
+    if sig_str.len() != 130 && sig_str.len() != 128 {
+        return Err(anyhow::anyhow!("Invalid signature length: {} chars, expected 130 (or 128 for EIP-2098) for ECDSA", sig_str.len()));
     }
     
     let sig_bytes = hex::decode(sig_str)
         .map_err(|e| anyhow::anyhow!("Invalid hex in signature '{}': {}", signature, e))?;
     
+    if sig_bytes.len() == 64 {
+        // The y-parity lives in the top bit of `s`; expand to canonical (r, s, v)
+        let expanded = alloy::primitives::Signature::from_erc2098(&sig_bytes).as_bytes();
+        return Ok(Bytes::from(expanded.to_vec()));
+    }
+    
     Ok(Bytes::from(sig_bytes))
 }
 
+/// The alternate of the default FoundryEncoder, when `encoder.verify_parity` asks for one
+fn default_parity_encoder(config: &AppConfig) -> Option<Arc<dyn CallDataEncoder>> {
+    config.encoder.verify_parity.then(|| {
+        Arc::new(
+            AlloyEncoder::new(Arc::new(AbiRegistry::new()))
+                .with_selector_overrides(SelectorOverrides::from_config(&config.contracts))
+                .with_max_calldata_bytes(config.encoder.max_calldata_bytes),
+        ) as Arc<dyn CallDataEncoder>
+    })
+}
+
+/// OIF fill description of `output` that the destination oracle attests to:
+/// `solver | orderId | timestamp | token | amount | recipient | remoteCall | fulfillmentContext`,
+/// packed, with each dynamic field prefixed by its uint16 length
+pub fn fill_description(solver: FixedBytes<32>, order_id: FixedBytes<32>, timestamp: u32, output: &MandateOutputParams) -> Vec<u8> {
+    let mut description = Vec::with_capacity(168 + output.remote_call.len() + output.fulfillment_context.len());
+    description.extend_from_slice(solver.as_slice());
+    description.extend_from_slice(order_id.as_slice());
+    description.extend_from_slice(&timestamp.to_be_bytes());
+    description.extend_from_slice(output.token.as_slice());
+    description.extend_from_slice(&output.amount.to_be_bytes::<32>());
+    description.extend_from_slice(output.recipient.as_slice());
+    for field in [&output.remote_call, &output.fulfillment_context] {
+        description.extend_from_slice(&(field.len() as u16).to_be_bytes());
+        description.extend_from_slice(field);
+    }
+    description
+}
+
 /// Convert Address to bytes32 (padded with zeros)
 fn address_to_bytes32(address: Address) -> FixedBytes<32> {
     let mut bytes = [0u8; 32];
@@ -457,7 +617,7 @@ mod tests {
     use super::*;
     use crate::contracts::abi::AbiRegistry;
     use crate::config::{AppConfig, ServerConfig, ChainConfig, ChainDetails, SolverConfig, ContractConfig, MonitoringConfig, PersistenceConfig};
-    use crate::models::{StandardOrder, MandateOutput, OrderStatus};
+    use crate::models::{StandardOrder, MandateOutput, OrderStatus, ExecutionPriority, AddressEncoding};
     use alloy::primitives::Address;
     use chrono::Utc;
     use uuid::Uuid;
@@ -468,34 +628,99 @@ mod tests {
             server: ServerConfig {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
+                log_format: Default::default(),
+                shutdown_grace_seconds: 30,
+                rate_limit: Default::default(),
+                admin_token: None,
             },
             chains: ChainConfig {
                 origin: ChainDetails {
-                    rpc_url: "http://localhost:8545".to_string(),
+                    rpc_url: "http://localhost:8545".into(),
+                    read_rpc_url: None,
                     chain_id: 31337,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
                 destination: ChainDetails {
-                    rpc_url: "http://localhost:8546".to_string(),
+                    rpc_url: "http://localhost:8546".into(),
+                    read_rpc_url: None,
                     chain_id: 31338,
+                    max_gas_price_wei: None,
+                    gas: Default::default(),
+                    confirmations: 0,
+                    rpc_timeout_seconds: 30,
+                    block_height_ttl_ms: 2000,
+                    coin_filler: None,
+                    settler_compact: None,
                 },
+                registry: Default::default(),
+                warn_on_chain_id_mismatch: false,
+                log_rpc_requests: false,
             },
             solver: SolverConfig {
                 private_key: "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string(),
                 finalization_delay_seconds: 30,
+                allowed_input_tokens: Vec::new(),
+                denied_input_tokens: Vec::new(),
+                allowed_users: Vec::new(),
+                denied_users: Vec::new(),
+                allowed_oracles: Vec::new(),
+                dry_run: false,
+                production: false,
+                min_profit_margin_bps: None,
+                token_prices: std::collections::HashMap::new(),
+                fill_amount_tolerance_bps: 0,
+                max_fill_retries: 3,
+                retry_backoff_seconds: vec![30, 120, 600],
+                skip_fill_proof_check: false,
+                max_inputs: None,
+                max_outputs: None,
+                order_id_mode: Default::default(),
+                simulate_finalization: true,
+                simulate_fill: true,
+                signature_scheme: Default::default(),
+                name: None,
             },
             contracts: ContractConfig {
                 the_compact: "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0".to_string(),
                 settler_compact: "0x5FC8d32690cc91D4c39d9d3abcBD16989F875707".to_string(),
                 coin_filler: "0xCf7Ed3AccA5a467e9e704C703E8D87F634fB0Fc9".to_string(),
+                fill_selector: None,
+                finalise_selector: None,
+                multicall: None,
+                abi_dir: None,
+                verify_domain_separator: Default::default(),
             },
             monitoring: MonitoringConfig {
                 enabled: true,
                 check_interval_seconds: 60,
+                pause_unhealthy_chains: true,
+                max_concurrent_operations: 4,
+                max_batch_finalize: 500,
+                batch_chunk_size: 50,
             },
             persistence: PersistenceConfig {
                 enabled: true,
                 data_file: "data/orders.json".to_string(),
+                backend: Default::default(),
+                database_url: None,
+                tx_journal_file: None,
+                max_orders_in_memory: None,
+                archive_file: None,
+                retention_seconds: None,
             },
+            coordinator: crate::config::CoordinatorConfig::default(),
+            balance: crate::config::BalanceConfig::default(),
+            gas: crate::config::GasConfig::default(),
+            backpressure: crate::config::BackpressureConfig::default(),
+            encoder: crate::config::EncoderConfig::default(),
+            callbacks: crate::config::CallbackConfig::default(),
+            allowances: Default::default(),
         })
     }
 
@@ -508,18 +733,19 @@ mod tests {
             fill_deadline: 4294967295,
             local_oracle: Address::from_str("0x0165878a594ca255338adfa4d48449f69242eb8f").unwrap(),
             inputs: vec![(
-                "232173931049414487598928205764542517475099722052565410375093941968804628563".to_string(),
-                "100000000000000000000".to_string()
+                U256::from_str("232173931049414487598928205764542517475099722052565410375093941968804628563").unwrap(),
+                U256::from_str("100000000000000000000").unwrap()
             )],
             outputs: vec![MandateOutput {
                 remote_oracle: Address::from_str("0xe7f1725e7734ce288f8367e1bb143e90bb3f0512").unwrap(),
                 remote_filler: Address::from_str("0x5fbdb2315678afecb367f032d93f642f64180aa3").unwrap(),
                 chain_id: 31338,
                 token: Address::from_str("0x9fe46736679d2d9a65f0992f2272de9f3c7fa6e0").unwrap(),
-                amount: "99000000000000000000".to_string(),
+                amount: U256::from_str("99000000000000000000").unwrap(),
                 recipient: Address::from_str("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266").unwrap(),
                 remote_call: None,
                 fulfillment_context: None,
+                address_encoding: Default::default(),
             }],
         };
 
@@ -536,6 +762,18 @@ mod tests {
             fill_tx_hash: None,
             finalize_tx_hash: None,
             error_message: None,
+            filled_at: None,
+            fill_warning: None,
+            status_history: Vec::new(),
+            priority: ExecutionPriority::Normal,
+            fill_attempts: 0,
+            fill_retry_at: None,
+            correlation_id: "test".to_string(),
+            allocator_signature: None,
+            callback_url: None,
+            fill_gas: None,
+            output_fill_txs: Vec::new(),
+            fill_sending: false,
         }
     }
 
@@ -590,6 +828,20 @@ mod tests {
         println!("   Outputs: {} items", standard_params.outputs.len());
     }
 
+    #[test]
+    fn test_order_conversion_uses_the_output_address_encoding() {
+        let config = create_test_config();
+        let orchestrator = FinalizationOrchestrator::new(Arc::new(AbiRegistry::new()), config)
+            .expect("Orchestrator creation");
+        let mut test_order = create_test_order();
+        test_order.standard_order.outputs[0].address_encoding = AddressEncoding::RightPad20;
+
+        let output = &orchestrator.convert_order_to_standard_params(&test_order).unwrap().outputs[0];
+        let recipient = test_order.standard_order.outputs[0].recipient;
+        assert_eq!(output.recipient[..20], recipient[..]);
+        assert_eq!(output.recipient[20..], [0u8; 12]);
+    }
+
     #[test]
     fn test_signature_handling() {
         let config = create_test_config();
@@ -642,6 +894,217 @@ mod tests {
         println!("   Solvers: {} items", params.solvers.len());
     }
 
+    #[tokio::test]
+    async fn test_allocator_signature_comes_from_the_order() {
+        let orchestrator = FinalizationOrchestrator::new(Arc::new(AbiRegistry::new()), create_test_config())
+            .expect("Orchestrator creation");
+        let mut test_order = create_test_order();
+        test_order.allocator_signature = Some(format!("0x{}", "cd".repeat(65)));
+
+        let params = orchestrator.prepare_finalization_params(&test_order).await.unwrap();
+        assert_eq!(params.allocator_sig, Bytes::from(vec![0xcd; 65]));
+
+        test_order.allocator_signature = Some("0xnothex".to_string());
+        assert!(orchestrator.prepare_finalization_params(&test_order).await.is_err());
+    }
+
+    #[test]
+    fn test_compact_signatures_are_expanded() {
+        use alloy::signers::{local::PrivateKeySigner, SignerSync};
+
+        let signer = PrivateKeySigner::random();
+        // Sign until both parities are covered, as compact form folds v into s
+        let mut parities = std::collections::HashSet::new();
+        for i in 0u8.. {
+            let sig = signer.sign_hash_sync(&alloy::primitives::B256::repeat_byte(i)).unwrap();
+            let canonical = Bytes::from(sig.as_bytes().to_vec());
+
+            let full = validate_and_parse_signature(&format!("0x{}", hex::encode(sig.as_bytes()))).unwrap();
+            assert_eq!(full, canonical, "65-byte signatures pass through");
+
+            let compact = validate_and_parse_signature(&format!("0x{}", hex::encode(sig.as_erc2098()))).unwrap();
+            assert_eq!(compact, canonical, "64-byte signatures expand with the recovered v");
+
+            parities.insert(sig.v());
+            if parities.len() == 2 {
+                break;
+            }
+        }
+
+        assert!(validate_and_parse_signature(&format!("0x{}", "ab".repeat(63))).is_err());
+        assert!(validate_and_parse_signature(&format!("0x{}", "ab".repeat(66))).is_err());
+    }
+
     // Note: Integration tests with actual blockchain calls would require running test nodes
     // These tests verify the modular structure and parameter handling without network calls
-} 
\ No newline at end of file
+
+    /// Executor that counts send_transaction calls
+    #[derive(Default)]
+    struct MockExecutor {
+        sent: std::sync::atomic::AtomicUsize,
+        estimated_on: std::sync::Mutex<Vec<ChainType>>,
+        /// Contract each gas estimate was made against
+        estimated_to: std::sync::Mutex<Vec<Address>>,
+        /// Revert data static calls fail with, when set
+        revert: Option<Vec<u8>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for MockExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<String> {
+            self.sent.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok("0xfinalize".to_string())
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            match &self.revert {
+                Some(data) => Err(crate::contracts::execution::ExecutionError::CallReverted { data: data.clone() }.into()),
+                None => Ok(Vec::new()),
+            }
+        }
+
+        async fn estimate_gas(&self, chain: ChainType, _call_data: Vec<u8>, to: Address, _from: Address) -> Result<u64> {
+            self.estimated_on.lock().unwrap().push(chain);
+            self.estimated_to.lock().unwrap().push(to);
+            Ok(450000)
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "MockExecutor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_finalization_does_not_send_transaction() {
+        let mut config = (*create_test_config()).clone();
+        config.solver.dry_run = true;
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = FinalizationOrchestrator::new_with_traits(
+            Arc::new(crate::contracts::encoding::AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(config),
+        );
+
+        let result = orchestrator.execute_finalization(&create_test_order()).await.unwrap();
+
+        assert_eq!(result, "dry-run:450000");
+        assert_eq!(executor.sent.load(std::sync::atomic::Ordering::SeqCst), 0, "dry run must not call send_transaction");
+    }
+
+    #[tokio::test]
+    async fn test_reverting_simulation_skips_the_send() {
+        use alloy::sol_types::{Revert, SolError};
+
+        let orchestrator = |executor: Arc<MockExecutor>, simulate: bool| {
+            let mut config = (*create_test_config()).clone();
+            config.solver.simulate_finalization = simulate;
+            FinalizationOrchestrator::new_with_traits(
+                Arc::new(crate::contracts::encoding::AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+                executor,
+                Arc::new(config),
+            )
+        };
+        let reverting = || Arc::new(MockExecutor {
+            revert: Some(Revert::from("Order already finalised").abi_encode()),
+            ..Default::default()
+        });
+
+        let executor = reverting();
+        let error = orchestrator(executor.clone(), true).execute_finalization(&create_test_order()).await.unwrap_err();
+        assert!(error.to_string().contains("Order already finalised"), "{}", error);
+        assert_eq!(executor.sent.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        // With the simulation off the transaction goes out regardless
+        let executor = reverting();
+        orchestrator(executor.clone(), false).execute_finalization(&create_test_order()).await.unwrap();
+        assert_eq!(executor.sent.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let executor = Arc::new(MockExecutor::default());
+        orchestrator(executor.clone(), true).execute_finalization(&create_test_order()).await.unwrap();
+        assert_eq!(executor.sent.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_finalization_runs_on_the_order_origin_chain() {
+        let mut config = (*create_test_config()).clone();
+        config.solver.dry_run = true;
+        let other_settler = Address::from([0x77; 20]);
+        config.chains.registry.insert(31339, ChainDetails {
+            chain_id: 31339,
+            settler_compact: Some(other_settler.to_string()),
+            ..config.chains.origin.clone()
+        });
+        config.chains.build_registry().unwrap();
+        let executor = Arc::new(MockExecutor::default());
+        let orchestrator = FinalizationOrchestrator::new_with_traits(
+            Arc::new(crate::contracts::encoding::AlloyEncoder::new(Arc::new(AbiRegistry::new()))),
+            executor.clone(),
+            Arc::new(config),
+        );
+
+        // The configured origin keeps its ChainType
+        let mut order = create_test_order();
+        orchestrator.execute_finalization(&order).await.unwrap();
+
+        // An order opened on another registered chain is finalized there
+        order.standard_order.origin_chain_id = 31339;
+        orchestrator.execute_finalization(&order).await.unwrap();
+        assert_eq!(*executor.estimated_on.lock().unwrap(), vec![ChainType::Origin, ChainType::ById(31339)]);
+        // ...through that chain's SettlerCompact
+        let default_settler: Address = orchestrator.config.contracts.settler_compact.parse().unwrap();
+        assert_eq!(*executor.estimated_to.lock().unwrap(), vec![default_settler, other_settler]);
+
+        // An order from a chain the solver does not know is refused before any call
+        order.standard_order.origin_chain_id = 1;
+        let error = orchestrator.execute_finalization(&order).await.unwrap_err();
+        assert!(error.to_string().contains("chain 1, which is not configured"), "{}", error);
+        assert!(orchestrator.estimate_finalization_gas(&order).await.is_err());
+        assert_eq!(executor.estimated_on.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_encoder_divergence_is_detected() {
+        let mut config = (*create_test_config()).clone();
+        config.solver.dry_run = true;
+        let alloy_encoder = || crate::contracts::encoding::AlloyEncoder::new(Arc::new(AbiRegistry::new()));
+        let order = create_test_order();
+
+        // Same encoder on both sides: no divergence
+        let orchestrator = FinalizationOrchestrator::new_with_traits(
+            Arc::new(alloy_encoder()),
+            Arc::new(MockExecutor::default()),
+            Arc::new(config.clone()),
+        )
+        .with_parity_encoder(Arc::new(alloy_encoder()));
+        let call_data = orchestrator.encoder.encode_finalize_call(&order).unwrap();
+        assert!(orchestrator.encoders_agree(&order, &call_data));
+
+        // An alternate that encodes a different selector diverges, yet finalization proceeds
+        let drifted = alloy_encoder().with_selector_overrides(SelectorOverrides {
+            fill: None,
+            finalise: Some([0xde, 0xad, 0xbe, 0xef]),
+        });
+        let orchestrator = FinalizationOrchestrator::new_with_traits(
+            Arc::new(alloy_encoder()),
+            Arc::new(MockExecutor::default()),
+            Arc::new(config),
+        )
+        .with_parity_encoder(Arc::new(drifted));
+        assert!(!orchestrator.encoders_agree(&order, &call_data));
+        assert_eq!(orchestrator.execute_finalization(&order).await.unwrap(), "dry-run:450000");
+    }
+
+    #[test]
+    fn test_parity_check_follows_config() {
+        let mut config = (*create_test_config()).clone();
+        assert!(default_parity_encoder(&config).is_none());
+
+        config.encoder.verify_parity = true;
+        let orchestrator = FinalizationOrchestrator::new(Arc::new(AbiRegistry::new()), Arc::new(config)).unwrap();
+        assert!(orchestrator.parity_encoder.is_some());
+    }
+}
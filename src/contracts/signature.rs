@@ -0,0 +1,384 @@
+use alloy::primitives::{Address, FixedBytes, Signature, B256, U256};
+use alloy::sol_types::{eip712_domain, Eip712Domain, SolCall, SolStruct};
+use anyhow::{anyhow, bail, Result};
+use std::str::FromStr;
+
+use crate::config::{AppConfig, DomainSeparatorCheck, SignatureScheme};
+use crate::contracts::execution::{ChainType, ExecutionEngine};
+use crate::contracts::factory;
+use crate::models::StandardOrder;
+
+/// EIP-712 domain orders are signed under: TheCompact on the origin chain
+pub fn order_domain(config: &AppConfig) -> Result<Eip712Domain> {
+    order_domain_on(config, config.chains.origin.chain_id)
+}
+
+/// EIP-712 domain of orders opened on `chain_id`
+pub fn order_domain_on(config: &AppConfig, chain_id: u64) -> Result<Eip712Domain> {
+    let the_compact = Address::from_str(&config.contracts.the_compact)
+        .map_err(|e| anyhow!("Invalid TheCompact address in config: {}", e))?;
+
+    Ok(eip712_domain! {
+        name: "The Compact",
+        version: "1",
+        chain_id: chain_id,
+        verifying_contract: the_compact,
+    })
+}
+
+/// Chain `order` was opened on, as the executor addresses it
+fn origin_chain(order: &StandardOrder, config: &AppConfig) -> ChainType {
+    if order.origin_chain_id == config.chains.origin.chain_id {
+        ChainType::Origin
+    } else {
+        ChainType::ById(order.origin_chain_id)
+    }
+}
+
+/// Separator of `order_domain`, as TheCompact's `DOMAIN_SEPARATOR()` should report it
+pub fn domain_separator(config: &AppConfig) -> Result<B256> {
+    Ok(order_domain(config)?.separator())
+}
+
+/// Compare TheCompact's on-chain `DOMAIN_SEPARATOR()` with `domain_separator`, as
+/// `contracts.verify_domain_separator` asks
+///
+/// A mismatch means signatures are checked against the wrong domain (wrong chain id
+/// or contract address). A TheCompact that cannot be queried is only warned about.
+pub async fn verify_domain_separator(executor: &dyn ExecutionEngine, config: &AppConfig) -> Result<()> {
+    let check = config.contracts.verify_domain_separator;
+    if check == DomainSeparatorCheck::Off {
+        return Ok(());
+    }
+
+    let expected = domain_separator(config)?;
+    let the_compact = Address::from_str(&config.contracts.the_compact)
+        .map_err(|e| anyhow!("Invalid TheCompact address in config: {}", e))?;
+    let call_data = factory::TheCompact::DOMAIN_SEPARATORCall {}.abi_encode();
+    let onchain = match executor
+        .static_call(ChainType::Origin, call_data, the_compact, executor.wallet_address())
+        .await
+        .and_then(|result| Ok(factory::TheCompact::DOMAIN_SEPARATORCall::abi_decode_returns(&result)?))
+    {
+        Ok(separator) => separator,
+        Err(e) => {
+            tracing::warn!("Could not read DOMAIN_SEPARATOR() from TheCompact {}: {}", the_compact, e);
+            return Ok(());
+        }
+    };
+
+    if onchain == expected {
+        tracing::info!("TheCompact {} domain separator matches the configuration", the_compact);
+        return Ok(());
+    }
+    let message = format!(
+        "TheCompact {} reports domain separator {}, but chain {} and this address give {}",
+        the_compact, onchain, config.chains.origin.chain_id, expected
+    );
+    match check {
+        DomainSeparatorCheck::Error => bail!(message),
+        _ => {
+            tracing::warn!("{}", message);
+            Ok(())
+        }
+    }
+}
+
+/// EIP-712 signing hash of a StandardOrder, using the type strings from `factory.rs`
+pub fn standard_order_digest(order: &StandardOrder, domain: &Eip712Domain) -> Result<B256> {
+    Ok(to_sol_order(order)?.eip712_signing_hash(domain))
+}
+
+/// Recover the address that produced `signature` (65-byte hex) over the order digest
+pub fn recover_order_signer(order: &StandardOrder, signature: &str, domain: &Eip712Domain) -> Result<Address> {
+    let sig_bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+        .map_err(|e| anyhow!("Signature is not valid hex: {}", e))?;
+    if sig_bytes.len() != 65 {
+        return Err(anyhow!("Signature must be 65 bytes, got {}", sig_bytes.len()));
+    }
+
+    let signature = Signature::try_from(sig_bytes.as_slice())
+        .map_err(|e| anyhow!("Malformed signature: {}", e))?;
+    let digest = standard_order_digest(order, domain)?;
+
+    signature
+        .recover_address_from_prehash(&digest)
+        .map_err(|e| anyhow!("Signature recovery failed: {}", e))
+}
+
+/// What ERC-1271 `isValidSignature` returns for a signature the wallet accepts
+pub const ERC1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// Check that `order.user` authorized `order` with `signature`, under `solver.signature_scheme`
+///
+/// An ECDSA signature by the user is accepted without going on-chain. Otherwise, under
+/// `auto`, a user with code on the origin chain is a contract wallet and is asked through
+/// ERC-1271; for an EOA, or when the code cannot be read, the ECDSA error stands.
+pub async fn verify_order_signer(
+    order: &StandardOrder,
+    signature: &str,
+    config: &AppConfig,
+    executor: &dyn ExecutionEngine,
+) -> Result<(), String> {
+    let ecdsa_error = match verify_order_signature(order, signature, config) {
+        Ok(()) => return Ok(()),
+        Err(e) if config.solver.signature_scheme == SignatureScheme::Ecdsa => return Err(e),
+        Err(e) => e,
+    };
+
+    match executor.get_code(origin_chain(order, config), order.user).await {
+        Ok(code) if !code.is_empty() => verify_erc1271_signature(order, signature, config, executor).await,
+        Ok(_) => Err(ecdsa_error),
+        Err(e) => {
+            tracing::warn!("Could not read code of order user {} to check an ERC-1271 signature: {}", order.user, e);
+            Err(ecdsa_error)
+        }
+    }
+}
+
+/// Ask the contract wallet at `order.user` whether `signature` is valid for the order digest
+async fn verify_erc1271_signature(
+    order: &StandardOrder,
+    signature: &str,
+    config: &AppConfig,
+    executor: &dyn ExecutionEngine,
+) -> Result<(), String> {
+    let digest = order_domain_on(config, order.origin_chain_id)
+        .and_then(|domain| standard_order_digest(order, &domain))
+        .map_err(|e| e.to_string())?;
+    let signature = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+        .map_err(|e| format!("Invalid order signature: Signature is not valid hex: {}", e))?;
+
+    let call_data = factory::IERC1271::isValidSignatureCall { hash: digest, signature: signature.into() }.abi_encode();
+    let magic_value = executor
+        .static_call(origin_chain(order, config), call_data, order.user, executor.wallet_address())
+        .await
+        .and_then(|result| Ok(factory::IERC1271::isValidSignatureCall::abi_decode_returns(&result)?))
+        .map_err(|e| format!("Contract wallet {} did not validate the order signature: {}", order.user, e))?;
+
+    if magic_value != ERC1271_MAGIC_VALUE {
+        return Err(format!(
+            "Contract wallet {} rejected the order signature (isValidSignature returned {})",
+            order.user, magic_value
+        ));
+    }
+    Ok(())
+}
+
+/// Check that `signature` over `order` was produced by `order.user` with ECDSA
+pub fn verify_order_signature(order: &StandardOrder, signature: &str, config: &AppConfig) -> Result<(), String> {
+    let domain = order_domain_on(config, order.origin_chain_id).map_err(|e| e.to_string())?;
+    let signer = recover_order_signer(order, signature, &domain)
+        .map_err(|e| format!("Invalid order signature: {}", e))?;
+
+    if signer != order.user {
+        return Err(format!(
+            "Order signature was produced by {}, not by the order's user {}",
+            signer, order.user
+        ));
+    }
+    Ok(())
+}
+
+fn to_sol_order(order: &StandardOrder) -> Result<factory::StandardOrder> {
+    let inputs = order
+        .inputs
+        .iter()
+        .map(|&(token_id, amount)| factory::Input { tokenId: token_id, amount })
+        .collect();
+
+    let outputs = order
+        .outputs
+        .iter()
+        .map(|output| {
+            Ok(factory::MandateOutput {
+                remoteOracle: address_to_bytes32(output.remote_oracle),
+                remoteFiller: address_to_bytes32(output.remote_filler),
+                chainId: U256::from(output.chain_id),
+                token: address_to_bytes32(output.token),
+                amount: output.amount,
+                recipient: address_to_bytes32(output.recipient),
+                remoteCall: output.remote_call_bytes()?,
+                fulfillmentContext: output.fulfillment_context_bytes()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(factory::StandardOrder {
+        user: order.user,
+        nonce: U256::from(order.nonce),
+        originChainId: U256::from(order.origin_chain_id),
+        expires: U256::from(order.expires),
+        fillDeadline: U256::from(order.fill_deadline),
+        localOracle: order.local_oracle,
+        inputs,
+        outputs,
+    })
+}
+
+fn address_to_bytes32(address: Address) -> FixedBytes<32> {
+    let mut bytes = [0u8; 32];
+    bytes[12..].copy_from_slice(address.as_slice());
+    FixedBytes::from(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::execution::GasParams;
+    use crate::models::MandateOutput;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::signers::SignerSync;
+
+    fn create_test_order(user: Address) -> StandardOrder {
+        StandardOrder {
+            user,
+            nonce: 1,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        }
+    }
+
+    fn sign_order(signer: &PrivateKeySigner, order: &StandardOrder, config: &AppConfig) -> String {
+        let digest = standard_order_digest(order, &order_domain_on(config, order.origin_chain_id).unwrap()).unwrap();
+        let signature = signer.sign_hash_sync(&digest).unwrap();
+        format!("0x{}", hex::encode(signature.as_bytes()))
+    }
+
+    #[test]
+    fn test_valid_signature_recovers_user() {
+        let config = AppConfig::default();
+        let signer = PrivateKeySigner::random();
+        let order = create_test_order(signer.address());
+        let signature = sign_order(&signer, &order, &config);
+
+        let domain = order_domain(&config).unwrap();
+        assert_eq!(recover_order_signer(&order, &signature, &domain).unwrap(), signer.address());
+        assert!(verify_order_signature(&order, &signature, &config).is_ok());
+
+        // An order opened on another chain is signed under that chain's domain
+        let mut other = order.clone();
+        other.origin_chain_id = 31339;
+        assert_ne!(order_domain_on(&config, 31339).unwrap().separator(), domain.separator());
+        let signature = sign_order(&signer, &other, &config);
+        assert!(verify_order_signature(&other, &signature, &config).is_ok());
+    }
+
+    #[test]
+    fn test_tampered_order_is_rejected() {
+        let config = AppConfig::default();
+        let signer = PrivateKeySigner::random();
+        let mut order = create_test_order(signer.address());
+        let signature = sign_order(&signer, &order, &config);
+
+        // Raising the output amount after signing changes the digest
+        order.outputs[0].amount = U256::from(1000);
+
+        let err = verify_order_signature(&order, &signature, &config).unwrap_err();
+        assert!(err.contains("not by the order's user"));
+    }
+
+    #[test]
+    fn test_domain_separator_matches_known_vector() {
+        // keccak256(abi.encode(EIP712Domain typehash, keccak256("The Compact"), keccak256("1"),
+        // 1, 0x00000000000000171ede64904551eeDF3C6C9788))
+        let mut config = AppConfig::default();
+        config.chains.origin.chain_id = 1;
+        config.contracts.the_compact = "0x00000000000000171ede64904551eeDF3C6C9788".to_string();
+
+        let expected = B256::from_str("0x4ac11bdf0eb5972bae47825af851d20c342d88f466669ec58827be03650df019").unwrap();
+        assert_eq!(domain_separator(&config).unwrap(), expected);
+
+        config.chains.origin.chain_id = 10;
+        assert_ne!(domain_separator(&config).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_malformed_signature_is_rejected() {
+        let config = AppConfig::default();
+        let order = create_test_order(Address::from([0x11; 20]));
+
+        let err = verify_order_signature(&order, "0x1234", &config).unwrap_err();
+        assert!(err.contains("65 bytes"));
+    }
+
+    /// Origin chain where `code` is deployed at every address; `isValidSignature`
+    /// returns `magic_value` for `digest` and zero for anything else
+    struct WalletExecutor {
+        code: Vec<u8>,
+        digest: B256,
+        magic_value: [u8; 4],
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for WalletExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<String> {
+            bail!("not used")
+        }
+
+        async fn static_call(&self, _chain: ChainType, call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            let call = factory::IERC1271::isValidSignatureCall::abi_decode(&call_data)?;
+            let magic_value = if call.hash == self.digest { self.magic_value } else { [0; 4] };
+            Ok(factory::IERC1271::isValidSignatureCall::abi_encode_returns(&FixedBytes(magic_value)))
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        async fn get_code(&self, _chain: ChainType, _address: Address) -> Result<Vec<u8>> {
+            Ok(self.code.clone())
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::ZERO
+        }
+
+        fn description(&self) -> &str {
+            "WalletExecutor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_eoa_signature_is_recovered() {
+        let config = AppConfig::default();
+        let signer = PrivateKeySigner::random();
+        let order = create_test_order(signer.address());
+        let signature = sign_order(&signer, &order, &config);
+        let eoa = WalletExecutor { code: Vec::new(), digest: B256::ZERO, magic_value: ERC1271_MAGIC_VALUE };
+
+        assert!(verify_order_signer(&order, &signature, &config, &eoa).await.is_ok());
+        let other = create_test_order(Address::from([0x11; 20]));
+        assert!(verify_order_signer(&other, &signature, &config, &eoa).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_contract_wallet_signature_uses_erc1271() {
+        let mut config = AppConfig::default();
+        let order = create_test_order(Address::from([0x11; 20]));
+        let digest = standard_order_digest(&order, &order_domain(&config).unwrap()).unwrap();
+        let wallet = WalletExecutor { code: vec![0x60, 0x80], digest, magic_value: ERC1271_MAGIC_VALUE };
+
+        // Not an ECDSA signature, but the wallet accepts it for this order
+        assert!(verify_order_signer(&order, "0x1234", &config, &wallet).await.is_ok());
+
+        let mut tampered = order.clone();
+        tampered.outputs[0].amount = U256::from(1000);
+        let err = verify_order_signer(&tampered, "0x1234", &config, &wallet).await.unwrap_err();
+        assert!(err.contains("rejected the order signature"), "{}", err);
+
+        config.solver.signature_scheme = SignatureScheme::Ecdsa;
+        assert!(verify_order_signer(&order, "0x1234", &config, &wallet).await.is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header;
+use actix_web::middleware::{from_fn, Next};
+use actix_web::{web, Error, HttpResponse, Result};
+use serde_json::json;
+
+use crate::config::{AppConfig, SharedConfig};
+use crate::handlers::rate_limit::RateLimiter;
+use crate::services::pause::PauseSwitch;
+use crate::services::validation::OrderAccess;
+
+/// Stop automatic order processing; fills already under way run to completion
+pub async fn pause(paused: web::Data<PauseSwitch>) -> Result<HttpResponse> {
+    paused.pause();
+    tracing::warn!("Order processing paused by operator");
+    Ok(HttpResponse::Ok().json(json!({ "paused": true })))
+}
+
+pub async fn resume(paused: web::Data<PauseSwitch>) -> Result<HttpResponse> {
+    paused.resume();
+    tracing::info!("Order processing resumed by operator");
+    Ok(HttpResponse::Ok().json(json!({ "paused": false })))
+}
+
+/// Load the configuration again and apply it to the services that support it
+///
+/// Refused with 409 when a field that is only read at startup changed, and with
+/// 400 when the new configuration is invalid; nothing is applied then.
+pub async fn reload_config(
+    shared: web::Data<SharedConfig>,
+    rate_limiter: web::Data<RateLimiter>,
+) -> Result<HttpResponse> {
+    let config = match AppConfig::load().await {
+        Ok(config) => config,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Failed to load configuration",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    Ok(apply_config(config, &shared, &rate_limiter))
+}
+
+/// Make a freshly loaded `config` current for every service sharing `shared`
+fn apply_config(config: AppConfig, shared: &SharedConfig, rate_limiter: &RateLimiter) -> HttpResponse {
+    // Intake reads the access lists on every submission; unparseable ones would refuse them all
+    if let Err(e) = OrderAccess::from_config(&config.solver) {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "Invalid configuration",
+            "details": e.to_string()
+        }));
+    }
+
+    let rate_limit = config.server.rate_limit.clone();
+    if let Err(e) = shared.replace(config) {
+        tracing::warn!("Configuration reload refused: {}", e);
+        return HttpResponse::Conflict().json(json!({
+            "error": e.to_string(),
+            "fields": e.fields
+        }));
+    }
+    rate_limiter.reconfigure(rate_limit);
+    tracing::info!("Configuration reloaded by operator");
+    HttpResponse::Ok().json(json!({ "reloaded": true }))
+}
+
+/// Middleware refusing requests without `Authorization: Bearer <server.admin_token>` with 401;
+/// requests pass untouched when no token is configured
+pub async fn require_admin_token<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    let expected = req
+        .app_data::<web::Data<SharedConfig>>()
+        .and_then(|config| config.current().server.admin_token.clone());
+    if let Some(expected) = expected {
+        let presented = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if !presented.is_some_and(|token| tokens_match(token, &expected)) {
+            tracing::warn!("Refused admin request to {} without a valid token", req.path());
+            let response = HttpResponse::Unauthorized().json(json!({ "error": "Admin token required" }));
+            return Ok(req.into_response(response).map_into_right_body());
+        }
+    }
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+/// Compare without stopping at the first difference, so timing does not reveal the token
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented.bytes().zip(expected.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/v1/admin/pause", web::post().to(pause).wrap(from_fn(require_admin_token)))
+       .route("/api/v1/admin/resume", web::post().to(resume).wrap(from_fn(require_admin_token)))
+       .route("/api/v1/admin/reload-config", web::post().to(reload_config).wrap(from_fn(require_admin_token)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_endpoints_toggle_the_switch() {
+        let paused = PauseSwitch::new();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(paused.clone()))
+                .configure(config),
+        )
+        .await;
+
+        let req = test::TestRequest::post().uri("/api/v1/admin/pause").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        assert!(paused.is_paused());
+
+        let req = test::TestRequest::post().uri("/api/v1/admin/resume").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        assert!(!paused.is_paused());
+    }
+
+    #[actix_web::test]
+    async fn test_admin_token_is_required_once_configured() {
+        let paused = PauseSwitch::new();
+        let mut config = AppConfig::default();
+        config.server.admin_token = Some("s3cret".to_string());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(paused.clone()))
+                .app_data(web::Data::new(SharedConfig::new(config)))
+                .configure(super::config),
+        )
+        .await;
+
+        for authorization in [None, Some("Bearer wrong"), Some("s3cret")] {
+            let mut req = test::TestRequest::post().uri("/api/v1/admin/pause");
+            if let Some(authorization) = authorization {
+                req = req.insert_header((header::AUTHORIZATION, authorization));
+            }
+            assert_eq!(test::call_service(&app, req.to_request()).await.status(), 401);
+        }
+        assert!(!paused.is_paused());
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/admin/pause")
+            .insert_header((header::AUTHORIZATION, "Bearer s3cret"))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        assert!(paused.is_paused());
+    }
+
+    #[actix_web::test]
+    async fn test_reloaded_access_lists_apply_to_the_next_submission() {
+        use crate::handlers::orders::submit_order;
+        use crate::services::intake::OrderIntake;
+        use crate::services::metrics::SolverMetrics;
+        use crate::storage::{MemoryStorage, Storage};
+        use std::sync::Arc;
+
+        let shared = SharedConfig::new(AppConfig::default());
+        let rate_limiter = RateLimiter::default();
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new().with_shared_config(shared.clone())))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .route("/api/v1/orders", web::post().to(submit_order)),
+        )
+        .await;
+        let submission = |nonce: u64| json!({
+            "order": {
+                "user": "0x1111111111111111111111111111111111111111",
+                "nonce": nonce,
+                "originChainId": 31337,
+                "expires": 4294967295u64,
+                "fillDeadline": 4294967295u64,
+                "localOracle": "0x2222222222222222222222222222222222222222",
+                "inputs": [["1", "100"]],
+                "outputs": [{
+                    "remoteOracle": "0x3333333333333333333333333333333333333333",
+                    "remoteFiller": "0x4444444444444444444444444444444444444444",
+                    "chainId": 31338,
+                    "token": "0x5555555555555555555555555555555555555555",
+                    "amount": "99",
+                    "recipient": "0x6666666666666666666666666666666666666666"
+                }]
+            },
+            "signature": "0x"
+        });
+
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(submission(1)).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+        // An invalid deny list is refused and leaves the running config alone
+        let mut reloaded = AppConfig::default();
+        reloaded.solver.denied_users = vec!["not-an-address".to_string()];
+        assert_eq!(apply_config(reloaded.clone(), &shared, &rate_limiter).status(), 400);
+        assert!(shared.current().solver.denied_users.is_empty());
+
+        // Once the user is denied, the same running intake refuses them
+        reloaded.solver.denied_users = vec!["0x1111111111111111111111111111111111111111".to_string()];
+        assert_eq!(apply_config(reloaded, &shared, &rate_limiter).status(), 200);
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(submission(2)).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "user_not_allowed");
+        assert_eq!(storage.count().await, 1);
+    }
+}
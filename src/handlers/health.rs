@@ -1,14 +1,34 @@
 use actix_web::{web, HttpResponse, Result};
+use alloy::providers::Provider;
+use serde::Serialize;
 use serde_json::json;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::contracts::ContractFactory;
+use crate::services::balance;
 
-pub async fn health_check() -> Result<HttpResponse> {
+/// Maximum time to wait for a chain's RPC to answer `eth_blockNumber`
+const CHAIN_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reachability report for a single chain RPC
+#[derive(Debug, Serialize)]
+pub struct ChainHealth {
+    pub chain_id: u64,
+    pub ok: bool,
+    pub latest_block: Option<u64>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+pub async fn health_check(
+    contract_factory: web::Data<Arc<ContractFactory>>,
+) -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(json!({
         "status": "healthy",
         "service": "oif-solver-rust",
         "version": "0.1.0",
+        "solver": contract_factory.config.solver.name(),
         "timestamp": chrono::Utc::now().to_rfc3339()
     })))
 }
@@ -51,7 +71,122 @@ pub async fn blockchain_health_check(
     }
 }
 
+/// Query the latest block on a chain, bounded by `timeout`
+pub async fn probe_chain(provider: &(dyn Provider + Send + Sync), chain_id: u64, timeout: Duration) -> ChainHealth {
+    let started = Instant::now();
+    let result = tokio::time::timeout(timeout, provider.get_block_number()).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(Ok(block)) => ChainHealth {
+            chain_id,
+            ok: true,
+            latest_block: Some(block),
+            latency_ms,
+            error: None,
+        },
+        Ok(Err(e)) => ChainHealth {
+            chain_id,
+            ok: false,
+            latest_block: None,
+            latency_ms,
+            error: Some(e.to_string()),
+        },
+        Err(_) => ChainHealth {
+            chain_id,
+            ok: false,
+            latest_block: None,
+            latency_ms,
+            error: Some(format!("Timed out after {}s", timeout.as_secs())),
+        },
+    }
+}
+
+pub async fn chains_health_check(
+    contract_factory: web::Data<Arc<ContractFactory>>,
+) -> Result<HttpResponse> {
+    let (origin_provider, destination_provider) = match (
+        contract_factory.get_origin_provider(),
+        contract_factory.get_destination_provider(),
+    ) {
+        (Ok(origin), Ok(destination)) => (origin, destination),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("Chain providers unavailable: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Chain providers unavailable",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let (executor, reserve) = match (contract_factory.executor(), contract_factory.config.balance.reserve()) {
+        (Ok(executor), Ok(reserve)) => (executor, reserve),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("Solver balances unavailable: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Solver balances unavailable",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let config = &contract_factory.config;
+    let funded_chains = balance::configured_chains(config);
+    let (origin, destination, funding) = tokio::join!(
+        probe_chain(origin_provider, config.chains.origin.chain_id, CHAIN_PROBE_TIMEOUT),
+        probe_chain(destination_provider, config.chains.destination.chain_id, CHAIN_PROBE_TIMEOUT),
+        balance::check_funding(executor, &funded_chains, reserve, CHAIN_PROBE_TIMEOUT),
+    );
+
+    let funded = funding.iter().all(|chain| chain.funded);
+    let status = if origin.ok && destination.ok && funded { "healthy" } else { "degraded" };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "status": status,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "chains": {
+            "origin": origin,
+            "destination": destination
+        },
+        "funding": funding
+    })))
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.route("/api/v1/health", web::get().to(health_check))
-       .route("/api/v1/health/blockchain", web::get().to(blockchain_health_check));
+       .route("/api/v1/health/blockchain", web::get().to(blockchain_health_check))
+       .route("/api/v1/health/chains", web::get().to(chains_health_check));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use actix_web::{test, App};
+    use alloy::providers::ProviderBuilder;
+
+    #[actix_web::test]
+    async fn test_health_reports_solver_name() {
+        let mut config = AppConfig::default();
+        config.solver.name = Some("solver-eu-1".to_string());
+        let factory = Arc::new(ContractFactory::new(config).await.unwrap());
+        let app = test::init_service(App::new().app_data(web::Data::new(factory)).configure(super::config)).await;
+
+        let request = test::TestRequest::get().uri("/api/v1/health").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(body["solver"], "solver-eu-1");
+    }
+
+    #[tokio::test]
+    async fn test_probe_reports_unreachable_chain() {
+        // Nothing listens on port 1, so the RPC call fails instead of hanging
+        let provider = ProviderBuilder::new().connect_http("http://127.0.0.1:1".parse().unwrap());
+
+        let health = probe_chain(&provider, 31337, Duration::from_secs(2)).await;
+
+        assert_eq!(health.chain_id, 31337);
+        assert!(!health.ok);
+        assert!(health.latest_block.is_none());
+        assert!(health.error.is_some());
+    }
 } 
\ No newline at end of file
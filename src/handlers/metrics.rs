@@ -0,0 +1,28 @@
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+
+use crate::services::metrics::SolverMetrics;
+use crate::storage::Storage;
+
+/// Prometheus text exposition of the solver's metrics
+pub async fn get_metrics(
+    storage: web::Data<dyn Storage>,
+    metrics: web::Data<SolverMetrics>,
+) -> Result<HttpResponse> {
+    match metrics.render(storage.get_ref()).await {
+        Ok(body) => Ok(HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4")
+            .body(body)),
+        Err(e) => {
+            tracing::error!("Failed to render metrics: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to render metrics",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(get_metrics));
+}
@@ -1,6 +1,9 @@
+pub mod admin;
 pub mod health;
+pub mod metrics;
 pub mod orders;
 pub mod queue;
+pub mod rate_limit;
 
 pub use health::*;
 pub use orders::*;
@@ -1,34 +1,178 @@
 use actix_web::{web, HttpResponse, Result, HttpRequest};
+use actix_web::middleware::from_fn;
+use alloy::primitives::Address;
+use serde::Deserialize;
 use serde_json::json;
 use uuid::Uuid;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use crate::models::{Order, OrderSubmission, OrderResponse};
-use crate::storage::MemoryStorage;
-use crate::services::OrderMonitoringService;
+use crate::config::SharedConfig;
+use crate::models::{Order, OrderStatus, OrderSubmission, OrderResponse};
+use crate::models::erc7683::Erc7683Submission;
+use crate::storage::{OrderFilter, Storage};
+use crate::services::{FinalizationRefused, OrderMonitoringService};
+use crate::services::intake::{IntakeLoad, OrderIntake};
+use crate::services::validation::{self, OrderValidationError};
+use crate::services::metrics::SolverMetrics;
+use crate::services::order_logs::{OrderLogs, ORDER_SPAN};
+use crate::handlers::rate_limit::limit_requests;
+use tracing::Instrument;
+
+/// Header whose value, when present, becomes the submitted order's correlation id
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
 
 pub async fn submit_order(
+    req: HttpRequest,
     req_body: web::Json<OrderSubmission>,
-    storage: web::Data<MemoryStorage>,
+    storage: web::Data<dyn Storage>,
+    intake: web::Data<OrderIntake>,
+    metrics: web::Data<SolverMetrics>,
 ) -> Result<HttpResponse> {
+    if let Some(reason) = intake.blocked_reason() {
+        tracing::warn!("Rejecting new order: {}", reason);
+        return Ok(HttpResponse::ServiceUnavailable().json(json!({
+            "error": "Solver is not accepting new orders",
+            "details": reason
+        })));
+    }
+
+    // Tell clients to back off while the queue is near or at capacity
+    let load = match storage.get_queue_status().await {
+        Ok(queue_status) => intake.load(&queue_status),
+        Err(e) => {
+            tracing::error!("Failed to read queue status: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to store order",
+                "details": e.to_string()
+            })));
+        }
+    };
+    if load.saturated {
+        tracing::warn!("Rejecting new order: queue saturated ({} pending, {} in flight)", load.pending, load.in_flight);
+        return Ok(HttpResponse::ServiceUnavailable()
+            .insert_header(("Retry-After", load.retry_after_seconds.unwrap_or_default().to_string()))
+            .json(json!({
+                "error": "Solver is saturated",
+                "load": load
+            })));
+    }
+
+    if let Err(reason) = intake.check_order_size(&req_body.order) {
+        tracing::warn!("Rejecting new order: {}", reason);
+        return Ok(validation_error_response(&reason));
+    }
+    if let Err(reason) = intake.check_order_access(&req_body.order) {
+        tracing::warn!("Rejecting new order: {}", reason);
+        return Ok(validation_error_response(&reason));
+    }
+    if let Some(Err(reason)) = req_body.callback_url.as_deref().map(validation::validate_callback_url) {
+        tracing::warn!("Rejecting new order: {}", reason);
+        return Ok(validation_error_response(&reason));
+    }
+
+    // Reject orders whose (user, nonce) is already held by an order we track;
+    // the nonce is single-use, so only one of them could ever finalize
+    let standard_order = &req_body.order;
+    match storage
+        .find_nonce_holder(standard_order.user, standard_order.nonce, standard_order.origin_chain_id)
+        .await
+    {
+        Ok(Some(existing)) => {
+            tracing::warn!(
+                "Duplicate user nonce: user {} nonce {} is held by order {}",
+                standard_order.user, standard_order.nonce, existing.id
+            );
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": "duplicate user nonce",
+                "id": existing.id
+            })));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("Failed to check for duplicate order: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to store order",
+                "details": e.to_string()
+            })));
+        }
+    }
+
     // Create new order from submission
-    let order = Order::new(req_body.order.clone(), req_body.signature.clone());
+    let mut order = intake.new_order(req_body.order.clone(), req_body.signature.clone())
+        .with_allocator_signature(req_body.allocator_signature.clone())
+        .with_callback_url(req_body.callback_url.clone());
+    let request_id = req.headers().get(REQUEST_ID_HEADER).and_then(|value| value.to_str().ok());
+    if let Some(request_id) = request_id.filter(|id| !id.is_empty()) {
+        order = order.with_correlation_id(request_id);
+    }
+    let span = tracing::info_span!(ORDER_SPAN, order_id = %order.id, correlation_id = %order.correlation_id);
+    store_order(order, load, storage, metrics).instrument(span).await
+}
+
+/// Accept an ERC-7683 order carrying a `MandateERC7683`; once converted it is
+/// handled exactly like a `POST /api/v1/orders` submission
+pub async fn submit_erc7683_order(
+    req: HttpRequest,
+    req_body: web::Json<Erc7683Submission>,
+    storage: web::Data<dyn Storage>,
+    intake: web::Data<OrderIntake>,
+    metrics: web::Data<SolverMetrics>,
+) -> Result<HttpResponse> {
+    let submission = match req_body.to_order_submission() {
+        Ok(submission) => submission,
+        Err(e) => {
+            tracing::warn!("Rejecting ERC-7683 order: {}", e);
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Invalid order submission",
+                "code": "malformed_order",
+                "message": e.to_string()
+            })));
+        }
+    };
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    if let Some(open_deadline) = req_body.order.open_deadline().filter(|&deadline| deadline as u64 <= now) {
+        tracing::warn!("Rejecting ERC-7683 order: open deadline {} has passed", open_deadline);
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Order would not be filled",
+            "code": "open_deadline_passed",
+            "message": format!("Open deadline has passed: {} <= {}", open_deadline, now)
+        })));
+    }
+
+    submit_order(req, web::Json(submission), storage, intake, metrics).await
+}
+
+async fn store_order(
+    order: Order,
+    load: IntakeLoad,
+    storage: web::Data<dyn Storage>,
+    metrics: web::Data<SolverMetrics>,
+) -> Result<HttpResponse> {
     let order_id = order.id;
+    let correlation_id = order.correlation_id.clone();
 
-    // Store order
-    match storage.store_order(order).await {
+    match storage.create_order(order).await {
         Ok(_) => {
+            metrics.orders_created.inc();
             tracing::info!("Order {} submitted successfully", order_id);
             tracing::info!("========================================");
             tracing::info!("📋 ORDER ID: {}", order_id);
             tracing::info!("🔗 Test finalization with: curl -X POST http://127.0.0.1:3000/api/v1/orders/{}/finalize", order_id);
             tracing::info!("========================================");
             
-            Ok(HttpResponse::Created().json(json!({
+            let mut response = HttpResponse::Created();
+            response.insert_header((REQUEST_ID_HEADER, correlation_id.clone()));
+            if let Some(retry_after) = load.retry_after_seconds {
+                response.insert_header(("Retry-After", retry_after.to_string()));
+            }
+            Ok(response.json(json!({
                 "id": order_id,
+                "correlation_id": correlation_id,
                 "status": "pending",
-                "message": "Order submitted successfully"
+                "message": "Order submitted successfully",
+                "load": load
             })))
         }
         Err(e) => {
@@ -41,9 +185,53 @@ pub async fn submit_order(
     }
 }
 
+/// 400 naming why an order would not be filled; 500 when the solver's own settings are at fault
+pub fn validation_error_response(error: &OrderValidationError) -> HttpResponse {
+    let mut response = match error {
+        OrderValidationError::Misconfigured(_) => HttpResponse::InternalServerError(),
+        _ => HttpResponse::BadRequest(),
+    };
+    response.json(json!({
+        "error": "Order would not be filled",
+        "code": error.code(),
+        "message": error.to_string()
+    }))
+}
+
+/// Validate a signed order and estimate its fill without storing it
+pub async fn estimate_order(
+    req_body: web::Json<OrderSubmission>,
+    monitoring_service: web::Data<Arc<OrderMonitoringService>>,
+) -> Result<HttpResponse> {
+    let order = Order::new(req_body.order.clone(), req_body.signature.clone())
+        .with_allocator_signature(req_body.allocator_signature.clone());
+    let cross_chain_service = monitoring_service.cross_chain_service();
+
+    // The same checks a fill runs, so an estimate means the order would be accepted
+    if let Err(reason) = cross_chain_service.validate_fill_preconditions(&order).await {
+        return Ok(validation_error_response(&reason));
+    }
+
+    match cross_chain_service.estimate_fill_gas(&order).await {
+        Ok(estimate) => Ok(HttpResponse::Ok().json(json!({
+            "gas_limit": estimate.gas_limit.to::<u64>(),
+            "gas_price": estimate.gas_price.to_string(),
+            "total_cost": estimate.total_cost.to_string(),
+            "is_affordable": estimate.is_affordable
+        }))),
+        Err(e) => {
+            tracing::error!("Failed to estimate fill: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to estimate fill",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
 pub async fn get_order(
     path: web::Path<String>,
-    storage: web::Data<MemoryStorage>,
+    storage: web::Data<dyn Storage>,
 ) -> Result<HttpResponse> {
     let order_id_str = path.into_inner();
     
@@ -78,9 +266,147 @@ pub async fn get_order(
     }
 }
 
+/// Status transitions of an order, oldest first
+pub async fn get_order_history(
+    path: web::Path<String>,
+    storage: web::Data<dyn Storage>,
+) -> Result<HttpResponse> {
+    let order_id = match Uuid::from_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Invalid order ID format"
+            })))
+        }
+    };
+
+    match storage.get_order(order_id).await {
+        Ok(Some(order)) => Ok(HttpResponse::Ok().json(order.status_history)),
+        Ok(None) => {
+            Ok(HttpResponse::NotFound().json(json!({
+                "error": "Order not found"
+            })))
+        }
+        Err(e) => {
+            tracing::error!("Failed to retrieve order: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to retrieve order",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+/// Stop an order that has not been filled yet; filled orders can no longer be cancelled
+pub async fn cancel_order(
+    path: web::Path<String>,
+    storage: web::Data<dyn Storage>,
+) -> Result<HttpResponse> {
+    let order_id = match Uuid::from_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Invalid order ID format"
+            })))
+        }
+    };
+
+    let mut order = match storage.get_order(order_id).await {
+        Ok(Some(order)) => order,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Order not found"
+            })))
+        }
+        Err(e) => {
+            tracing::error!("Failed to retrieve order: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to retrieve order",
+                "details": e.to_string()
+            })))
+        }
+    };
+
+    if !order.can_cancel() {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": if order.fill_sending { "Order's fill is already being sent" } else { "Order can no longer be cancelled" },
+            "status": order.status
+        })));
+    }
+
+    // Only cancel the copy that was checked: if the fill started in between, it is too late
+    let read_at = order.updated_at;
+    order.update_status(OrderStatus::Cancelled);
+    match storage.update_order_if_unchanged(order, read_at).await {
+        Ok(true) => {
+            tracing::info!("Order {} cancelled", order_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "id": order_id,
+                "status": OrderStatus::Cancelled,
+                "message": "Order cancelled"
+            })))
+        }
+        Ok(false) => {
+            let status = storage.get_order(order_id).await.ok().flatten().map(|current| current.status);
+            Ok(HttpResponse::Conflict().json(json!({
+                "error": "Order changed while it was being cancelled; the cancel came too late",
+                "status": status
+            })))
+        }
+        Err(e) => {
+            tracing::error!("Failed to cancel order: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to cancel order",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+/// Recent log lines captured while processing an order, oldest first
+pub async fn get_order_logs(
+    path: web::Path<String>,
+    storage: web::Data<dyn Storage>,
+    order_logs: web::Data<OrderLogs>,
+) -> Result<HttpResponse> {
+    let order_id = match Uuid::from_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Invalid order ID format"
+            })))
+        }
+    };
+
+    match storage.get_order(order_id).await {
+        Ok(Some(_)) => Ok(HttpResponse::Ok().json(order_logs.get(order_id))),
+        Ok(None) => {
+            Ok(HttpResponse::NotFound().json(json!({
+                "error": "Order not found"
+            })))
+        }
+        Err(e) => {
+            tracing::error!("Failed to retrieve order: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to retrieve order",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+/// Query of `POST /api/v1/orders/{id}/finalize`
+#[derive(Debug, Default, Deserialize)]
+pub struct FinalizeQuery {
+    /// Skip the `finalization_delay_seconds` wait; the order must still be Filled
+    #[serde(default)]
+    pub force: bool,
+}
+
 pub async fn finalize_order(
     path: web::Path<String>,
-    storage: web::Data<MemoryStorage>,
+    query: web::Query<FinalizeQuery>,
+    storage: web::Data<dyn Storage>,
     monitoring_service: web::Data<Arc<OrderMonitoringService>>,
 ) -> Result<HttpResponse> {
     let order_id_str = path.into_inner();
@@ -99,8 +425,17 @@ pub async fn finalize_order(
     match storage.get_order(order_id).await {
         Ok(Some(_)) => {
             // Trigger manual finalization
-            match monitoring_service.trigger_finalization(order_id).await {
+            match monitoring_service.trigger_finalization(order_id, query.force).await {
                 Ok(true) => {
+                    let finalized = storage.get_order(order_id).await.ok().flatten();
+                    if let Some(order) = finalized.filter(|order| order.is_dry_run()) {
+                        return Ok(HttpResponse::Ok().json(json!({
+                            "id": order_id,
+                            "dry_run": true,
+                            "finalize_tx_hash": order.finalize_tx_hash,
+                            "message": "Dry run: finalization encoded and gas-estimated, no transaction was sent"
+                        })));
+                    }
                     Ok(HttpResponse::Ok().json(json!({
                         "id": order_id,
                         "message": "Finalization triggered successfully"
@@ -111,6 +446,16 @@ pub async fn finalize_order(
                         "error": "Finalization failed"
                     })))
                 }
+                Err(e) if e.downcast_ref::<FinalizationRefused>().is_some() => {
+                    let mut response = HttpResponse::Conflict();
+                    if let Some(FinalizationRefused::TooEarly { elapsed, delay }) = e.downcast_ref() {
+                        response.insert_header(("Retry-After", (delay - elapsed).to_string()));
+                    }
+                    Ok(response.json(json!({
+                        "error": "Finalization not started",
+                        "message": e.to_string()
+                    })))
+                }
                 Err(e) => {
                     tracing::error!("Failed to trigger finalization: {}", e);
                     Ok(HttpResponse::InternalServerError().json(json!({
@@ -135,8 +480,1222 @@ pub async fn finalize_order(
     }
 }
 
+/// Body of `POST /api/v1/orders/finalize/batch`: the listed orders, or every Filled one
+#[derive(Debug, Deserialize)]
+pub struct BatchFinalizeRequest {
+    #[serde(default)]
+    pub order_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub all_filled: bool,
+    /// Skip the `finalization_delay_seconds` wait, as for a single order
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Finalize several orders at once, each under the same preconditions as
+/// `POST /api/v1/orders/{id}/finalize`
+///
+/// Orders are taken `monitoring.batch_chunk_size` at a time, each chunk finalizing
+/// concurrently bounded by `monitoring.max_concurrent_operations`; more than
+/// `monitoring.max_batch_finalize` ids are refused, and `all_filled` takes only as many
+/// Filled orders as fit, reporting the rest as `remaining`. The response carries one
+/// result per order, in request order.
+pub async fn finalize_orders_batch(
+    request: web::Json<BatchFinalizeRequest>,
+    storage: web::Data<dyn Storage>,
+    monitoring_service: web::Data<Arc<OrderMonitoringService>>,
+    config: web::Data<SharedConfig>,
+) -> Result<HttpResponse> {
+    let request = request.into_inner();
+    let limits = config.current().monitoring.clone();
+    if request.order_ids.len() > limits.max_batch_finalize {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Too many orders in one batch",
+            "code": "batch_too_large",
+            "message": format!("{} order ids exceed the maximum of {}", request.order_ids.len(), limits.max_batch_finalize)
+        })));
+    }
+    let mut order_ids = request.order_ids;
+    let mut seen: std::collections::HashSet<Uuid> = std::collections::HashSet::new();
+    order_ids.retain(|id| seen.insert(*id));
+    let mut remaining = 0;
+    if request.all_filled {
+        match storage.get_orders_by_status(OrderStatus::Filled).await {
+            Ok(mut filled) => {
+                // Oldest first, up to the batch maximum; the rest wait for another call
+                filled.sort_by_key(|order| (order.created_at, order.id));
+                filled.retain(|order| !seen.contains(&order.id));
+                let room = limits.max_batch_finalize.saturating_sub(order_ids.len());
+                remaining = filled.len().saturating_sub(room);
+                order_ids.extend(filled.into_iter().take(room).map(|order| order.id));
+            }
+            Err(e) => {
+                tracing::error!("Failed to retrieve filled orders: {}", e);
+                return Ok(HttpResponse::InternalServerError().json(json!({
+                    "error": "Failed to retrieve filled orders",
+                    "details": e.to_string()
+                })));
+            }
+        }
+    }
+    if order_ids.is_empty() && !request.all_filled {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Pass order_ids or all_filled: true"
+        })));
+    }
+
+    let mut results = Vec::with_capacity(order_ids.len());
+    for chunk in order_ids.chunks(limits.batch_chunk_size.max(1)) {
+        let finalizations = chunk
+            .iter()
+            .map(|&order_id| finalize_one(order_id, request.force, storage.get_ref(), &monitoring_service));
+        results.extend(futures_util::future::join_all(finalizations).await);
+    }
+    let finalized = results.iter().filter(|result| result["outcome"] == "finalized").count();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "total": results.len(),
+        "finalized": finalized,
+        "remaining": remaining,
+        "results": results
+    })))
+}
+
+/// Finalize one order of a batch and describe the outcome: `finalized`, `failed`,
+/// `refused` (preconditions not met), `not_found` or `error`
+async fn finalize_one(
+    order_id: Uuid,
+    force: bool,
+    storage: &dyn Storage,
+    monitoring_service: &OrderMonitoringService,
+) -> serde_json::Value {
+    match storage.get_order(order_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return json!({ "id": order_id, "outcome": "not_found" }),
+        Err(e) => return json!({ "id": order_id, "outcome": "error", "message": e.to_string() }),
+    }
+
+    let (outcome, message) = match monitoring_service.trigger_finalization(order_id, force).await {
+        Ok(true) => ("finalized", None),
+        Ok(false) => ("failed", None),
+        Err(e) if e.downcast_ref::<FinalizationRefused>().is_some() => ("refused", Some(e.to_string())),
+        Err(e) => ("error", Some(e.to_string())),
+    };
+    let order = storage.get_order(order_id).await.ok().flatten();
+    json!({
+        "id": order_id,
+        "outcome": outcome,
+        "status": order.as_ref().map(|order| &order.status),
+        "finalize_tx_hash": order.as_ref().and_then(|order| order.finalize_tx_hash.as_ref()),
+        "message": message.or_else(|| order.and_then(|order| order.error_message))
+    })
+}
+
+/// Most orders one `GET /api/v1/orders` page returns
+const MAX_LIST_LIMIT: usize = 500;
+
+fn default_list_limit() -> usize {
+    50
+}
+
+/// Filters and page of `GET /api/v1/orders`; `chain` is the origin chain id
+#[derive(Debug, Deserialize)]
+pub struct OrderListQuery {
+    pub status: Option<OrderStatus>,
+    pub user: Option<Address>,
+    pub chain: Option<u64>,
+    #[serde(default = "default_list_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// Orders matching the query, oldest first, one page at a time
+pub async fn list_orders(
+    query: web::Query<OrderListQuery>,
+    storage: web::Data<dyn Storage>,
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let filter = OrderFilter { status: query.status, user: query.user, origin_chain_id: query.chain };
+    let limit = query.limit.min(MAX_LIST_LIMIT);
+
+    match storage.search_orders(&filter, limit, query.offset).await {
+        Ok(page) => {
+            let orders: Vec<OrderResponse> = page.orders.iter().map(Order::to_response).collect();
+            Ok(HttpResponse::Ok().json(json!({
+                "count": orders.len(),
+                "orders": orders,
+                "total": page.total,
+                "limit": limit,
+                "offset": query.offset
+            })))
+        }
+        Err(e) => {
+            tracing::error!("Failed to list orders: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to list orders",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+/// Orders fetched from storage per chunk of an export
+const EXPORT_PAGE_SIZE: usize = 500;
+
+const EXPORT_CSV_HEADER: &str = "id,user,nonce,origin_chain_id,status,inputs,outputs,fill_tx_hash,finalize_tx_hash,\
+fill_gas_cost_wei,error_message,created_at,filled_at,updated_at\n";
+
+/// Query of `GET /api/v1/orders/export`
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default = "default_export_format")]
+    pub format: String,
+}
+
+fn default_export_format() -> String {
+    "csv".to_string()
+}
+
+/// Every order, oldest first, as CSV
+///
+/// Orders are read and written a page at a time, so the whole store is never
+/// held in memory. `inputs` lists `token:amount` pairs and `outputs`
+/// `chain_id:token:amount` triples, each separated by `;`.
+pub async fn export_orders(
+    query: web::Query<ExportQuery>,
+    storage: web::Data<dyn Storage>,
+) -> Result<HttpResponse> {
+    if query.format != "csv" {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Unsupported export format",
+            "message": format!("format {:?} is not supported; use csv", query.format)
+        })));
+    }
+
+    let header = futures_util::stream::once(async { Ok(bytes::Bytes::from_static(EXPORT_CSV_HEADER.as_bytes())) });
+    let rows = futures_util::stream::try_unfold(Some(0usize), move |offset| {
+        let storage = storage.clone();
+        async move {
+            let Some(offset) = offset else {
+                return Ok(None);
+            };
+            let page = storage.search_orders(&OrderFilter::default(), EXPORT_PAGE_SIZE, offset).await
+                .map_err(|e| {
+                    tracing::error!("Order export failed at offset {}: {}", offset, e);
+                    actix_web::error::ErrorInternalServerError(e)
+                })?;
+            let next = (page.orders.len() == EXPORT_PAGE_SIZE).then_some(offset + EXPORT_PAGE_SIZE);
+            let chunk: String = page.orders.iter().map(csv_row).collect();
+            Ok::<_, actix_web::Error>(Some((bytes::Bytes::from(chunk), next)))
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(("Content-Disposition", "attachment; filename=\"orders.csv\""))
+        .streaming(futures_util::StreamExt::chain(header, rows)))
+}
+
+/// One CSV line for `order`, in `EXPORT_CSV_HEADER` column order
+fn csv_row(order: &Order) -> String {
+    let standard_order = &order.standard_order;
+    let inputs = standard_order.inputs.iter()
+        .map(|(token_id, amount)| format!("{}:{}", validation::decode_token_id(*token_id).token, amount))
+        .collect::<Vec<_>>()
+        .join(";");
+    let outputs = standard_order.outputs.iter()
+        .map(|output| format!("{}:{}:{}", output.chain_id, output.token, output.amount))
+        .collect::<Vec<_>>()
+        .join(";");
+    let fields = [
+        order.id.to_string(),
+        standard_order.user.to_string(),
+        standard_order.nonce.to_string(),
+        standard_order.origin_chain_id.to_string(),
+        format!("{:?}", order.status),
+        inputs,
+        outputs,
+        order.fill_tx_hash.clone().unwrap_or_default(),
+        order.finalize_tx_hash.clone().unwrap_or_default(),
+        order.fill_gas.map(|gas| gas.gas_cost_wei.to_string()).unwrap_or_default(),
+        order.error_message.clone().unwrap_or_default(),
+        order.created_at.to_rfc3339(),
+        order.filled_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+        order.updated_at.to_rfc3339(),
+    ];
+    let mut row = fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",");
+    row.push('\n');
+    row
+}
+
+/// `field` quoted when it holds a separator, quote or line break
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Answer unparseable list filters (e.g. an unknown status) with a JSON 400
+fn list_query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(|err, _req| {
+        let response = HttpResponse::BadRequest().json(json!({
+            "error": "Invalid order query",
+            "message": err.to_string()
+        }));
+        actix_web::error::InternalError::from_response(err, response).into()
+    })
+}
+
+/// Answer undecodable submissions (e.g. a non-numeric amount) with a JSON 400
+fn submission_json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|err, _req| {
+        let response = HttpResponse::BadRequest().json(json!({
+            "error": "Invalid order submission",
+            "code": "malformed_order",
+            "message": err.to_string()
+        }));
+        actix_web::error::InternalError::from_response(err, response).into()
+    })
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
-    cfg.route("/api/v1/orders", web::post().to(submit_order))
+    // Only the routes taking new orders are rate limited
+    cfg.service(web::resource("/api/v1/orders")
+           .app_data(submission_json_config())
+           .app_data(list_query_config())
+           .route(web::get().to(list_orders))
+           .route(web::post().to(submit_order).wrap(from_fn(limit_requests))))
+       .service(web::resource("/api/v1/orders/erc7683")
+           .app_data(submission_json_config())
+           .route(web::post().to(submit_erc7683_order).wrap(from_fn(limit_requests))))
+       .service(web::resource("/api/v1/orders/estimate")
+           .app_data(submission_json_config())
+           .wrap(from_fn(limit_requests))
+           .route(web::post().to(estimate_order)))
+       .route("/api/v1/orders/export", web::get().to(export_orders))
+       .route("/api/v1/orders/finalize/batch", web::post().to(finalize_orders_batch))
        .route("/api/v1/orders/{id}", web::get().to(get_order))
-       .route("/api/v1/orders/{id}/finalize", web::post().to(finalize_order));
-} 
\ No newline at end of file
+       .route("/api/v1/orders/{id}/history", web::get().to(get_order_history))
+       .route("/api/v1/orders/{id}/logs", web::get().to(get_order_logs))
+       .route("/api/v1/orders/{id}/finalize", web::post().to(finalize_order))
+       .route("/api/v1/orders/{id}/cancel", web::post().to(cancel_order));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use actix_web::{test, App};
+    use serde_json::Value;
+
+    fn order_submission() -> Value {
+        json!({
+            "order": {
+                "user": "0x1111111111111111111111111111111111111111",
+                "nonce": 7,
+                "originChainId": 31337,
+                "expires": 4294967295u64,
+                "fillDeadline": 4294967295u64,
+                "localOracle": "0x2222222222222222222222222222222222222222",
+                "inputs": [["1", "100"]],
+                "outputs": [{
+                    "remoteOracle": "0x3333333333333333333333333333333333333333",
+                    "remoteFiller": "0x4444444444444444444444444444444444444444",
+                    "chainId": 31338,
+                    "token": "0x5555555555555555555555555555555555555555",
+                    "amount": "99",
+                    "recipient": "0x6666666666666666666666666666666666666666"
+                }]
+            },
+            "signature": "0x"
+        })
+    }
+
+    #[actix_web::test]
+    async fn test_erc7683_submission_is_stored_as_standard_order() {
+        use crate::models::erc7683::{mandate_order_data_type, MandateERC7683, MandateOutputData};
+        use alloy::primitives::{Bytes, U256};
+        use alloy::sol_types::SolValue;
+
+        let order_data = MandateERC7683 {
+            expires: u32::MAX,
+            localOracle: Address::from([0x22; 20]),
+            inputs: vec![[U256::from(1), U256::from(100)]],
+            outputs: vec![MandateOutputData {
+                remoteOracle: Address::from([0x33; 20]).into_word(),
+                remoteFiller: Address::from([0x44; 20]).into_word(),
+                chainId: U256::from(31338),
+                token: Address::from([0x55; 20]).into_word(),
+                amount: U256::from(99),
+                recipient: Address::from([0x66; 20]).into_word(),
+                remoteCall: Bytes::new(),
+                fulfillmentContext: Bytes::new(),
+            }],
+        };
+        let payload = json!({
+            "order": {
+                "originSettler": "0x7777777777777777777777777777777777777777",
+                "user": "0x1111111111111111111111111111111111111111",
+                "nonce": "7",
+                "originChainId": 31337,
+                "openDeadline": u32::MAX,
+                "fillDeadline": u32::MAX,
+                "orderDataType": mandate_order_data_type(),
+                "orderData": Bytes::from(order_data.abi_encode()),
+            },
+            "signature": "0x"
+        });
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new()))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .configure(config),
+        )
+        .await;
+
+        let req = test::TestRequest::post().uri("/api/v1/orders/erc7683").set_json(&payload).to_request();
+        let created: Value = test::call_and_read_body_json(&app, req).await;
+        let id = Uuid::from_str(created["id"].as_str().unwrap()).unwrap();
+        let stored = storage.get_order(id).await.unwrap().unwrap();
+        assert_eq!(stored.standard_order.nonce, 7);
+        assert_eq!(stored.standard_order.outputs[0].recipient, Address::from([0x66; 20]));
+
+        // The open deadline of a gasless order must still be ahead
+        let mut expired = payload.clone();
+        expired["order"]["nonce"] = json!("8");
+        expired["order"]["openDeadline"] = json!(1);
+        let req = test::TestRequest::post().uri("/api/v1/orders/erc7683").set_json(&expired).to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp["code"], "open_deadline_passed");
+    }
+
+    #[actix_web::test]
+    async fn test_submission_logs_carry_order_and_correlation_ids() {
+        use crate::config::LogFormat;
+        use crate::logging::{capture::Buffer, format_layer};
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(format_layer(LogFormat::Json, move || writer.clone()));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new()))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .route("/api/v1/orders", web::post().to(submit_order)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/orders")
+            .insert_header((REQUEST_ID_HEADER, "req-42"))
+            .set_json(order_submission())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        assert_eq!(resp.headers().get(REQUEST_ID_HEADER).unwrap(), "req-42");
+        let created: Value = test::read_body_json(resp).await;
+        let order_id = created["id"].as_str().unwrap().to_string();
+
+        let stored = storage.get_order(Uuid::from_str(&order_id).unwrap()).await.unwrap().unwrap();
+        assert_eq!(stored.correlation_id, "req-42");
+
+        let line = buffer
+            .json_lines()
+            .into_iter()
+            .find(|line| line["message"] == format!("Order {} submitted successfully", order_id))
+            .expect("submission was logged");
+        assert_eq!(line["span"]["name"], ORDER_SPAN);
+        assert_eq!(line["span"]["order_id"], order_id);
+        assert_eq!(line["span"]["correlation_id"], "req-42");
+    }
+
+    #[actix_web::test]
+    async fn test_malformed_amounts_are_rejected_at_submission() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new()))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .configure(config),
+        )
+        .await;
+
+        let mut submission = order_submission();
+        submission["order"]["outputs"][0]["amount"] = json!("lots");
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(submission).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "malformed_order");
+        assert!(body["message"].as_str().unwrap().contains("invalid uint256 \"lots\""), "{}", body);
+        assert_eq!(storage.count().await, 0);
+    }
+
+    #[actix_web::test]
+    async fn test_duplicate_submission_returns_conflict() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new()))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .route("/api/v1/orders", web::post().to(submit_order)),
+        )
+        .await;
+
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(order_submission()).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let created: Value = test::read_body_json(resp).await;
+
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(order_submission()).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 409);
+        let conflict: Value = test::read_body_json(resp).await;
+
+        assert_eq!(conflict["id"], created["id"]);
+        assert_eq!(storage.count().await, 1);
+    }
+
+    #[actix_web::test]
+    async fn test_same_user_nonce_is_rejected_until_first_fails() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new()))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .route("/api/v1/orders", web::post().to(submit_order)),
+        )
+        .await;
+
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(order_submission()).to_request();
+        let created: Value = test::read_body_json(test::call_service(&app, req).await).await;
+
+        // A different order reusing the same (user, nonce)
+        let mut second = order_submission();
+        second["order"]["outputs"][0]["amount"] = json!("98");
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(second.clone()).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 409);
+        let conflict: Value = test::read_body_json(resp).await;
+        assert_eq!(conflict["error"], "duplicate user nonce");
+        assert_eq!(conflict["id"], created["id"]);
+
+        // A failed order never consumed the nonce, so it can be taken over
+        let first_id = Uuid::from_str(created["id"].as_str().unwrap()).unwrap();
+        let mut first = storage.get_order(first_id).await.unwrap().unwrap();
+        first.set_error("fill reverted".to_string());
+        storage.update_order(first).await.unwrap();
+
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(second).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+        assert_eq!(storage.count().await, 2);
+    }
+
+    #[actix_web::test]
+    async fn test_blocked_intake_rejects_orders() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let intake = OrderIntake::new();
+        intake.block("low_balance", "Solver balance below minimum on chain(s) 31338".to_string());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(intake))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .route("/api/v1/orders", web::post().to(submit_order)),
+        )
+        .await;
+
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(order_submission()).to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), 503);
+        assert_eq!(storage.count().await, 0);
+    }
+
+    #[actix_web::test]
+    async fn test_oversized_orders_are_rejected() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let mut limits = crate::config::AppConfig::default();
+        limits.solver.max_inputs = Some(2);
+        limits.solver.max_outputs = Some(1);
+        let intake = OrderIntake::new().with_shared_config(SharedConfig::new(limits));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(intake))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .route("/api/v1/orders", web::post().to(submit_order)),
+        )
+        .await;
+
+        let mut too_many_outputs = order_submission();
+        let output = too_many_outputs["order"]["outputs"][0].clone();
+        too_many_outputs["order"]["outputs"] = json!([output, output]);
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(too_many_outputs).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "too_many_outputs");
+
+        let mut too_many_inputs = order_submission();
+        too_many_inputs["order"]["inputs"] = json!([["1", "100"], ["2", "100"], ["3", "100"]]);
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(too_many_inputs).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "too_many_inputs");
+        assert_eq!(storage.count().await, 0);
+
+        // Within both limits
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(order_submission()).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 201);
+    }
+
+    #[actix_web::test]
+    async fn test_saturated_queue_signals_backpressure() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let mut config = crate::config::AppConfig::default();
+        config.backpressure = crate::config::BackpressureConfig {
+            max_queue_depth: Some(3),
+            high_water_ratio: 0.5,
+            retry_after_seconds: 15,
+        };
+        let intake = OrderIntake::new().with_shared_config(SharedConfig::new(config));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(intake))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .route("/api/v1/orders", web::post().to(submit_order)),
+        )
+        .await;
+
+        let mut responses = Vec::new();
+        for nonce in 1..=4 {
+            let mut submission = order_submission();
+            submission["order"]["nonce"] = json!(nonce);
+            let req = test::TestRequest::post().uri("/api/v1/orders").set_json(submission).to_request();
+            responses.push(test::call_service(&app, req).await);
+        }
+
+        // Below the high-water mark: accepted without a hint
+        assert_eq!(responses[0].status(), 201);
+        assert!(responses[0].headers().get("Retry-After").is_none());
+        // Two of three slots taken: accepted, but clients are asked to slow down
+        assert_eq!(responses[2].status(), 201);
+        assert_eq!(responses[2].headers().get("Retry-After").unwrap(), "15");
+        // Full: refused until the queue drains
+        let saturated = responses.pop().unwrap();
+        assert_eq!(saturated.status(), 503);
+        assert_eq!(saturated.headers().get("Retry-After").unwrap(), "15");
+        let body: Value = test::read_body_json(saturated).await;
+        assert_eq!(body["load"]["pending"], 3);
+        assert_eq!(body["load"]["capacity"], 3);
+        assert_eq!(storage.count().await, 3);
+    }
+
+    #[actix_web::test]
+    async fn test_orders_export_as_csv() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .configure(config),
+        )
+        .await;
+
+        let standard_order: crate::models::StandardOrder = serde_json::from_value(order_submission()["order"].clone()).unwrap();
+        let mut order = Order::new(standard_order, "0x".to_string());
+        order.set_error("rpc down, retrying".to_string());
+        storage.create_order(order.clone()).await.unwrap();
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/api/v1/orders/export?format=csv").to_request()).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("content-type").unwrap(), "text/csv; charset=utf-8");
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("id,user,nonce,origin_chain_id,status,inputs,outputs,fill_tx_hash"));
+        assert!(lines[1].starts_with(&format!("{},{},{},31337,Failed,", order.id, order.standard_order.user, order.standard_order.nonce)), "{}", lines[1]);
+        assert!(lines[1].contains(",\"rpc down, retrying\","), "{}", lines[1]);
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/api/v1/orders/export?format=xml").to_request()).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_orders_are_listed_by_filter_and_page() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .configure(config),
+        )
+        .await;
+
+        // Three orders from 0x11..11 and two from 0x99..99, one second apart
+        let start = chrono::Utc::now();
+        for nonce in 1..=5u64 {
+            let mut standard_order: crate::models::StandardOrder = serde_json::from_value(order_submission()["order"].clone()).unwrap();
+            standard_order.nonce = nonce;
+            if nonce % 2 == 0 {
+                standard_order.user = Address::from([0x99; 20]);
+            }
+            let mut order = Order::new(standard_order, "0x".to_string());
+            order.created_at = start + chrono::Duration::seconds(nonce as i64);
+            storage.create_order(order).await.unwrap();
+        }
+        let list = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+        let user = "0x1111111111111111111111111111111111111111";
+
+        let body: Value = test::read_body_json(test::call_service(&app, list("/api/v1/orders")).await).await;
+        assert_eq!(body["total"], 5);
+        assert_eq!(body["count"], 5);
+
+        let body: Value = test::read_body_json(
+            test::call_service(&app, list(&format!("/api/v1/orders?user={}&limit=2", user))).await,
+        )
+        .await;
+        assert_eq!(body["total"], 3);
+        assert_eq!(body["count"], 2);
+        let first_page: Vec<Value> = body["orders"].as_array().unwrap().iter().map(|o| o["id"].clone()).collect();
+
+        // Pages follow on from each other; past the end they are empty
+        let body: Value = test::read_body_json(
+            test::call_service(&app, list(&format!("/api/v1/orders?user={}&limit=2&offset=2", user))).await,
+        )
+        .await;
+        assert_eq!(body["count"], 1);
+        assert!(!first_page.contains(&body["orders"][0]["id"]));
+        let body: Value = test::read_body_json(
+            test::call_service(&app, list(&format!("/api/v1/orders?user={}&limit=2&offset=3", user))).await,
+        )
+        .await;
+        assert_eq!(body["count"], 0);
+        assert_eq!(body["total"], 3);
+
+        let body: Value = test::read_body_json(
+            test::call_service(&app, list("/api/v1/orders?status=Pending&chain=1")).await,
+        )
+        .await;
+        assert_eq!(body["total"], 0);
+
+        let resp = test::call_service(&app, list("/api/v1/orders?status=Lost")).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_order_history_records_transitions() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new()))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .configure(config),
+        )
+        .await;
+
+        let req = test::TestRequest::post().uri("/api/v1/orders").set_json(order_submission()).to_request();
+        let created: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        let order_id = Uuid::from_str(created["id"].as_str().unwrap()).unwrap();
+
+        // Drive the order the way the fill path does
+        let mut order = storage.get_order(order_id).await.unwrap().unwrap();
+        order.update_status(crate::models::OrderStatus::Processing);
+        order.set_fill_tx("0xabc".to_string());
+        order.update_status(crate::models::OrderStatus::Filled);
+        storage.update_order(order).await.unwrap();
+
+        let req = test::TestRequest::get().uri(&format!("/api/v1/orders/{}/history", order_id)).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let history: Value = test::read_body_json(resp).await;
+
+        let history = history.as_array().unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0]["from"], Value::Null);
+        assert_eq!(history[0]["to"], "Pending");
+        assert_eq!(history[1]["from"], "Pending");
+        assert_eq!(history[1]["to"], "Processing");
+        assert_eq!(history[2]["to"], "Filled");
+        assert_eq!(history[2]["tx_hash"], "0xabc");
+    }
+
+    #[actix_web::test]
+    async fn test_order_logs_are_scoped_to_the_order() {
+        use crate::config::AppConfig;
+        use crate::services::CrossChainService;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        // Info and above, as in main; dependencies' trace output varies between calls
+        let order_logs = OrderLogs::new();
+        let subscriber = tracing_subscriber::registry()
+            .with(order_logs.layer())
+            .with(tracing_subscriber::filter::LevelFilter::INFO);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new()))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .app_data(web::Data::new(order_logs.clone()))
+                .configure(config),
+        )
+        .await;
+
+        let mut ids = Vec::new();
+        for nonce in [1, 2] {
+            let mut submission = order_submission();
+            submission["order"]["nonce"] = json!(nonce);
+            let req = test::TestRequest::post().uri("/api/v1/orders").set_json(submission).to_request();
+            let created: Value = test::read_body_json(test::call_service(&app, req).await).await;
+            ids.push(Uuid::from_str(created["id"].as_str().unwrap()).unwrap());
+        }
+
+        // Both fills stop at validation (the orders are unsigned), logging along the way
+        let service = CrossChainService::new(storage.clone(), AppConfig::default()).await.unwrap();
+        for id in &ids {
+            service.process_fill(*id).await.unwrap();
+        }
+
+        let req = test::TestRequest::get().uri(&format!("/api/v1/orders/{}/logs", ids[0])).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let logs: Value = test::read_body_json(resp).await;
+        let messages: Vec<&str> = logs.as_array().unwrap().iter().map(|line| line["message"].as_str().unwrap()).collect();
+
+        assert!(messages.contains(&format!("Processing fill for order: {}", ids[0]).as_str()));
+        assert!(messages.iter().any(|message| message.starts_with("Fill validation failed")));
+        assert!(messages.iter().all(|message| !message.contains(&ids[1].to_string())));
+        assert_eq!(order_logs.get(ids[1]).len(), messages.len());
+    }
+
+    #[actix_web::test]
+    async fn test_force_skips_only_the_finalization_delay() {
+        use crate::config::AppConfig;
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let monitoring_service = OrderMonitoringService::new(storage.clone(), AppConfig::default()).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(Arc::new(monitoring_service)))
+                .configure(config),
+        )
+        .await;
+
+        let standard_order: crate::models::StandardOrder = serde_json::from_value(order_submission()["order"].clone()).unwrap();
+        let pending = Order::new(standard_order.clone(), "0x".to_string());
+        let mut filled = Order::new(crate::models::StandardOrder { nonce: 8, ..standard_order }, "0x".to_string());
+        filled.set_fill_tx("0xfill".to_string());
+        filled.update_status(OrderStatus::Filled);
+        storage.create_order(pending.clone()).await.unwrap();
+        storage.create_order(filled.clone()).await.unwrap();
+        let finalize = |id: Uuid, query: &str| test::TestRequest::post().uri(&format!("/api/v1/orders/{}/finalize{}", id, query)).to_request();
+
+        // Filled a moment ago: still inside the 30s delay
+        let resp = test::call_service(&app, finalize(filled.id, "")).await;
+        assert_eq!(resp.status(), 409);
+        let retry_after: u64 = resp.headers().get("Retry-After").unwrap().to_str().unwrap().parse().unwrap();
+        assert!((29..=30).contains(&retry_after));
+        assert_eq!(storage.get_order(filled.id).await.unwrap().unwrap().status, OrderStatus::Filled);
+
+        // Forced, it goes ahead (and fails here only for want of a chain to send to)
+        let resp = test::call_service(&app, finalize(filled.id, "?force=true")).await;
+        assert_ne!(resp.status(), 409);
+        assert_ne!(storage.get_order(filled.id).await.unwrap().unwrap().status, OrderStatus::Filled);
+
+        // Force does not lift the Filled precondition, and the refused order is left as it was
+        let resp = test::call_service(&app, finalize(pending.id, "?force=true")).await;
+        assert_eq!(resp.status(), 409);
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["message"].as_str().unwrap().contains("must be Filled"));
+        assert_eq!(storage.get_order(pending.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+    }
+
+    #[actix_web::test]
+    async fn test_batch_finalizes_each_order_under_the_same_preconditions() {
+        use crate::config::AppConfig;
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let monitoring_service = OrderMonitoringService::new(storage.clone(), AppConfig::default()).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(Arc::new(monitoring_service)))
+                .app_data(web::Data::new(SharedConfig::new(AppConfig::default())))
+                .configure(config),
+        )
+        .await;
+
+        let standard_order: crate::models::StandardOrder = serde_json::from_value(order_submission()["order"].clone()).unwrap();
+        let pending = Order::new(standard_order.clone(), "0x".to_string());
+        let filled: Vec<Order> = (8..10)
+            .map(|nonce| {
+                let mut order = Order::new(crate::models::StandardOrder { nonce, ..standard_order.clone() }, "0x".to_string());
+                order.set_fill_tx("0xfill".to_string());
+                order.update_status(OrderStatus::Filled);
+                order
+            })
+            .collect();
+        for order in filled.iter().chain([&pending]) {
+            storage.create_order(order.clone()).await.unwrap();
+        }
+        let unknown = Uuid::new_v4();
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/orders/finalize/batch")
+            .set_json(json!({ "order_ids": [filled[0].id, filled[1].id, pending.id, unknown], "force": true }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["total"], 4);
+        let results = body["results"].as_array().unwrap();
+
+        // Both filled orders went through finalization (which fails here only for want of a chain)
+        for (result, order) in results.iter().zip(&filled) {
+            assert_eq!(result["id"], order.id.to_string());
+            assert!(["finalized", "failed", "error"].contains(&result["outcome"].as_str().unwrap()), "{}", result);
+            assert_ne!(storage.get_order(order.id).await.unwrap().unwrap().status, OrderStatus::Filled);
+        }
+        // The Filled precondition still holds, and unknown ids are reported
+        assert_eq!(results[2]["outcome"], "refused");
+        assert!(results[2]["message"].as_str().unwrap().contains("must be Filled"));
+        assert_eq!(storage.get_order(pending.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+        assert_eq!(results[3]["outcome"], "not_found");
+
+        let req = test::TestRequest::post().uri("/api/v1/orders/finalize/batch").set_json(json!({})).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_batch_refuses_too_many_ids_and_works_through_large_ones_in_chunks() {
+        use crate::config::AppConfig;
+
+        let mut limits = AppConfig::default();
+        limits.monitoring.max_batch_finalize = 20;
+        limits.monitoring.batch_chunk_size = 3;
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let monitoring_service = OrderMonitoringService::new(storage.clone(), AppConfig::default()).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(Arc::new(monitoring_service)))
+                .app_data(web::Data::new(SharedConfig::new(limits)))
+                .configure(config),
+        )
+        .await;
+
+        let standard_order: crate::models::StandardOrder = serde_json::from_value(order_submission()["order"].clone()).unwrap();
+        let mut ids = Vec::new();
+        for nonce in 0..21 {
+            let order = Order::new(crate::models::StandardOrder { nonce, ..standard_order.clone() }, "0x".to_string());
+            storage.create_order(order.clone()).await.unwrap();
+            ids.push(order.id);
+        }
+        let batch = |ids: &[Uuid]| test::TestRequest::post().uri("/api/v1/orders/finalize/batch").set_json(json!({ "order_ids": ids })).to_request();
+
+        // One id over the maximum: refused outright
+        let resp = test::call_service(&app, batch(&ids)).await;
+        assert_eq!(resp.status(), 400);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "batch_too_large");
+
+        // At the maximum, every chunk is worked through and results keep request order
+        let resp = test::call_service(&app, batch(&ids[..20])).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["total"], 20);
+        let results = body["results"].as_array().unwrap();
+        for (result, id) in results.iter().zip(&ids) {
+            assert_eq!(result["id"], id.to_string());
+            assert_eq!(result["outcome"], "refused");
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_batch_of_all_filled_takes_the_oldest_up_to_the_maximum() {
+        use crate::config::AppConfig;
+
+        let mut limits = AppConfig::default();
+        limits.monitoring.max_batch_finalize = 3;
+        limits.monitoring.batch_chunk_size = 2;
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let monitoring_service = OrderMonitoringService::new(storage.clone(), AppConfig::default()).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(Arc::new(monitoring_service)))
+                .app_data(web::Data::new(SharedConfig::new(limits)))
+                .configure(config),
+        )
+        .await;
+
+        let standard_order: crate::models::StandardOrder = serde_json::from_value(order_submission()["order"].clone()).unwrap();
+        let mut filled: Vec<Order> = (0..5)
+            .map(|nonce| {
+                let mut order = Order::new(crate::models::StandardOrder { nonce, ..standard_order.clone() }, "0x".to_string());
+                order.set_fill_tx("0xfill".to_string());
+                order.update_status(OrderStatus::Filled);
+                order
+            })
+            .collect();
+        for order in &filled {
+            storage.create_order(order.clone()).await.unwrap();
+        }
+        filled.sort_by_key(|order| (order.created_at, order.id));
+
+        // Still inside the finalization delay, so each is refused and stays Filled
+        let req = test::TestRequest::post().uri("/api/v1/orders/finalize/batch").set_json(json!({ "all_filled": true })).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["total"], 3);
+        assert_eq!(body["remaining"], 2);
+        let results = body["results"].as_array().unwrap();
+        for (result, order) in results.iter().zip(&filled) {
+            assert_eq!(result["id"], order.id.to_string());
+            assert_eq!(result["outcome"], "refused");
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_cancel_only_unfilled_orders() {
+        use crate::config::AppConfig;
+        use crate::services::CrossChainService;
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new()))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .configure(config),
+        )
+        .await;
+
+        let mut ids = Vec::new();
+        for nonce in [1, 2] {
+            let mut submission = order_submission();
+            submission["order"]["nonce"] = json!(nonce);
+            let req = test::TestRequest::post().uri("/api/v1/orders").set_json(submission).to_request();
+            let created: Value = test::read_body_json(test::call_service(&app, req).await).await;
+            ids.push(Uuid::from_str(created["id"].as_str().unwrap()).unwrap());
+        }
+
+        // Pending -> Cancelled
+        let req = test::TestRequest::post().uri(&format!("/api/v1/orders/{}/cancel", ids[0])).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["status"], "Cancelled");
+        assert_eq!(storage.get_order(ids[0]).await.unwrap().unwrap().status, OrderStatus::Cancelled);
+
+        // Processing -> Cancelled is allowed too; cancelling twice is not
+        let mut processing = storage.get_order(ids[1]).await.unwrap().unwrap();
+        processing.update_status(OrderStatus::Processing);
+        storage.update_order(processing.clone()).await.unwrap();
+        let req = test::TestRequest::post().uri(&format!("/api/v1/orders/{}/cancel", ids[1])).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        let req = test::TestRequest::post().uri(&format!("/api/v1/orders/{}/cancel", ids[1])).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+        // Once its fill is being sent, a Processing order is no longer cancellable
+        let mut sending = storage.get_order(ids[1]).await.unwrap().unwrap();
+        sending.update_status(OrderStatus::Processing);
+        sending.start_fill_send();
+        storage.update_order(sending).await.unwrap();
+        let req = test::TestRequest::post().uri(&format!("/api/v1/orders/{}/cancel", ids[1])).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 409);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["status"], "Processing");
+
+        // Filled, Finalizing and Finalized orders can no longer be cancelled
+        for status in [OrderStatus::Filled, OrderStatus::Finalizing, OrderStatus::Finalized] {
+            processing.update_status(status.clone());
+            storage.update_order(processing.clone()).await.unwrap();
+            let req = test::TestRequest::post().uri(&format!("/api/v1/orders/{}/cancel", ids[1])).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 409, "{:?} must not be cancellable", status);
+            let body: Value = test::read_body_json(resp).await;
+            assert_eq!(body["status"], json!(status));
+        }
+
+        // The fill path leaves cancelled orders alone
+        let service = CrossChainService::new(storage.clone(), AppConfig::default()).await.unwrap();
+        let result = service.process_fill(ids[0]).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(storage.get_order(ids[0]).await.unwrap().unwrap().status, OrderStatus::Cancelled);
+    }
+
+    #[actix_web::test]
+    async fn test_estimate_validates_without_storing() {
+        use crate::config::AppConfig;
+        use crate::contracts::execution::{ChainType, ExecutionEngine, GasParams};
+        use crate::contracts::{signature, ContractFactory};
+        use crate::services::CrossChainService;
+        use alloy::primitives::Address;
+        use alloy::signers::local::PrivateKeySigner;
+        use alloy::signers::SignerSync;
+
+        struct EstimatingExecutor;
+
+        #[async_trait::async_trait]
+        impl ExecutionEngine for EstimatingExecutor {
+            async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> anyhow::Result<String> {
+                panic!("an estimate must not send transactions");
+            }
+
+            async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> anyhow::Result<Vec<u8>> {
+                Ok(Vec::new())
+            }
+
+            async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> anyhow::Result<u64> {
+                Ok(84_000)
+            }
+
+            async fn get_gas_price(&self, _chain: ChainType) -> anyhow::Result<u128> {
+                Ok(2_000_000_000)
+            }
+
+            fn wallet_address(&self) -> Address {
+                Address::from([0x11; 20])
+            }
+
+            fn description(&self) -> &str {
+                "EstimatingExecutor"
+            }
+        }
+
+        let config = AppConfig::default();
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(Arc::new(EstimatingExecutor));
+        let cross_chain_service = CrossChainService::new_with_factory(storage.clone(), Arc::new(factory), config.clone());
+        let monitoring_service = OrderMonitoringService::new(storage.clone(), config.clone())
+            .await
+            .unwrap()
+            .with_cross_chain_service(cross_chain_service);
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(Arc::new(monitoring_service)))
+                .configure(super::config),
+        )
+        .await;
+
+        // Sign the submission as its user
+        let signer = PrivateKeySigner::random();
+        let mut submission = order_submission();
+        submission["order"]["user"] = json!(signer.address());
+        let standard_order: crate::models::StandardOrder = serde_json::from_value(submission["order"].clone()).unwrap();
+        let domain = signature::order_domain(&config).unwrap();
+        let digest = signature::standard_order_digest(&standard_order, &domain).unwrap();
+        submission["signature"] = json!(format!("0x{}", hex::encode(signer.sign_hash_sync(&digest).unwrap().as_bytes())));
+
+        let req = test::TestRequest::post().uri("/api/v1/orders/estimate").set_json(&submission).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["gas_limit"], 100_800, "84000 estimated plus the 20% buffer");
+        assert_eq!(body["gas_price"], "2000000000");
+        assert_eq!(body["total_cost"], (100_800u64 * 2_000_000_000).to_string());
+        assert_eq!(storage.count().await, 0, "estimates must not persist orders");
+
+        // An unsigned order and a body that is not an order are both refused
+        let req = test::TestRequest::post().uri("/api/v1/orders/estimate").set_json(order_submission()).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], "bad_signature");
+        assert!(body["message"].as_str().unwrap().contains("Invalid order signature"));
+        let req = test::TestRequest::post().uri("/api/v1/orders/estimate").set_json(json!({ "order": 42 })).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_submissions_over_the_rate_limit_are_refused() {
+        use crate::config::{AppConfig, RateLimitConfig, TokenBucketConfig};
+        use crate::contracts::ContractFactory;
+        use crate::handlers::rate_limit::RateLimiter;
+
+        let limiter = RateLimiter::new(RateLimitConfig {
+            global: None,
+            per_ip: Some(TokenBucketConfig { per_second: 0.001, burst: 2 }),
+        });
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let factory = Arc::new(ContractFactory::new(AppConfig::default()).await.unwrap());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(storage.clone()))
+                .app_data(web::Data::new(OrderIntake::new()))
+                .app_data(web::Data::new(SolverMetrics::default()))
+                .app_data(web::Data::new(limiter))
+                .app_data(web::Data::new(factory))
+                .configure(config)
+                .configure(crate::handlers::health::config),
+        )
+        .await;
+        let client = "10.0.0.1:4000".parse().unwrap();
+
+        let submit = || {
+            test::TestRequest::post()
+                .uri("/api/v1/orders")
+                .peer_addr(client)
+                .set_json(order_submission())
+                .to_request()
+        };
+        assert_eq!(test::call_service(&app, submit()).await.status(), 201);
+        assert_eq!(test::call_service(&app, submit()).await.status(), 409);
+
+        let resp = test::call_service(&app, submit()).await;
+        assert_eq!(resp.status(), 429);
+        assert!(resp.headers().contains_key("Retry-After"));
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"], "Too many requests");
+
+        // Another client has its own budget, and reads are never limited
+        let req = test::TestRequest::post()
+            .uri("/api/v1/orders")
+            .peer_addr("10.0.0.2:4000".parse().unwrap())
+            .set_json(order_submission())
+            .to_request();
+        assert_ne!(test::call_service(&app, req).await.status(), 429);
+        let req = test::TestRequest::get().uri("/api/v1/health").peer_addr(client).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        assert_eq!(storage.count().await, 1);
+    }
+
+    #[actix_web::test]
+    async fn test_validation_error_response_carries_code() {
+        use crate::services::validation::OrderValidationError;
+
+        let resp = validation_error_response(&OrderValidationError::UnknownChain { id: 1 });
+        assert_eq!(resp.status(), 400);
+        let body: Value = serde_json::from_slice(&actix_web::body::to_bytes(resp.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["code"], "unknown_chain");
+        assert_eq!(body["message"], "Order origin chain 1 is not a configured chain");
+
+        // The solver's own configuration is not the client's fault
+        let resp = validation_error_response(&OrderValidationError::Misconfigured("bad allowlist".to_string()));
+        assert_eq!(resp.status(), 500);
+    }
+}
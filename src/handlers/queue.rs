@@ -1,14 +1,62 @@
 use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::str::FromStr;
+use uuid::Uuid;
 
-use crate::storage::MemoryStorage;
+use crate::models::{sort_by_priority, ExecutionPriority, Order, OrderStatus};
+use crate::storage::{QueueStatus, Storage};
+
+/// A pending or processing order, as listed by `GET /api/v1/queue`
+#[derive(Debug, Serialize)]
+pub struct QueueEntry {
+    pub id: Uuid,
+    pub status: OrderStatus,
+    pub priority: ExecutionPriority,
+    pub created_at: DateTime<Utc>,
+    pub age_seconds: i64,
+}
+
+impl QueueEntry {
+    fn new(order: &Order, now: DateTime<Utc>) -> Self {
+        Self {
+            id: order.id,
+            status: order.status.clone(),
+            priority: order.priority,
+            created_at: order.created_at,
+            age_seconds: (now - order.created_at).num_seconds(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueReport {
+    #[serde(flatten)]
+    pub counts: QueueStatus,
+    /// Queued orders in the order the monitor will process them
+    pub orders: Vec<QueueEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriorityRequest {
+    pub priority: ExecutionPriority,
+}
 
 pub async fn get_queue_status(
-    storage: web::Data<MemoryStorage>,
+    storage: web::Data<dyn Storage>,
 ) -> Result<HttpResponse> {
-    match storage.get_queue_status().await {
-        Ok(queue_status) => {
-            Ok(HttpResponse::Ok().json(queue_status))
+    match storage.get_all_orders().await {
+        Ok(orders) => {
+            let counts = QueueStatus::from_orders(&orders);
+            let mut queued: Vec<_> = orders.into_iter().filter(Order::is_queued).collect();
+            sort_by_priority(&mut queued);
+
+            let now = Utc::now();
+            Ok(HttpResponse::Ok().json(QueueReport {
+                counts,
+                orders: queued.iter().map(|order| QueueEntry::new(order, now)).collect(),
+            }))
         }
         Err(e) => {
             tracing::error!("Failed to get queue status: {}", e);
@@ -20,24 +68,59 @@ pub async fn get_queue_status(
     }
 }
 
-pub async fn get_all_orders(
-    storage: web::Data<MemoryStorage>,
+/// Change the processing priority of a queued order
+pub async fn set_order_priority(
+    path: web::Path<String>,
+    request: web::Json<PriorityRequest>,
+    storage: web::Data<dyn Storage>,
 ) -> Result<HttpResponse> {
-    match storage.get_all_orders().await {
-        Ok(orders) => {
-            let order_responses: Vec<_> = orders.into_iter()
-                .map(|order| order.to_response())
-                .collect();
-            
+    let order_id = match Uuid::from_str(&path.into_inner()) {
+        Ok(id) => id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Invalid order ID format"
+            })))
+        }
+    };
+
+    let mut order = match storage.get_order(order_id).await {
+        Ok(Some(order)) => order,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Order not found"
+            })))
+        }
+        Err(e) => {
+            tracing::error!("Failed to retrieve order: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to retrieve order",
+                "details": e.to_string()
+            })))
+        }
+    };
+
+    if !order.is_queued() {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": "Order is no longer queued",
+            "status": order.status
+        })));
+    }
+
+    let previous = order.priority;
+    order.priority = request.priority;
+    match storage.update_order(order).await {
+        Ok(_) => {
+            tracing::info!("Order {} priority changed from {:?} to {:?}", order_id, previous, request.priority);
             Ok(HttpResponse::Ok().json(json!({
-                "orders": order_responses,
-                "count": order_responses.len()
+                "id": order_id,
+                "priority": request.priority,
+                "previous_priority": previous
             })))
         }
         Err(e) => {
-            tracing::error!("Failed to get all orders: {}", e);
+            tracing::error!("Failed to update order priority: {}", e);
             Ok(HttpResponse::InternalServerError().json(json!({
-                "error": "Failed to get all orders",
+                "error": "Failed to update order priority",
                 "details": e.to_string()
             })))
         }
@@ -46,5 +129,79 @@ pub async fn get_all_orders(
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.route("/api/v1/queue", web::get().to(get_queue_status))
-       .route("/api/v1/orders", web::get().to(get_all_orders));
+       .route("/api/v1/queue/{id}/priority", web::post().to(set_order_priority));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MandateOutput, StandardOrder};
+    use crate::storage::MemoryStorage;
+    use actix_web::{test, App};
+    use alloy::primitives::{Address, U256};
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    fn queued_order(nonce: u64) -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        };
+        Order::new(standard_order, "0x".to_string())
+    }
+
+    #[actix_web::test]
+    async fn test_reprioritize_reorders_queue() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let older = queued_order(1);
+        let newer = queued_order(2);
+        let mut filled = queued_order(3);
+        filled.update_status(OrderStatus::Filled);
+        for order in [&older, &newer, &filled] {
+            storage.create_order(order.clone()).await.unwrap();
+        }
+        let app = test::init_service(App::new().app_data(web::Data::from(storage.clone())).configure(config)).await;
+
+        let queue_ids = |body: &Value| -> Vec<String> {
+            body["orders"].as_array().unwrap().iter().map(|entry| entry["id"].as_str().unwrap().to_string()).collect()
+        };
+
+        let req = test::TestRequest::get().uri("/api/v1/queue").to_request();
+        let body: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(body["pending"], 2);
+        assert_eq!(queue_ids(&body), vec![older.id.to_string(), newer.id.to_string()]);
+        assert_eq!(body["orders"][0]["priority"], "Normal");
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/queue/{}/priority", newer.id))
+            .set_json(json!({ "priority": "Urgent" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(storage.get_order(newer.id).await.unwrap().unwrap().priority, ExecutionPriority::Urgent);
+
+        let req = test::TestRequest::get().uri("/api/v1/queue").to_request();
+        let body: Value = test::read_body_json(test::call_service(&app, req).await).await;
+        assert_eq!(queue_ids(&body), vec![newer.id.to_string(), older.id.to_string()]);
+
+        // Orders that already left the queue cannot be reprioritized
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/queue/{}/priority", filled.id))
+            .set_json(json!({ "priority": "High" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 409);
+    }
 } 
\ No newline at end of file
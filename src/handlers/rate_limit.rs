@@ -0,0 +1,175 @@
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpResponse};
+use serde_json::json;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use arc_swap::ArcSwap;
+
+use crate::config::{RateLimitConfig, TokenBucketConfig};
+
+/// Past this many tracked clients, buckets that have refilled are forgotten
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(limit: &TokenBucketConfig, now: Instant) -> Self {
+        Self { tokens: limit.burst as f64, updated: now }
+    }
+
+    fn refill(&mut self, limit: &TokenBucketConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_second).min(limit.burst as f64);
+        self.updated = now;
+    }
+
+    fn is_full(&self, limit: &TokenBucketConfig) -> bool {
+        self.tokens >= limit.burst as f64
+    }
+
+    /// Time until a token is available, zero if one is already
+    fn wait(&self, limit: &TokenBucketConfig) -> Duration {
+        if self.tokens >= 1.0 {
+            return Duration::ZERO;
+        }
+        if limit.per_second <= 0.0 {
+            return Duration::MAX;
+        }
+        Duration::from_secs_f64((1.0 - self.tokens) / limit.per_second)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    global: Option<Bucket>,
+    per_ip: HashMap<Option<IpAddr>, Bucket>,
+}
+
+/// Token-bucket limits on requests, globally and per client IP
+///
+/// A request takes one token from each configured bucket, or none if either is empty.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    config: Arc<ArcSwap<RateLimitConfig>>,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config: Arc::new(ArcSwap::from_pointee(config)),
+            buckets: Arc::default(),
+        }
+    }
+
+    /// Apply new limits to every clone of this limiter, starting all buckets full
+    pub fn reconfigure(&self, config: RateLimitConfig) {
+        let mut buckets = self.buckets.lock().unwrap();
+        self.config.store(Arc::new(config));
+        *buckets = Buckets::default();
+    }
+
+    /// Admit a request from `ip`, or return how long until it would be admitted
+    pub fn check(&self, ip: Option<IpAddr>) -> Result<(), Duration> {
+        self.check_at(ip, Instant::now())
+    }
+
+    fn check_at(&self, ip: Option<IpAddr>, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let Buckets { global, per_ip } = &mut *buckets;
+        let config = self.config.load();
+
+        let global = config.global.as_ref().map(|limit| {
+            let bucket = global.get_or_insert_with(|| Bucket::full(limit, now));
+            bucket.refill(limit, now);
+            (limit, bucket)
+        });
+
+        if let Some(limit) = &config.per_ip {
+            if per_ip.len() >= MAX_TRACKED_CLIENTS {
+                per_ip.retain(|_, bucket| {
+                    bucket.refill(limit, now);
+                    !bucket.is_full(limit)
+                });
+            }
+        }
+        let client = config.per_ip.as_ref().map(|limit| {
+            let bucket = per_ip.entry(ip).or_insert_with(|| Bucket::full(limit, now));
+            bucket.refill(limit, now);
+            (limit, bucket)
+        });
+
+        let wait = [&global, &client]
+            .into_iter()
+            .flatten()
+            .map(|(limit, bucket)| bucket.wait(limit))
+            .max()
+            .unwrap_or_default();
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        for (_, bucket) in [global, client].into_iter().flatten() {
+            bucket.tokens -= 1.0;
+        }
+        Ok(())
+    }
+}
+
+/// Middleware refusing requests over the `RateLimiter` in app data with 429;
+/// requests pass untouched when none is registered
+pub async fn limit_requests<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    if let Some(limiter) = req.app_data::<web::Data<RateLimiter>>() {
+        let ip = req.peer_addr().map(|addr| addr.ip());
+        if let Err(wait) = limiter.check(ip) {
+            let retry_after = wait.as_secs_f64().ceil().min(u32::MAX as f64) as u64;
+            tracing::warn!("Rate limit exceeded for {:?} on {}", ip, req.path());
+            let response = HttpResponse::TooManyRequests()
+                .insert_header(("Retry-After", retry_after.to_string()))
+                .json(json!({
+                    "error": "Too many requests",
+                    "retry_after_seconds": retry_after
+                }));
+            return Ok(req.into_response(response).map_into_right_body());
+        }
+    }
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets_refill_over_time() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            global: Some(TokenBucketConfig { per_second: 1.0, burst: 3 }),
+            per_ip: Some(TokenBucketConfig { per_second: 1.0, burst: 1 }),
+        });
+        let (a, b) = (Some("10.0.0.1".parse().unwrap()), Some("10.0.0.2".parse().unwrap()));
+        let start = Instant::now();
+
+        assert!(limiter.check_at(a, start).is_ok());
+        let wait = limiter.check_at(a, start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+        // Another client has its own bucket, but shares the global one
+        assert!(limiter.check_at(b, start).is_ok());
+        // A refused request does not spend a global token
+        assert!(limiter.check_at(None, start).is_ok());
+        assert!(limiter.check_at(Some("10.0.0.3".parse().unwrap()), start).is_err());
+
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.check_at(a, later).is_ok());
+    }
+}
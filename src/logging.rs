@@ -0,0 +1,96 @@
+use anyhow::Result;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::{fmt, reload, Layer, Registry};
+
+use crate::config::LogFormat;
+
+/// The stdout formatting layer, boxed so `server.log_format` can swap it at runtime
+pub type FormatLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Handle for replacing the installed `FormatLayer` once the configuration is loaded
+pub type FormatHandle = reload::Handle<FormatLayer, Registry>;
+
+/// Formatting layer for `format` writing to `writer`
+///
+/// JSON lines carry event fields (such as `tx_hash`) at the top level and the
+/// fields of the enclosing span (such as `order_id`) under `span`.
+pub fn format_layer<W>(format: LogFormat, writer: W) -> FormatLayer
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => fmt::layer().with_writer(writer).boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+/// Switch stdout logging to `format`
+pub fn set_format(handle: &FormatHandle, format: LogFormat) -> Result<()> {
+    handle
+        .reload(format_layer(format, std::io::stdout))
+        .map_err(|e| anyhow::anyhow!("Failed to switch log format: {}", e))
+}
+
+/// In-memory log capture for tests that assert on emitted lines
+#[cfg(test)]
+pub(crate) mod capture {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    pub(crate) struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Buffer {
+        /// Captured JSON lines, oldest first
+        pub(crate) fn json_lines(&self) -> Vec<serde_json::Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    impl Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(bytes)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::capture::Buffer;
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_json_lines_carry_structured_fields() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(format_layer(LogFormat::Json, move || writer.clone()));
+
+        let order_id = uuid::Uuid::new_v4();
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("order", order_id = %order_id).entered();
+            tracing::info!(tx_hash = "0xabc", "Fill executed successfully");
+        });
+
+        let lines = buffer.json_lines();
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line["message"], "Fill executed successfully");
+        assert_eq!(line["tx_hash"], "0xabc");
+        assert_eq!(line["span"]["order_id"], order_id.to_string());
+    }
+}
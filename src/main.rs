@@ -1,54 +1,115 @@
 pub mod config;
 pub mod contracts;
 pub mod handlers;
+pub mod logging;
 pub mod models;
 pub mod server;
 pub mod services;
 pub mod storage;
 
 use anyhow::Result;
+use std::path::Path;
+use std::sync::Arc;
 use tracing::{info, error, warn};
-use tracing_subscriber;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter};
 use tokio::signal;
+use tokio::sync::Semaphore;
 
-use crate::config::AppConfig;
+use crate::config::{AppConfig, LogFormat, PersistenceBackend, SharedConfig};
 use crate::server::SolverServer;
-use crate::storage::memory::MemoryStorage;
+use crate::storage::{SqliteStorage, Storage};
 use crate::services::monitoring::OrderMonitoringService;
+use crate::services::heartbeat::HeartbeatService;
+use crate::services::balance::BalanceMonitor;
+use crate::services::intake::OrderIntake;
+use crate::services::validation::OrderAccess;
+use crate::services::in_flight::InFlightTracker;
+use crate::services::shutdown::graceful_shutdown;
+use crate::services::metrics::SolverMetrics;
+use crate::services::order_logs::OrderLogs;
+use crate::services::pause::PauseSwitch;
+use crate::contracts::encoding::replay::{self, ReplayOp};
+use crate::contracts::execution::AlloyExecutor;
 
 #[actix_web::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter("info")
+    // Initialize logging, keeping recent lines per order for /orders/{id}/logs.
+    // Lines are text until the configuration picks `server.log_format`.
+    let order_logs = OrderLogs::new();
+    let (format_layer, format_handle) = reload::Layer::new(logging::format_layer(LogFormat::Text, std::io::stdout));
+    tracing_subscriber::registry()
+        .with(format_layer)
+        .with(EnvFilter::new("info"))
+        .with(order_logs.layer())
         .init();
 
     info!("Starting OIF Solver Rust POC");
 
     // Load configuration
     let config = AppConfig::load().await?;
-    info!("Configuration loaded successfully");
+    if config.server.log_format != LogFormat::Text {
+        logging::set_format(&format_handle, config.server.log_format)?;
+    }
+    info!("Configuration loaded successfully (solver {})", config.solver.name());
+    contracts::encoding::selectors::log_selectors_in_effect(&config);
+
+    // `oif-solver-rust migrate` imports the JSON persistence file into SQLite and exits
+    if std::env::args().nth(1).as_deref() == Some("migrate") {
+        return migrate_json_to_sqlite(&config).await;
+    }
+
+    // `oif-solver-rust encode --order-id <uuid> [--op fill|finalize]` prints a stored order's calldata and exits
+    if std::env::args().nth(1).as_deref() == Some("encode") {
+        return encode_stored_order(&config, std::env::args().skip(2).collect()).await;
+    }
 
     // Initialize storage
-    let storage = MemoryStorage::new();
+    let storage: Arc<dyn Storage> = storage::open(&config.persistence).await?;
     info!("Storage initialized");
 
     // Load persisted data if enabled
-    if config.persistence.enabled {
+    let uses_data_file = config.persistence.enabled && config.persistence.backend == PersistenceBackend::Json;
+    if uses_data_file {
         info!("Loading persisted data from: {}", config.persistence.data_file);
-        if let Err(e) = storage.load_from_file(&config.persistence.data_file).await {
+        if let Err(e) = storage.load_from_file(Path::new(&config.persistence.data_file)).await {
             warn!("Failed to load persisted data: {}", e);
             info!("Starting with empty storage");
         } else {
             let count = storage.count().await;
             info!("Successfully loaded {} orders from persistence file", count);
         }
+    } else if config.persistence.enabled {
+        info!("Using SQLite storage at {} ({} orders)", config.persistence.database_url(), storage.count().await);
     } else {
         info!("Persistence disabled, starting with empty storage");
     }
 
+    // Refuse to sign for a chain other than the one configured
+    let executor = Arc::new(AlloyExecutor::new(Arc::new(config.clone()))?);
+    executor.verify_chain_ids().await?;
+    contracts::signature::verify_domain_separator(executor.as_ref(), &config).await?;
+
+    // Metrics shared by the background services and the /metrics endpoint
+    let metrics = SolverMetrics::new()?;
+    // Fills and finalizations under way, waited for on shutdown
+    let in_flight = InFlightTracker::new();
+
+    // Settings POST /api/v1/admin/reload-config can change without a restart
+    let shared_config = SharedConfig::new(config.clone());
+    // Flipped by POST /api/v1/admin/pause and /resume
+    let paused = PauseSwitch::new();
+    // Bounds fills and finalizations across the monitor and the HTTP endpoints
+    let operations = Arc::new(Semaphore::new(config.monitoring.max_concurrent_operations.max(1)));
+
     // Initialize monitoring service
-    let monitoring_service: OrderMonitoringService = OrderMonitoringService::new(storage.clone(), config.clone()).await?;
+    let monitoring_service: OrderMonitoringService = OrderMonitoringService::new(storage.clone(), config.clone()).await?
+        .with_shared_config(shared_config.clone())
+        .with_metrics(metrics.clone())
+        .with_in_flight_tracker(in_flight.clone())
+        .with_pause_switch(paused.clone())
+        .with_operation_limit(operations.clone());
     info!("Order monitoring service initialized");
 
     // Start background monitoring
@@ -58,16 +119,62 @@ async fn main() -> Result<()> {
         }
     });
 
+    // Announce the solver to the coordinator, if one is configured
+    match HeartbeatService::new(storage.clone(), config.clone())? {
+        Some(heartbeat_service) => {
+            tokio::spawn(async move {
+                if let Err(e) = heartbeat_service.start().await {
+                    error!("Heartbeat service error: {}", e);
+                }
+            });
+        }
+        None => info!("No coordinator configured, heartbeat disabled"),
+    }
+
+    // Refuse to start with access lists that would reject every submission
+    OrderAccess::from_config(&config.solver)?;
+    let intake = OrderIntake::new().with_shared_config(shared_config.clone());
+    if config.server.admin_token.is_none() {
+        warn!("No server.admin_token configured, the admin endpoints accept any caller");
+    }
+
+    // Check solver balances at startup and keep watching them
+    let balance_monitor = BalanceMonitor::new(executor, intake.clone(), config.clone())?;
+    if balance_monitor.is_enabled() {
+        match balance_monitor.check_balances().await {
+            Ok(alerts) if !alerts.is_empty() && !intake.is_accepting() => {
+                warn!("Solver starts with new orders blocked until balances are topped up");
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Initial balance check failed: {}", e),
+        }
+        tokio::spawn(async move {
+            if let Err(e) = balance_monitor.start().await {
+                error!("Balance monitor error: {}", e);
+            }
+        });
+    } else {
+        info!("No min_balance_alert configured, balance monitor disabled");
+    }
+
     // Start HTTP server
-    let server = SolverServer::new(storage.clone(), config.clone()).await?;
+    let server = SolverServer::new(storage.clone(), intake, metrics, in_flight.clone(), config.clone()).await?
+        .with_order_logs(order_logs)
+        .with_shared_config(shared_config)
+        .with_pause_switch(paused)
+        .with_operation_limit(operations);
     info!("Starting HTTP server on {}:{}", config.server.host, config.server.port);
     
+    let server = server.start().await?;
+    let server_handle = server.handle();
+    
     // Create storage reference for shutdown handling
     let storage_for_shutdown = storage.clone();
     let config_for_shutdown = config.clone();
     
-    // Handle shutdown signals
-    let shutdown_handle = tokio::spawn(async move {
+    // Handle shutdown signals; the server ignores them and is stopped here once
+    // running fills and finalizations have recorded their outcome and data is saved
+    tokio::spawn(async move {
         // Wait for shutdown signal
         #[cfg(unix)]
         {
@@ -86,27 +193,14 @@ async fn main() -> Result<()> {
             info!("Received Ctrl+C");
         }
         
-        // Perform graceful shutdown - save data if persistence is enabled
-        info!("Shutting down server gracefully...");
-        
-        if config_for_shutdown.persistence.enabled {
-            info!("Saving data to file: {}", config_for_shutdown.persistence.data_file);
-            if let Err(e) = storage_for_shutdown.save_to_file(&config_for_shutdown.persistence.data_file).await {
-                error!("Failed to save data during shutdown: {}", e);
-            } else {
-                let count = storage_for_shutdown.count().await;
-                info!("Successfully saved {} orders to persistence file", count);
-            }
-        } else {
-            info!("Persistence disabled, skipping data save");
-        }
-        
-        info!("Server shutdown complete");
+        let grace = std::time::Duration::from_secs(config_for_shutdown.server.shutdown_grace_seconds);
+        let data_file = uses_data_file.then(|| Path::new(&config_for_shutdown.persistence.data_file));
+        graceful_shutdown(&in_flight, grace, &storage_for_shutdown, data_file, server_handle).await;
     });
     
-    // Run server, monitoring service, and shutdown handler concurrently
+    // Run server and monitoring service concurrently; the server returns once shutdown stopped it
     tokio::select! {
-        result = server.run() => {
+        result = server => {
             if let Err(e) = result {
                 error!("Server error: {}", e);
             }
@@ -114,10 +208,40 @@ async fn main() -> Result<()> {
         _ = monitoring_handle => {
             error!("Monitoring service stopped unexpectedly");
         }
-        _ = shutdown_handle => {
-            info!("Shutdown signal received, stopping server");
-        }
     }
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Copy every order in `persistence.data_file` into the SQLite database at `persistence.database_url`
+async fn migrate_json_to_sqlite(config: &AppConfig) -> Result<()> {
+    let data_file = Path::new(&config.persistence.data_file);
+    let database_url = config.persistence.database_url();
+    info!("Migrating orders from {} to {}", data_file.display(), database_url);
+
+    let sqlite = SqliteStorage::connect(database_url).await?;
+    let imported = sqlite.import_json_file(data_file).await?;
+    info!("Migrated {} orders; the database now holds {}", imported, sqlite.count().await);
+    info!("Set persistence.backend = \"sqlite\" to run from the database");
+    Ok(())
+}
+
+/// Encode a stored order with both encoders and print the calldata, plus where it differs
+async fn encode_stored_order(config: &AppConfig, args: Vec<String>) -> Result<()> {
+    let flag = |name: &str| args.iter().position(|arg| arg == name).and_then(|i| args.get(i + 1));
+    let order_id: uuid::Uuid = flag("--order-id")
+        .ok_or_else(|| anyhow::anyhow!("Usage: encode --order-id <uuid> [--op fill|finalize]"))?
+        .parse()?;
+    let op: ReplayOp = flag("--op").map(String::as_str).unwrap_or("finalize").parse()?;
+
+    let storage = storage::open(&config.persistence).await?;
+    if config.persistence.backend == PersistenceBackend::Json {
+        storage.load_from_file(Path::new(&config.persistence.data_file)).await?;
+    }
+    let order = storage.get_order(order_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Order {} not found in persistence", order_id))?;
+
+    let comparison = replay::compare_encoders(&order, op, config)?;
+    println!("{}", comparison.report());
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! Serde adapters for uint256 order values, which travel as decimal strings
+//! but are parsed into `U256` once, when the order is deserialized
+
+use alloy::primitives::U256;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A `U256` written as a decimal string; reads decimal or `0x` hex strings and JSON integers
+pub mod u256_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+        deserializer.deserialize_any(U256Visitor)
+    }
+}
+
+/// `[tokenId, amount]` pairs, each value as in `u256_string`
+pub mod u256_pairs {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(pairs: &[(U256, U256)], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(pairs.iter().map(|(a, b)| (Wire(*a), Wire(*b))))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<(U256, U256)>, D::Error> {
+        let pairs = Vec::<(Wire, Wire)>::deserialize(deserializer)?;
+        Ok(pairs.into_iter().map(|(Wire(a), Wire(b))| (a, b)).collect())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Wire(#[serde(with = "u256_string")] U256);
+
+struct U256Visitor;
+
+impl Visitor<'_> for U256Visitor {
+    type Value = U256;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a uint256 as a decimal or 0x-prefixed hex string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<U256, E> {
+        let digits = value.strip_prefix("0x").unwrap_or(value);
+        let is_hex = digits.len() != value.len();
+        let valid = !digits.is_empty()
+            && digits.chars().all(|c| if is_hex { c.is_ascii_hexdigit() } else { c.is_ascii_digit() });
+        if !valid {
+            return Err(E::custom(format!("invalid uint256 \"{}\"", value)));
+        }
+        U256::from_str(value).map_err(|e| E::custom(format!("invalid uint256 \"{}\": {}", value, e)))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<U256, E> {
+        Ok(U256::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Amounts {
+        #[serde(with = "u256_string")]
+        amount: U256,
+        #[serde(with = "u256_pairs")]
+        inputs: Vec<(U256, U256)>,
+    }
+
+    #[test]
+    fn test_large_values_round_trip_as_decimal_strings() {
+        let json = serde_json::json!({
+            "amount": U256::MAX.to_string(),
+            "inputs": [["1", "1000000000000000000000000"]]
+        });
+        let amounts: Amounts = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(amounts.amount, U256::MAX);
+        assert_eq!(amounts.inputs, vec![(U256::from(1), U256::from(10).pow(U256::from(24)))]);
+        assert_eq!(serde_json::to_value(&amounts).unwrap(), json);
+
+        // Hex strings and plain integers are read, but written back as decimal strings
+        let amounts: Amounts = serde_json::from_str(r#"{"amount": "0xff", "inputs": [[1, 2]]}"#).unwrap();
+        assert_eq!(amounts, Amounts { amount: U256::from(255), inputs: vec![(U256::from(1), U256::from(2))] });
+    }
+
+    #[test]
+    fn test_non_numeric_amounts_are_rejected() {
+        for amount in [r#""lots""#, r#""""#, r#""-1""#, r#""1.5""#, r#""0x""#, "-1", "1.5"] {
+            let json = format!(r#"{{"amount": {}, "inputs": []}}"#, amount);
+            assert!(serde_json::from_str::<Amounts>(&json).is_err(), "{} was accepted", amount);
+        }
+
+        // Past 2^256 - 1
+        let json = format!(r#"{{"amount": "{}0", "inputs": []}}"#, U256::MAX);
+        assert!(serde_json::from_str::<Amounts>(&json).is_err());
+
+        let error = serde_json::from_str::<Amounts>(r#"{"amount": "1", "inputs": [["1", "lots"]]}"#).unwrap_err();
+        assert!(error.to_string().contains("invalid uint256 \"lots\""), "{}", error);
+    }
+}
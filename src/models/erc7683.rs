@@ -0,0 +1,295 @@
+//! ERC-7683 cross-chain orders, converted into the solver's `StandardOrder`
+//!
+//! Only orders whose `orderData` is an ABI-encoded `MandateERC7683` (the OIF
+//! mandate: expiry, local oracle, inputs and outputs) can be converted; its
+//! `orderDataType` must be that struct's EIP-712 type hash.
+
+use alloy::primitives::{keccak256, Address, Bytes, FixedBytes, U256};
+use alloy::sol_types::{SolStruct, SolValue};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AddressEncoding, MandateOutput, OrderSubmission, RawIdentifiers, StandardOrder};
+
+mod abi {
+    alloy::sol! {
+        struct MandateOutput {
+            bytes32 remoteOracle;
+            bytes32 remoteFiller;
+            uint256 chainId;
+            bytes32 token;
+            uint256 amount;
+            bytes32 recipient;
+            bytes remoteCall;
+            bytes fulfillmentContext;
+        }
+
+        struct MandateERC7683 {
+            uint32 expires;
+            address localOracle;
+            uint256[2][] inputs;
+            MandateOutput[] outputs;
+        }
+    }
+}
+
+pub use abi::{MandateERC7683, MandateOutput as MandateOutputData};
+
+/// An ERC-7683 order could not be turned into a `StandardOrder`
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum Erc7683Error {
+    #[error("Unsupported orderDataType {actual}, expected MandateERC7683 ({expected})")]
+    UnsupportedOrderDataType { actual: FixedBytes<32>, expected: FixedBytes<32> },
+
+    #[error("orderData is not an ABI-encoded MandateERC7683: {0}")]
+    BadOrderData(String),
+
+    #[error("{field} {value} does not fit in 64 bits")]
+    OutOfRange { field: String, value: U256 },
+}
+
+/// `GaslessCrossChainOrder`: signed by the user, opened on their behalf
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GaslessCrossChainOrder {
+    pub origin_settler: Address,
+    pub user: Address,
+    #[serde(with = "crate::models::amount::u256_string")]
+    pub nonce: U256,
+    pub origin_chain_id: u64,
+    pub open_deadline: u32,
+    pub fill_deadline: u32,
+    pub order_data_type: FixedBytes<32>,
+    pub order_data: Bytes,
+}
+
+/// `OnchainCrossChainOrder`, plus the user, nonce and chain of the `open` call
+/// that the struct itself does not carry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnchainCrossChainOrder {
+    pub user: Address,
+    #[serde(with = "crate::models::amount::u256_string")]
+    pub nonce: U256,
+    pub origin_chain_id: u64,
+    pub fill_deadline: u32,
+    pub order_data_type: FixedBytes<32>,
+    pub order_data: Bytes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Erc7683Order {
+    Gasless(GaslessCrossChainOrder),
+    Onchain(OnchainCrossChainOrder),
+}
+
+/// Body of `POST /api/v1/orders/erc7683`; the remaining fields are as in `OrderSubmission`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Erc7683Submission {
+    pub order: Erc7683Order,
+    pub signature: String,
+    #[serde(default)]
+    pub allocator_signature: Option<String>,
+    #[serde(default)]
+    pub callback_url: Option<String>,
+}
+
+/// The `orderDataType` of orders carrying a `MandateERC7683`
+pub fn mandate_order_data_type() -> FixedBytes<32> {
+    keccak256(MandateERC7683::eip712_encode_type().as_bytes())
+}
+
+impl Erc7683Order {
+    /// Deadline for opening a gasless order; onchain orders are already open
+    pub fn open_deadline(&self) -> Option<u32> {
+        match self {
+            Self::Gasless(order) => Some(order.open_deadline),
+            Self::Onchain(_) => None,
+        }
+    }
+
+    /// The order as the solver fills and finalizes it
+    pub fn to_standard_order(&self) -> Result<StandardOrder, Erc7683Error> {
+        let (user, nonce, origin_chain_id, fill_deadline, order_data_type, order_data) = match self {
+            Self::Gasless(o) => (o.user, o.nonce, o.origin_chain_id, o.fill_deadline, o.order_data_type, &o.order_data),
+            Self::Onchain(o) => (o.user, o.nonce, o.origin_chain_id, o.fill_deadline, o.order_data_type, &o.order_data),
+        };
+
+        let expected = mandate_order_data_type();
+        if order_data_type != expected {
+            return Err(Erc7683Error::UnsupportedOrderDataType { actual: order_data_type, expected });
+        }
+        let mandate = MandateERC7683::abi_decode(order_data).map_err(|e| Erc7683Error::BadOrderData(e.to_string()))?;
+
+        let outputs = mandate
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(i, output)| to_mandate_output(i, output))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(StandardOrder {
+            user,
+            nonce: to_u64("nonce", nonce)?,
+            origin_chain_id,
+            expires: mandate.expires as u64,
+            fill_deadline: fill_deadline as u64,
+            local_oracle: mandate.localOracle,
+            inputs: mandate.inputs.iter().map(|[token_id, amount]| (*token_id, *amount)).collect(),
+            outputs,
+        })
+    }
+}
+
+impl Erc7683Submission {
+    /// The equivalent submission of a `StandardOrder`
+    pub fn to_order_submission(&self) -> Result<OrderSubmission, Erc7683Error> {
+        Ok(OrderSubmission {
+            order: self.order.to_standard_order()?,
+            signature: self.signature.clone(),
+            allocator_signature: self.allocator_signature.clone(),
+            callback_url: self.callback_url.clone(),
+        })
+    }
+}
+
+/// An output whose identifiers are all left-padded addresses keeps the default
+/// encoding; any other identifier makes it `Raw32` so it is re-encoded verbatim
+fn to_mandate_output(index: usize, output: &MandateOutputData) -> Result<MandateOutput, Erc7683Error> {
+    let ids = [output.remoteOracle, output.remoteFiller, output.token, output.recipient];
+    let address_encoding = if ids.iter().all(|id| id[..12].iter().all(|&b| b == 0)) {
+        AddressEncoding::LeftPad20
+    } else {
+        AddressEncoding::Raw32(RawIdentifiers {
+            remote_oracle: output.remoteOracle,
+            remote_filler: output.remoteFiller,
+            token: output.token,
+            recipient: output.recipient,
+        })
+    };
+
+    Ok(MandateOutput {
+        remote_oracle: Address::from_word(output.remoteOracle),
+        remote_filler: Address::from_word(output.remoteFiller),
+        chain_id: to_u64(&format!("Output[{}]: chainId", index), output.chainId)?,
+        token: Address::from_word(output.token),
+        amount: output.amount,
+        recipient: Address::from_word(output.recipient),
+        remote_call: Some(output.remoteCall.to_string()),
+        fulfillment_context: Some(output.fulfillmentContext.to_string()),
+        address_encoding,
+    })
+}
+
+fn to_u64(field: &str, value: U256) -> Result<u64, Erc7683Error> {
+    u64::try_from(value).map_err(|_| Erc7683Error::OutOfRange { field: field.to_string(), value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn left_pad(address: Address) -> FixedBytes<32> {
+        address.into_word()
+    }
+
+    fn mandate() -> MandateERC7683 {
+        MandateERC7683 {
+            expires: 4_000_000_000,
+            localOracle: Address::from([0x22; 20]),
+            inputs: vec![[U256::from(7), U256::from(100)]],
+            outputs: vec![MandateOutputData {
+                remoteOracle: left_pad(Address::from([0x33; 20])),
+                remoteFiller: left_pad(Address::from([0x44; 20])),
+                chainId: U256::from(31338),
+                token: left_pad(Address::from([0x55; 20])),
+                amount: U256::from(99),
+                recipient: left_pad(Address::from([0x66; 20])),
+                remoteCall: Bytes::new(),
+                fulfillmentContext: Bytes::new(),
+            }],
+        }
+    }
+
+    fn gasless_payload(order_data: &MandateERC7683) -> serde_json::Value {
+        json!({
+            "order": {
+                "originSettler": "0x7777777777777777777777777777777777777777",
+                "user": "0x1111111111111111111111111111111111111111",
+                "nonce": "42",
+                "originChainId": 31337,
+                "openDeadline": 3_900_000_000u32,
+                "fillDeadline": 3_950_000_000u32,
+                "orderDataType": mandate_order_data_type(),
+                "orderData": Bytes::from(order_data.abi_encode()),
+            },
+            "signature": "0xabcd",
+            "callback_url": "https://example.com/hook"
+        })
+    }
+
+    #[test]
+    fn test_gasless_order_maps_to_standard_order() {
+        let submission: Erc7683Submission = serde_json::from_value(gasless_payload(&mandate())).unwrap();
+        assert_eq!(submission.order.open_deadline(), Some(3_900_000_000));
+
+        let converted = submission.to_order_submission().unwrap();
+        let order = &converted.order;
+        assert_eq!(order.user, Address::from([0x11; 20]));
+        assert_eq!(order.nonce, 42);
+        assert_eq!(order.origin_chain_id, 31337);
+        assert_eq!(order.expires, 4_000_000_000);
+        assert_eq!(order.fill_deadline, 3_950_000_000);
+        assert_eq!(order.local_oracle, Address::from([0x22; 20]));
+        assert_eq!(order.inputs, vec![(U256::from(7), U256::from(100))]);
+
+        let output = &order.outputs[0];
+        assert_eq!(output.remote_oracle, Address::from([0x33; 20]));
+        assert_eq!(output.remote_filler, Address::from([0x44; 20]));
+        assert_eq!(output.chain_id, 31338);
+        assert_eq!(output.token, Address::from([0x55; 20]));
+        assert_eq!(output.amount, U256::from(99));
+        assert_eq!(output.recipient, Address::from([0x66; 20]));
+        assert_eq!(output.address_encoding, AddressEncoding::LeftPad20);
+        assert_eq!(output.remote_call_bytes().unwrap(), Bytes::new());
+
+        assert_eq!(converted.signature, "0xabcd");
+        assert_eq!(converted.callback_url.as_deref(), Some("https://example.com/hook"));
+    }
+
+    #[test]
+    fn test_onchain_order_and_native_identifiers() {
+        let mut data = mandate();
+        let recipient = FixedBytes::from([0xab; 32]);
+        data.outputs[0].recipient = recipient;
+        let mut payload = gasless_payload(&data);
+        let order = payload["order"].as_object_mut().unwrap();
+        order.remove("originSettler");
+        order.remove("openDeadline");
+
+        let submission: Erc7683Submission = serde_json::from_value(payload).unwrap();
+        assert!(matches!(submission.order, Erc7683Order::Onchain(_)));
+        let output = &submission.order.to_standard_order().unwrap().outputs[0];
+        match &output.address_encoding {
+            AddressEncoding::Raw32(raw) => assert_eq!(raw.recipient, recipient),
+            other => panic!("expected Raw32, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_order_data_is_refused() {
+        let mut payload = gasless_payload(&mandate());
+        payload["order"]["orderDataType"] = json!(FixedBytes::<32>::ZERO);
+        let submission: Erc7683Submission = serde_json::from_value(payload).unwrap();
+        assert!(matches!(
+            submission.order.to_standard_order(),
+            Err(Erc7683Error::UnsupportedOrderDataType { .. })
+        ));
+
+        let mut payload = gasless_payload(&mandate());
+        payload["order"]["orderData"] = json!("0x1234");
+        let submission: Erc7683Submission = serde_json::from_value(payload).unwrap();
+        assert!(matches!(submission.order.to_standard_order(), Err(Erc7683Error::BadOrderData(_))));
+    }
+}
@@ -1,3 +1,5 @@
+pub mod amount;
+pub mod erc7683;
 pub mod order;
 pub mod mandate;
 
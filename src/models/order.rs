@@ -1,4 +1,4 @@
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{keccak256, Address, Bytes, FixedBytes, U256};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -14,7 +14,9 @@ pub struct StandardOrder {
     pub fill_deadline: u64,
     #[serde(rename = "localOracle")]
     pub local_oracle: Address,
-    pub inputs: Vec<(String, String)>, // [tokenId, amount] tuples
+    /// `[tokenId, amount]` pairs
+    #[serde(with = "crate::models::amount::u256_pairs")]
+    pub inputs: Vec<(U256, U256)>,
     pub outputs: Vec<MandateOutput>,
 }
 
@@ -27,14 +29,88 @@ pub struct MandateOutput {
     #[serde(rename = "chainId")]
     pub chain_id: u64,
     pub token: Address,
-    pub amount: String,
+    #[serde(with = "crate::models::amount::u256_string")]
+    pub amount: U256,
     pub recipient: Address,
     #[serde(rename = "remoteCall", default)]
     pub remote_call: Option<String>,
     #[serde(rename = "fulfillmentContext", default)]
     pub fulfillment_context: Option<String>,
+    #[serde(rename = "addressEncoding", default)]
+    pub address_encoding: AddressEncoding,
 }
 
+/// How an output's addresses become the bytes32 fields of the on-chain MandateOutput
+///
+/// EVM oracles expect the address right-aligned (`LeftPad20`); oracles on other
+/// chains may expect it left-aligned or a native 32-byte identifier instead.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum AddressEncoding {
+    /// 12 zero bytes followed by the address, as Solidity's `bytes32(uint256(uint160(a)))`
+    #[default]
+    LeftPad20,
+    /// The address followed by 12 zero bytes
+    RightPad20,
+    /// Identifiers given verbatim by the order; the 20-byte addresses are not used
+    Raw32(RawIdentifiers),
+}
+
+/// Native 32-byte identifiers of a `Raw32` output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawIdentifiers {
+    #[serde(rename = "remoteOracle")]
+    pub remote_oracle: FixedBytes<32>,
+    #[serde(rename = "remoteFiller")]
+    pub remote_filler: FixedBytes<32>,
+    pub token: FixedBytes<32>,
+    pub recipient: FixedBytes<32>,
+}
+
+/// The address-typed fields of a MandateOutput
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputField {
+    RemoteOracle,
+    RemoteFiller,
+    Token,
+    Recipient,
+}
+
+impl AddressEncoding {
+    /// Encode `address`, stored in `field` of the output, as bytes32
+    pub fn encode(&self, field: OutputField, address: Address) -> FixedBytes<32> {
+        let mut bytes = [0u8; 32];
+        match self {
+            Self::LeftPad20 => bytes[12..].copy_from_slice(address.as_slice()),
+            Self::RightPad20 => bytes[..20].copy_from_slice(address.as_slice()),
+            Self::Raw32(raw) => {
+                return match field {
+                    OutputField::RemoteOracle => raw.remote_oracle,
+                    OutputField::RemoteFiller => raw.remote_filler,
+                    OutputField::Token => raw.token,
+                    OutputField::Recipient => raw.recipient,
+                };
+            }
+        }
+        FixedBytes::from(bytes)
+    }
+}
+
+/// The bytes32 order id used on-chain for the order with id `order_id`: `keccak256(order_id)`
+pub fn onchain_order_id(order_id: &str) -> FixedBytes<32> {
+    keccak256(order_id.as_bytes())
+}
+
+/// Output token standing for the chain's native currency; such outputs are
+/// filled by sending their amount as the fill transaction's value
+pub const NATIVE_TOKEN: Address = Address::ZERO;
+
+/// Prefix of the synthetic "transaction hash" recorded in dry-run mode, followed by the gas estimate
+pub const DRY_RUN_TX_PREFIX: &str = "dry-run:";
+
+/// Prefix of the fill "transaction hash" recorded when an earlier, unrecorded fill
+/// was found on-chain, followed by the fill timestamp CoinFiller stored
+pub const ONCHAIN_FILL_TX_PREFIX: &str = "filled-onchain:";
+
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum OrderStatus {
     Pending,
@@ -43,6 +119,10 @@ pub enum OrderStatus {
     Finalizing,
     Finalized,
     Failed,
+    /// Stopped by an operator before it was filled
+    Cancelled,
+    /// Its `expires` timestamp passed before it was filled
+    Expired,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,12 +136,107 @@ pub struct Order {
     pub fill_tx_hash: Option<String>,
     pub finalize_tx_hash: Option<String>,
     pub error_message: Option<String>,
+    /// When the fill transaction was recorded; set once, unlike `updated_at`
+    #[serde(default)]
+    pub filled_at: Option<DateTime<Utc>>,
+    /// Set when the fill transaction succeeded but did not have the expected effect
+    #[serde(default)]
+    pub fill_warning: Option<String>,
+    /// Every status change, oldest first; empty for orders persisted before it was tracked
+    #[serde(default)]
+    pub status_history: Vec<StatusTransition>,
+    /// Pending orders are filled highest priority first; Normal for orders persisted before it was tracked
+    #[serde(default)]
+    pub priority: ExecutionPriority,
+    /// Fill attempts that failed; bounded by `solver.max_fill_retries`
+    #[serde(default)]
+    pub fill_attempts: u32,
+    /// When a Failed order whose fill failed transiently goes back to Pending
+    #[serde(default)]
+    pub fill_retry_at: Option<DateTime<Utc>>,
+    /// Id tying together the logs of every step taken for this order
+    #[serde(default = "new_correlation_id")]
+    pub correlation_id: String,
+    /// Hex allocator signature passed to `finalise`; none for an always-OK allocator
+    #[serde(default)]
+    pub allocator_signature: Option<String>,
+    /// URL the order's final state is POSTed to once it is Filled, Finalized or Failed
+    #[serde(default)]
+    pub callback_url: Option<String>,
+    /// What the fill transaction cost, read from its receipt
+    #[serde(default)]
+    pub fill_gas: Option<GasUsage>,
+    /// Fill transaction of each output, in output order; `fill_tx_hash` is the first
+    #[serde(default)]
+    pub output_fill_txs: Vec<String>,
+    /// Set once the fill transaction is about to be sent, after which the order can no
+    /// longer be cancelled; cleared by the next status change
+    #[serde(default)]
+    pub fill_sending: bool,
+}
+
+/// What a mined transaction cost, as recorded in its receipt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasUsage {
+    pub gas_used: u64,
+    /// Price (wei) actually paid per unit of gas
+    pub effective_gas_price: u128,
+    /// `gas_used * effective_gas_price`
+    #[serde(with = "crate::models::amount::u256_string")]
+    pub gas_cost_wei: U256,
+}
+
+impl GasUsage {
+    pub fn new(gas_used: u64, effective_gas_price: u128) -> Self {
+        Self {
+            gas_used,
+            effective_gas_price,
+            gas_cost_wei: U256::from(gas_used) * U256::from(effective_gas_price),
+        }
+    }
+
+    /// The cost of this transaction and `other` together, at their gas-weighted average price
+    pub fn plus(self, other: GasUsage) -> Self {
+        let gas_used = self.gas_used + other.gas_used;
+        let gas_cost_wei = self.gas_cost_wei + other.gas_cost_wei;
+        let effective_gas_price = match gas_used {
+            0 => 0,
+            _ => u128::try_from(gas_cost_wei / U256::from(gas_used)).unwrap_or(u128::MAX),
+        };
+        Self { gas_used, effective_gas_price, gas_cost_wei }
+    }
+}
+
+/// How urgently the monitor should fill an order, lowest to highest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ExecutionPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    /// `None` for the initial status of a new order
+    pub from: Option<OrderStatus>,
+    pub to: OrderStatus,
+    pub timestamp: DateTime<Utc>,
+    /// Transaction that caused the transition (fill or finalization), if any
+    pub tx_hash: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderSubmission {
     pub order: StandardOrder,
     pub signature: String,
+    /// Hex allocator signature over the claim; omitted for allocators that need none
+    #[serde(default)]
+    pub allocator_signature: Option<String>,
+    /// http(s) URL notified when the order reaches a terminal state
+    #[serde(default)]
+    pub callback_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,18 +246,48 @@ pub struct OrderResponse {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub fill_tx_hash: Option<String>,
+    /// Fill transaction of each output, in output order
+    #[serde(default)]
+    pub output_fill_txs: Vec<String>,
     pub finalize_tx_hash: Option<String>,
     pub error_message: Option<String>,
+    pub fill_warning: Option<String>,
+    /// True when the recorded transactions are dry-run estimates, not real broadcasts
+    pub dry_run: bool,
+    #[serde(default)]
+    pub priority: ExecutionPriority,
+    #[serde(default)]
+    pub correlation_id: String,
+    #[serde(default)]
+    pub fill_gas: Option<GasUsage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FillResult {
     pub success: bool,
     pub tx_hash: Option<String>,
-    pub gas_cost: Option<U256>,
+    /// Cost of the transaction, when its receipt could be read
+    pub gas: Option<GasUsage>,
     pub error: Option<String>,
 }
 
+impl StandardOrder {
+    /// Id shared by every submission of this order, so submitters can precompute it
+    ///
+    /// The first 16 bytes of `keccak256(abi.encodePacked(user, uint256(nonce), uint256(originChainId)))`,
+    /// stamped as a version 8 (custom) UUID.
+    pub fn deterministic_id(&self) -> Uuid {
+        let mut packed = Vec::with_capacity(20 + 32 + 32);
+        packed.extend_from_slice(self.user.as_slice());
+        packed.extend_from_slice(&U256::from(self.nonce).to_be_bytes::<32>());
+        packed.extend_from_slice(&U256::from(self.origin_chain_id).to_be_bytes::<32>());
+        let hash = keccak256(&packed);
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash[..16]);
+        uuid::Builder::from_custom_bytes(bytes).into_uuid()
+    }
+}
+
 impl Order {
     pub fn new(standard_order: StandardOrder, signature: String) -> Self {
         let now = Utc::now();
@@ -96,17 +301,84 @@ impl Order {
             fill_tx_hash: None,
             finalize_tx_hash: None,
             error_message: None,
+            filled_at: None,
+            fill_warning: None,
+            status_history: vec![StatusTransition {
+                from: None,
+                to: OrderStatus::Pending,
+                timestamp: now,
+                tx_hash: None,
+            }],
+            priority: ExecutionPriority::Normal,
+            fill_attempts: 0,
+            fill_retry_at: None,
+            correlation_id: new_correlation_id(),
+            allocator_signature: None,
+            callback_url: None,
+            fill_gas: None,
+            output_fill_txs: Vec::new(),
+            fill_sending: false,
         }
     }
 
+    /// Replace the random id with one derived from the order's contents (see `StandardOrder::deterministic_id`)
+    pub fn with_deterministic_id(mut self) -> Self {
+        self.id = self.standard_order.deterministic_id();
+        self
+    }
+
+    /// The bytes32 id this order is filled under on-chain
+    pub fn onchain_id(&self) -> FixedBytes<32> {
+        onchain_order_id(&self.id.to_string())
+    }
+
+    /// Attach the allocator's signature, which finalization sends next to the sponsor's
+    pub fn with_allocator_signature(mut self, allocator_signature: Option<String>) -> Self {
+        self.allocator_signature = allocator_signature;
+        self
+    }
+
+    /// POST the order's final state to `callback_url` (see `services::callbacks`)
+    pub fn with_callback_url(mut self, callback_url: Option<String>) -> Self {
+        self.callback_url = callback_url;
+        self
+    }
+
+    /// Use the caller's id (e.g. an `X-Request-Id` header) to correlate this order's logs
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = correlation_id.into();
+        self
+    }
+
     pub fn update_status(&mut self, status: OrderStatus) {
+        let now = Utc::now();
+        let tx_hash = match status {
+            OrderStatus::Filled => self.fill_tx_hash.clone(),
+            OrderStatus::Finalized => self.finalize_tx_hash.clone(),
+            _ => None,
+        };
+        self.status_history.push(StatusTransition {
+            from: Some(self.status.clone()),
+            to: status.clone(),
+            timestamp: now,
+            tx_hash,
+        });
         self.status = status;
+        self.updated_at = now;
+        self.fill_sending = false;
+    }
+
+    /// Mark the fill transaction as about to be sent
+    pub fn start_fill_send(&mut self) {
+        self.fill_sending = true;
         self.updated_at = Utc::now();
     }
 
     pub fn set_fill_tx(&mut self, tx_hash: String) {
+        let now = Utc::now();
         self.fill_tx_hash = Some(tx_hash);
-        self.updated_at = Utc::now();
+        self.filled_at.get_or_insert(now);
+        self.updated_at = now;
     }
 
     pub fn set_finalize_tx(&mut self, tx_hash: String) {
@@ -114,10 +386,35 @@ impl Order {
         self.updated_at = Utc::now();
     }
 
+    /// Whether the order's `expires` timestamp (unix seconds) is at or before `now`
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.standard_order.expires <= now
+    }
+
+    /// Whether the order will not change again: finalized, cancelled, expired, or
+    /// failed with no fill retry scheduled
+    pub fn is_finished(&self) -> bool {
+        match self.status {
+            OrderStatus::Finalized | OrderStatus::Cancelled | OrderStatus::Expired => true,
+            OrderStatus::Failed => self.fill_retry_at.is_none(),
+            _ => false,
+        }
+    }
+
+    /// Only orders whose fill has not started being sent can be cancelled
+    pub fn can_cancel(&self) -> bool {
+        matches!(self.status, OrderStatus::Pending | OrderStatus::Processing) && !self.fill_sending
+    }
+
     pub fn set_error(&mut self, error: String) {
         self.error_message = Some(error);
-        self.status = OrderStatus::Failed;
-        self.updated_at = Utc::now();
+        self.fill_retry_at = None;
+        self.update_status(OrderStatus::Failed);
+    }
+
+    /// Whether a failed fill is scheduled to be retried by `now`
+    pub fn fill_retry_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == OrderStatus::Failed && self.fill_retry_at.is_some_and(|retry_at| retry_at <= now)
     }
 
     pub fn to_response(&self) -> OrderResponse {
@@ -127,10 +424,39 @@ impl Order {
             created_at: self.created_at,
             updated_at: self.updated_at,
             fill_tx_hash: self.fill_tx_hash.clone(),
+            output_fill_txs: self.output_fill_txs.clone(),
             finalize_tx_hash: self.finalize_tx_hash.clone(),
             error_message: self.error_message.clone(),
+            fill_warning: self.fill_warning.clone(),
+            dry_run: self.is_dry_run(),
+            priority: self.priority,
+            correlation_id: self.correlation_id.clone(),
+            fill_gas: self.fill_gas,
         }
     }
+
+    /// Whether the order is still waiting for (or in the middle of) its fill
+    pub fn is_queued(&self) -> bool {
+        matches!(self.status, OrderStatus::Pending | OrderStatus::Processing)
+    }
+
+    /// Whether the fill or finalization was only simulated (see `solver.dry_run`)
+    pub fn is_dry_run(&self) -> bool {
+        [&self.fill_tx_hash, &self.finalize_tx_hash]
+            .into_iter()
+            .flatten()
+            .any(|tx_hash| tx_hash.starts_with(DRY_RUN_TX_PREFIX))
+    }
+}
+
+/// Fresh correlation id for orders submitted without one
+pub fn new_correlation_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// Sort `orders` into processing order: highest priority first, oldest first within a priority
+pub fn sort_by_priority(orders: &mut [Order]) {
+    orders.sort_by_key(|order| (std::cmp::Reverse(order.priority), order.created_at));
 }
 
 impl MandateOutput {
@@ -139,7 +465,7 @@ impl MandateOutput {
         remote_filler: Address,
         chain_id: u64,
         token: Address,
-        amount: String,
+        amount: U256,
         recipient: Address,
     ) -> Self {
         Self {
@@ -151,16 +477,49 @@ impl MandateOutput {
             recipient,
             remote_call: Some("0x".to_string()),
             fulfillment_context: Some("0x".to_string()),
+            address_encoding: AddressEncoding::LeftPad20,
         }
     }
+
+    /// Whether the output carries a non-empty `remoteCall` payload
+    pub fn has_remote_call(&self) -> bool {
+        self.remote_call_bytes().is_ok_and(|call| !call.is_empty())
+    }
+
+    /// `remoteCall` as encoded on-chain (see `optional_hex_bytes`)
+    pub fn remote_call_bytes(&self) -> anyhow::Result<Bytes> {
+        optional_hex_bytes("remoteCall", self.remote_call.as_deref())
+    }
+
+    /// `fulfillmentContext` as encoded on-chain (see `optional_hex_bytes`)
+    pub fn fulfillment_context_bytes(&self) -> anyhow::Result<Bytes> {
+        optional_hex_bytes("fulfillmentContext", self.fulfillment_context.as_deref())
+    }
+}
+
+/// Decode an optional hex `bytes` field of an output
+///
+/// Missing, `""`, `"0x"` and the literal `"null"` some clients send all mean no
+/// data and encode as zero-length bytes; the contracts cannot tell them apart.
+/// Anything else must be well-formed hex, so malformed input is refused instead
+/// of being encoded as something the user never signed.
+pub fn optional_hex_bytes(field: &str, value: Option<&str>) -> anyhow::Result<Bytes> {
+    let value = match value {
+        None | Some("null") => return Ok(Bytes::new()),
+        Some(value) => value,
+    };
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    hex::decode(digits)
+        .map(Bytes::from)
+        .map_err(|e| anyhow::anyhow!("Invalid hex in {} {:?}: {}", field, value, e))
 }
 
 impl FillResult {
-    pub fn success(tx_hash: String, gas_cost: Option<U256>) -> Self {
+    pub fn success(tx_hash: String, gas: Option<GasUsage>) -> Self {
         Self {
             success: true,
             tx_hash: Some(tx_hash),
-            gas_cost,
+            gas,
             error: None,
         }
     }
@@ -169,7 +528,7 @@ impl FillResult {
         Self {
             success: false,
             tx_hash: None,
-            gas_cost: None,
+            gas: None,
             error: Some(error),
         }
     }
@@ -179,4 +538,152 @@ impl Default for OrderStatus {
     fn default() -> Self {
         OrderStatus::Pending
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orders_without_history_still_deserialize() {
+        let order = Order::new(
+            StandardOrder {
+                user: Address::from([0x11; 20]),
+                nonce: 1,
+                origin_chain_id: 31337,
+                expires: 4294967295,
+                fill_deadline: 4294967295,
+                local_oracle: Address::from([0x22; 20]),
+                inputs: vec![(U256::from(1), U256::from(100))],
+                outputs: vec![],
+            },
+            "0x".to_string(),
+        );
+        let mut value = serde_json::to_value(&order).unwrap();
+        value.as_object_mut().unwrap().remove("status_history");
+        value.as_object_mut().unwrap().remove("priority");
+
+        let loaded: Order = serde_json::from_value(value).unwrap();
+        assert!(loaded.status_history.is_empty());
+        assert_eq!(loaded.priority, ExecutionPriority::Normal);
+    }
+
+    #[test]
+    fn test_deterministic_id_depends_only_on_user_nonce_and_chain() {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce: 7,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![],
+        };
+        let first = Order::new(standard_order.clone(), "0x01".to_string()).with_deterministic_id();
+        let mut resubmitted = standard_order.clone();
+        resubmitted.fill_deadline = 1;
+        let second = Order::new(resubmitted, "0x02".to_string()).with_deterministic_id();
+
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.onchain_id(), second.onchain_id());
+        assert_eq!(first.onchain_id(), keccak256(first.id.to_string().as_bytes()));
+        assert_eq!(first.id.get_version_num(), 8);
+
+        let mut other_nonce = standard_order.clone();
+        other_nonce.nonce = 8;
+        assert_ne!(other_nonce.deterministic_id(), first.id);
+        let mut other_chain = standard_order;
+        other_chain.origin_chain_id = 1;
+        assert_ne!(other_chain.deterministic_id(), first.id);
+
+        // Random ids stay the default
+        assert_ne!(Order::new(other_chain.clone(), "0x".to_string()).id, Order::new(other_chain, "0x".to_string()).id);
+    }
+
+    #[test]
+    fn test_address_encoding_layouts() {
+        let address = Address::from([0xaa; 20]);
+
+        let left = AddressEncoding::LeftPad20.encode(OutputField::Token, address);
+        assert_eq!(left[..12], [0u8; 12]);
+        assert_eq!(left[12..], [0xaa; 20]);
+
+        let right = AddressEncoding::RightPad20.encode(OutputField::Token, address);
+        assert_eq!(right[..20], [0xaa; 20]);
+        assert_eq!(right[20..], [0u8; 12]);
+
+        let raw = AddressEncoding::Raw32(RawIdentifiers {
+            remote_oracle: FixedBytes::from([0x01; 32]),
+            remote_filler: FixedBytes::from([0x02; 32]),
+            token: FixedBytes::from([0x03; 32]),
+            recipient: FixedBytes::from([0x04; 32]),
+        });
+        assert_eq!(raw.encode(OutputField::RemoteOracle, address), FixedBytes::from([0x01; 32]));
+        assert_eq!(raw.encode(OutputField::RemoteFiller, address), FixedBytes::from([0x02; 32]));
+        assert_eq!(raw.encode(OutputField::Token, address), FixedBytes::from([0x03; 32]));
+        assert_eq!(raw.encode(OutputField::Recipient, address), FixedBytes::from([0x04; 32]));
+    }
+
+    #[test]
+    fn test_address_encoding_is_read_from_the_output() {
+        let mut value = serde_json::to_value(MandateOutput::new(
+            Address::from([0x11; 20]),
+            Address::from([0x22; 20]),
+            31338,
+            Address::from([0x33; 20]),
+            U256::from(1),
+            Address::from([0x44; 20]),
+        ))
+        .unwrap();
+
+        value.as_object_mut().unwrap().remove("addressEncoding");
+        let output: MandateOutput = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(output.address_encoding, AddressEncoding::LeftPad20);
+
+        value["addressEncoding"] = serde_json::json!("RightPad20");
+        let output: MandateOutput = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(output.address_encoding, AddressEncoding::RightPad20);
+
+        let id = format!("0x{}", "ab".repeat(32));
+        value["addressEncoding"] = serde_json::json!({
+            "Raw32": { "remoteOracle": id, "remoteFiller": id, "token": id, "recipient": id }
+        });
+        let output: MandateOutput = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            output.address_encoding.encode(OutputField::Recipient, output.recipient),
+            FixedBytes::from([0xab; 32])
+        );
+    }
+
+    #[test]
+    fn test_optional_hex_bytes() {
+        let mut output = MandateOutput::new(
+            Address::from([0x33; 20]),
+            Address::from([0x44; 20]),
+            31338,
+            Address::from([0x55; 20]),
+            U256::from(99),
+            Address::from([0x66; 20]),
+        );
+
+        // Valid hex, with or without the prefix
+        output.remote_call = Some("0xdeadbeef".to_string());
+        assert_eq!(output.remote_call_bytes().unwrap(), Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert!(output.has_remote_call());
+        assert_eq!(optional_hex_bytes("remoteCall", Some("beef")).unwrap(), Bytes::from(vec![0xbe, 0xef]));
+
+        // Missing and empty are both zero-length
+        for empty in [None, Some(""), Some("0x"), Some("null")] {
+            assert!(optional_hex_bytes("remoteCall", empty).unwrap().is_empty(), "{:?}", empty);
+        }
+        output.remote_call = None;
+        assert!(!output.has_remote_call());
+
+        // Malformed hex is an error, not silently empty
+        output.fulfillment_context = Some("0xnothex".to_string());
+        let err = output.fulfillment_context_bytes().unwrap_err();
+        assert!(err.to_string().contains("Invalid hex in fulfillmentContext"), "{}", err);
+        assert!(optional_hex_bytes("remoteCall", Some("0xabc")).is_err(), "odd length");
+    }
+}
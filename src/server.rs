@@ -1,50 +1,115 @@
-use actix_web::{web, App, HttpServer, middleware::Logger, Result};
+use actix_web::{web, App, HttpServer, dev::Server, middleware::Logger, Result};
 use actix_cors::Cors;
 use std::sync::Arc;
 
-use crate::config::AppConfig;
-use crate::storage::MemoryStorage;
+use crate::config::{AppConfig, SharedConfig};
+use crate::storage::Storage;
 use crate::services::OrderMonitoringService;
+use crate::services::intake::OrderIntake;
+use crate::services::in_flight::InFlightTracker;
+use crate::services::metrics::SolverMetrics;
+use crate::services::pause::PauseSwitch;
+use crate::services::order_logs::OrderLogs;
 use crate::contracts::ContractFactory;
 use crate::handlers;
+use crate::handlers::rate_limit::RateLimiter;
 
 pub struct SolverServer {
-    storage: MemoryStorage,
-    monitoring_service: Arc<OrderMonitoringService>,
+    storage: Arc<dyn Storage>,
+    monitoring_service: OrderMonitoringService,
     contract_factory: Arc<ContractFactory>,
+    intake: OrderIntake,
+    order_logs: OrderLogs,
+    metrics: SolverMetrics,
+    rate_limiter: RateLimiter,
+    paused: PauseSwitch,
+    shared_config: SharedConfig,
     config: AppConfig,
 }
 
 impl SolverServer {
-    pub async fn new(storage: MemoryStorage, config: AppConfig) -> Result<Self, anyhow::Error> {
+    pub async fn new(
+        storage: Arc<dyn Storage>,
+        intake: OrderIntake,
+        metrics: SolverMetrics,
+        in_flight: InFlightTracker,
+        config: AppConfig,
+    ) -> Result<Self, anyhow::Error> {
         // Create contract factory
         let contract_factory = ContractFactory::new(config.clone()).await?;
         let contract_factory = Arc::new(contract_factory);
 
         // Create monitoring service
-        let monitoring_service = OrderMonitoringService::new(storage.clone(), config.clone()).await?;
-        let monitoring_service = Arc::new(monitoring_service);
+        let paused = PauseSwitch::new();
+        let shared_config = SharedConfig::new(config.clone());
+        let monitoring_service = OrderMonitoringService::new(storage.clone(), config.clone()).await?
+            .with_shared_config(shared_config.clone())
+            .with_metrics(metrics.clone())
+            .with_in_flight_tracker(in_flight)
+            .with_pause_switch(paused.clone());
 
         Ok(Self {
             storage,
             monitoring_service,
             contract_factory,
+            intake,
+            order_logs: OrderLogs::new(),
+            metrics,
+            rate_limiter: RateLimiter::new(config.server.rate_limit.clone()),
+            paused,
+            shared_config,
             config,
         })
     }
 
+    /// Reload into `shared_config`, e.g. to share it with services built outside the server
+    pub fn with_shared_config(mut self, shared_config: SharedConfig) -> Self {
+        self.monitoring_service = self.monitoring_service.with_shared_config(shared_config.clone());
+        self.shared_config = shared_config;
+        self
+    }
+
+    /// Toggle `paused` from the admin endpoints, e.g. the switch of the monitor processing orders
+    pub fn with_pause_switch(mut self, paused: PauseSwitch) -> Self {
+        self.monitoring_service = self.monitoring_service.with_pause_switch(paused.clone());
+        self.paused = paused;
+        self
+    }
+
+    /// Count manual and batch finalizations against `operations`, the limit the running monitor fills under
+    pub fn with_operation_limit(mut self, operations: Arc<tokio::sync::Semaphore>) -> Self {
+        self.monitoring_service = self.monitoring_service.with_operation_limit(operations);
+        self
+    }
+
+    /// Serve order logs from the buffer fed by the global subscriber
+    pub fn with_order_logs(mut self, order_logs: OrderLogs) -> Self {
+        self.order_logs = order_logs;
+        self
+    }
+
     pub async fn run(self) -> std::io::Result<()> {
+        self.start().await?.await
+    }
+
+    /// Bind the HTTP server and start background monitoring; the returned server runs
+    /// until its handle stops it
+    ///
+    /// OS signals are left to the caller, which stops the server once in-flight work
+    /// is drained and saved (see `shutdown::graceful_shutdown`).
+    pub async fn start(self) -> std::io::Result<Server> {
         let bind_address = format!("{}:{}", self.config.server.host, self.config.server.port);
         
         // Start background monitoring
         tracing::info!("Starting background monitoring service...");
-        if let Err(e) = self.monitoring_service.start_background_monitoring().await {
+        let monitoring_service = Arc::new(self.monitoring_service);
+        if let Err(e) = monitoring_service.start_background_monitoring().await {
             tracing::error!("Failed to start monitoring service: {}", e);
         }
         
         tracing::info!("Starting HTTP server on {}", bind_address);
 
-        HttpServer::new(move || {
+        Ok(HttpServer::new(move || {
             let cors = Cors::default()
                 .allow_any_origin()
                 .allow_any_method()
@@ -52,19 +117,27 @@ impl SolverServer {
                 .max_age(3600);
 
             App::new()
-                .app_data(web::Data::new(self.storage.clone()))
-                .app_data(web::Data::new(self.monitoring_service.clone()))
+                .app_data(web::Data::from(self.storage.clone()))
+                .app_data(web::Data::new(monitoring_service.clone()))
                 .app_data(web::Data::new(self.contract_factory.clone()))
+                .app_data(web::Data::new(self.intake.clone()))
+                .app_data(web::Data::new(self.order_logs.clone()))
+                .app_data(web::Data::new(self.metrics.clone()))
+                .app_data(web::Data::new(self.rate_limiter.clone()))
+                .app_data(web::Data::new(self.paused.clone()))
+                .app_data(web::Data::new(self.shared_config.clone()))
                 .wrap(cors)
                 .wrap(Logger::default())
                 .configure(handlers::health::config)
                 .configure(handlers::orders::config)
                 .configure(handlers::queue::config)
+                .configure(handlers::metrics::config)
+                .configure(handlers::admin::config)
                 .route("/", web::get().to(api_info))
         })
+        .disable_signals()
         .bind(&bind_address)?
-        .run()
-        .await
+        .run())
     }
 
 
@@ -78,10 +151,24 @@ async fn api_info() -> Result<actix_web::HttpResponse> {
         "endpoints": {
             "health": "GET /api/v1/health",
             "blockchain_health": "GET /api/v1/health/blockchain",
+            "chains_health": "GET /api/v1/health/chains",
+            "list_orders": "GET /api/v1/orders?status=&user=&chain=&limit=&offset=",
             "submit_order": "POST /api/v1/orders",
+            "submit_erc7683_order": "POST /api/v1/orders/erc7683",
+            "estimate_order": "POST /api/v1/orders/estimate",
+            "export_orders": "GET /api/v1/orders/export?format=csv",
             "get_order": "GET /api/v1/orders/{id}",
-            "finalize_order": "POST /api/v1/orders/{id}/finalize",
-            "queue_status": "GET /api/v1/queue"
+            "order_history": "GET /api/v1/orders/{id}/history",
+            "order_logs": "GET /api/v1/orders/{id}/logs",
+            "finalize_order": "POST /api/v1/orders/{id}/finalize?force=",
+            "finalize_orders": "POST /api/v1/orders/finalize/batch",
+            "cancel_order": "POST /api/v1/orders/{id}/cancel",
+            "queue_status": "GET /api/v1/queue",
+            "queue_priority": "POST /api/v1/queue/{id}/priority",
+            "pause": "POST /api/v1/admin/pause",
+            "resume": "POST /api/v1/admin/resume",
+            "reload_config": "POST /api/v1/admin/reload-config",
+            "metrics": "GET /metrics"
         }
     })))
 } 
\ No newline at end of file
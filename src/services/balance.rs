@@ -0,0 +1,303 @@
+use alloy::primitives::U256;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio::time::interval;
+use tracing::{error, info};
+
+use crate::config::AppConfig;
+use crate::contracts::execution::{ChainType, ExecutionEngine};
+use crate::services::intake::OrderIntake;
+
+const INTAKE_BLOCK_KEY: &str = "low_balance";
+
+/// A chain whose solver balance is below the configured minimum
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceAlert {
+    pub chain: ChainType,
+    pub chain_id: u64,
+    pub balance: U256,
+    pub threshold: U256,
+}
+
+/// Watches the solver wallet balance on both chains
+pub struct BalanceMonitor {
+    executor: Arc<dyn ExecutionEngine>,
+    intake: OrderIntake,
+    config: AppConfig,
+    threshold: Option<U256>,
+}
+
+impl BalanceMonitor {
+    pub fn new(executor: Arc<dyn ExecutionEngine>, intake: OrderIntake, config: AppConfig) -> Result<Self> {
+        let threshold = config
+            .balance
+            .min_balance_alert
+            .as_deref()
+            .map(|value| U256::from_str(value).map_err(|e| anyhow!("Invalid min_balance_alert {}: {}", value, e)))
+            .transpose()?;
+
+        Ok(Self {
+            executor,
+            intake,
+            config,
+            threshold,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.threshold.is_some()
+    }
+
+    /// Check both chains once, raising alerts and updating order intake
+    pub async fn check_balances(&self) -> Result<Vec<BalanceAlert>> {
+        let Some(threshold) = self.threshold else {
+            return Ok(Vec::new());
+        };
+
+        let chains = [
+            (ChainType::Origin, self.config.chains.origin.chain_id),
+            (ChainType::Destination, self.config.chains.destination.chain_id),
+        ];
+
+        let mut alerts = Vec::new();
+        for (chain, chain_id) in chains {
+            let balance = self.executor.get_balance(chain).await?;
+            if is_below_threshold(balance, threshold) {
+                error!(
+                    "🚨 CRITICAL: solver balance on chain {} is {} wei, below the {} wei minimum. Top up {}",
+                    chain_id, balance, threshold, self.executor.wallet_address()
+                );
+                alerts.push(BalanceAlert { chain, chain_id, balance, threshold });
+            } else {
+                info!("💰 Solver balance on chain {}: {} wei", chain_id, balance);
+            }
+        }
+
+        if self.config.balance.block_orders_when_low {
+            if alerts.is_empty() {
+                self.intake.unblock(INTAKE_BLOCK_KEY);
+            } else {
+                let chain_ids: Vec<String> = alerts.iter().map(|a| a.chain_id.to_string()).collect();
+                self.intake.block(
+                    INTAKE_BLOCK_KEY,
+                    format!("Solver balance below minimum on chain(s) {}", chain_ids.join(", ")),
+                );
+            }
+        }
+
+        Ok(alerts)
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        info!("Starting balance monitor");
+
+        let mut interval = interval(Duration::from_secs(self.config.balance.check_interval_seconds));
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.check_balances().await {
+                error!("Error checking solver balances: {}", e);
+            }
+        }
+    }
+}
+
+pub fn is_below_threshold(balance: U256, threshold: U256) -> bool {
+    balance < threshold
+}
+
+/// Solver balance on one chain, checked against the reserve it should hold
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainFunding {
+    pub chain_id: u64,
+    /// Wei, in decimal; `None` when the balance could not be read
+    pub balance: Option<String>,
+    pub reserve: String,
+    pub funded: bool,
+    pub error: Option<String>,
+}
+
+/// Every chain the solver is configured for: origin, destination, then the
+/// rest of the registry by chain id
+pub fn configured_chains(config: &AppConfig) -> Vec<(ChainType, u64)> {
+    let origin = config.chains.origin.chain_id;
+    let destination = config.chains.destination.chain_id;
+    let mut chains = vec![(ChainType::Origin, origin), (ChainType::Destination, destination)];
+    let mut others: Vec<u64> = config.chains.registry.keys()
+        .copied()
+        .filter(|chain_id| *chain_id != origin && *chain_id != destination)
+        .collect();
+    others.sort();
+    chains.extend(others.into_iter().map(|chain_id| (ChainType::ById(chain_id), chain_id)));
+    chains
+}
+
+/// Read the solver balance on every chain in `chains` in parallel, each read
+/// bounded by `timeout`; a chain whose balance can't be read is not funded
+pub async fn check_funding(
+    executor: Arc<dyn ExecutionEngine>,
+    chains: &[(ChainType, u64)],
+    reserve: U256,
+    timeout: Duration,
+) -> Vec<ChainFunding> {
+    let mut reads = JoinSet::new();
+    for (index, (chain, chain_id)) in chains.iter().copied().enumerate() {
+        let executor = executor.clone();
+        reads.spawn(async move {
+            let balance = match tokio::time::timeout(timeout, executor.get_balance(chain)).await {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(_) => Err(format!("Timed out after {}s", timeout.as_secs())),
+            };
+            (index, chain_id, balance)
+        });
+    }
+
+    let mut funding = Vec::with_capacity(chains.len());
+    while let Some(read) = reads.join_next().await {
+        let Ok((index, chain_id, balance)) = read else { continue };
+        let report = match balance {
+            Ok(balance) => ChainFunding {
+                chain_id,
+                balance: Some(balance.to_string()),
+                reserve: reserve.to_string(),
+                funded: !is_below_threshold(balance, reserve),
+                error: None,
+            },
+            Err(error) => ChainFunding {
+                chain_id,
+                balance: None,
+                reserve: reserve.to_string(),
+                funded: false,
+                error: Some(error),
+            },
+        };
+        funding.push((index, report));
+    }
+    funding.sort_by_key(|(index, _)| *index);
+    funding.into_iter().map(|(_, report)| report).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::execution::GasParams;
+    use alloy::primitives::Address;
+    use async_trait::async_trait;
+
+    struct MockExecutor {
+        origin_balance: U256,
+        destination_balance: U256,
+    }
+
+    #[async_trait]
+    impl ExecutionEngine for MockExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<String> {
+            Ok("0x".to_string())
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        async fn get_balance(&self, chain: ChainType) -> Result<U256> {
+            Ok(match chain {
+                ChainType::Origin => self.origin_balance,
+                ChainType::Destination => self.destination_balance,
+                ChainType::ById(chain_id) => anyhow::bail!("unknown chain {}", chain_id),
+            })
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "MockExecutor"
+        }
+    }
+
+    fn test_config(block_orders_when_low: bool) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.balance.min_balance_alert = Some("1000".to_string());
+        config.balance.block_orders_when_low = block_orders_when_low;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_low_balance_alerts_and_blocks_intake() {
+        let intake = OrderIntake::new();
+        let executor = Arc::new(MockExecutor {
+            origin_balance: U256::from(5000),
+            destination_balance: U256::from(10),
+        });
+        let monitor = BalanceMonitor::new(executor, intake.clone(), test_config(true)).unwrap();
+
+        let alerts = monitor.check_balances().await.unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].chain, ChainType::Destination);
+        assert_eq!(alerts[0].balance, U256::from(10));
+        assert!(!intake.is_accepting());
+        assert!(intake.blocked_reason().unwrap().contains("31338"));
+    }
+
+    #[tokio::test]
+    async fn test_low_balance_only_alerts_when_blocking_disabled() {
+        let intake = OrderIntake::new();
+        let executor = Arc::new(MockExecutor {
+            origin_balance: U256::ZERO,
+            destination_balance: U256::ZERO,
+        });
+        let monitor = BalanceMonitor::new(executor, intake.clone(), test_config(false)).unwrap();
+
+        assert_eq!(monitor.check_balances().await.unwrap().len(), 2);
+        assert!(intake.is_accepting());
+    }
+
+    #[tokio::test]
+    async fn test_funding_compares_balances_with_the_reserve() {
+        let executor = Arc::new(MockExecutor {
+            origin_balance: U256::from(999),
+            destination_balance: U256::from(1000),
+        });
+        let chains = [
+            (ChainType::Origin, 31337),
+            (ChainType::Destination, 31338),
+            (ChainType::ById(31339), 31339),
+        ];
+
+        let funding = check_funding(executor, &chains, U256::from(1000), Duration::from_secs(5)).await;
+
+        assert_eq!(funding.len(), 3);
+        assert_eq!((funding[0].chain_id, funding[0].funded), (31337, false));
+        assert_eq!(funding[0].balance.as_deref(), Some("999"));
+        assert_eq!((funding[1].chain_id, funding[1].funded), (31338, true));
+        assert_eq!(funding[1].reserve, "1000");
+        // A balance that can't be read counts as unfunded
+        assert!(!funding[2].funded);
+        assert!(funding[2].balance.is_none());
+        assert!(funding[2].error.as_deref().unwrap().contains("31339"));
+    }
+
+    #[tokio::test]
+    async fn test_topped_up_balance_reopens_intake() {
+        let intake = OrderIntake::new();
+        intake.block(INTAKE_BLOCK_KEY, "low".to_string());
+        let executor = Arc::new(MockExecutor {
+            origin_balance: U256::from(1000),
+            destination_balance: U256::from(1000),
+        });
+        let monitor = BalanceMonitor::new(executor, intake.clone(), test_config(true)).unwrap();
+
+        assert!(monitor.check_balances().await.unwrap().is_empty());
+        assert!(intake.is_accepting());
+    }
+}
@@ -0,0 +1,135 @@
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use crate::config::CallbackConfig;
+use crate::models::{Order, OrderResponse, OrderStatus};
+
+/// POSTs an order's state to the `callback_url` it was submitted with once it is
+/// Filled, Finalized or Failed
+///
+/// Deliveries run in the background with a bounded number of attempts, so a slow
+/// or failing endpoint never holds up fills and finalizations.
+#[derive(Clone)]
+pub struct CallbackNotifier {
+    client: reqwest::Client,
+    max_attempts: u32,
+    retry_delay: Duration,
+}
+
+impl CallbackNotifier {
+    pub fn new(config: &CallbackConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            max_attempts: config.max_attempts.max(1),
+            retry_delay: Duration::from_millis(config.retry_delay_ms),
+        }
+    }
+
+    /// Start delivering `order`'s state if it has a callback URL and is in a terminal state;
+    /// the returned task resolves to whether the endpoint accepted it
+    pub fn notify(&self, order: &Order) -> Option<JoinHandle<bool>> {
+        let url = order.callback_url.clone()?;
+        if !is_terminal(order) {
+            return None;
+        }
+
+        let payload = order.to_response();
+        let notifier = self.clone();
+        Some(tokio::spawn(async move { notifier.deliver(&url, &payload).await }))
+    }
+
+    async fn deliver(&self, url: &str, payload: &OrderResponse) -> bool {
+        for attempt in 1..=self.max_attempts {
+            let result = self
+                .client
+                .post(url)
+                .json(payload)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            match result {
+                Ok(_) => {
+                    info!("Notified {} that order {} is {:?}", url, payload.id, payload.status);
+                    return true;
+                }
+                Err(e) if attempt < self.max_attempts => {
+                    warn!("Callback for order {} failed (attempt {}/{}): {}", payload.id, attempt, self.max_attempts, e);
+                    sleep(self.retry_delay.saturating_mul(1 << (attempt - 1).min(16))).await;
+                }
+                Err(e) => {
+                    warn!("Giving up on callback for order {} after {} attempts: {}", payload.id, attempt, e);
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Whether the order will not change state again on its own; a failed fill that is
+/// scheduled for retry is not final yet
+fn is_terminal(order: &Order) -> bool {
+    match order.status {
+        OrderStatus::Filled | OrderStatus::Finalized => true,
+        OrderStatus::Failed => order.fill_retry_at.is_none(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MandateOutput, StandardOrder};
+    use alloy::primitives::{Address, U256};
+
+    fn create_test_order() -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce: 1,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        };
+        Order::new(standard_order, "0x".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_only_terminal_orders_with_a_url_are_notified() {
+        let notifier = CallbackNotifier::new(&CallbackConfig::default());
+        let mut order = create_test_order();
+        order.update_status(OrderStatus::Filled);
+        assert!(notifier.notify(&order).is_none(), "no callback_url");
+
+        let mut order = create_test_order().with_callback_url(Some("http://127.0.0.1:1/callback".to_string()));
+        assert!(notifier.notify(&order).is_none(), "still pending");
+
+        order.set_error("rpc down".to_string());
+        order.fill_retry_at = Some(chrono::Utc::now());
+        assert!(notifier.notify(&order).is_none(), "fill will be retried");
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoint_gives_up_after_max_attempts() {
+        let config = CallbackConfig { max_attempts: 2, retry_delay_ms: 1, timeout_seconds: 1 };
+        let mut order = create_test_order().with_callback_url(Some("http://127.0.0.1:1/callback".to_string()));
+        order.set_error("invalid signature".to_string());
+
+        let delivery = CallbackNotifier::new(&config).notify(&order).unwrap();
+        assert!(!delivery.await.unwrap());
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+use crate::models::Order;
+
+/// Chains the monitor currently considers unreachable, keyed by chain id
+///
+/// Orders that need an unhealthy chain stay queued until it recovers instead of
+/// burning fill attempts against an RPC that cannot answer.
+#[derive(Debug, Clone, Default)]
+pub struct ChainHealthGate {
+    unhealthy: Arc<RwLock<BTreeMap<u64, String>>>,
+}
+
+impl ChainHealthGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mark_unhealthy(&self, chain_id: u64, reason: String) {
+        let previous = self.unhealthy.write().unwrap().insert(chain_id, reason.clone());
+        if previous.is_none() {
+            warn!("Chain {} marked unhealthy, pausing its orders: {}", chain_id, reason);
+        }
+    }
+
+    pub fn mark_healthy(&self, chain_id: u64) {
+        if self.unhealthy.write().unwrap().remove(&chain_id).is_some() {
+            info!("Chain {} recovered, resuming its orders", chain_id);
+        }
+    }
+
+    pub fn is_healthy(&self, chain_id: u64) -> bool {
+        !self.unhealthy.read().unwrap().contains_key(&chain_id)
+    }
+
+    /// The first destination chain of `order` that is unhealthy, with the reason
+    pub fn blocking_chain(&self, order: &Order) -> Option<(u64, String)> {
+        let unhealthy = self.unhealthy.read().unwrap();
+        order
+            .standard_order
+            .outputs
+            .iter()
+            .find_map(|output| unhealthy.get(&output.chain_id).map(|reason| (output.chain_id, reason.clone())))
+    }
+}
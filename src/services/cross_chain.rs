@@ -1,32 +1,72 @@
 use anyhow::Result;
 use tracing::{info, error, warn};
-use alloy::providers::Provider;
 use alloy::primitives::U256;
 use std::sync::Arc;
 
-use crate::config::AppConfig;
+use crate::config::{AppConfig, ChainDetails, SharedConfig};
 use crate::contracts::ContractFactory;
-use crate::models::{Order, OrderStatus, FillResult, MandateOutput};
-use crate::storage::MemoryStorage;
+use crate::contracts::encoding::FillRequest;
+use crate::models::{GasUsage, MandateOutput, Order, OrderStatus, FillResult, ONCHAIN_FILL_TX_PREFIX};
+use crate::contracts::execution;
+use crate::contracts::execution::traits::ChainType;
+use crate::contracts::operations::fill::AlreadyFilled;
+use crate::storage::Storage;
+use crate::services::{gas, validation};
+use crate::services::validation::OrderValidationError;
+use crate::services::callbacks::CallbackNotifier;
+use crate::services::metrics::SolverMetrics;
+use crate::services::pricing::{PriceOracle, StaticPriceOracle};
+use crate::contracts::signature;
 
 #[derive(Clone)]
 pub struct CrossChainService {
-    storage: MemoryStorage,
+    storage: Arc<dyn Storage>,
     contract_factory: Arc<ContractFactory>,
-    config: AppConfig,
+    metrics: SolverMetrics,
+    price_oracle: Arc<dyn PriceOracle>,
+    notifier: CallbackNotifier,
+    config: SharedConfig,
 }
 
 impl CrossChainService {
-    pub async fn new(storage: MemoryStorage, config: AppConfig) -> Result<Self> {
+    pub async fn new(storage: Arc<dyn Storage>, config: AppConfig) -> Result<Self> {
         let contract_factory = Arc::new(ContractFactory::new(config.clone()).await?);
         
-        Ok(Self {
+        Ok(Self::new_with_factory(storage, contract_factory, config))
+    }
+
+    /// Create a CrossChainService around an existing (possibly customised) ContractFactory
+    pub fn new_with_factory(storage: Arc<dyn Storage>, contract_factory: Arc<ContractFactory>, config: AppConfig) -> Self {
+        Self {
             storage,
             contract_factory,
-            config,
-        })
+            metrics: SolverMetrics::default(),
+            price_oracle: Arc::new(StaticPriceOracle::new(config.solver.token_prices.clone())),
+            notifier: CallbackNotifier::new(&config.callbacks),
+            config: SharedConfig::new(config),
+        }
+    }
+
+    /// Read validation, gas and profitability settings from `config` on every order,
+    /// so a reloaded configuration applies to the next one
+    pub fn with_shared_config(mut self, config: SharedConfig) -> Self {
+        self.config = config;
+        self
     }
 
+    /// Value tokens with `price_oracle` instead of the configured static prices
+    pub fn with_price_oracle(mut self, price_oracle: Arc<dyn PriceOracle>) -> Self {
+        self.price_oracle = price_oracle;
+        self
+    }
+
+    /// Record fill outcomes and durations in `metrics`
+    pub fn with_metrics(mut self, metrics: SolverMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    #[tracing::instrument(name = "order", skip_all, fields(order_id = %order_id, correlation_id = tracing::field::Empty))]
     pub async fn process_fill(&self, order_id: uuid::Uuid) -> Result<FillResult> {
         // Get order from storage
         let mut order = match self.storage.get_order(order_id).await? {
@@ -37,33 +77,65 @@ impl CrossChainService {
                 return Ok(FillResult::failure(error_msg));
             }
         };
+        tracing::Span::current().record("correlation_id", order.correlation_id.as_str());
+
+        if order.status == OrderStatus::Cancelled {
+            info!("Skipping fill for cancelled order: {}", order_id);
+            return Ok(FillResult::failure("Order was cancelled".to_string()));
+        }
+        if order.status == OrderStatus::Expired {
+            info!("Skipping fill for expired order: {}", order_id);
+            return Ok(FillResult::failure("Order has expired".to_string()));
+        }
 
         info!("Processing fill for order: {}", order_id);
+        let started = std::time::Instant::now();
 
         // Update status to processing
         order.update_status(OrderStatus::Processing);
         self.storage.update_order(order.clone()).await?;
 
         // Validate order before fill
-        if let Err(validation_error) = self.validate_fill_preconditions(&order) {
+        if let Err(validation_error) = self.validate_fill_preconditions(&order).await {
             let error_msg = format!("Fill validation failed: {}", validation_error);
             error!("{}", error_msg);
             order.set_error(error_msg.clone());
-            self.storage.update_order(order).await?;
+            self.metrics.orders_failed.inc();
+            self.storage.update_order(order.clone()).await?;
+            self.notifier.notify(&order);
             return Ok(FillResult::failure(error_msg));
         }
 
+        // From here on the order can no longer be cancelled; a cancel that landed
+        // while it was being validated wins instead
+        let read_at = order.updated_at;
+        order.start_fill_send();
+        if !self.storage.update_order_if_unchanged(order.clone(), read_at).await? {
+            let status = self.storage.get_order(order_id).await?.map(|current| current.status);
+            info!("Order {} changed to {:?} before its fill was sent, not filling", order_id, status);
+            return Ok(FillResult::failure(match status {
+                Some(OrderStatus::Cancelled) => "Order was cancelled".to_string(),
+                _ => "Order was changed while it was being filled".to_string(),
+            }));
+        }
+
         // Execute fill on destination chain
-        match self.execute_fill(&order).await {
+        match self.execute_fill(&mut order).await {
             Ok(fill_result) => {
                 if fill_result.success {
                     if let Some(tx_hash) = &fill_result.tx_hash {
-                        info!("Fill executed successfully: {}", tx_hash);
+                        info!(tx_hash = %tx_hash, "Fill executed successfully");
                         
                         // Update order with fill transaction hash and status
                         order.set_fill_tx(tx_hash.clone());
+                        if !order.is_dry_run() {
+                            self.verify_fill(&mut order).await;
+                        }
                         order.update_status(OrderStatus::Filled);
                         self.storage.update_order(order.clone()).await?;
+                        self.notifier.notify(&order);
+                        self.metrics.orders_filled.inc();
+                        self.metrics.fill_duration_seconds.observe(started.elapsed().as_secs_f64());
                         
                         // Clear logging for manual finalization testing
                         info!("========================================");
@@ -72,84 +144,194 @@ impl CrossChainService {
                         info!("🔗 Finalize with: curl -X POST http://127.0.0.1:3000/api/v1/orders/{}/finalize", order.id);
                         info!("========================================");
                     }
-                    Ok(fill_result)
+                    Ok(FillResult { gas: order.fill_gas, ..fill_result })
                 } else {
                     let error_msg = fill_result.error.unwrap_or("Unknown fill error".to_string());
                     error!("Fill execution failed: {}", error_msg);
                     
                     // Update order with error
+                    order.fill_attempts += 1;
                     order.set_error(error_msg.clone());
-                    self.storage.update_order(order).await?;
+                    self.metrics.orders_failed.inc();
+                    self.storage.update_order(order.clone()).await?;
+                    self.notifier.notify(&order);
                     Ok(FillResult::failure(error_msg))
                 }
             }
+            Err(e) if gas::is_gas_price_too_high(&e) => {
+                // Keep the order pending so monitoring retries once gas comes down
+                warn!("Deferring fill for order {}: {}", order.id, e);
+                order.update_status(OrderStatus::Pending);
+                self.storage.update_order(order).await?;
+
+                Ok(FillResult::failure(e.to_string()))
+            }
+            Err(e) if execution::error::is_retryable(&e) => {
+                let error_msg = format!("Fill execution error: {}", e);
+                order.fill_attempts += 1;
+                order.set_error(error_msg.clone());
+
+                // Transient RPC trouble: monitoring puts the order back once the backoff elapses
+                match self.config.current().solver.fill_retry_delay(order.fill_attempts) {
+                    Some(delay) => {
+                        warn!("Fill attempt {} for order {} failed, retrying in {}s: {}", order.fill_attempts, order.id, delay, e);
+                        order.fill_retry_at = Some(chrono::Utc::now() + chrono::Duration::seconds(delay as i64));
+                    }
+                    None => {
+                        error!("Fill for order {} failed {} times, giving up: {}", order.id, order.fill_attempts, e);
+                        self.metrics.orders_failed.inc();
+                    }
+                }
+                self.storage.update_order(order.clone()).await?;
+                self.notifier.notify(&order);
+
+                Ok(FillResult::failure(error_msg))
+            }
+            Err(e) if e.downcast_ref::<AlreadyFilled>().is_some() => {
+                let filled = e.downcast::<AlreadyFilled>()?;
+                self.record_existing_fill(order, filled).await
+            }
             Err(e) => {
                 let error_msg = format!("Fill execution error: {}", e);
                 error!("{}", error_msg);
                 
                 // Update order with error
+                order.fill_attempts += 1;
                 order.set_error(error_msg.clone());
-                self.storage.update_order(order).await?;
+                self.metrics.orders_failed.inc();
+                self.storage.update_order(order.clone()).await?;
+                self.notifier.notify(&order);
 
                 Ok(FillResult::failure(error_msg))
             }
         }
     }
 
-    fn validate_fill_preconditions(&self, order: &Order) -> Result<(), String> {
+    /// Adopt a fill CoinFiller already recorded instead of sending another one.
+    /// Only a fill by this solver can be finalized by it; any other fails the order.
+    async fn record_existing_fill(&self, mut order: Order, filled: AlreadyFilled) -> Result<FillResult> {
+        if filled.solver != self.get_solver_identifier().await? {
+            let error_msg = format!("Output already filled on-chain by another solver {}", filled.solver);
+            warn!("Order {}: {}", order.id, error_msg);
+            order.set_error(error_msg.clone());
+            self.metrics.orders_failed.inc();
+            self.storage.update_order(order.clone()).await?;
+            self.notifier.notify(&order);
+            return Ok(FillResult::failure(error_msg));
+        }
+
+        info!("Order {} was already filled by this solver on-chain, recording it as filled", order.id);
+        let tx_hash = format!("{}{}", ONCHAIN_FILL_TX_PREFIX, filled.timestamp);
+        order.set_fill_tx(tx_hash.clone());
+        order.fill_warning = Some("Fill found on-chain from an earlier attempt; its transaction hash was not recorded".to_string());
+        order.update_status(OrderStatus::Filled);
+        self.storage.update_order(order.clone()).await?;
+        self.notifier.notify(&order);
+        self.metrics.orders_filled.inc();
+
+        Ok(FillResult::success(tx_hash, None))
+    }
+
+    /// Inspect the receipt of every output's fill, recording their combined gas cost
+    /// and a warning if any fill needs attention
+    async fn verify_fill(&self, order: &mut Order) {
+        let outputs = &order.standard_order.outputs;
+        let mut warnings = Vec::new();
+        let mut gas: Option<GasUsage> = None;
+        for (index, (output, tx_hash)) in outputs.iter().zip(&order.output_fill_txs).enumerate() {
+            // Fills adopted from an earlier attempt have no receipt to inspect
+            if tx_hash.starts_with(ONCHAIN_FILL_TX_PREFIX) {
+                continue;
+            }
+            match self.contract_factory
+                .verify_fill(tx_hash, &order.id.to_string(), output.chain_id, output.recipient, output.has_remote_call())
+                .await
+            {
+                Ok(check) => {
+                    if let Some(warning) = check.warning {
+                        warn!("⚠️ Order {} fill of output {} flagged: {}", order.id, index, warning);
+                        warnings.push(match outputs.len() {
+                            1 => warning,
+                            _ => format!("Output[{}]: {}", index, warning),
+                        });
+                    }
+                    info!("Fill of output {} for order {} used {} gas at {} wei ({} wei)",
+                          index, order.id, check.gas.gas_used, check.gas.effective_gas_price, check.gas.gas_cost_wei);
+                    gas = Some(gas.map_or(check.gas, |total| total.plus(check.gas)));
+                }
+                Err(e) => warn!("Could not verify fill receipt {} for order {}: {}", tx_hash, order.id, e),
+            }
+        }
+        order.fill_warning = (!warnings.is_empty()).then(|| warnings.join("; "));
+        if gas.is_some() {
+            order.fill_gas = gas;
+        }
+    }
+
+    /// Checks a fill must pass before anything is sent: deadline, amounts, allowlists and the user's signature
+    pub async fn validate_fill_preconditions(&self, order: &Order) -> Result<(), OrderValidationError> {
         let standard_order = &order.standard_order;
 
         // Check fill deadline
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|e| format!("Time error: {}", e))?
+            .map_err(|e| OrderValidationError::Misconfigured(format!("Time error: {}", e)))?
             .as_secs();
 
         if standard_order.fill_deadline <= now {
-            return Err(format!(
-                "Fill deadline has passed: {} <= {}",
-                standard_order.fill_deadline, now
-            ));
+            return Err(OrderValidationError::DeadlinePassed {
+                deadline: standard_order.fill_deadline,
+                now,
+            });
         }
 
         // Check that we have outputs
         if standard_order.outputs.is_empty() {
-            return Err("Order has no outputs".to_string());
+            return Err(OrderValidationError::NoOutputs);
         }
 
         // Check that we have inputs
         if standard_order.inputs.is_empty() {
-            return Err("Order has no inputs".to_string());
+            return Err(OrderValidationError::NoInputs);
         }
 
-        // Validate output amounts
-        for output in &standard_order.outputs {
-            if output.amount.parse::<u128>().is_err() {
-                return Err(format!("Invalid output amount: {}", output.amount));
-            }
-        }
+        let config = self.config.current();
+        let solver = &config.solver;
+        validation::validate_order_size(standard_order, solver.max_inputs, solver.max_outputs)?;
+
+        // Inputs must be locked on our origin chain (and pass the token lists, if configured)
+        validation::validate_order_inputs(standard_order, &config)?;
+        validation::validate_order_user(standard_order, &config)?;
+
+        // Only fill orders whose oracles we can settle through, or the fill is unfinalizable
+        validation::validate_order_oracles(standard_order, &config)?;
+
+        // The order must be signed by its user, otherwise the on-chain fill only wastes gas
+        let executor = self.contract_factory.executor()
+            .map_err(|e| OrderValidationError::Misconfigured(e.to_string()))?;
+        signature::verify_order_signer(standard_order, &order.signature, &config, executor.as_ref())
+            .await
+            .map_err(OrderValidationError::BadSignature)?;
 
         Ok(())
     }
 
-    async fn execute_fill(&self, order: &Order) -> Result<FillResult> {
+    /// Fill every output of `order`, recording each fill transaction in `output_fill_txs`
+    async fn execute_fill(&self, order: &mut Order) -> Result<FillResult> {
         let standard_order = &order.standard_order;
         
-        // Get the first output (destination chain)
-        let destination_output = &standard_order.outputs[0];
-        
         info!(
-            "Executing fill for order {} on chain {}",
-            order.id, destination_output.chain_id
+            "Executing fill for order {} across {} output(s)",
+            order.id, standard_order.outputs.len()
         );
 
         // Log the fill parameters that will be sent
         info!("Fill parameters:");
         info!("  Order ID: {}", order.id);
-        info!("  Output token: {:?}", destination_output.token);
-        info!("  Output amount: {}", destination_output.amount);
-        info!("  Recipient: {:?}", destination_output.recipient);
-        info!("  Chain ID: {}", destination_output.chain_id);
+        for (index, output) in standard_order.outputs.iter().enumerate() {
+            info!("  Output[{}]: {} of {:?} to {:?} on chain {}",
+                  index, output.amount, output.token, output.recipient, output.chain_id);
+        }
 
         // Check chain connectivity before execution
         match self.contract_factory.check_chain_connectivity().await {
@@ -162,66 +344,69 @@ impl CrossChainService {
             }
         }
 
+        // Estimate gas for fill (refuses while gas is above the configured cap)
+        let gas_estimate = self.estimate_fill_gas(order).await?;
+        info!("Fill gas estimate: {} wei", gas_estimate.total_cost);
+
+        // Refuse fills that would leave less than the configured margin
+        if let Some(min_margin_bps) = self.config.current().solver.min_profit_margin_bps {
+            let profitability = self.profitability(order, gas_estimate.total_cost)?;
+            info!("Fill margin: {} bps (minimum {} bps)", profitability.margin_bps, min_margin_bps);
+            if !profitability.meets(min_margin_bps) {
+                return Ok(FillResult::failure(format!(
+                    "Order is not profitable: margin {} bps is below the minimum of {} bps",
+                    profitability.margin_bps, min_margin_bps
+                )));
+            }
+        }
+
         // Execute real fill using contract factory
-        let tx_hash = self.execute_real_fill(order).await?;
+        self.execute_real_fill(order).await?;
         
-        Ok(FillResult::success(tx_hash, None))
+        Ok(FillResult::success(order.output_fill_txs[0].clone(), None))
     }
 
-    async fn execute_real_fill(&self, order: &Order) -> Result<String> {
-        info!("Executing real CoinFiller.fill() transaction");
-
-        let destination_output = &order.standard_order.outputs[0];
+    /// Send the fill of each output not yet recorded in `output_fill_txs`, on the chain it is paid on
+    ///
+    /// Outputs this solver already filled on-chain are adopted rather than sent again;
+    /// if that covers every output, the `AlreadyFilled` error is returned so the order
+    /// is recorded as an existing fill. An output filled by another solver is an error.
+    async fn execute_real_fill(&self, order: &mut Order) -> Result<()> {
+        info!("Executing real CoinFiller.fill() transactions");
 
-        // Create order ID as bytes32
-        let order_id_bytes32 = self.contract_factory.string_to_order_id(&order.id.to_string());
-        
-        // Create mandate output for contract call
-        let mandate_output_contract = self.create_contract_mandate_output(destination_output)?;
-        
-        // Get solver identifier
         let solver_identifier = self.get_solver_identifier().await?;
-        
-        // Use the original fill deadline from the order (cast to u32)
-        let fill_deadline = order.standard_order.fill_deadline as u32;
-
         info!("Contract call parameters:");
-        info!("  Fill deadline: {}", fill_deadline);
-        info!("  Order ID: {:?}", order_id_bytes32);
+        info!("  Fill deadline: {}", order.standard_order.fill_deadline as u32);
         info!("  Solver identifier: {:?}", solver_identifier);
 
-        // For now, delegate to the simplified contract factory method
-        // TODO: Replace with direct alloy contract call once we have real contracts
-        let tx_hash = self.contract_factory.fill_order(
-            &order.id.to_string(),
-            fill_deadline,
-            destination_output.remote_oracle,
-            destination_output.token,
-            destination_output.amount.parse().unwrap_or_default(),
-            destination_output.recipient,
-        ).await?;
-
-        info!("Fill transaction hash: {}", tx_hash);
-        Ok(tx_hash)
-    }
-
-    fn create_contract_mandate_output(&self, output: &MandateOutput) -> Result<crate::contracts::factory::MandateOutput> {
-        Ok(crate::contracts::factory::MandateOutput {
-            remoteOracle: self.contract_factory.address_to_bytes32(output.remote_oracle),
-            remoteFiller: self.contract_factory.address_to_bytes32(output.remote_filler),
-            chainId: alloy::primitives::U256::from(output.chain_id),
-            token: self.contract_factory.address_to_bytes32(output.token),
-            amount: output.amount.parse().unwrap_or_default(),
-            recipient: self.contract_factory.address_to_bytes32(output.recipient),
-            remoteCall: output.remote_call.as_ref()
-                .and_then(|s| hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok())
-                .unwrap_or_default()
-                .into(),
-            fulfillmentContext: output.fulfillment_context.as_ref()
-                .and_then(|s| hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok())
-                .unwrap_or_default()
-                .into(),
-        })
+        // Outputs sent by an earlier attempt keep their recorded fill
+        let outputs = order.standard_order.outputs.clone();
+        let mut adopted = None;
+        for (index, output) in outputs.iter().enumerate().skip(order.output_fill_txs.len()) {
+            info!("Filling output {} on chain {}", index, output.chain_id);
+            let tx_hash = match self.contract_factory.fill_order(&fill_request(order, output)).await {
+                Ok(tx_hash) => tx_hash,
+                Err(e) => match e.downcast::<AlreadyFilled>() {
+                    Ok(filled) if filled.solver == solver_identifier => {
+                        info!("Output {} of order {} was already filled by this solver", index, order.id);
+                        let tx_hash = format!("{}{}", ONCHAIN_FILL_TX_PREFIX, filled.timestamp);
+                        adopted.get_or_insert(filled);
+                        tx_hash
+                    }
+                    Ok(filled) => return Err(filled.into()),
+                    Err(e) => return Err(e),
+                },
+            };
+            info!(tx_hash = %tx_hash, "Fill transaction sent for output {}", index);
+            order.output_fill_txs.push(tx_hash);
+        }
+
+        if let Some(filled) = adopted {
+            if order.output_fill_txs.iter().all(|tx_hash| tx_hash.starts_with(ONCHAIN_FILL_TX_PREFIX)) {
+                return Err(filled.into());
+            }
+        }
+        Ok(())
     }
 
     async fn get_solver_identifier(&self) -> Result<alloy::primitives::FixedBytes<32>> {
@@ -230,48 +415,609 @@ impl CrossChainService {
         Ok(self.contract_factory.address_to_bytes32(solver_address))
     }
 
-    // Gas estimation functionality (will be enhanced)
+    /// Gas the fills of `order` would be sent with, estimated per output on the chain it is paid on
+    ///
+    /// Each output's fill is estimated with `eth_estimateGas` on its encoded calldata plus
+    /// the chain's `buffer_pct` (the default fill limit when the node cannot estimate it),
+    /// and priced at the fill's bid, exactly as the fill is sent. Every chain is held to
+    /// its own gas price cap and to the solver's balance there. Nothing is sent or stored.
     pub async fn estimate_fill_gas(&self, order: &Order) -> Result<GasEstimate> {
-        let destination_output = &order.standard_order.outputs[0];
-        
-        info!("Estimating gas for fill operation on chain {}", destination_output.chain_id);
+        info!("Estimating gas for {} fill(s)", order.standard_order.outputs.len());
+        let config = self.config.current();
+
+        // Cost of the fills on each chain the order pays on
+        let mut chain_costs: Vec<(ChainType, U256)> = Vec::new();
+        let mut gas_limit = U256::ZERO;
+        for output in &order.standard_order.outputs {
+            let (chain, details) = output_chain(&config, output.chain_id)?;
+            let gas = self.contract_factory.estimate_fill_gas(&fill_request(order, output)).await?;
+            info!("  Chain {}: {} gas at {} wei", details.chain_id, gas.gas_limit, gas.gas_price);
+
+            // Defer the fill while any output's chain is above its gas price cap
+            gas::check_gas_price_cap(details, gas.gas_price as u128)?;
+
+            let cost = U256::from(gas.gas_limit) * U256::from(gas.gas_price);
+            gas_limit += U256::from(gas.gas_limit);
+            match chain_costs.iter_mut().find(|(known, _)| *known == chain) {
+                Some((_, total)) => *total += cost,
+                None => chain_costs.push((chain, cost)),
+            }
+        }
 
-        // Get destination provider for gas estimation
-        let provider = self.contract_factory.get_destination_provider()?;
-        
-        // Get current gas price
-        let gas_price = provider.get_gas_price().await.unwrap_or_default();
-        
-        // Conservative gas limit estimate for fill operations
-        let base_gas_limit = 300_000u64;
-        let gas_multiplier = 1.2; // 20% buffer
-        let gas_limit = ((base_gas_limit as f64) * gas_multiplier) as u64;
-        
-        let total_cost = alloy::primitives::U256::from(gas_limit) * alloy::primitives::U256::from(gas_price);
-        
+        let total_cost = chain_costs.iter().fold(U256::ZERO, |total, (_, cost)| total + cost);
         info!("Gas estimation:");
         info!("  Gas limit: {}", gas_limit);
-        info!("  Gas price: {}", gas_price);
         info!("  Total cost: {}", total_cost);
 
+        let executor = self.contract_factory.executor()?;
+        let mut is_affordable = true;
+        for (chain, cost) in &chain_costs {
+            is_affordable &= gas::is_affordable(executor.as_ref(), *chain, *cost).await;
+        }
         Ok(GasEstimate {
-            gas_limit: alloy::primitives::U256::from(gas_limit),
-            gas_price: alloy::primitives::U256::from(gas_price),
+            gas_limit,
+            // Weighted by gas across the chains the order pays on
+            gas_price: if gas_limit.is_zero() { U256::ZERO } else { total_cost / gas_limit },
             total_cost,
-            is_affordable: true, // TODO: Add actual affordability check
+            is_affordable,
         })
     }
 
+    /// Value of the order's inputs against its outputs plus the estimated fill gas
+    pub async fn compute_profitability(&self, order: &Order) -> Result<Profitability> {
+        let gas_estimate = self.estimate_fill_gas(order).await?;
+        self.profitability(order, gas_estimate.total_cost)
+    }
+
+    fn profitability(&self, order: &Order, gas_cost: U256) -> Result<Profitability> {
+        let standard_order = &order.standard_order;
+
+        let mut input_value = U256::ZERO;
+        for &(token_id, amount) in &standard_order.inputs {
+            let token = validation::decode_token_id(token_id).token;
+            input_value = input_value.saturating_add(self.price_oracle.value_of(standard_order.origin_chain_id, token, amount)?);
+        }
+
+        let mut output_value = U256::ZERO;
+        for output in &standard_order.outputs {
+            output_value = output_value.saturating_add(self.price_oracle.value_of(output.chain_id, output.token, output.amount)?);
+        }
+
+        Ok(Profitability::new(input_value, output_value, gas_cost))
+    }
+
     // Public accessor for contract factory (for monitoring service)
     pub fn get_contract_factory(&self) -> &ContractFactory {
         &*self.contract_factory
     }
 }
 
+/// The `FillRequest` paying `output` of `order`
+fn fill_request(order: &Order, output: &MandateOutput) -> FillRequest {
+    FillRequest {
+        order_id: order.id.to_string(),
+        fill_deadline: order.standard_order.fill_deadline as u32,
+        remote_oracle: output.remote_oracle,
+        token: output.token,
+        amount: output.amount,
+        recipient: output.recipient,
+        address_encoding: output.address_encoding.clone(),
+        chain_id: Some(output.chain_id),
+    }
+}
+
+/// The chain an output paid on `chain_id` is filled on, as `FillOrchestrator` resolves it
+fn output_chain(config: &AppConfig, chain_id: u64) -> Result<(ChainType, &ChainDetails)> {
+    if chain_id == config.chains.destination.chain_id {
+        return Ok((ChainType::Destination, &config.chains.destination));
+    }
+    let details = config.chains.get(chain_id)
+        .ok_or_else(|| anyhow::anyhow!("Output is paid on chain {}, which is not configured", chain_id))?;
+    Ok((ChainType::ById(chain_id), details))
+}
+
 #[derive(Debug, Clone)]
 pub struct GasEstimate {
     pub gas_limit: alloy::primitives::U256,
     pub gas_price: alloy::primitives::U256,
     pub total_cost: alloy::primitives::U256,
     pub is_affordable: bool,
-} 
\ No newline at end of file
+}
+
+/// What an order pays against what filling it costs, valued by the price oracle
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profitability {
+    pub input_value: U256,
+    pub output_value: U256,
+    pub gas_cost: U256,
+    /// (inputs - outputs - gas) / inputs in basis points; negative for a loss
+    pub margin_bps: i64,
+}
+
+impl Profitability {
+    pub fn new(input_value: U256, output_value: U256, gas_cost: U256) -> Self {
+        let costs = output_value.saturating_add(gas_cost);
+        let margin_bps = if input_value.is_zero() {
+            i64::MIN
+        } else if input_value >= costs {
+            ((input_value - costs).saturating_mul(U256::from(10_000)) / input_value).saturating_to::<i64>()
+        } else {
+            -((costs - input_value).saturating_mul(U256::from(10_000)) / input_value).saturating_to::<i64>()
+        };
+
+        Self {
+            input_value,
+            output_value,
+            gas_cost,
+            margin_bps,
+        }
+    }
+
+    pub fn meets(&self, min_margin_bps: u32) -> bool {
+        self.margin_bps >= min_margin_bps as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{GasUsage, MandateOutput, StandardOrder};
+    use crate::services::gas::GasPriceTooHigh;
+    use crate::storage::MemoryStorage;
+    use crate::contracts::execution::{ChainType, ExecutionEngine, GasParams, TransactionReceiptInfo};
+    use crate::services::metrics::SolverMetrics;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use alloy::primitives::Address;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::signers::SignerSync;
+
+    fn create_test_order() -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce: 1,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        };
+        Order::new(standard_order, "0x".to_string())
+    }
+
+    /// Minimal JSON-RPC node answering every call (e.g. eth_gasPrice) with 2 gwei
+    fn spawn_mock_rpc() -> (u16, actix_web::dev::ServerHandle) {
+        let server = HttpServer::new(|| {
+            App::new().default_service(web::to(|body: web::Json<serde_json::Value>| async move {
+                HttpResponse::Ok().json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": body["id"],
+                    "result": "0x77359400",
+                }))
+            }))
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let port = server.addrs()[0].port();
+        let server = server.run();
+        let server_handle = server.handle();
+        actix_web::rt::spawn(server);
+        (port, server_handle)
+    }
+
+    /// Executor that accepts every transaction without touching a chain
+    struct MockExecutor;
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for MockExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<String> {
+            Ok("0xfill".to_string())
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        async fn get_gas_price(&self, _chain: ChainType) -> Result<u128> {
+            Ok(2_000_000_000)
+        }
+
+        async fn get_balance(&self, _chain: ChainType) -> Result<U256> {
+            Ok(U256::from(10u64).pow(U256::from(18)))
+        }
+
+        async fn get_receipt(&self, _chain: ChainType, tx_hash: &str) -> Result<TransactionReceiptInfo> {
+            Ok(TransactionReceiptInfo {
+                tx_hash: tx_hash.to_string(),
+                status: true,
+                gas_used: 95_000,
+                effective_gas_price: 3_000_000_000,
+                logs: Vec::new(),
+            })
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "MockExecutor"
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_validation_errors_carry_codes() {
+        let code = |config: AppConfig, order: Order| async move {
+            let service = CrossChainService::new(Arc::new(MemoryStorage::new()), config).await.unwrap();
+            service.validate_fill_preconditions(&order).await.unwrap_err().code()
+        };
+        let config = AppConfig::default();
+
+        let mut order = create_test_order();
+        order.standard_order.fill_deadline = 1;
+        assert_eq!(code(config.clone(), order).await, "deadline_passed");
+
+        let mut order = create_test_order();
+        order.standard_order.outputs.clear();
+        assert_eq!(code(config.clone(), order).await, "no_outputs");
+
+        let mut order = create_test_order();
+        order.standard_order.inputs.clear();
+        assert_eq!(code(config.clone(), order).await, "no_inputs");
+
+        let mut limited = config.clone();
+        limited.solver.max_outputs = Some(0);
+        assert_eq!(code(limited, create_test_order()).await, "too_many_outputs");
+
+        let mut order = create_test_order();
+        order.standard_order.origin_chain_id = 1;
+        assert_eq!(code(config.clone(), order).await, "unknown_chain");
+
+        let mut allowlisted = config.clone();
+        allowlisted.solver.allowed_input_tokens = vec![format!("{}", Address::from([0x77; 20]))];
+        assert_eq!(code(allowlisted, create_test_order()).await, "token_not_allowed");
+
+        let mut allowlisted = config.clone();
+        allowlisted.solver.allowed_oracles = vec![format!("{}", Address::from([0x77; 20]))];
+        assert_eq!(code(allowlisted, create_test_order()).await, "oracle_not_allowed");
+
+        let mut misconfigured = config.clone();
+        misconfigured.solver.allowed_oracles = vec!["not-an-address".to_string()];
+        assert_eq!(code(misconfigured, create_test_order()).await, "solver_misconfigured");
+
+        // Everything else checks out, but the order is unsigned
+        assert_eq!(code(config, create_test_order()).await, "bad_signature");
+    }
+
+    #[actix_web::test]
+    async fn test_fill_estimate_refuses_above_gas_price_cap() {
+        let (port, server_handle) = spawn_mock_rpc();
+
+        let mut config = AppConfig::default();
+        config.chains.destination.rpc_url = format!("http://127.0.0.1:{}", port).into();
+        config.chains.destination.max_gas_price_wei = Some(1_000_000_000);
+        let service = CrossChainService::new(Arc::new(MemoryStorage::new()), config.clone()).await.unwrap();
+
+        let err = service.estimate_fill_gas(&create_test_order()).await.unwrap_err();
+        let err = err.downcast_ref::<GasPriceTooHigh>().expect("should be a gas price cap error");
+        assert_eq!(err.gas_price, 2_000_000_000);
+        assert_eq!(err.max_gas_price, 1_000_000_000);
+
+        // Raising the cap lets the estimate through
+        config.chains.destination.max_gas_price_wei = Some(2_000_000_000);
+        let service = CrossChainService::new(Arc::new(MemoryStorage::new()), config).await.unwrap();
+        let estimate = service.estimate_fill_gas(&create_test_order()).await.unwrap();
+        assert_eq!(estimate.gas_price, U256::from(2_000_000_000u64));
+        // The mock balance of 2 gwei cannot cover the estimated gas at 2 gwei
+        assert!(!estimate.is_affordable);
+
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_reloaded_gas_cap_applies_to_next_estimate() {
+        let (port, server_handle) = spawn_mock_rpc();
+
+        let mut config = AppConfig::default();
+        config.chains.destination.rpc_url = format!("http://127.0.0.1:{}", port).into();
+        config.chains.destination.max_gas_price_wei = Some(1_000_000_000);
+        let shared = SharedConfig::new(config.clone());
+        let service = CrossChainService::new(Arc::new(MemoryStorage::new()), config.clone()).await.unwrap()
+            .with_shared_config(shared.clone());
+        assert!(service.estimate_fill_gas(&create_test_order()).await.is_err());
+
+        // The same service picks up the raised cap without being rebuilt
+        config.chains.destination.max_gas_price_wei = Some(2_000_000_000);
+        shared.replace(config).unwrap();
+        let estimate = service.estimate_fill_gas(&create_test_order()).await.unwrap();
+        assert_eq!(estimate.gas_price, U256::from(2_000_000_000u64));
+
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_process_fill_counts_filled_orders() {
+        let (port, server_handle) = spawn_mock_rpc();
+        let mut config = AppConfig::default();
+        config.chains.origin.rpc_url = format!("http://127.0.0.1:{}", port).into();
+        config.chains.destination.rpc_url = format!("http://127.0.0.1:{}", port).into();
+        config.persistence.enabled = false;
+
+        // An order signed by its user, so fill validation passes
+        let signer = PrivateKeySigner::random();
+        let mut order = create_test_order();
+        order.standard_order.user = signer.address();
+        let domain = signature::order_domain(&config).unwrap();
+        let digest = signature::standard_order_digest(&order.standard_order, &domain).unwrap();
+        order.signature = format!("0x{}", hex::encode(signer.sign_hash_sync(&digest).unwrap().as_bytes()));
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        storage.create_order(order.clone()).await.unwrap();
+
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(Arc::new(MockExecutor));
+        let metrics = SolverMetrics::new().unwrap();
+        let service = CrossChainService::new_with_factory(storage.clone(), Arc::new(factory), config)
+            .with_metrics(metrics.clone());
+
+        let result = service.process_fill(order.id).await.unwrap();
+        assert!(result.success, "fill should succeed: {:?}", result.error);
+        assert_eq!(metrics.orders_filled.get(), 1);
+
+        // Gas accounting comes from the fill receipt and shows up in the API response
+        let gas = GasUsage::new(95_000, 3_000_000_000);
+        assert_eq!(gas.gas_cost_wei, U256::from(285_000_000_000_000u64));
+        assert_eq!(result.gas, Some(gas));
+        let stored = storage.get_order(order.id).await.unwrap().unwrap();
+        assert_eq!(stored.fill_gas, Some(gas));
+        let response = serde_json::to_value(stored.to_response()).unwrap();
+        assert_eq!(response["fill_gas"]["gas_used"], 95_000);
+        assert_eq!(response["fill_gas"]["gas_cost_wei"], "285000000000000");
+        assert_eq!(metrics.orders_failed.get(), 0);
+        assert_eq!(metrics.fill_duration_seconds.get_sample_count(), 1);
+
+        let rendered = metrics.render(storage.as_ref()).await.unwrap();
+        assert!(rendered.contains("solver_orders_filled_total 1"));
+        assert!(rendered.contains("solver_pending_orders 0"));
+
+        server_handle.stop(true).await;
+    }
+
+    #[actix_web::test]
+    async fn test_fill_gas_is_estimated_per_output_with_buffer() {
+        let config = AppConfig::default();
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(Arc::new(MockExecutor));
+        let service = CrossChainService::new_with_factory(Arc::new(MemoryStorage::new()), Arc::new(factory), config);
+
+        let single = create_test_order();
+        let mut double = create_test_order();
+        let mut second_output = double.standard_order.outputs[0].clone();
+        second_output.recipient = Address::from([0x77; 20]);
+        double.standard_order.outputs.push(second_output);
+
+        // Every output's fill is estimated (21000 each from the mock executor) plus the 20% buffer
+        let single_estimate = service.estimate_fill_gas(&single).await.unwrap();
+        assert_eq!(single_estimate.gas_limit, U256::from(25_200));
+        assert_eq!(single_estimate.gas_price, U256::from(2_000_000_000u64));
+        assert_eq!(single_estimate.total_cost, U256::from(25_200u64 * 2_000_000_000));
+        assert!(single_estimate.is_affordable);
+        let double_estimate = service.estimate_fill_gas(&double).await.unwrap();
+        assert_eq!(double_estimate.gas_limit, U256::from(50_400));
+        assert_eq!(double_estimate.total_cost, U256::from(50_400u64 * 2_000_000_000));
+    }
+
+    #[actix_web::test]
+    async fn test_profitability_check() {
+        let mut config = AppConfig::default();
+        config.solver.min_profit_margin_bps = Some(50);
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(Arc::new(MockExecutor));
+        let service = CrossChainService::new_with_factory(Arc::new(MemoryStorage::new()), Arc::new(factory), config);
+
+        // Gas at 2 gwei costs 25_200 * 2 gwei = 0.0000504 of the 0.01 input, about 50 bps
+        let mut order = create_test_order();
+        order.standard_order.inputs = vec![(U256::from(1), U256::from(10_000_000_000_000_000u64))];
+
+        // 2% spread: 200 bps minus 50.4 bps of gas
+        order.standard_order.outputs[0].amount = U256::from(9_800_000_000_000_000u64);
+        let profitable = service.compute_profitability(&order).await.unwrap();
+        assert_eq!(profitable.gas_cost, U256::from(50_400_000_000_000u64));
+        assert_eq!(profitable.margin_bps, 149);
+        assert!(profitable.meets(50));
+
+        // 0.01% spread does not even cover gas
+        order.standard_order.outputs[0].amount = U256::from(9_999_000_000_000_000u64);
+        let unprofitable = service.compute_profitability(&order).await.unwrap();
+        assert!(unprofitable.margin_bps < 0);
+        assert!(!unprofitable.meets(50));
+
+        let result = service.execute_fill(&mut order).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not profitable"));
+    }
+
+    /// Executor whose static calls report an existing CoinFiller fill record by `solver`
+    struct FilledExecutor {
+        solver: alloy::primitives::FixedBytes<32>,
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for FilledExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<String> {
+            panic!("an output that is already filled must not be sent again");
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            use crate::contracts::factory::CoinFiller;
+            use alloy::sol_types::SolCall;
+            let record = CoinFiller::filledOutputsReturn { solver: self.solver, timestamp: 1_700_000_000 };
+            Ok(CoinFiller::filledOutputsCall::abi_encode_returns(&record))
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "FilledExecutor"
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_process_fill_adopts_existing_onchain_fill() {
+        let (port, server_handle) = spawn_mock_rpc();
+        let mut config = AppConfig::default();
+        config.chains.origin.rpc_url = format!("http://127.0.0.1:{}", port).into();
+        config.chains.destination.rpc_url = format!("http://127.0.0.1:{}", port).into();
+        config.persistence.enabled = false;
+
+        let signer = PrivateKeySigner::random();
+        let mut order = create_test_order();
+        order.standard_order.user = signer.address();
+        let domain = signature::order_domain(&config).unwrap();
+        let digest = signature::standard_order_digest(&order.standard_order, &domain).unwrap();
+        order.signature = format!("0x{}", hex::encode(signer.sign_hash_sync(&digest).unwrap().as_bytes()));
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        storage.create_order(order.clone()).await.unwrap();
+        let factory = ContractFactory::new(config.clone()).await.unwrap();
+        let solver_address = factory.get_wallet_address().unwrap();
+        let solver = factory.address_to_bytes32(solver_address);
+
+        // Filled earlier by this solver: recorded as filled without a new send
+        let factory = factory.with_executor(Arc::new(FilledExecutor { solver }));
+        let service = CrossChainService::new_with_factory(storage.clone(), Arc::new(factory), config.clone());
+        let result = service.process_fill(order.id).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let stored = storage.get_order(order.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, OrderStatus::Filled);
+        assert_eq!(stored.fill_tx_hash.as_deref(), Some("filled-onchain:1700000000"));
+
+        // Filled by someone else: the order fails rather than being finalized by us
+        let mut other = order.clone();
+        other.id = uuid::Uuid::new_v4();
+        other.status = OrderStatus::Pending;
+        other.fill_tx_hash = None;
+        storage.create_order(other.clone()).await.unwrap();
+        let factory = ContractFactory::new(config.clone()).await.unwrap()
+            .with_executor(Arc::new(FilledExecutor { solver: alloy::primitives::FixedBytes::from([0x99; 32]) }));
+        let service = CrossChainService::new_with_factory(storage.clone(), Arc::new(factory), config);
+        let result = service.process_fill(other.id).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(storage.get_order(other.id).await.unwrap().unwrap().status, OrderStatus::Failed);
+
+        server_handle.stop(true).await;
+    }
+
+    /// Executor recording the chain of every fill sent and receipt read
+    #[derive(Default)]
+    struct RecordingExecutor {
+        sent: std::sync::Mutex<Vec<ChainType>>,
+        receipts: std::sync::Mutex<Vec<(ChainType, String)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for RecordingExecutor {
+        async fn send_transaction(&self, chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<String> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(chain);
+            Ok(format!("0xfill{}", sent.len()))
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        async fn get_gas_price(&self, _chain: ChainType) -> Result<u128> {
+            Ok(2_000_000_000)
+        }
+
+        async fn get_receipt(&self, chain: ChainType, tx_hash: &str) -> Result<TransactionReceiptInfo> {
+            self.receipts.lock().unwrap().push((chain, tx_hash.to_string()));
+            Ok(TransactionReceiptInfo {
+                tx_hash: tx_hash.to_string(),
+                status: true,
+                gas_used: 95_000,
+                effective_gas_price: 3_000_000_000,
+                logs: Vec::new(),
+            })
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "RecordingExecutor"
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_every_output_is_filled_and_verified_on_its_chain() {
+        let (port, server_handle) = spawn_mock_rpc();
+        let mut config = AppConfig::default();
+        config.chains.origin.rpc_url = format!("http://127.0.0.1:{}", port).into();
+        config.chains.destination.rpc_url = format!("http://127.0.0.1:{}", port).into();
+        config.persistence.enabled = false;
+        let mut other_chain = config.chains.destination.clone();
+        other_chain.chain_id = 31339;
+        config.chains.registry.insert(31339, other_chain);
+
+        // One output on the destination, one on another registered chain
+        let signer = PrivateKeySigner::random();
+        let mut order = create_test_order();
+        order.standard_order.user = signer.address();
+        let mut second_output = order.standard_order.outputs[0].clone();
+        second_output.chain_id = 31339;
+        second_output.recipient = Address::from([0x77; 20]);
+        order.standard_order.outputs.push(second_output);
+        let domain = signature::order_domain(&config).unwrap();
+        let digest = signature::standard_order_digest(&order.standard_order, &domain).unwrap();
+        order.signature = format!("0x{}", hex::encode(signer.sign_hash_sync(&digest).unwrap().as_bytes()));
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        storage.create_order(order.clone()).await.unwrap();
+        let executor = Arc::new(RecordingExecutor::default());
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(executor.clone());
+        let service = CrossChainService::new_with_factory(storage.clone(), Arc::new(factory), config);
+
+        // The estimate prices each output on its own chain
+        let estimate = service.estimate_fill_gas(&order).await.unwrap();
+        assert_eq!(estimate.gas_limit, U256::from(50_400));
+        assert_eq!(estimate.total_cost, U256::from(50_400u64 * 2_000_000_000));
+
+        let result = service.process_fill(order.id).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(*executor.sent.lock().unwrap(), vec![ChainType::Destination, ChainType::ById(31339)]);
+        assert_eq!(
+            *executor.receipts.lock().unwrap(),
+            vec![(ChainType::Destination, "0xfill1".to_string()), (ChainType::ById(31339), "0xfill2".to_string())]
+        );
+
+        let stored = storage.get_order(order.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, OrderStatus::Filled);
+        assert_eq!(stored.fill_tx_hash.as_deref(), Some("0xfill1"));
+        assert_eq!(stored.output_fill_txs, vec!["0xfill1".to_string(), "0xfill2".to_string()]);
+        assert_eq!(stored.fill_gas, Some(GasUsage::new(190_000, 3_000_000_000)));
+
+        server_handle.stop(true).await;
+    }
+}
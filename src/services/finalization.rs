@@ -1,34 +1,60 @@
 use anyhow::Result;
 use tracing::{info, error, warn};
-use alloy::providers::Provider;
-use alloy::primitives::U256;
 use std::sync::Arc;
+use chrono::{DateTime, Utc};
 
 use crate::config::AppConfig;
 use crate::contracts::ContractFactory;
+use crate::contracts::execution::traits::ChainType;
 use crate::models::{Order, OrderStatus, FillResult};
-use crate::storage::MemoryStorage;
+use crate::storage::Storage;
+use crate::services::gas;
+use crate::services::callbacks::CallbackNotifier;
+use crate::services::metrics::SolverMetrics;
 
 #[derive(Clone)]
 pub struct FinalizationService {
-    storage: MemoryStorage,
+    storage: Arc<dyn Storage>,
     contract_factory: Arc<ContractFactory>,
+    metrics: SolverMetrics,
+    notifier: CallbackNotifier,
     config: AppConfig,
 }
 
 impl FinalizationService {
-    pub async fn new(storage: MemoryStorage, config: AppConfig) -> Result<Self> {
+    pub async fn new(storage: Arc<dyn Storage>, config: AppConfig) -> Result<Self> {
         let contract_factory = Arc::new(ContractFactory::new(config.clone()).await?);
         
         Ok(Self {
             storage,
             contract_factory,
+            metrics: SolverMetrics::default(),
+            notifier: CallbackNotifier::new(&config.callbacks),
             config,
         })
     }
 
+    /// Create a FinalizationService around an existing (possibly customised) ContractFactory
+    pub fn new_with_factory(storage: Arc<dyn Storage>, contract_factory: Arc<ContractFactory>, config: AppConfig) -> Self {
+        Self {
+            storage,
+            contract_factory,
+            metrics: SolverMetrics::default(),
+            notifier: CallbackNotifier::new(&config.callbacks),
+            config,
+        }
+    }
+
+    /// Record finalization outcomes and durations in `metrics`
+    pub fn with_metrics(mut self, metrics: SolverMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    #[tracing::instrument(name = "order", skip_all, fields(order_id = %order_id, correlation_id = tracing::field::Empty))]
     pub async fn finalize_order(&self, order_id: uuid::Uuid) -> Result<FillResult> {
         info!("Starting finalization for order: {}", order_id);
+        let started = std::time::Instant::now();
 
         // Get order from storage
         let mut order = match self.storage.get_order(order_id).await? {
@@ -39,13 +65,21 @@ impl FinalizationService {
                 return Ok(FillResult::failure(error_msg));
             }
         };
+        tracing::Span::current().record("correlation_id", order.correlation_id.as_str());
+
+        if order.status == OrderStatus::Cancelled {
+            info!("Skipping finalization for cancelled order: {}", order_id);
+            return Ok(FillResult::failure("Order was cancelled".to_string()));
+        }
 
         // Validate order can be finalized
-        if let Err(validation_error) = self.validate_finalization_preconditions(&order) {
+        if let Err(validation_error) = self.validate_finalization_preconditions(&order).await {
             let error_msg = format!("Finalization validation failed: {}", validation_error);
             error!("{}", error_msg);
             order.set_error(error_msg.clone());
-            self.storage.update_order(order).await?;
+            self.metrics.orders_failed.inc();
+            self.storage.update_order(order.clone()).await?;
+            self.notifier.notify(&order);
             return Ok(FillResult::failure(error_msg));
         }
 
@@ -58,12 +92,15 @@ impl FinalizationService {
             Ok(finalize_result) => {
                 if finalize_result.success {
                     if let Some(tx_hash) = &finalize_result.tx_hash {
-                        info!("Finalization executed successfully: {}", tx_hash);
+                        info!(tx_hash = %tx_hash, "Finalization executed successfully");
                         
                         // Update order with finalization transaction hash and status
                         order.set_finalize_tx(tx_hash.clone());
                         order.update_status(OrderStatus::Finalized);
-                        self.storage.update_order(order).await?;
+                        self.storage.update_order(order.clone()).await?;
+                        self.notifier.notify(&order);
+                        self.metrics.orders_finalized.inc();
+                        self.metrics.finalization_duration_seconds.observe(started.elapsed().as_secs_f64());
                     }
                     Ok(finalize_result)
                 } else {
@@ -71,22 +108,33 @@ impl FinalizationService {
                     error!("Finalization failed: {}", error_msg);
                     
                     order.set_error(error_msg.clone());
-                    self.storage.update_order(order).await?;
+                    self.metrics.orders_failed.inc();
+                    self.storage.update_order(order.clone()).await?;
+                    self.notifier.notify(&order);
                     Ok(FillResult::failure(error_msg))
                 }
             }
+            Err(e) if gas::is_gas_price_too_high(&e) => {
+                // Back to Filled so finalization is retried once gas comes down
+                warn!("Deferring finalization for order {}: {}", order.id, e);
+                order.update_status(OrderStatus::Filled);
+                self.storage.update_order(order).await?;
+                Ok(FillResult::failure(e.to_string()))
+            }
             Err(e) => {
                 let error_msg = format!("Finalization execution error: {}", e);
                 error!("{}", error_msg);
                 
                 order.set_error(error_msg.clone());
-                self.storage.update_order(order).await?;
+                self.metrics.orders_failed.inc();
+                self.storage.update_order(order.clone()).await?;
+                self.notifier.notify(&order);
                 Ok(FillResult::failure(error_msg))
             }
         }
     }
 
-    fn validate_finalization_preconditions(&self, order: &Order) -> Result<(), String> {
+    async fn validate_finalization_preconditions(&self, order: &Order) -> Result<(), String> {
         // Check order is in correct state for finalization
         match order.status {
             OrderStatus::Filled => {
@@ -122,6 +170,30 @@ impl FinalizationService {
             ));
         }
 
+        // Only the order holding the user's nonce can finalize; the others would revert
+        if let Some(holder) = self.storage
+            .find_nonce_holder(standard_order.user, standard_order.nonce, standard_order.origin_chain_id)
+            .await
+            .map_err(|e| format!("Could not look up the holder of the user's nonce: {}", e))?
+        {
+            if holder.id != order.id {
+                warn!("Skipping finalization of order {}: nonce is held by order {}", order.id, holder.id);
+                return Err(format!(
+                    "duplicate user nonce: nonce {} of user {} is held by order {}",
+                    standard_order.nonce, standard_order.user, holder.id
+                ));
+            }
+        }
+
+        // The destination fill may have been reorged away since it was recorded
+        if !self.config.solver.skip_fill_proof_check {
+            match self.contract_factory.fill_is_proven(order).await {
+                Ok(true) => info!("Fill for order {} is proven on the local oracle", order.id),
+                Ok(false) => return Err("Fill is not proven on the local oracle".to_string()),
+                Err(e) => return Err(format!("Could not confirm the fill proof on the local oracle: {}", e)),
+            }
+        }
+
         Ok(())
     }
 
@@ -177,18 +249,27 @@ impl FinalizationService {
         // Execute finalization
         let tx_hash = self.contract_factory.finalize_order(order).await?;
 
-        info!("Finalization transaction hash: {}", tx_hash);
+        info!(tx_hash = %tx_hash, "Finalization transaction sent");
         Ok(tx_hash)
     }
 
     async fn estimate_finalization_gas(&self, order: &Order) -> Result<GasEstimate> {
         info!("Estimating gas for finalization operation");
 
-        // Get origin provider for gas estimation
-        let provider = self.contract_factory.get_origin_provider()?;
+        // Finalization runs on the chain the order was opened on
+        let origin_chain_id = order.standard_order.origin_chain_id;
+        let (chain, details) = if origin_chain_id == self.config.chains.origin.chain_id {
+            (ChainType::Origin, &self.config.chains.origin)
+        } else {
+            let details = self.config.chains.get(origin_chain_id).ok_or_else(|| {
+                anyhow::anyhow!("Order {} originates on chain {}, which is not configured", order.id, origin_chain_id)
+            })?;
+            (ChainType::ById(origin_chain_id), details)
+        };
+        let executor = self.contract_factory.executor()?;
         
         // Get current gas price
-        let gas_price = provider.get_gas_price().await.unwrap_or_default();
+        let gas_price = gas::strategy_gas_price(executor.as_ref(), chain, details, &self.config.gas).await.unwrap_or_default();
         
         // Conservative gas limit estimate for finalization operations
         let base_gas_limit = 500_000u64; // Higher than fill due to more complex logic
@@ -202,14 +283,36 @@ impl FinalizationService {
         info!("  Gas price: {}", gas_price);
         info!("  Total cost: {}", total_cost);
 
+        // Defer finalization while the origin chain is above its gas price cap
+        gas::check_gas_price_cap(details, gas_price)?;
+
         Ok(GasEstimate {
             gas_limit: alloy::primitives::U256::from(gas_limit),
             gas_price: alloy::primitives::U256::from(gas_price),
             total_cost,
-            is_affordable: true, // TODO: Add actual affordability check based on solver balance
+            is_affordable: gas::is_affordable(executor.as_ref(), chain, total_cost).await,
         })
     }
 
+    /// Check that a manually requested finalization of `order` may start: it must be
+    /// Filled and, unless `force`d, past `finalization_delay_seconds` since its fill
+    pub fn check_manual_finalization(&self, order: &Order, force: bool) -> Result<(), FinalizationRefused> {
+        if order.status != OrderStatus::Filled {
+            return Err(FinalizationRefused::NotFilled(order.status.clone()));
+        }
+        if force {
+            warn!("⚠️ FORCED finalization of order {}: skipping the {}s finalization delay", order.id, self.config.solver.finalization_delay_seconds);
+            return Ok(());
+        }
+
+        // Orders filled before the fill time was tracked are treated as due
+        let delay = self.config.solver.finalization_delay_seconds;
+        match seconds_since_fill(order, Utc::now()) {
+            Some(elapsed) if elapsed < delay => Err(FinalizationRefused::TooEarly { elapsed, delay }),
+            _ => Ok(()),
+        }
+    }
+
     // Monitor fill status and trigger finalization when appropriate
     pub async fn monitor_and_finalize_pending_orders(&self) -> Result<()> {
         info!("Monitoring filled orders for automatic finalization");
@@ -222,10 +325,10 @@ impl FinalizationService {
             // Check if enough time has passed since fill (finalization delay)
             let finalization_delay = self.config.solver.finalization_delay_seconds;
             
-            let elapsed = std::time::SystemTime::now()
-                .duration_since(order.updated_at.into())
-                .unwrap_or_default()
-                .as_secs();
+            let Some(elapsed) = seconds_since_fill(&order, Utc::now()) else {
+                warn!("Order {} is Filled but has no fill time, skipping auto-finalization", order.id);
+                continue;
+            };
                 
             if elapsed >= finalization_delay {
                 info!("Auto-finalizing order {} after {} seconds", order.id, elapsed);
@@ -252,10 +355,280 @@ impl FinalizationService {
     }
 }
 
+/// Why a manually requested finalization was not started; the order is left untouched
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FinalizationRefused {
+    #[error("Order cannot be finalized in status {0:?}, it must be Filled")]
+    NotFilled(OrderStatus),
+
+    #[error("Fill is {elapsed}s old, finalization waits {delay}s after the fill; retry later or pass force=true")]
+    TooEarly { elapsed: u64, delay: u64 },
+}
+
+/// Seconds elapsed since the order's fill was recorded, or `None` if it never was
+///
+/// Measured from `filled_at` rather than `updated_at`, which later status
+/// touches would reset.
+pub fn seconds_since_fill(order: &Order, now: DateTime<Utc>) -> Option<u64> {
+    let filled_at = order.filled_at?;
+    Some((now - filled_at).num_seconds().max(0) as u64)
+}
+
 #[derive(Debug, Clone)]
 pub struct GasEstimate {
     pub gas_limit: alloy::primitives::U256,
     pub gas_price: alloy::primitives::U256,
     pub total_cost: alloy::primitives::U256,
     pub is_affordable: bool,
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MandateOutput, StandardOrder};
+    use alloy::primitives::U256;
+    use crate::storage::MemoryStorage;
+    use crate::contracts::execution::{ChainType, ExecutionEngine, GasParams};
+    use crate::contracts::factory::IOracle;
+    use crate::contracts::operations::settlement::fill_description;
+    use alloy::primitives::{Address, FixedBytes};
+    use alloy::sol_types::SolCall;
+
+    fn create_filled_order(amount: u64) -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce: 7,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(amount),
+                Address::from([0x66; 20]),
+            )],
+        };
+        let mut order = Order::new(standard_order, "0x".to_string());
+        order.set_fill_tx("0xabc".to_string());
+        order.update_status(OrderStatus::Filled);
+        order
+    }
+
+    #[tokio::test]
+    async fn test_second_order_with_same_nonce_is_not_finalized() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let first = create_filled_order(99);
+        let mut second = create_filled_order(98);
+        second.created_at = first.created_at + chrono::Duration::seconds(1);
+        storage.create_order(first.clone()).await.unwrap();
+        storage.create_order(second.clone()).await.unwrap();
+
+        let service = FinalizationService::new(storage.clone(), AppConfig::default()).await.unwrap();
+        let result = service.finalize_order(second.id).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("duplicate user nonce"));
+        let second = storage.get_order(second.id).await.unwrap().unwrap();
+        assert_eq!(second.status, OrderStatus::Failed);
+        assert!(second.error_message.unwrap().contains(&first.id.to_string()));
+
+        // The first order is untouched and still waiting for finalization
+        assert_eq!(storage.get_order(first.id).await.unwrap().unwrap().status, OrderStatus::Filled);
+    }
+
+    /// Executor whose local oracle answers every `isProven` query with `proven`
+    struct OracleExecutor {
+        proven: bool,
+        static_calls: std::sync::Mutex<Vec<Address>>,
+        sends: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for OracleExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _gas: GasParams) -> Result<String> {
+            self.sends.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok("0xfinalize".to_string())
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, to: Address, _from: Address) -> Result<Vec<u8>> {
+            self.static_calls.lock().unwrap().push(to);
+            Ok(IOracle::isProvenCall::abi_encode_returns(&self.proven))
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: Address, _from: Address) -> Result<u64> {
+            Ok(450000)
+        }
+
+        fn wallet_address(&self) -> Address {
+            Address::from([0x77; 20])
+        }
+
+        fn description(&self) -> &str {
+            "OracleExecutor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unproven_fill_is_not_finalized() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let order = create_filled_order(99);
+        storage.create_order(order.clone()).await.unwrap();
+
+        let config = AppConfig::default();
+        let executor = Arc::new(OracleExecutor {
+            proven: false,
+            static_calls: Default::default(),
+            sends: Default::default(),
+        });
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(executor.clone());
+        let service = FinalizationService::new_with_factory(storage.clone(), Arc::new(factory), config);
+
+        let result = service.finalize_order(order.id).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not proven"));
+        assert_eq!(*executor.static_calls.lock().unwrap(), vec![order.standard_order.local_oracle]);
+        assert_eq!(executor.sends.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert_eq!(storage.get_order(order.id).await.unwrap().unwrap().status, OrderStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_trusted_setup_skips_fill_proof_check() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let order = create_filled_order(99);
+        storage.create_order(order.clone()).await.unwrap();
+
+        let mut config = AppConfig::default();
+        config.solver.skip_fill_proof_check = true;
+        let executor = Arc::new(OracleExecutor {
+            proven: false,
+            static_calls: Default::default(),
+            sends: Default::default(),
+        });
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(executor.clone());
+        let service = FinalizationService::new_with_factory(storage.clone(), Arc::new(factory), config);
+
+        let result = service.finalize_order(order.id).await.unwrap();
+
+        // Whatever happens further on, the oracle was never asked
+        assert!(!result.error.unwrap_or_default().contains("proven"));
+        assert!(executor.static_calls.lock().unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_callback_fires_on_finalization() {
+        use actix_web::{web, App, HttpResponse, HttpServer};
+        use std::sync::Mutex;
+
+        let received: Arc<Mutex<Vec<serde_json::Value>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let server = HttpServer::new(move || {
+            let sink = sink.clone();
+            App::new().route(
+                "/callback",
+                web::post().to(move |body: web::Json<serde_json::Value>| {
+                    let sink = sink.clone();
+                    async move {
+                        sink.lock().unwrap().push(body.into_inner());
+                        HttpResponse::Ok().finish()
+                    }
+                }),
+            )
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let port = server.addrs()[0].port();
+        let server = server.run();
+        let server_handle = server.handle();
+        actix_web::rt::spawn(server);
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let order = create_filled_order(99).with_callback_url(Some(format!("http://127.0.0.1:{}/callback", port)));
+        storage.create_order(order.clone()).await.unwrap();
+
+        let mut config = AppConfig::default();
+        config.contracts.settler_compact = "0x00000000000000000000000000000000000000aa".to_string();
+        let executor = Arc::new(OracleExecutor {
+            proven: true,
+            static_calls: Default::default(),
+            sends: Default::default(),
+        });
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(executor);
+        let service = FinalizationService::new_with_factory(storage.clone(), Arc::new(factory), config);
+
+        // Finalise encoding needs Foundry's cast; without it the order ends Failed instead
+        let result = service.finalize_order(order.id).await.unwrap();
+        let finalized = storage.get_order(order.id).await.unwrap().unwrap();
+
+        // Delivery happens in the background; give it a moment
+        for _ in 0..50 {
+            if !received.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        server_handle.stop(true).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["id"], order.id.to_string());
+        assert_eq!(received[0]["status"], serde_json::to_value(&finalized.status).unwrap());
+        if result.success {
+            assert_eq!(received[0]["status"], "Finalized");
+            assert_eq!(received[0]["finalize_tx_hash"], "0xfinalize");
+        } else {
+            assert_eq!(received[0]["status"], "Failed");
+        }
+    }
+
+    #[test]
+    fn test_fill_description_layout() {
+        let output = crate::contracts::encoding::MandateOutputParams {
+            remote_oracle: FixedBytes::from([0x01; 32]),
+            remote_filler: FixedBytes::from([0x02; 32]),
+            chain_id: U256::from(31338),
+            token: FixedBytes::from([0x03; 32]),
+            amount: U256::from(99),
+            recipient: FixedBytes::from([0x04; 32]),
+            remote_call: vec![0xaa, 0xbb].into(),
+            fulfillment_context: Default::default(),
+        };
+        let description = fill_description(FixedBytes::from([0x05; 32]), FixedBytes::from([0x06; 32]), 7, &output);
+
+        assert_eq!(description.len(), 32 + 32 + 4 + 32 + 32 + 32 + 2 + 2 + 2);
+        assert_eq!(&description[64..68], &7u32.to_be_bytes());
+        assert_eq!(description[131], 99);
+        assert_eq!(&description[164..170], &[0x00, 0x02, 0xaa, 0xbb, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_fill_in_the_past_is_due_for_finalization() {
+        let delay = AppConfig::default().solver.finalization_delay_seconds;
+        let mut order = create_filled_order(99);
+        order.filled_at = Some(Utc::now() - chrono::Duration::seconds(delay as i64 + 5));
+        // An unrelated touch afterwards must not restart the window
+        order.updated_at = Utc::now();
+
+        assert!(seconds_since_fill(&order, Utc::now()).unwrap() >= delay);
+    }
+
+    #[test]
+    fn test_recent_fill_is_not_due_for_finalization() {
+        let delay = AppConfig::default().solver.finalization_delay_seconds;
+        let mut order = create_filled_order(99);
+        let filled_at = order.filled_at.expect("set_fill_tx records the fill time");
+
+        // Recording the fill again keeps the original time
+        order.set_fill_tx("0xdef".to_string());
+        assert_eq!(order.filled_at, Some(filled_at));
+        assert!(seconds_since_fill(&order, Utc::now()).unwrap() < delay);
+
+        // Orders without a recorded fill are skipped
+        order.filled_at = None;
+        assert_eq!(seconds_since_fill(&order, Utc::now()), None);
+    }
+}
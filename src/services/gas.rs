@@ -0,0 +1,108 @@
+use alloy::primitives::U256;
+use tracing::{info, warn};
+
+use crate::config::{ChainDetails, GasConfig};
+use crate::contracts::execution::traits::{resolve_gas_price, ChainType, ExecutionEngine};
+
+/// The current gas price is above the chain's `max_gas_price_wei`
+///
+/// Transient by nature: callers should keep the order and retry on a later cycle
+/// instead of marking it failed.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Gas price {gas_price} wei on chain {chain_id} exceeds the {max_gas_price} wei cap, retry later")]
+pub struct GasPriceTooHigh {
+    pub chain_id: u64,
+    pub gas_price: u128,
+    pub max_gas_price: u128,
+}
+
+/// Refuse to proceed when `gas_price` is above the chain's configured cap
+pub fn check_gas_price_cap(chain: &ChainDetails, gas_price: u128) -> Result<(), GasPriceTooHigh> {
+    let Some(max_gas_price) = chain.max_gas_price_wei else {
+        return Ok(());
+    };
+
+    if gas_price > max_gas_price {
+        warn!(
+            "⛽ Gas price {} wei on chain {} is above the {} wei cap",
+            gas_price, chain.chain_id, max_gas_price
+        );
+        return Err(GasPriceTooHigh {
+            chain_id: chain.chain_id,
+            gas_price,
+            max_gas_price,
+        });
+    }
+
+    info!("  Gas price cap: {} wei (observed {} wei)", max_gas_price, gas_price);
+    Ok(())
+}
+
+/// Gas price (wei) the solver would bid on `chain` under its gas strategy and
+/// price bounds, with the executor's view of that chain as the price oracle
+pub async fn strategy_gas_price(
+    executor: &dyn ExecutionEngine,
+    chain: ChainType,
+    details: &ChainDetails,
+    gas: &GasConfig,
+) -> anyhow::Result<u128> {
+    let profile = gas.for_chain(details);
+    let gas_price = resolve_gas_price(profile.strategy, || executor.get_gas_price(chain)).await?;
+    Ok(profile.bound_price(gas_price))
+}
+
+/// Whether `error` is a gas price cap refusal that should be retried later
+pub fn is_gas_price_too_high(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<GasPriceTooHigh>().is_some()
+}
+
+/// Whether the solver's wallet holds at least `total_cost` on `chain`
+///
+/// Assumed affordable when the balance cannot be read; the executor checks
+/// again right before sending.
+pub async fn is_affordable(executor: &dyn ExecutionEngine, chain: ChainType, total_cost: U256) -> bool {
+    match executor.get_balance(chain).await {
+        Ok(balance) => {
+            info!("  Solver balance on {:?} chain: {} wei", chain, balance);
+            if balance < total_cost {
+                warn!("⚠️ Solver {} holds {} wei on {:?} chain, below the estimated {} wei gas cost",
+                      executor.wallet_address(), balance, chain, total_cost);
+            }
+            balance >= total_cost
+        }
+        Err(e) => {
+            warn!("Could not fetch solver balance on {:?} chain: {}", chain, e);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(max_gas_price_wei: Option<u128>) -> ChainDetails {
+        ChainDetails {
+            rpc_url: "http://localhost:8546".into(),
+            read_rpc_url: None,
+            chain_id: 31338,
+            max_gas_price_wei,
+            gas: Default::default(),
+            confirmations: 0,
+            rpc_timeout_seconds: 30,
+            block_height_ttl_ms: 2000,
+            coin_filler: None,
+            settler_compact: None,
+        }
+    }
+
+    #[test]
+    fn test_gas_price_cap() {
+        assert!(check_gas_price_cap(&chain(None), u128::MAX).is_ok());
+        assert!(check_gas_price_cap(&chain(Some(100)), 100).is_ok());
+
+        let err = check_gas_price_cap(&chain(Some(100)), 101).unwrap_err();
+        assert_eq!(err.max_gas_price, 100);
+        assert!(is_gas_price_too_high(&err.into()));
+    }
+}
@@ -0,0 +1,185 @@
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use crate::config::AppConfig;
+use crate::storage::Storage;
+
+/// Upper bound for the delay between heartbeats while the coordinator is failing
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Availability announcement POSTed to the coordinator
+#[derive(Debug, Serialize)]
+pub struct Heartbeat {
+    pub solver_address: Address,
+    pub supported_chains: Vec<u64>,
+    pub supported_tokens: Vec<String>,
+    pub capacity: HeartbeatCapacity,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HeartbeatCapacity {
+    pub max_concurrent_orders: usize,
+    pub in_flight_orders: usize,
+}
+
+/// Periodically announces this solver to an external coordinator
+pub struct HeartbeatService {
+    client: reqwest::Client,
+    coordinator_url: String,
+    interval: Duration,
+    solver_address: Address,
+    storage: Arc<dyn Storage>,
+    config: AppConfig,
+}
+
+impl HeartbeatService {
+    /// Returns `None` when no coordinator URL is configured
+    pub fn new(storage: Arc<dyn Storage>, config: AppConfig) -> Result<Option<Self>> {
+        let Some(coordinator_url) = config.coordinator.url.clone() else {
+            return Ok(None);
+        };
+
+        let solver_address = PrivateKeySigner::from_str(&config.solver.private_key)
+            .map_err(|e| anyhow!("Invalid solver private key: {}", e))?
+            .address();
+
+        Ok(Some(Self {
+            client: reqwest::Client::new(),
+            coordinator_url,
+            interval: Duration::from_secs(config.coordinator.heartbeat_interval_seconds),
+            solver_address,
+            storage,
+            config,
+        }))
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        info!("Sending heartbeats to coordinator at {}", self.coordinator_url);
+
+        let mut failures = 0u32;
+        loop {
+            match self.send_heartbeat().await {
+                Ok(()) => failures = 0,
+                Err(e) => {
+                    failures = failures.saturating_add(1);
+                    warn!("Heartbeat to coordinator failed ({} in a row): {}", failures, e);
+                }
+            }
+
+            sleep(next_delay(self.interval, failures)).await;
+        }
+    }
+
+    pub async fn send_heartbeat(&self) -> Result<()> {
+        let heartbeat = self.build_heartbeat().await?;
+
+        self.client
+            .post(&self.coordinator_url)
+            .json(&heartbeat)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    async fn build_heartbeat(&self) -> Result<Heartbeat> {
+        let queue = self.storage.get_queue_status().await?;
+
+        Ok(Heartbeat {
+            solver_address: self.solver_address,
+            supported_chains: vec![
+                self.config.chains.origin.chain_id,
+                self.config.chains.destination.chain_id,
+            ],
+            supported_tokens: self.config.solver.allowed_input_tokens.clone(),
+            capacity: HeartbeatCapacity {
+                max_concurrent_orders: self.config.coordinator.max_concurrent_orders,
+                in_flight_orders: queue.pending + queue.processing,
+            },
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+/// Delay before the next heartbeat: the configured interval, doubled for each
+/// consecutive failure and capped at `MAX_BACKOFF`
+pub fn next_delay(interval: Duration, consecutive_failures: u32) -> Duration {
+    let factor = 1u32.checked_shl(consecutive_failures.min(16)).unwrap_or(u32::MAX);
+    interval.saturating_mul(factor).min(MAX_BACKOFF.max(interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use crate::storage::MemoryStorage;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_next_delay_backs_off() {
+        let interval = Duration::from_secs(10);
+
+        assert_eq!(next_delay(interval, 0), Duration::from_secs(10));
+        assert_eq!(next_delay(interval, 1), Duration::from_secs(20));
+        assert_eq!(next_delay(interval, 3), Duration::from_secs(80));
+        assert_eq!(next_delay(interval, 30), MAX_BACKOFF);
+    }
+
+    #[actix_web::test]
+    async fn test_heartbeat_posted_at_interval() {
+        let received: Arc<Mutex<Vec<serde_json::Value>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let sink = received.clone();
+        let server = HttpServer::new(move || {
+            let sink = sink.clone();
+            App::new().route(
+                "/heartbeat",
+                web::post().to(move |body: web::Json<serde_json::Value>| {
+                    let sink = sink.clone();
+                    async move {
+                        sink.lock().unwrap().push(body.into_inner());
+                        HttpResponse::Ok().finish()
+                    }
+                }),
+            )
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let port = server.addrs()[0].port();
+        let server = server.run();
+        let server_handle = server.handle();
+        actix_web::rt::spawn(server);
+
+        let mut config = AppConfig::default();
+        config.coordinator.url = Some(format!("http://127.0.0.1:{}/heartbeat", port));
+        config.coordinator.heartbeat_interval_seconds = 1;
+
+        let service = HeartbeatService::new(Arc::new(MemoryStorage::new()), config).unwrap().unwrap();
+        let _ = tokio::time::timeout(Duration::from_millis(2500), service.start()).await;
+        server_handle.stop(true).await;
+
+        // Heartbeats at t=0s, 1s and 2s; allow for scheduling slack
+        let received = received.lock().unwrap();
+        assert!((2..=3).contains(&received.len()), "got {} heartbeats", received.len());
+        assert_eq!(received[0]["supported_chains"], serde_json::json!([31337, 31338]));
+        assert_eq!(received[0]["capacity"]["max_concurrent_orders"], 10);
+        assert_eq!(received[0]["capacity"]["in_flight_orders"], 0);
+        assert!(received[0]["solver_address"].is_string());
+    }
+
+    #[test]
+    fn test_disabled_without_coordinator_url() {
+        let service = HeartbeatService::new(Arc::new(MemoryStorage::new()), AppConfig::default()).unwrap();
+        assert!(service.is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Counts fills and finalizations under way, so shutdown can wait for them
+///
+/// Clones share one count. Once `drain` has been called, `begin` refuses new
+/// operations so the count can only go down.
+#[derive(Debug, Clone, Default)]
+pub struct InFlightTracker {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    count: AtomicUsize,
+    draining: AtomicBool,
+    idle: Notify,
+}
+
+/// One tracked operation; it stops counting when dropped
+#[derive(Debug)]
+pub struct InFlightGuard {
+    inner: Arc<Inner>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.inner.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.inner.idle.notify_waiters();
+        }
+    }
+}
+
+impl InFlightTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count an operation until the returned guard is dropped; `None` once draining
+    pub fn begin(&self) -> Option<InFlightGuard> {
+        // Counted before the check, so a concurrent `drain` either waits for it or refuses it
+        self.inner.count.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightGuard { inner: self.inner.clone() };
+        (!self.is_draining()).then_some(guard)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.inner.count.load(Ordering::SeqCst)
+    }
+
+    /// Whether shutdown has begun and no new operation should start
+    pub fn is_draining(&self) -> bool {
+        self.inner.draining.load(Ordering::SeqCst)
+    }
+
+    /// Stop new operations and wait up to `grace` for the running ones to finish;
+    /// returns whether they all did
+    pub async fn drain(&self, grace: Duration) -> bool {
+        self.inner.draining.store(true, Ordering::SeqCst);
+        tokio::time::timeout(grace, async {
+            loop {
+                let idle = self.inner.idle.notified();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_waits_for_tracked_operations() {
+        let tracker = InFlightTracker::new();
+        let finished = Arc::new(AtomicBool::new(false));
+
+        let guard = tracker.begin().unwrap();
+        let operation = tokio::spawn({
+            let finished = finished.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                finished.store(true, Ordering::SeqCst);
+                drop(guard);
+            }
+        });
+
+        assert!(tracker.drain(Duration::from_secs(5)).await);
+        assert!(finished.load(Ordering::SeqCst), "shutdown proceeded before the operation finished");
+        assert!(tracker.is_draining());
+        assert_eq!(tracker.in_flight(), 0);
+        operation.await.unwrap();
+
+        // No operation starts once draining
+        assert!(tracker.begin().is_none());
+        assert_eq!(tracker.in_flight(), 0);
+
+        // An operation outliving the grace period is given up on
+        let stuck = InFlightTracker::new();
+        let _guard = stuck.begin().unwrap();
+        assert!(!stuck.drain(Duration::from_millis(10)).await);
+    }
+}
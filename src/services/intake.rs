@@ -0,0 +1,119 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use crate::config::{AppConfig, OrderIdMode, SharedConfig};
+use crate::models::{Order, StandardOrder};
+use crate::services::validation::{self, OrderAccess, OrderValidationError};
+use crate::storage::QueueStatus;
+
+/// Shared switch deciding whether new orders are accepted
+///
+/// Independent subsystems each hold their own block (keyed by a short name),
+/// so lifting one condition never reopens intake while another still applies.
+/// Backpressure, size limits, access lists and the order id mode are read from
+/// the configuration on every submission, so a reload applies to the next one.
+#[derive(Clone)]
+pub struct OrderIntake {
+    blocks: Arc<RwLock<BTreeMap<&'static str, String>>>,
+    config: SharedConfig,
+}
+
+/// Current queue load, reported to submitters so they can slow down
+#[derive(Debug, Clone, Serialize)]
+pub struct IntakeLoad {
+    pub pending: usize,
+    pub in_flight: usize,
+    pub capacity: Option<usize>,
+    /// (pending + in_flight) / capacity, when a capacity is configured
+    pub utilization: Option<f64>,
+    /// Seconds clients should wait before submitting again, once above the high-water mark
+    #[serde(skip)]
+    pub retry_after_seconds: Option<u64>,
+    /// At or over capacity: new orders are refused
+    #[serde(skip)]
+    pub saturated: bool,
+}
+
+impl Default for OrderIntake {
+    fn default() -> Self {
+        Self {
+            blocks: Arc::default(),
+            config: SharedConfig::new(AppConfig::default()),
+        }
+    }
+}
+
+impl OrderIntake {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the intake settings from `config`, e.g. the one the admin reload replaces
+    pub fn with_shared_config(mut self, config: SharedConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// A new order for `standard_order`, with its id assigned per the configured mode
+    pub fn new_order(&self, standard_order: StandardOrder, signature: String) -> Order {
+        let order = Order::new(standard_order, signature);
+        match self.config.current().solver.order_id_mode {
+            OrderIdMode::Random => order,
+            OrderIdMode::Deterministic => order.with_deterministic_id(),
+        }
+    }
+
+    /// Whether `order` is small enough to be accepted under `solver.max_inputs` / `solver.max_outputs`
+    pub fn check_order_size(&self, order: &StandardOrder) -> Result<(), OrderValidationError> {
+        let config = self.config.current();
+        validation::validate_order_size(order, config.solver.max_inputs, config.solver.max_outputs)
+    }
+
+    /// Whether `order`'s user and input tokens may be accepted under the solver's access lists
+    pub fn check_order_access(&self, order: &StandardOrder) -> Result<(), OrderValidationError> {
+        OrderAccess::from_config(&self.config.current().solver)?.check(order)
+    }
+
+    /// Stop accepting orders for `reason` until `unblock(key)` is called
+    pub fn block(&self, key: &'static str, reason: String) {
+        self.blocks.write().unwrap().insert(key, reason);
+    }
+
+    pub fn unblock(&self, key: &'static str) {
+        self.blocks.write().unwrap().remove(key);
+    }
+
+    pub fn is_accepting(&self) -> bool {
+        self.blocks.read().unwrap().is_empty()
+    }
+
+    /// Reasons for every active block, joined for display
+    pub fn blocked_reason(&self) -> Option<String> {
+        let blocks = self.blocks.read().unwrap();
+        if blocks.is_empty() {
+            return None;
+        }
+        Some(blocks.values().cloned().collect::<Vec<_>>().join("; "))
+    }
+
+    /// Load implied by `queue` against the configured queue depth
+    pub fn load(&self, queue: &QueueStatus) -> IntakeLoad {
+        let config = self.config.current();
+        let backpressure = &config.backpressure;
+        let depth = queue.pending + queue.processing;
+        let capacity = backpressure.max_queue_depth;
+        let utilization = capacity.map(|capacity| depth as f64 / capacity.max(1) as f64);
+        let saturated = capacity.is_some_and(|capacity| depth >= capacity);
+        let busy = utilization.is_some_and(|utilization| utilization >= backpressure.high_water_ratio);
+
+        IntakeLoad {
+            pending: queue.pending,
+            in_flight: queue.processing,
+            capacity,
+            utilization,
+            retry_after_seconds: (saturated || busy).then_some(backpressure.retry_after_seconds),
+            saturated,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use anyhow::Result;
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder};
+
+use crate::storage::Storage;
+
+/// Prometheus metrics for order throughput and latency, shared by the
+/// services that move orders along and the `/metrics` endpoint
+#[derive(Clone)]
+pub struct SolverMetrics {
+    registry: Registry,
+    pub orders_created: IntCounter,
+    pub orders_filled: IntCounter,
+    pub orders_finalized: IntCounter,
+    pub orders_failed: IntCounter,
+    pub fill_duration_seconds: Histogram,
+    pub finalization_duration_seconds: Histogram,
+    pub pending_orders: IntGauge,
+}
+
+impl SolverMetrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+
+        let orders_created = IntCounter::new("solver_orders_created_total", "Orders accepted by the solver")?;
+        let orders_filled = IntCounter::new("solver_orders_filled_total", "Orders filled on the destination chain")?;
+        let orders_finalized = IntCounter::new("solver_orders_finalized_total", "Orders finalized on the origin chain")?;
+        let orders_failed = IntCounter::new("solver_orders_failed_total", "Orders that failed to fill or finalize")?;
+        let fill_duration_seconds = Histogram::with_opts(HistogramOpts::new(
+            "solver_fill_duration_seconds",
+            "Time taken to fill an order",
+        ))?;
+        let finalization_duration_seconds = Histogram::with_opts(HistogramOpts::new(
+            "solver_finalization_duration_seconds",
+            "Time taken to finalize an order",
+        ))?;
+        let pending_orders = IntGauge::new("solver_pending_orders", "Orders waiting to be filled")?;
+
+        registry.register(Box::new(orders_created.clone()))?;
+        registry.register(Box::new(orders_filled.clone()))?;
+        registry.register(Box::new(orders_finalized.clone()))?;
+        registry.register(Box::new(orders_failed.clone()))?;
+        registry.register(Box::new(fill_duration_seconds.clone()))?;
+        registry.register(Box::new(finalization_duration_seconds.clone()))?;
+        registry.register(Box::new(pending_orders.clone()))?;
+
+        Ok(Self {
+            registry,
+            orders_created,
+            orders_filled,
+            orders_finalized,
+            orders_failed,
+            fill_duration_seconds,
+            finalization_duration_seconds,
+            pending_orders,
+        })
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Refresh the pending gauge from storage and render every metric in the text format
+    pub async fn render(&self, storage: &dyn Storage) -> Result<String> {
+        self.pending_orders.set(storage.get_pending_orders().await?.len() as i64);
+
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+impl Default for SolverMetrics {
+    fn default() -> Self {
+        Self::new().expect("solver metric definitions are valid")
+    }
+}
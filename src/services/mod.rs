@@ -1,6 +1,19 @@
+pub mod balance;
+pub mod callbacks;
+pub mod chain_health;
 pub mod cross_chain;
 pub mod finalization;
+pub mod gas;
+pub mod heartbeat;
+pub mod in_flight;
+pub mod intake;
+pub mod metrics;
 pub mod monitoring;
+pub mod order_logs;
+pub mod pause;
+pub mod pricing;
+pub mod shutdown;
+pub mod validation;
 
 pub use cross_chain::*;
 pub use finalization::*;
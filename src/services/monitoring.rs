@@ -1,22 +1,38 @@
 use anyhow::Result;
 use std::time::Duration;
-use tokio::time::{interval, sleep};
+use tokio::sync::Semaphore;
+use tokio::time::interval;
 use tracing::{info, error, warn};
 
-use crate::config::AppConfig;
-use crate::models::OrderStatus;
-use crate::storage::MemoryStorage;
+use std::sync::Arc;
+
+use crate::config::{AppConfig, SharedConfig};
+use crate::handlers::health::probe_chain;
+use crate::models::{sort_by_priority, Order, OrderStatus};
+use crate::storage::Storage;
 use crate::services::{CrossChainService, FinalizationService};
+use crate::services::chain_health::ChainHealthGate;
+use crate::services::in_flight::InFlightTracker;
+use crate::services::metrics::SolverMetrics;
+use crate::services::pause::PauseSwitch;
+
+/// Maximum time to wait for each chain's RPC during the health refresh
+const CHAIN_HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct OrderMonitoringService {
-    storage: MemoryStorage,
+    storage: Arc<dyn Storage>,
     cross_chain_service: CrossChainService,
     finalization_service: FinalizationService,
+    chain_health: ChainHealthGate,
+    in_flight: InFlightTracker,
+    paused: PauseSwitch,
+    /// Bounds fills and finalizations running at once to `monitoring.max_concurrent_operations`
+    operations: Arc<Semaphore>,
     config: AppConfig,
 }
 
 impl OrderMonitoringService {
-    pub async fn new(storage: MemoryStorage, config: AppConfig) -> Result<Self> {
+    pub async fn new(storage: Arc<dyn Storage>, config: AppConfig) -> Result<Self> {
         let cross_chain_service = CrossChainService::new(storage.clone(), config.clone()).await?;
         let finalization_service = FinalizationService::new(storage.clone(), config.clone()).await?;
 
@@ -24,19 +40,98 @@ impl OrderMonitoringService {
             storage,
             cross_chain_service,
             finalization_service,
+            chain_health: ChainHealthGate::new(),
+            in_flight: InFlightTracker::new(),
+            paused: PauseSwitch::new(),
+            operations: Arc::new(Semaphore::new(config.monitoring.max_concurrent_operations.max(1))),
             config,
         })
     }
 
+    /// Gate processing on `chain_health`, e.g. to share it with other components
+    pub fn with_chain_health(mut self, chain_health: ChainHealthGate) -> Self {
+        self.chain_health = chain_health;
+        self
+    }
+
+    /// Count fills and finalizations in `in_flight`, so shutdown can wait for them
+    pub fn with_in_flight_tracker(mut self, in_flight: InFlightTracker) -> Self {
+        self.in_flight = in_flight;
+        self
+    }
+
+    /// Skip order processing while `paused` is set, e.g. by the admin endpoints
+    pub fn with_pause_switch(mut self, paused: PauseSwitch) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    /// Count fills and finalizations against `operations`, e.g. to share the limit with
+    /// the monitor serving the HTTP endpoints
+    pub fn with_operation_limit(mut self, operations: Arc<Semaphore>) -> Self {
+        self.operations = operations;
+        self
+    }
+
+    /// Fill orders through `cross_chain_service`, e.g. one built around a custom executor
+    pub fn with_cross_chain_service(mut self, cross_chain_service: CrossChainService) -> Self {
+        self.cross_chain_service = cross_chain_service;
+        self
+    }
+
+    /// Validate and price orders against `config`, which the admin API can reload
+    pub fn with_shared_config(mut self, config: SharedConfig) -> Self {
+        self.cross_chain_service = self.cross_chain_service.with_shared_config(config);
+        self
+    }
+
+    /// The service filling orders, e.g. to preview a fill without queueing it
+    pub fn cross_chain_service(&self) -> &CrossChainService {
+        &self.cross_chain_service
+    }
+
+    /// Share `metrics` with the fill and finalization services
+    pub fn with_metrics(mut self, metrics: SolverMetrics) -> Self {
+        self.cross_chain_service = self.cross_chain_service.with_metrics(metrics.clone());
+        self.finalization_service = self.finalization_service.with_metrics(metrics);
+        self
+    }
+
     pub async fn start(&self) -> Result<()> {
         info!("Starting order monitoring service");
 
         // Create interval timer for periodic checks
         let mut interval = interval(Duration::from_secs(5)); // Check every 5 seconds
+        let sweep_interval = Duration::from_secs(self.config.monitoring.check_interval_seconds);
+        let mut last_sweep: Option<std::time::Instant> = None;
 
         loop {
             interval.tick().await;
 
+            // Retire orders that expired before they could be filled
+            if last_sweep.is_none_or(|swept| swept.elapsed() >= sweep_interval) {
+                if let Err(e) = self.expire_stale_orders().await {
+                    error!("Error expiring stale orders: {}", e);
+                }
+                if let Err(e) = self.purge_finished_orders().await {
+                    error!("Error purging finished orders: {}", e);
+                }
+                last_sweep = Some(std::time::Instant::now());
+            }
+
+            if self.config.monitoring.pause_unhealthy_chains {
+                self.refresh_chain_health().await;
+            }
+
+            // Put failed fills whose retry backoff has elapsed back in the queue
+            if let Err(e) = self.requeue_failed_fills().await {
+                error!("Error requeueing failed fills: {}", e);
+            }
+
+            if self.paused.is_paused() {
+                continue;
+            }
+
             // Process pending orders
             if let Err(e) = self.process_pending_orders().await {
                 error!("Error processing pending orders: {}", e);
@@ -50,37 +145,168 @@ impl OrderMonitoringService {
     }
 
     async fn process_pending_orders(&self) -> Result<()> {
-        let pending_orders = self.storage.get_pending_orders().await?;
+        let mut pending_orders = self.storage.get_pending_orders().await?;
         
         if pending_orders.is_empty() {
             return Ok(());
         }
 
         info!("Processing {} pending orders", pending_orders.len());
+        sort_by_priority(&mut pending_orders);
+        let now = chrono::Utc::now().timestamp() as u64;
 
+        let mut fills = Vec::new();
         for order in pending_orders {
-            info!("Processing fill for order: {}", order.id);
-            
-            match self.cross_chain_service.process_fill(order.id).await {
-                Ok(result) => {
-                    if result.success {
-                        info!("Order {} filled successfully", order.id);
-                    } else {
-                        warn!("Order {} fill failed: {:?}", order.id, result.error);
-                    }
+            // Left for the next expiry sweep
+            if order.is_expired(now) {
+                info!("Skipping expired order {}", order.id);
+                continue;
+            }
+
+            if self.config.monitoring.pause_unhealthy_chains {
+                if let Some((chain_id, reason)) = self.chain_health.blocking_chain(&order) {
+                    info!("Skipping order {}: chain {} is unhealthy ({})", order.id, chain_id, reason);
+                    continue;
                 }
-                Err(e) => {
-                    error!("Error processing fill for order {}: {}", order.id, e);
+            }
+
+            fills.push(self.fill_order(order.id));
+        }
+
+        // Permits are handed out in queue order, so higher priorities still start first
+        futures_util::future::join_all(fills).await;
+        Ok(())
+    }
+
+    /// Fill `order_id` once an operation permit is free, unless the solver was paused
+    /// or started shutting down while it waited
+    async fn fill_order(&self, order_id: uuid::Uuid) {
+        let _permit = match self.operations.acquire().await {
+            Ok(permit) => permit,
+            Err(e) => {
+                error!("No operation permit for order {}: {}", order_id, e);
+                return;
+            }
+        };
+        // Checked per order, so a pause also stops the batch under way
+        if self.paused.is_paused() {
+            info!("Solver paused, leaving order {} queued", order_id);
+            return;
+        }
+        let Some(_in_flight) = self.in_flight.begin() else {
+            info!("Shutting down, leaving order {} queued", order_id);
+            return;
+        };
+        info!("Processing fill for order: {}", order_id);
+
+        match self.cross_chain_service.process_fill(order_id).await {
+            Ok(result) => {
+                if result.success {
+                    info!("Order {} filled successfully", order_id);
+                } else {
+                    warn!("Order {} fill failed: {:?}", order_id, result.error);
+                }
+            }
+            Err(e) => {
+                error!("Error processing fill for order {}: {}", order_id, e);
+            }
+        }
+    }
+
+    /// Move Pending and Processing orders past their `expires` timestamp to Expired, returning how many were moved
+    pub async fn expire_stale_orders(&self) -> Result<usize> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let mut expired = 0;
+        for status in [OrderStatus::Pending, OrderStatus::Processing] {
+            for mut order in self.storage.get_orders_by_status(status).await? {
+                if !order.is_expired(now) {
+                    continue;
                 }
+                info!("Order {} expired at {} before it was filled", order.id, order.standard_order.expires);
+                order.error_message = Some(format!("Order expired at {}", order.standard_order.expires));
+                order.update_status(OrderStatus::Expired);
+                self.storage.update_order(order).await?;
+                expired += 1;
             }
+        }
+        Ok(expired)
+    }
 
-            // Small delay between processing orders to avoid overwhelming the system
-            sleep(Duration::from_millis(100)).await;
+    /// Append finished orders last updated more than `persistence.retention_seconds` ago
+    /// to the archive file, then delete them, returning how many were deleted
+    ///
+    /// Orders still in flight (or awaiting a fill retry) are never touched, and nothing
+    /// is deleted when the archive cannot be written.
+    pub async fn purge_finished_orders(&self) -> Result<usize> {
+        let Some(retention) = self.config.persistence.retention_seconds else {
+            return Ok(0);
+        };
+        let cutoff = chrono::Utc::now() - chrono::Duration::seconds(retention.min(i64::MAX as u64) as i64);
+
+        let mut expired: Vec<Order> = self
+            .storage
+            .get_all_orders()
+            .await?
+            .into_iter()
+            .filter(|order| order.is_finished() && order.updated_at < cutoff)
+            .collect();
+        if expired.is_empty() {
+            return Ok(0);
+        }
+        expired.sort_by_key(|order| (order.updated_at, order.id));
+
+        let archive_file = std::path::Path::new(self.config.persistence.archive_file());
+        crate::storage::append_orders_file(archive_file, &expired).await?;
+        let mut deleted = 0;
+        for order in &expired {
+            if self.storage.delete_order(order.id).await? {
+                deleted += 1;
+            }
         }
+        info!("Purged {} finished order(s) older than {}s to {}", deleted, retention, archive_file.display());
+        Ok(deleted)
+    }
 
+    /// Return failed orders whose fill retry is due to Pending
+    async fn requeue_failed_fills(&self) -> Result<()> {
+        let now = chrono::Utc::now();
+        for mut order in self.storage.get_orders_by_status(OrderStatus::Failed).await? {
+            if !order.fill_retry_due(now) {
+                continue;
+            }
+            info!("Retrying fill for order {} (failed {} times)", order.id, order.fill_attempts);
+            order.fill_retry_at = None;
+            order.error_message = None;
+            order.update_status(OrderStatus::Pending);
+            self.storage.update_order(order).await?;
+        }
         Ok(())
     }
 
+    /// Probe every configured chain and record which ones can currently be used
+    async fn refresh_chain_health(&self) {
+        let factory = self.cross_chain_service.get_contract_factory();
+        let chains = &self.config.chains;
+        let chain_ids: std::collections::BTreeSet<u64> = [chains.origin.chain_id, chains.destination.chain_id]
+            .into_iter()
+            .chain(chains.registry.keys().copied())
+            .collect();
+
+        let probes = chain_ids.into_iter().map(|chain_id| async move {
+            let error = match factory.get_chain_provider(chain_id).await {
+                Ok(provider) => probe_chain(provider.as_ref(), chain_id, CHAIN_HEALTH_TIMEOUT).await.error,
+                Err(e) => Some(e.to_string()),
+            };
+            (chain_id, error)
+        });
+        for (chain_id, error) in futures_util::future::join_all(probes).await {
+            match error {
+                None => self.chain_health.mark_healthy(chain_id),
+                Some(reason) => self.chain_health.mark_unhealthy(chain_id, reason),
+            }
+        }
+    }
+
     async fn process_filled_orders(&self) -> Result<()> {
         let filled_orders = self.storage.get_orders_by_status(OrderStatus::Filled).await?;
         
@@ -118,9 +344,18 @@ impl OrderMonitoringService {
         Ok(())
     }
 
-    pub async fn trigger_finalization(&self, order_id: uuid::Uuid) -> Result<bool> {
+    /// Finalize `order_id` on an operator's request; refused with `FinalizationRefused` unless the
+    /// order is Filled and, without `force`, past the finalization delay
+    pub async fn trigger_finalization(&self, order_id: uuid::Uuid, force: bool) -> Result<bool> {
         info!("Manual finalization triggered for order: {}", order_id);
-        
+        if let Some(order) = self.storage.get_order(order_id).await? {
+            self.finalization_service.check_manual_finalization(&order, force)?;
+        }
+        let Some(_in_flight) = self.in_flight.begin() else {
+            anyhow::bail!("Solver is shutting down, finalization of order {} not started", order_id);
+        };
+        let _permit = self.operations.acquire().await?;
+
         match self.finalization_service.finalize_order(order_id).await {
             Ok(result) => {
                 if result.success {
@@ -213,4 +448,426 @@ impl OrderMonitoringService {
 
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::execution::{ChainType, ExecutionEngine, ExecutionError, GasParams};
+    use crate::contracts::{signature, ContractFactory};
+    use crate::models::{ExecutionPriority, MandateOutput, Order, StandardOrder};
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::signers::SignerSync;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::storage::MemoryStorage;
+    use alloy::primitives::{Address, U256};
+
+    fn create_test_order(nonce: u64, destination_chain_id: u64) -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                destination_chain_id,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        };
+        Order::new(standard_order, "0x".to_string())
+    }
+
+    /// An order signed by its user, so fill validation passes
+    fn create_signed_test_order(config: &AppConfig, nonce: u64) -> Order {
+        let signer = PrivateKeySigner::random();
+        let mut order = create_test_order(nonce, 31338);
+        order.standard_order.user = signer.address();
+        let domain = signature::order_domain(config).unwrap();
+        let digest = signature::standard_order_digest(&order.standard_order, &domain).unwrap();
+        order.signature = format!("0x{}", hex::encode(signer.sign_hash_sync(&digest).unwrap().as_bytes()));
+        order
+    }
+
+    #[tokio::test]
+    async fn test_orders_for_unhealthy_chains_are_skipped() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let stalled = create_test_order(1, 31338);
+        let healthy = create_test_order(2, 31339);
+        storage.create_order(stalled.clone()).await.unwrap();
+        storage.create_order(healthy.clone()).await.unwrap();
+
+        let chain_health = ChainHealthGate::new();
+        chain_health.mark_unhealthy(31338, "connection refused".to_string());
+        let service = OrderMonitoringService::new(storage.clone(), AppConfig::default())
+            .await
+            .unwrap()
+            .with_chain_health(chain_health.clone());
+
+        service.process_pending_orders().await.unwrap();
+
+        // The order for the unhealthy chain is untouched; the other one was attempted
+        assert_eq!(storage.get_order(stalled.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+        assert_ne!(storage.get_order(healthy.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+
+        // Once the chain recovers its orders are picked up again
+        chain_health.mark_healthy(31338);
+        service.process_pending_orders().await.unwrap();
+        assert_ne!(storage.get_order(stalled.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+    }
+
+    /// JSON-RPC node answering every request with block 1
+    fn spawn_rpc() -> (String, actix_web::dev::ServerHandle) {
+        use actix_web::{web, App, HttpResponse, HttpServer};
+
+        let server = HttpServer::new(|| {
+            App::new().default_service(web::to(|body: web::Json<serde_json::Value>| async move {
+                HttpResponse::Ok().json(serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "result": "0x1" }))
+            }))
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let url = format!("http://127.0.0.1:{}", server.addrs()[0].port());
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+        (url, handle)
+    }
+
+    #[actix_web::test]
+    async fn test_registry_chains_are_probed_and_gate_their_orders() {
+        let (rpc_url, rpc_handle) = spawn_rpc();
+        let mut config = AppConfig::default();
+        config.chains.origin.rpc_url = rpc_url.clone().into();
+        config.chains.destination.rpc_url = rpc_url.into();
+        // A chain only the registry knows, whose RPC refuses connections
+        let mut registry_only = config.chains.destination.clone();
+        registry_only.chain_id = 31340;
+        registry_only.rpc_url = "http://127.0.0.1:1".to_string().into();
+        config.chains.registry.insert(31340, registry_only);
+        config.chains.build_registry().unwrap();
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let stalled = create_test_order(1, 31340);
+        let healthy = create_test_order(2, 31338);
+        storage.create_order(stalled.clone()).await.unwrap();
+        storage.create_order(healthy.clone()).await.unwrap();
+
+        let chain_health = ChainHealthGate::new();
+        let service = OrderMonitoringService::new(storage.clone(), config)
+            .await
+            .unwrap()
+            .with_chain_health(chain_health.clone());
+
+        service.refresh_chain_health().await;
+        assert!(chain_health.is_healthy(31337));
+        assert!(chain_health.is_healthy(31338));
+        assert!(!chain_health.is_healthy(31340));
+
+        // The registry chain's order stays queued; the destination chain's one was attempted
+        service.process_pending_orders().await.unwrap();
+        assert_eq!(storage.get_order(stalled.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+        assert_ne!(storage.get_order(healthy.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+
+        rpc_handle.stop(true).await;
+    }
+
+    #[tokio::test]
+    async fn test_paused_service_leaves_orders_queued() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let order = create_test_order(1, 31338);
+        storage.create_order(order.clone()).await.unwrap();
+
+        let paused = PauseSwitch::new();
+        let service = OrderMonitoringService::new(storage.clone(), AppConfig::default())
+            .await
+            .unwrap()
+            .with_pause_switch(paused.clone());
+
+        paused.pause();
+        service.process_pending_orders().await.unwrap();
+        assert_eq!(storage.get_order(order.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+
+        paused.resume();
+        service.process_pending_orders().await.unwrap();
+        assert_ne!(storage.get_order(order.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+    }
+
+    #[actix_web::test]
+    async fn test_pause_endpoint_stops_the_monitor() {
+        use actix_web::{test, web, App};
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let order = create_test_order(1, 31338);
+        storage.create_order(order.clone()).await.unwrap();
+
+        // The switch the admin endpoints flip is the one the monitor checks
+        let paused = PauseSwitch::new();
+        let service = OrderMonitoringService::new(storage.clone(), AppConfig::default())
+            .await
+            .unwrap()
+            .with_pause_switch(paused.clone());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(paused))
+                .configure(crate::handlers::admin::config),
+        )
+        .await;
+
+        let req = test::TestRequest::post().uri("/api/v1/admin/pause").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        service.process_pending_orders().await.unwrap();
+        assert_eq!(storage.get_order(order.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+
+        let req = test::TestRequest::post().uri("/api/v1/admin/resume").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        service.process_pending_orders().await.unwrap();
+        assert_ne!(storage.get_order(order.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_reprioritized_orders_are_processed_first() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let mut orders: Vec<Order> = (1..=3).map(|nonce| create_test_order(nonce, 31338)).collect();
+        for order in &orders {
+            storage.create_order(order.clone()).await.unwrap();
+        }
+
+        // The newest order is bumped ahead of the two older ones
+        orders[2].priority = ExecutionPriority::High;
+        storage.update_order(orders[2].clone()).await.unwrap();
+
+        // One fill at a time, so they run in the order they were queued
+        let mut config = AppConfig::default();
+        config.monitoring.max_concurrent_operations = 1;
+        let service = OrderMonitoringService::new(storage.clone(), config).await.unwrap();
+        service.process_pending_orders().await.unwrap();
+
+        // Each attempt stamps updated_at, so their order is the processing order
+        let mut processed = Vec::new();
+        for order in &orders {
+            processed.push(storage.get_order(order.id).await.unwrap().unwrap());
+        }
+        processed.sort_by_key(|order| order.updated_at);
+        let sequence: Vec<_> = processed.iter().map(|order| order.id).collect();
+        assert_eq!(sequence, vec![orders[2].id, orders[0].id, orders[1].id]);
+    }
+
+    /// Executor whose first `failures` sends fail as if the RPC were down
+    struct FlakyExecutor {
+        failures: usize,
+        sends: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for FlakyExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: alloy::primitives::Address, _gas: GasParams) -> Result<String> {
+            if self.sends.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(ExecutionError::RpcUnreachable("connection refused".to_string()).into());
+            }
+            Ok("0xfill".to_string())
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: alloy::primitives::Address, _from: alloy::primitives::Address) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: alloy::primitives::Address, _from: alloy::primitives::Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        fn wallet_address(&self) -> alloy::primitives::Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "FlakyExecutor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transient_fill_failures_are_retried() {
+        let mut config = AppConfig::default();
+        config.solver.max_fill_retries = 2;
+        config.solver.retry_backoff_seconds = vec![0];
+
+        let order = create_signed_test_order(&config, 1);
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        storage.create_order(order.clone()).await.unwrap();
+
+        let executor = Arc::new(FlakyExecutor { failures: 2, sends: AtomicUsize::new(0) });
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(executor.clone());
+        let cross_chain_service = CrossChainService::new_with_factory(storage.clone(), Arc::new(factory), config.clone());
+        let service = OrderMonitoringService::new(storage.clone(), config)
+            .await
+            .unwrap()
+            .with_cross_chain_service(cross_chain_service);
+
+        for attempt in 1..=2 {
+            service.process_pending_orders().await.unwrap();
+            let failed = storage.get_order(order.id).await.unwrap().unwrap();
+            assert_eq!(failed.status, OrderStatus::Failed);
+            assert_eq!(failed.fill_attempts, attempt);
+            assert!(failed.fill_retry_at.is_some(), "a transient failure should be scheduled for retry");
+
+            service.requeue_failed_fills().await.unwrap();
+            assert_eq!(storage.get_order(order.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+        }
+
+        // The third attempt goes through
+        service.process_pending_orders().await.unwrap();
+        let filled = storage.get_order(order.id).await.unwrap().unwrap();
+        assert_eq!(filled.status, OrderStatus::Filled);
+        assert_eq!(filled.fill_tx_hash.as_deref(), Some("0xfill"));
+        assert_eq!(executor.sends.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_finished_orders_past_retention_are_archived_and_deleted() {
+        let dir = std::env::temp_dir().join(format!("oif-retention-test-{}", uuid::Uuid::new_v4()));
+        let archive_file = dir.join("orders-archive.jsonl");
+        let mut config = AppConfig::default();
+        config.persistence.retention_seconds = Some(3600);
+        config.persistence.archive_file = Some(archive_file.to_string_lossy().into_owned());
+
+        let two_hours_ago = chrono::Utc::now() - chrono::Duration::hours(2);
+        let with_status = |nonce, status, updated_at| {
+            let mut order = create_test_order(nonce, 31338);
+            order.status = status;
+            order.updated_at = updated_at;
+            order
+        };
+        let old_finalized = with_status(1, OrderStatus::Finalized, two_hours_ago);
+        let recent_finalized = with_status(2, OrderStatus::Finalized, chrono::Utc::now());
+        let old_pending = with_status(3, OrderStatus::Pending, two_hours_ago);
+        let mut old_retrying = with_status(4, OrderStatus::Failed, two_hours_ago);
+        old_retrying.fill_retry_at = Some(chrono::Utc::now());
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        for order in [&old_finalized, &recent_finalized, &old_pending, &old_retrying] {
+            storage.create_order(order.clone()).await.unwrap();
+        }
+        let service = OrderMonitoringService::new(storage.clone(), config).await.unwrap();
+
+        assert_eq!(service.purge_finished_orders().await.unwrap(), 1);
+        assert!(storage.get_order(old_finalized.id).await.unwrap().is_none());
+        for kept in [&recent_finalized, &old_pending, &old_retrying] {
+            assert!(storage.get_order(kept.id).await.unwrap().is_some());
+        }
+
+        let archived: Vec<uuid::Uuid> = std::fs::read_to_string(&archive_file).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Order>(line).unwrap().id)
+            .collect();
+        assert_eq!(archived, vec![old_finalized.id]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_expired_orders_are_swept_and_never_filled() {
+        let config = AppConfig::default();
+        let mut expired = create_test_order(1, 31338);
+        expired.standard_order.expires = 1;
+        expired.standard_order.fill_deadline = 1;
+        let live = create_test_order(2, 31338);
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        storage.create_order(expired.clone()).await.unwrap();
+        storage.create_order(live.clone()).await.unwrap();
+
+        let executor = Arc::new(FlakyExecutor { failures: 0, sends: AtomicUsize::new(0) });
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(executor.clone());
+        let cross_chain_service = CrossChainService::new_with_factory(storage.clone(), Arc::new(factory), config.clone());
+        let service = OrderMonitoringService::new(storage.clone(), config)
+            .await
+            .unwrap()
+            .with_cross_chain_service(cross_chain_service);
+
+        // Selection skips it even before the sweep runs
+        service.process_pending_orders().await.unwrap();
+        assert_eq!(storage.get_order(expired.id).await.unwrap().unwrap().status, OrderStatus::Pending);
+
+        assert_eq!(service.expire_stale_orders().await.unwrap(), 1);
+        let swept = storage.get_order(expired.id).await.unwrap().unwrap();
+        assert_eq!(swept.status, OrderStatus::Expired);
+        assert!(swept.error_message.unwrap().contains("expired"));
+        assert_ne!(storage.get_order(live.id).await.unwrap().unwrap().status, OrderStatus::Expired);
+
+        // Neither the queue nor a direct fill picks it up again
+        service.process_pending_orders().await.unwrap();
+        let result = service.cross_chain_service().process_fill(expired.id).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(storage.get_order(expired.id).await.unwrap().unwrap().status, OrderStatus::Expired);
+        assert!(storage.get_order(expired.id).await.unwrap().unwrap().fill_tx_hash.is_none());
+        assert_eq!(service.expire_stale_orders().await.unwrap(), 0);
+    }
+
+    /// Executor whose sends take a while, recording how many overlap
+    #[derive(Default)]
+    struct SlowExecutor {
+        running: AtomicUsize,
+        max_running: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionEngine for SlowExecutor {
+        async fn send_transaction(&self, _chain: ChainType, _call_data: Vec<u8>, _to: alloy::primitives::Address, _gas: GasParams) -> Result<String> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok("0xfill".to_string())
+        }
+
+        async fn static_call(&self, _chain: ChainType, _call_data: Vec<u8>, _to: alloy::primitives::Address, _from: alloy::primitives::Address) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn estimate_gas(&self, _chain: ChainType, _call_data: Vec<u8>, _to: alloy::primitives::Address, _from: alloy::primitives::Address) -> Result<u64> {
+            Ok(21000)
+        }
+
+        fn wallet_address(&self) -> alloy::primitives::Address {
+            Address::from([0x11; 20])
+        }
+
+        fn description(&self) -> &str {
+            "SlowExecutor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_operations_are_bounded() {
+        let mut config = AppConfig::default();
+        config.monitoring.max_concurrent_operations = 2;
+
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let orders: Vec<Order> = (1..=4).map(|nonce| create_signed_test_order(&config, nonce)).collect();
+        for order in &orders {
+            storage.create_order(order.clone()).await.unwrap();
+        }
+
+        let executor = Arc::new(SlowExecutor::default());
+        let factory = ContractFactory::new(config.clone()).await.unwrap().with_executor(executor.clone());
+        let cross_chain_service = CrossChainService::new_with_factory(storage.clone(), Arc::new(factory), config.clone());
+        let service = OrderMonitoringService::new(storage.clone(), config)
+            .await
+            .unwrap()
+            .with_cross_chain_service(cross_chain_service);
+
+        // Queued fills run side by side, but never more than the limit at once
+        service.process_pending_orders().await.unwrap();
+        for order in &orders {
+            assert_eq!(storage.get_order(order.id).await.unwrap().unwrap().status, OrderStatus::Filled);
+        }
+        assert_eq!(executor.max_running.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,188 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+use uuid::Uuid;
+
+/// Name of the span that scopes an order's processing; it must carry an `order_id` field
+pub const ORDER_SPAN: &str = "order";
+
+/// Lines kept per order before the oldest are dropped
+pub const DEFAULT_LINES_PER_ORDER: usize = 200;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderLogLine {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// Recent tracing events per order, captured from within `order` spans
+#[derive(Debug, Clone)]
+pub struct OrderLogs {
+    lines: Arc<RwLock<HashMap<Uuid, VecDeque<OrderLogLine>>>>,
+    lines_per_order: usize,
+}
+
+impl Default for OrderLogs {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_LINES_PER_ORDER)
+    }
+}
+
+impl OrderLogs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(lines_per_order: usize) -> Self {
+        Self {
+            lines: Arc::new(RwLock::new(HashMap::new())),
+            lines_per_order: lines_per_order.max(1),
+        }
+    }
+
+    /// Layer feeding this buffer; install it on the global subscriber
+    pub fn layer(&self) -> OrderLogLayer {
+        OrderLogLayer { logs: self.clone() }
+    }
+
+    /// Captured lines for `order_id`, oldest first
+    pub fn get(&self, order_id: Uuid) -> Vec<OrderLogLine> {
+        self.lines
+            .read()
+            .unwrap()
+            .get(&order_id)
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn push(&self, order_id: Uuid, line: OrderLogLine) {
+        let mut lines = self.lines.write().unwrap();
+        let buffer = lines.entry(order_id).or_default();
+        if buffer.len() == self.lines_per_order {
+            buffer.pop_front();
+        }
+        buffer.push_back(line);
+    }
+}
+
+/// Order id attached to an `order` span's extensions
+struct OrderScope(Uuid);
+
+pub struct OrderLogLayer {
+    logs: OrderLogs,
+}
+
+impl<S> Layer<S> for OrderLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != ORDER_SPAN {
+            return;
+        }
+        let mut visitor = OrderIdVisitor(None);
+        attrs.record(&mut visitor);
+        if let (Some(order_id), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(OrderScope(order_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+        let Some(order_id) = scope
+            .into_iter()
+            .find_map(|span| span.extensions().get::<OrderScope>().map(|scope| scope.0))
+        else {
+            return;
+        };
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        self.logs.push(order_id, OrderLogLine {
+            timestamp: Utc::now(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.finish(),
+        });
+    }
+}
+
+struct OrderIdVisitor(Option<Uuid>);
+
+impl Visit for OrderIdVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "order_id" {
+            self.0 = Uuid::from_str(&format!("{:?}", value)).ok();
+        }
+    }
+}
+
+/// Renders an event as its message followed by any other fields as `key=value`
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        if self.fields.is_empty() {
+            self.message
+        } else if self.message.is_empty() {
+            self.fields
+        } else {
+            format!("{} {}", self.message, self.fields)
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            if !self.fields.is_empty() {
+                self.fields.push(' ');
+            }
+            let _ = write!(self.fields, "{}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_buffer_is_bounded_per_order() {
+        let logs = OrderLogs::with_capacity(2);
+        let subscriber = tracing_subscriber::registry().with(logs.layer());
+        let order_id = Uuid::new_v4();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("order", order_id = %order_id).entered();
+            for i in 0..3 {
+                tracing::info!(attempt = i, "step");
+            }
+        });
+
+        let lines = logs.get(order_id);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].message, "step attempt=1");
+        assert_eq!(lines[1].message, "step attempt=2");
+    }
+}
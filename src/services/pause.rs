@@ -0,0 +1,29 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Operator switch stopping automatic fills and finalizations without stopping the process
+///
+/// Clones share one flag. While paused the monitor keeps sweeping expired orders
+/// and probing chain health, but takes no new order into processing.
+#[derive(Debug, Clone, Default)]
+pub struct PauseSwitch {
+    paused: Arc<AtomicBool>,
+}
+
+impl PauseSwitch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,123 @@
+use actix_web::dev::ServerHandle;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::services::in_flight::InFlightTracker;
+use crate::storage::Storage;
+
+/// Stop the solver in the order that loses nothing: let running fills and
+/// finalizations record their outcome (up to `grace`), save the orders to
+/// `data_file` when one is in use, and only then stop the HTTP server
+///
+/// The server must be built with signals disabled, otherwise actix stops it on
+/// the signal itself and the process can exit before the drain and save.
+pub async fn graceful_shutdown(
+    in_flight: &InFlightTracker,
+    grace: Duration,
+    storage: &Arc<dyn Storage>,
+    data_file: Option<&Path>,
+    server: ServerHandle,
+) {
+    info!("Shutting down server gracefully...");
+    if in_flight.in_flight() > 0 {
+        info!("Waiting up to {:?} for {} in-flight operation(s)", grace, in_flight.in_flight());
+    }
+    if !in_flight.drain(grace).await {
+        warn!("{} operation(s) still in flight after {:?}, shutting down anyway", in_flight.in_flight(), grace);
+    }
+
+    match data_file {
+        Some(data_file) => {
+            info!("Saving data to file: {}", data_file.display());
+            if let Err(e) = storage.save_to_file(data_file).await {
+                error!("Failed to save data during shutdown: {}", e);
+            } else {
+                let count = storage.count().await;
+                info!("Successfully saved {} orders to persistence file", count);
+            }
+        }
+        None => info!("No persistence file in use, skipping data save"),
+    }
+
+    server.stop(true).await;
+    info!("Server shutdown complete");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MandateOutput, Order, StandardOrder};
+    use crate::storage::MemoryStorage;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use alloy::primitives::{Address, U256};
+
+    fn test_order() -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce: 1,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        };
+        Order::new(standard_order, "0x".to_string())
+    }
+
+    #[actix_web::test]
+    async fn test_server_outlives_in_flight_work_and_the_save() {
+        let server = HttpServer::new(|| App::new().default_service(web::to(HttpResponse::Ok)))
+            .workers(1)
+            .disable_signals()
+            .bind("127.0.0.1:0")
+            .unwrap()
+            .run();
+        let handle = server.handle();
+        let server = actix_web::rt::spawn(server);
+
+        let data_file = std::env::temp_dir().join(format!("shutdown-{}.json", uuid::Uuid::new_v4()));
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let in_flight = InFlightTracker::new();
+
+        // A fill under way that records its order only after shutdown has begun
+        let guard = in_flight.begin().unwrap();
+        let fill = actix_web::rt::spawn({
+            let storage = storage.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                storage.create_order(test_order()).await.unwrap();
+                drop(guard);
+            }
+        });
+
+        let shutdown = actix_web::rt::spawn({
+            let (storage, in_flight, data_file) = (storage.clone(), in_flight.clone(), data_file.clone());
+            async move {
+                graceful_shutdown(&in_flight, Duration::from_secs(5), &storage, Some(&data_file), handle).await;
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!server.is_finished(), "the server stopped while a fill was still running");
+
+        // The server only stops once the fill has finished and its order was saved
+        server.await.unwrap().unwrap();
+        fill.await.unwrap();
+        let saved = MemoryStorage::new();
+        saved.load_from_file(&data_file).await.unwrap();
+        assert_eq!(saved.count().await, 1);
+        shutdown.await.unwrap();
+
+        let _ = std::fs::remove_file(&data_file);
+    }
+}
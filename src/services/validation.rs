@@ -0,0 +1,359 @@
+use alloy::primitives::{Address, FixedBytes, U256};
+use std::str::FromStr;
+
+use crate::config::{AppConfig, SolverConfig};
+use crate::models::StandardOrder;
+
+/// Why an order will not be filled, with a stable `code` integrators can match on
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum OrderValidationError {
+    #[error("Fill deadline has passed: {deadline} <= {now}")]
+    DeadlinePassed { deadline: u64, now: u64 },
+
+    #[error("Order has no outputs")]
+    NoOutputs,
+
+    #[error("Order has no inputs")]
+    NoInputs,
+
+    #[error("Order has {count} inputs, more than the {max} accepted")]
+    TooManyInputs { count: usize, max: usize },
+
+    #[error("Order has {count} outputs, more than the {max} accepted")]
+    TooManyOutputs { count: usize, max: usize },
+
+    #[error("Order origin chain {id} is not a configured chain")]
+    UnknownChain { id: u64 },
+
+    #[error("Input[{input}]: token {token} is not an allowed input token on origin chain {chain_id}")]
+    TokenNotAllowed { input: usize, token: Address, chain_id: u64 },
+
+    #[error("Orders from user {user} are not accepted")]
+    UserNotAllowed { user: Address },
+
+    /// `location` names the oracle's place in the order, e.g. "Output[0]: remote oracle"
+    #[error("{location} {oracle} is not an allowed oracle")]
+    OracleNotAllowed { location: String, oracle: Address },
+
+    #[error("{0}")]
+    BadSignature(String),
+
+    #[error("callback_url {0:?} is not an http(s) URL")]
+    BadCallbackUrl(String),
+
+    /// The solver's own settings are unusable; not the order's fault
+    #[error("{0}")]
+    Misconfigured(String),
+}
+
+impl OrderValidationError {
+    /// Machine-readable identifier of the variant, stable across releases
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::DeadlinePassed { .. } => "deadline_passed",
+            Self::NoOutputs => "no_outputs",
+            Self::NoInputs => "no_inputs",
+            Self::TooManyInputs { .. } => "too_many_inputs",
+            Self::TooManyOutputs { .. } => "too_many_outputs",
+            Self::UnknownChain { .. } => "unknown_chain",
+            Self::TokenNotAllowed { .. } => "token_not_allowed",
+            Self::UserNotAllowed { .. } => "user_not_allowed",
+            Self::OracleNotAllowed { .. } => "oracle_not_allowed",
+            Self::BadSignature(_) => "bad_signature",
+            Self::BadCallbackUrl(_) => "bad_callback_url",
+            Self::Misconfigured(_) => "solver_misconfigured",
+        }
+    }
+}
+
+/// A TheCompact resource lock id split into its parts:
+/// the upper 96 bits are the lock tag, the lower 160 bits the token address
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedTokenId {
+    pub lock_tag: FixedBytes<12>,
+    pub token: Address,
+}
+
+/// Split a tokenId into its lock tag and token address
+pub fn decode_token_id(token_id: U256) -> DecodedTokenId {
+    let bytes: [u8; 32] = token_id.to_be_bytes();
+
+    DecodedTokenId {
+        lock_tag: FixedBytes::from_slice(&bytes[..12]),
+        token: Address::from_slice(&bytes[12..]),
+    }
+}
+
+/// Refuse orders with more inputs or outputs than configured, whose calldata
+/// would only grow past what a block (or the encoder) can take
+pub fn validate_order_size(
+    order: &StandardOrder,
+    max_inputs: Option<usize>,
+    max_outputs: Option<usize>,
+) -> Result<(), OrderValidationError> {
+    if let Some(max) = max_inputs.filter(|max| order.inputs.len() > *max) {
+        return Err(OrderValidationError::TooManyInputs { count: order.inputs.len(), max });
+    }
+    if let Some(max) = max_outputs.filter(|max| order.outputs.len() > *max) {
+        return Err(OrderValidationError::TooManyOutputs { count: order.outputs.len(), max });
+    }
+    Ok(())
+}
+
+/// Refuse callback URLs the notifier could not POST to
+pub fn validate_callback_url(url: &str) -> Result<(), OrderValidationError> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => Ok(()),
+        _ => Err(OrderValidationError::BadCallbackUrl(url.to_string())),
+    }
+}
+
+/// The solver's allow and deny lists for order users and input tokens
+///
+/// An empty allow list, or one containing `"*"`, allows everyone; a deny list
+/// entry wins over an allow list entry.
+#[derive(Debug, Clone, Default)]
+pub struct OrderAccess {
+    allowed_tokens: Option<Vec<Address>>,
+    denied_tokens: Vec<Address>,
+    allowed_users: Option<Vec<Address>>,
+    denied_users: Vec<Address>,
+}
+
+impl OrderAccess {
+    pub fn from_config(solver: &SolverConfig) -> Result<Self, OrderValidationError> {
+        Ok(Self {
+            allowed_tokens: parse_allow_list(&solver.allowed_input_tokens, "allowed input token")?,
+            denied_tokens: parse_addresses(&solver.denied_input_tokens, "denied input token")?,
+            allowed_users: parse_allow_list(&solver.allowed_users, "allowed user")?,
+            denied_users: parse_addresses(&solver.denied_users, "denied user")?,
+        })
+    }
+
+    /// Refuse orders from users outside the allow list or on the deny list
+    pub fn check_user(&self, order: &StandardOrder) -> Result<(), OrderValidationError> {
+        if !is_allowed(order.user, &self.allowed_users, &self.denied_users) {
+            return Err(OrderValidationError::UserNotAllowed { user: order.user });
+        }
+        Ok(())
+    }
+
+    /// Refuse orders with an input token outside the allow list or on the deny list
+    pub fn check_tokens(&self, order: &StandardOrder) -> Result<(), OrderValidationError> {
+        for (i, (token_id, _amount)) in order.inputs.iter().enumerate() {
+            let token = decode_token_id(*token_id).token;
+            if !is_allowed(token, &self.allowed_tokens, &self.denied_tokens) {
+                return Err(OrderValidationError::TokenNotAllowed {
+                    input: i,
+                    token,
+                    chain_id: order.origin_chain_id,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check(&self, order: &StandardOrder) -> Result<(), OrderValidationError> {
+        self.check_user(order)?;
+        self.check_tokens(order)
+    }
+}
+
+fn is_allowed(address: Address, allowed: &Option<Vec<Address>>, denied: &[Address]) -> bool {
+    !denied.contains(&address) && allowed.as_ref().is_none_or(|allowed| allowed.contains(&address))
+}
+
+/// `None` when the list allows any address
+fn parse_allow_list(list: &[String], what: &str) -> Result<Option<Vec<Address>>, OrderValidationError> {
+    if list.is_empty() || list.iter().any(|entry| entry == "*") {
+        return Ok(None);
+    }
+    parse_addresses(list, what).map(Some)
+}
+
+fn parse_addresses(list: &[String], what: &str) -> Result<Vec<Address>, OrderValidationError> {
+    list.iter()
+        .map(|entry| {
+            Address::from_str(entry).map_err(|e| {
+                OrderValidationError::Misconfigured(format!("Invalid {} {}: {}", what, entry, e))
+            })
+        })
+        .collect()
+}
+
+/// Check that every input is locked on the configured origin chain and that its
+/// token passes `solver.allowed_input_tokens` / `solver.denied_input_tokens`
+pub fn validate_order_inputs(order: &StandardOrder, config: &AppConfig) -> Result<(), OrderValidationError> {
+    if config.chains.get(order.origin_chain_id).is_none() {
+        return Err(OrderValidationError::UnknownChain { id: order.origin_chain_id });
+    }
+
+    OrderAccess::from_config(&config.solver)?.check_tokens(order)
+}
+
+/// Check the order's user against `solver.allowed_users` / `solver.denied_users`
+pub fn validate_order_user(order: &StandardOrder, config: &AppConfig) -> Result<(), OrderValidationError> {
+    OrderAccess::from_config(&config.solver)?.check_user(order)
+}
+
+/// When `solver.allowed_oracles` is set, check that the order's local oracle
+/// and every output's remote oracle are ones the solver can settle through
+pub fn validate_order_oracles(order: &StandardOrder, config: &AppConfig) -> Result<(), OrderValidationError> {
+    if config.solver.allowed_oracles.is_empty() {
+        return Ok(());
+    }
+
+    let allowed_oracles = config
+        .solver
+        .allowed_oracles
+        .iter()
+        .map(|oracle| {
+            Address::from_str(oracle).map_err(|e| {
+                OrderValidationError::Misconfigured(format!("Invalid allowed oracle {}: {}", oracle, e))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !allowed_oracles.contains(&order.local_oracle) {
+        return Err(OrderValidationError::OracleNotAllowed {
+            location: "Local oracle".to_string(),
+            oracle: order.local_oracle,
+        });
+    }
+
+    for (i, output) in order.outputs.iter().enumerate() {
+        if !allowed_oracles.contains(&output.remote_oracle) {
+            return Err(OrderValidationError::OracleNotAllowed {
+                location: format!("Output[{}]: remote oracle", i),
+                oracle: output.remote_oracle,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MandateOutput;
+
+    const TOKEN: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+
+    /// tokenId with a non-zero lock tag over `token`
+    fn token_id_for(token: &str) -> U256 {
+        let token = Address::from_str(token).unwrap();
+        let lock_tag: U256 = U256::from(0xabcdu64) << 160;
+        lock_tag | U256::from_be_slice(token.as_slice())
+    }
+
+    fn create_test_order(origin_chain_id: u64, token_id: U256) -> StandardOrder {
+        StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce: 1,
+            origin_chain_id,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(token_id, U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        }
+    }
+
+    #[test]
+    fn test_decode_token_id() {
+        let decoded = decode_token_id(token_id_for(TOKEN));
+
+        assert_eq!(decoded.token, Address::from_str(TOKEN).unwrap());
+        assert_eq!(decoded.lock_tag.as_slice()[10..], [0xab, 0xcd]);
+    }
+
+    #[test]
+    fn test_validate_order_inputs() {
+        let mut config = AppConfig::default();
+        config.solver.allowed_input_tokens = vec![TOKEN.to_string()];
+
+        // Allowlisted token locked on the configured origin chain
+        let valid = create_test_order(31337, token_id_for(TOKEN));
+        assert!(validate_order_inputs(&valid, &config).is_ok());
+
+        // Same input but the order claims a chain the solver does not know
+        let wrong_chain = create_test_order(1, token_id_for(TOKEN));
+        assert!(validate_order_inputs(&wrong_chain, &config).is_err());
+
+        // Any configured chain can be the origin, not only `chains.origin`
+        let mut registered = config.chains.origin.clone();
+        registered.chain_id = 1;
+        config.chains.registry.insert(1, registered);
+        assert!(validate_order_inputs(&wrong_chain, &config).is_ok());
+
+        // Token outside the allowlist
+        let unknown_token = create_test_order(31337, token_id_for("0x0000000000000000000000000000000000000bad"));
+        let err = validate_order_inputs(&unknown_token, &config).unwrap_err();
+        assert!(err.to_string().contains("not an allowed input token"));
+    }
+
+    #[test]
+    fn test_allow_and_deny_lists() {
+        let order = create_test_order(31337, token_id_for(TOKEN));
+        let user = format!("{}", order.user);
+        let mut config = AppConfig::default();
+
+        // Empty lists and wildcards accept anyone
+        assert!(OrderAccess::from_config(&config.solver).unwrap().check(&order).is_ok());
+        config.solver.allowed_users = vec!["*".to_string()];
+        config.solver.allowed_input_tokens = vec![TOKEN.to_string()];
+        assert!(OrderAccess::from_config(&config.solver).unwrap().check(&order).is_ok());
+
+        // A user missing from the allow list is refused
+        config.solver.allowed_users = vec![format!("{}", Address::from([0x99; 20]))];
+        let err = validate_order_user(&order, &config).unwrap_err();
+        assert_eq!(err, OrderValidationError::UserNotAllowed { user: order.user });
+        assert_eq!(err.code(), "user_not_allowed");
+
+        // Deny lists win over allow lists
+        config.solver.allowed_users = vec![user.clone()];
+        config.solver.denied_users = vec![user];
+        assert!(validate_order_user(&order, &config).is_err());
+        config.solver.denied_users.clear();
+        config.solver.denied_input_tokens = vec![TOKEN.to_string()];
+        assert_eq!(validate_order_inputs(&order, &config).unwrap_err().code(), "token_not_allowed");
+
+        config.solver.denied_users = vec!["nobody".to_string()];
+        assert_eq!(OrderAccess::from_config(&config.solver).unwrap_err().code(), "solver_misconfigured");
+    }
+
+    #[test]
+    fn test_validate_order_oracles() {
+        let order = create_test_order(31337, token_id_for(TOKEN));
+        let local_oracle = format!("{}", Address::from([0x22; 20]));
+        let remote_oracle = format!("{}", Address::from([0x33; 20]));
+
+        // No allowlist accepts any oracle
+        let mut config = AppConfig::default();
+        assert!(validate_order_oracles(&order, &config).is_ok());
+
+        // Both oracles allowlisted
+        config.solver.allowed_oracles = vec![local_oracle.clone(), remote_oracle];
+        assert!(validate_order_oracles(&order, &config).is_ok());
+
+        // Remote oracle missing from the allowlist
+        config.solver.allowed_oracles = vec![local_oracle];
+        let err = validate_order_oracles(&order, &config).unwrap_err();
+        assert!(err.to_string().contains("Output[0]: remote oracle"));
+
+        // Unsupported local oracle
+        let mut untrusted = order.clone();
+        untrusted.local_oracle = Address::from([0xba; 20]);
+        config.solver.allowed_oracles = vec![format!("{}", Address::from([0x33; 20]))];
+        let err = validate_order_oracles(&untrusted, &config).unwrap_err();
+        assert!(err.to_string().contains("Local oracle"));
+    }
+}
@@ -1,43 +1,129 @@
-use anyhow::Result;
+use alloy::primitives::Address;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
 
 use crate::models::{Order, OrderStatus};
+use crate::storage::Storage;
 
 #[derive(Debug, Clone)]
 pub struct MemoryStorage {
     orders: Arc<RwLock<HashMap<Uuid, Order>>>,
+    eviction: Option<Eviction>,
+}
+
+/// Bound on the orders kept in memory and where evicted ones go
+#[derive(Debug, Clone)]
+struct Eviction {
+    max_orders: usize,
+    archive_file: PathBuf,
 }
 
 impl MemoryStorage {
     pub fn new() -> Self {
         Self {
             orders: Arc::new(RwLock::new(HashMap::new())),
+            eviction: None,
         }
     }
 
-    pub async fn store_order(&self, order: Order) -> Result<()> {
+    /// Keep at most `max_orders` in memory by evicting finished orders, oldest first,
+    /// after appending them to `archive_file`
+    ///
+    /// Orders still in flight are never evicted, so the bound can be exceeded while
+    /// more than `max_orders` of them are.
+    pub fn with_max_orders(mut self, max_orders: usize, archive_file: impl Into<PathBuf>) -> Self {
+        self.eviction = Some(Eviction { max_orders, archive_file: archive_file.into() });
+        self
+    }
+
+    /// Archive and drop finished orders until `orders` fits the bound; on an archive
+    /// failure everything stays in memory
+    async fn evict(&self, orders: &mut HashMap<Uuid, Order>) {
+        let Some(eviction) = &self.eviction else {
+            return;
+        };
+        let excess = orders.len().saturating_sub(eviction.max_orders);
+        if excess == 0 {
+            return;
+        }
+
+        let mut evictable: Vec<&Order> = orders.values().filter(|order| order.is_finished()).collect();
+        evictable.sort_by_key(|order| (order.created_at, order.id));
+        let evicted: Vec<Order> = evictable.into_iter().take(excess).cloned().collect();
+        if evicted.is_empty() {
+            return;
+        }
+
+        if let Err(e) = append_orders_file(&eviction.archive_file, &evicted).await {
+            tracing::warn!("Failed to archive orders to {}, keeping them in memory: {}", eviction.archive_file.display(), e);
+            return;
+        }
+        for order in &evicted {
+            orders.remove(&order.id);
+        }
+        tracing::info!("Evicted {} finished order(s) to {}", evicted.len(), eviction.archive_file.display());
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn create_order(&self, order: Order) -> Result<()> {
         let mut orders = self.orders.write().await;
         orders.insert(order.id, order);
+        self.evict(&mut orders).await;
         Ok(())
     }
 
-    pub async fn get_order(&self, id: Uuid) -> Result<Option<Order>> {
+    async fn get_order(&self, id: Uuid) -> Result<Option<Order>> {
         let orders = self.orders.read().await;
         Ok(orders.get(&id).cloned())
     }
 
-    pub async fn update_order(&self, order: Order) -> Result<()> {
+    async fn update_order(&self, order: Order) -> Result<()> {
         let mut orders = self.orders.write().await;
         orders.insert(order.id, order);
+        self.evict(&mut orders).await;
         Ok(())
     }
 
-    pub async fn get_orders_by_status(&self, status: OrderStatus) -> Result<Vec<Order>> {
+    async fn update_order_if_unchanged(&self, order: Order, read_at: DateTime<Utc>) -> Result<bool> {
+        let mut orders = self.orders.write().await;
+        if orders.get(&order.id).map(|stored| stored.updated_at) != Some(read_at) {
+            return Ok(false);
+        }
+        orders.insert(order.id, order);
+        self.evict(&mut orders).await;
+        Ok(true)
+    }
+
+    async fn get_orders_by_user_nonce(
+        &self,
+        user: Address,
+        nonce: u64,
+        origin_chain_id: u64,
+    ) -> Result<Vec<Order>> {
+        let orders = self.orders.read().await;
+        Ok(orders
+            .values()
+            .filter(|order| {
+                order.standard_order.user == user
+                    && order.standard_order.nonce == nonce
+                    && order.standard_order.origin_chain_id == origin_chain_id
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn get_orders_by_status(&self, status: OrderStatus) -> Result<Vec<Order>> {
         let orders = self.orders.read().await;
         let filtered_orders = orders
             .values()
@@ -47,76 +133,33 @@ impl MemoryStorage {
         Ok(filtered_orders)
     }
 
-    pub async fn get_pending_orders(&self) -> Result<Vec<Order>> {
-        self.get_orders_by_status(OrderStatus::Pending).await
-    }
-
-    pub async fn get_processing_orders(&self) -> Result<Vec<Order>> {
-        self.get_orders_by_status(OrderStatus::Processing).await
-    }
-
-    pub async fn get_all_orders(&self) -> Result<Vec<Order>> {
+    async fn get_all_orders(&self) -> Result<Vec<Order>> {
         let orders = self.orders.read().await;
         Ok(orders.values().cloned().collect())
     }
 
-    pub async fn get_queue_status(&self) -> Result<QueueStatus> {
-        let orders = self.orders.read().await;
-        let mut pending = 0;
-        let mut processing = 0;
-        let mut filled = 0;
-        let mut finalized = 0;
-        let mut failed = 0;
-
-        for order in orders.values() {
-            match order.status {
-                OrderStatus::Pending => pending += 1,
-                OrderStatus::Processing => processing += 1,
-                OrderStatus::Filled => filled += 1,
-                OrderStatus::Finalizing => processing += 1, // Treat finalizing as processing
-                OrderStatus::Finalized => finalized += 1,
-                OrderStatus::Failed => failed += 1,
-            }
-        }
-
-        Ok(QueueStatus {
-            total: orders.len(),
-            pending,
-            processing,
-            filled,
-            finalized,
-            failed,
-        })
+    async fn delete_order(&self, id: Uuid) -> Result<bool> {
+        Ok(self.orders.write().await.remove(&id).is_some())
     }
 
     /// Save all orders to a JSON file
-    pub async fn save_to_file<P: AsRef<Path>>(&self, file_path: P) -> Result<()> {
-        let orders = self.orders.read().await;
-        let orders_vec: Vec<Order> = orders.values().cloned().collect();
-        
-        // Create directory if it doesn't exist
-        if let Some(parent) = file_path.as_ref().parent() {
-            fs::create_dir_all(parent).await?;
-        }
-        
-        let json_data = serde_json::to_string_pretty(&orders_vec)?;
-        fs::write(file_path, json_data).await?;
+    ///
+    /// The data is written to a temporary file next to the target, fsynced and
+    /// then renamed over the target, so a crash mid-save never leaves a
+    /// truncated file behind: readers see either the old or the new contents.
+    async fn save_to_file(&self, file_path: &Path) -> Result<()> {
+        let orders_vec: Vec<Order> = self.orders.read().await.values().cloned().collect();
+        write_orders_file(file_path, &orders_vec).await?;
         
         tracing::info!("Saved {} orders to file", orders_vec.len());
         Ok(())
     }
 
-    /// Load orders from a JSON file
-    pub async fn load_from_file<P: AsRef<Path>>(&self, file_path: P) -> Result<()> {
-        let file_path = file_path.as_ref();
-        
-        if !file_path.exists() {
+    async fn load_from_file(&self, file_path: &Path) -> Result<()> {
+        let Some(orders_vec) = read_orders_file(file_path).await? else {
             tracing::info!("Persistence file does not exist, starting with empty storage");
             return Ok(());
-        }
-        
-        let json_data = fs::read_to_string(file_path).await?;
-        let orders_vec: Vec<Order> = serde_json::from_str(&json_data)?;
+        };
         
         let mut orders = self.orders.write().await;
         orders.clear();
@@ -124,30 +167,316 @@ impl MemoryStorage {
         for order in orders_vec {
             orders.insert(order.id, order);
         }
+        self.evict(&mut orders).await;
         
         tracing::info!("Loaded {} orders from file", orders.len());
         Ok(())
     }
 
-    /// Get the total number of orders stored
-    pub async fn count(&self) -> usize {
+    async fn count(&self) -> usize {
         let orders = self.orders.read().await;
         orders.len()
     }
 }
 
-#[derive(Debug, serde::Serialize)]
-pub struct QueueStatus {
-    pub total: usize,
-    pub pending: usize,
-    pub processing: usize,
-    pub filled: usize,
-    pub finalized: usize,
-    pub failed: usize,
+/// Version of the orders file format written by `write_orders_file`
+///
+/// 1: a bare JSON array of orders (no version field)
+/// 2: `{"schema_version": 2, "orders": [...]}`, every order carrying a status history
+pub const ORDERS_SCHEMA_VERSION: u64 = 2;
+
+/// Atomically replace `file_path` with `orders` serialized as JSON
+pub(crate) async fn write_orders_file(file_path: &Path, orders: &[Order]) -> Result<()> {
+    // Create directory if it doesn't exist
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    
+    let json_data = serde_json::to_string_pretty(&json!({
+        "schema_version": ORDERS_SCHEMA_VERSION,
+        "orders": orders,
+    }))?;
+
+    // Write to a temp file in the same directory so the rename stays on one filesystem
+    let temp_path = temp_path_for(file_path);
+    let mut temp_file = fs::File::create(&temp_path).await?;
+    temp_file.write_all(json_data.as_bytes()).await?;
+    temp_file.sync_all().await?;
+    drop(temp_file);
+
+    if let Err(e) = fs::rename(&temp_path, file_path).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Append `orders` to `file_path`, one JSON object per line, and sync them to disk
+pub(crate) async fn append_orders_file(file_path: &Path, orders: &[Order]) -> Result<()> {
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let mut lines = String::new();
+    for order in orders {
+        lines.push_str(&serde_json::to_string(order)?);
+        lines.push('\n');
+    }
+
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(file_path).await?;
+    file.write_all(lines.as_bytes()).await?;
+    file.sync_all().await?;
+    Ok(())
+}
+
+/// Read orders from a JSON file written by `write_orders_file`, migrating older
+/// versions of the format; `None` if it doesn't exist
+pub(crate) async fn read_orders_file(file_path: &Path) -> Result<Option<Vec<Order>>> {
+    if !file_path.exists() {
+        return Ok(None);
+    }
+    
+    let json_data = fs::read_to_string(file_path).await?;
+    let value = serde_json::from_str(&json_data)
+        .with_context(|| format!("{} is not valid JSON", file_path.display()))?;
+    let orders = migrate_orders(value)
+        .with_context(|| format!("Failed to load orders from {}", file_path.display()))?;
+    Ok(Some(orders))
+}
+
+/// Upgrade a parsed orders file of any known version to the current one
+fn migrate_orders(value: Value) -> Result<Vec<Order>> {
+    let (mut version, mut orders) = match value {
+        Value::Array(orders) => (1, orders),
+        Value::Object(mut file) => {
+            let version = file
+                .get("schema_version")
+                .and_then(Value::as_u64)
+                .context("Missing or invalid schema_version")?;
+            let Some(Value::Array(orders)) = file.remove("orders") else {
+                bail!("Missing orders array");
+            };
+            (version, orders)
+        }
+        _ => bail!("Expected an array of orders or an object with schema_version"),
+    };
+    if version > ORDERS_SCHEMA_VERSION {
+        bail!(
+            "Schema version {} was written by a newer solver; this one reads up to version {}",
+            version,
+            ORDERS_SCHEMA_VERSION
+        );
+    }
+
+    while version < ORDERS_SCHEMA_VERSION {
+        match version {
+            1 => orders.iter_mut().for_each(migrate_v1_order),
+            _ => bail!("Unknown schema version {}", version),
+        }
+        version += 1;
+    }
+
+    orders
+        .into_iter()
+        .map(|order| serde_json::from_value(order).map_err(Into::into))
+        .collect()
+}
+
+/// v1 -> v2: give orders saved before status history was tracked one that matches
+/// their status: created Pending, then moved to the current status when last updated
+fn migrate_v1_order(order: &mut Value) {
+    let Some(order) = order.as_object_mut() else {
+        return;
+    };
+    let has_history = order
+        .get("status_history")
+        .and_then(Value::as_array)
+        .is_some_and(|history| !history.is_empty());
+    if has_history {
+        return;
+    }
+
+    let status = order.get("status").cloned().unwrap_or(Value::Null);
+    let mut history = vec![json!({
+        "from": null,
+        "to": "Pending",
+        "timestamp": order.get("created_at"),
+        "tx_hash": null,
+    })];
+    if status != json!("Pending") {
+        let tx_hash = match status.as_str() {
+            Some("Filled") => order.get("fill_tx_hash"),
+            Some("Finalized") => order.get("finalize_tx_hash"),
+            _ => None,
+        };
+        history.push(json!({
+            "from": "Pending",
+            "to": status,
+            "timestamp": order.get("updated_at"),
+            "tx_hash": tx_hash,
+        }));
+    }
+    order.insert("status_history".to_string(), Value::Array(history));
+}
+
+/// Temporary path used while atomically replacing `file_path`
+pub(crate) fn temp_path_for(file_path: &Path) -> PathBuf {
+    let mut file_name = file_path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_else(|| "orders.json".into());
+    file_name.push(".tmp");
+    file_path.with_file_name(file_name)
 }
 
 impl Default for MemoryStorage {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MandateOutput, StandardOrder};
+    use alloy::primitives::U256;
+
+    fn create_test_order(nonce: u64) -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        };
+        Order::new(standard_order, "0x".to_string())
+    }
+
+    fn test_data_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("oif-storage-test-{}", Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_save_to_file_is_atomic() {
+        let dir = test_data_dir();
+        let data_file = dir.join("orders.json");
+
+        // First complete save with one order
+        let storage = MemoryStorage::new();
+        storage.create_order(create_test_order(1)).await.unwrap();
+        storage.save_to_file(&data_file).await.unwrap();
+
+        // Simulate a crash mid-save: a partial write is left in the temp file
+        let json_data = serde_json::to_string_pretty(&storage.get_all_orders().await.unwrap()).unwrap();
+        fs::write(temp_path_for(&data_file), &json_data[..json_data.len() / 2]).await.unwrap();
+
+        // The target still holds the old, complete version
+        let reloaded = MemoryStorage::new();
+        reloaded.load_from_file(&data_file).await.unwrap();
+        assert_eq!(reloaded.count().await, 1);
+
+        // The next save replaces the file with the new, complete version
+        storage.create_order(create_test_order(2)).await.unwrap();
+        storage.save_to_file(&data_file).await.unwrap();
+
+        let reloaded = MemoryStorage::new();
+        reloaded.load_from_file(&data_file).await.unwrap();
+        assert_eq!(reloaded.count().await, 2);
+        assert!(!temp_path_for(&data_file).exists(), "Temp file should be renamed away");
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_only_finished_orders_are_evicted_and_archived() {
+        let dir = test_data_dir();
+        let archive_file = dir.join("orders-archive.jsonl");
+        let storage = MemoryStorage::new().with_max_orders(3, &archive_file);
+
+        let with_status = |nonce, status| {
+            let mut order = create_test_order(nonce);
+            order.status = status;
+            order
+        };
+        let mut retrying = with_status(1, OrderStatus::Failed);
+        retrying.fill_retry_at = Some(chrono::Utc::now());
+        let finalized = with_status(2, OrderStatus::Finalized);
+        let expired = with_status(3, OrderStatus::Expired);
+        for order in [retrying.clone(), finalized.clone(), expired.clone(), with_status(4, OrderStatus::Processing)] {
+            storage.create_order(order).await.unwrap();
+        }
+        assert_eq!(storage.count().await, 3);
+        assert!(storage.get_order(finalized.id).await.unwrap().is_none(), "oldest finished order goes first");
+
+        storage.create_order(with_status(5, OrderStatus::Pending)).await.unwrap();
+        assert!(storage.get_order(expired.id).await.unwrap().is_none());
+
+        // Nothing left that may be evicted: the bound gives way
+        storage.create_order(with_status(6, OrderStatus::Pending)).await.unwrap();
+        assert_eq!(storage.count().await, 4);
+        assert!(storage.get_order(retrying.id).await.unwrap().is_some(), "a fill retry is still due");
+
+        let archived: Vec<Uuid> = fs::read_to_string(&archive_file).await.unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Order>(line).unwrap().id)
+            .collect();
+        assert_eq!(archived, vec![finalized.id, expired.id]);
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_v1_file_is_migrated_to_current_version() {
+        let dir = test_data_dir();
+        let data_file = dir.join("orders.json");
+
+        // A v1 file: a bare array of orders saved before status history, priority,
+        // retries and correlation ids were tracked
+        let mut order = create_test_order(1);
+        order.fill_tx_hash = Some("0xfill".to_string());
+        order.status = OrderStatus::Filled;
+        let mut v1_order = serde_json::to_value(&order).unwrap();
+        for field in ["status_history", "priority", "fill_attempts", "fill_retry_at", "correlation_id", "fill_gas"] {
+            v1_order.as_object_mut().unwrap().remove(field);
+        }
+        fs::create_dir_all(&dir).await.unwrap();
+        fs::write(&data_file, serde_json::to_string(&vec![v1_order]).unwrap()).await.unwrap();
+
+        let storage = MemoryStorage::new();
+        storage.load_from_file(&data_file).await.unwrap();
+        let loaded = storage.get_order(order.id).await.unwrap().unwrap();
+        assert_eq!(loaded.status, OrderStatus::Filled);
+        assert_eq!(loaded.priority, crate::models::ExecutionPriority::Normal);
+        assert_eq!(loaded.fill_attempts, 0);
+        assert!(!loaded.correlation_id.is_empty());
+        let transitions: Vec<_> = loaded.status_history.iter().map(|t| (t.from.clone(), t.to.clone())).collect();
+        assert_eq!(
+            transitions,
+            vec![(None, OrderStatus::Pending), (Some(OrderStatus::Pending), OrderStatus::Filled)]
+        );
+        assert_eq!(loaded.status_history[1].tx_hash.as_deref(), Some("0xfill"));
+
+        // Saving writes the current version
+        storage.save_to_file(&data_file).await.unwrap();
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&data_file).await.unwrap()).unwrap();
+        assert_eq!(saved["schema_version"], ORDERS_SCHEMA_VERSION);
+        assert_eq!(saved["orders"].as_array().unwrap().len(), 1);
+
+        // A file from a newer solver is refused rather than misread
+        fs::write(&data_file, r#"{"schema_version": 99, "orders": []}"#).await.unwrap();
+        let error = MemoryStorage::new().load_from_file(&data_file).await.unwrap_err();
+        assert!(format!("{:#}", error).contains("newer solver"), "{:#}", error);
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -1,3 +1,173 @@
 pub mod memory;
+pub mod sqlite;
 
-pub use memory::*; 
\ No newline at end of file
+pub use memory::*;
+pub use sqlite::SqliteStorage;
+
+use alloy::primitives::Address;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::{PersistenceBackend, PersistenceConfig};
+use crate::models::{Order, OrderStatus};
+
+/// Storage selected by `persistence.backend`; the JSON backend starts out empty
+/// and is filled by `load_from_file`
+pub async fn open(config: &PersistenceConfig) -> Result<Arc<dyn Storage>> {
+    if !config.enabled {
+        return Ok(Arc::new(MemoryStorage::new()));
+    }
+    match config.backend {
+        PersistenceBackend::Json => Ok(Arc::new(match config.max_orders_in_memory {
+            Some(max_orders) => MemoryStorage::new().with_max_orders(max_orders, config.archive_file()),
+            None => MemoryStorage::new(),
+        })),
+        PersistenceBackend::Sqlite => Ok(Arc::new(SqliteStorage::connect(config.database_url()).await?)),
+    }
+}
+
+/// Order persistence backend shared by the services and HTTP handlers
+///
+/// `MemoryStorage` (optionally snapshotted to a JSON file) is the default;
+/// `SqliteStorage` keeps every write durable.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn create_order(&self, order: Order) -> Result<()>;
+
+    async fn get_order(&self, id: Uuid) -> Result<Option<Order>>;
+
+    async fn update_order(&self, order: Order) -> Result<()>;
+
+    /// Write `order` only if the stored copy still has the `updated_at` it was read
+    /// with; `false`, with nothing written, if it was changed in between
+    async fn update_order_if_unchanged(&self, order: Order, read_at: DateTime<Utc>) -> Result<bool>;
+
+    async fn get_orders_by_status(&self, status: OrderStatus) -> Result<Vec<Order>>;
+
+    async fn get_all_orders(&self) -> Result<Vec<Order>>;
+
+    /// Remove an order for good; `false` if there was none with this id
+    async fn delete_order(&self, id: Uuid) -> Result<bool>;
+
+    /// All orders sharing `(user, nonce, origin_chain_id)`, which identifies a
+    /// single StandardOrder regardless of the id we assigned it
+    async fn get_orders_by_user_nonce(&self, user: Address, nonce: u64, origin_chain_id: u64) -> Result<Vec<Order>>;
+
+    /// Get the total number of orders stored
+    async fn count(&self) -> usize;
+
+    /// Save all orders to a JSON file
+    async fn save_to_file(&self, file_path: &Path) -> Result<()>;
+
+    /// Load orders from a JSON file
+    async fn load_from_file(&self, file_path: &Path) -> Result<()>;
+
+    async fn get_pending_orders(&self) -> Result<Vec<Order>> {
+        self.get_orders_by_status(OrderStatus::Pending).await
+    }
+
+    async fn get_processing_orders(&self) -> Result<Vec<Order>> {
+        self.get_orders_by_status(OrderStatus::Processing).await
+    }
+
+    /// The order entitled to `(user, nonce, origin_chain_id)`: a finalized one if any,
+    /// otherwise the earliest submitted. Failed, cancelled and expired orders never consumed the nonce and are skipped.
+    async fn find_nonce_holder(&self, user: Address, nonce: u64, origin_chain_id: u64) -> Result<Option<Order>> {
+        let mut orders = self.get_orders_by_user_nonce(user, nonce, origin_chain_id).await?;
+        orders.retain(|order| !matches!(order.status, OrderStatus::Failed | OrderStatus::Cancelled | OrderStatus::Expired));
+        orders.sort_by_key(|order| (order.status != OrderStatus::Finalized, order.created_at));
+        Ok(orders.into_iter().next())
+    }
+
+    async fn get_queue_status(&self) -> Result<QueueStatus> {
+        let orders = self.get_all_orders().await?;
+        Ok(QueueStatus::from_orders(&orders))
+    }
+
+    /// Orders matching `filter`, oldest first (ties broken by id so pages are stable),
+    /// as the page of at most `limit` orders starting at `offset`
+    async fn search_orders(&self, filter: &OrderFilter, limit: usize, offset: usize) -> Result<OrderPage> {
+        let mut orders = self.get_all_orders().await?;
+        orders.retain(|order| filter.matches(order));
+        orders.sort_by_key(|order| (order.created_at, order.id));
+        let total = orders.len();
+        let orders = orders.into_iter().skip(offset).take(limit).collect();
+        Ok(OrderPage { orders, total })
+    }
+}
+
+/// Criteria for `Storage::search_orders`; unset fields match every order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderFilter {
+    pub status: Option<OrderStatus>,
+    pub user: Option<Address>,
+    pub origin_chain_id: Option<u64>,
+}
+
+impl OrderFilter {
+    pub fn matches(&self, order: &Order) -> bool {
+        self.status.as_ref().is_none_or(|status| order.status == *status)
+            && self.user.is_none_or(|user| order.standard_order.user == user)
+            && self.origin_chain_id.is_none_or(|chain_id| order.standard_order.origin_chain_id == chain_id)
+    }
+}
+
+/// One page of `Storage::search_orders` results
+#[derive(Debug)]
+pub struct OrderPage {
+    pub orders: Vec<Order>,
+    /// Orders matching the filter across all pages
+    pub total: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct QueueStatus {
+    pub total: usize,
+    pub pending: usize,
+    pub processing: usize,
+    pub filled: usize,
+    pub finalized: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub expired: usize,
+}
+
+impl QueueStatus {
+    pub fn from_orders(orders: &[Order]) -> Self {
+        let mut pending = 0;
+        let mut processing = 0;
+        let mut filled = 0;
+        let mut finalized = 0;
+        let mut failed = 0;
+        let mut cancelled = 0;
+        let mut expired = 0;
+
+        for order in orders {
+            match order.status {
+                OrderStatus::Pending => pending += 1,
+                OrderStatus::Processing => processing += 1,
+                OrderStatus::Filled => filled += 1,
+                OrderStatus::Finalizing => processing += 1, // Treat finalizing as processing
+                OrderStatus::Finalized => finalized += 1,
+                OrderStatus::Failed => failed += 1,
+                OrderStatus::Cancelled => cancelled += 1,
+                OrderStatus::Expired => expired += 1,
+            }
+        }
+
+        Self {
+            total: orders.len(),
+            pending,
+            processing,
+            filled,
+            finalized,
+            failed,
+            cancelled,
+            expired,
+        }
+    }
+}
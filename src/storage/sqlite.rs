@@ -0,0 +1,386 @@
+use alloy::primitives::Address;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+use std::path::Path;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::models::{Order, OrderStatus};
+use crate::storage::memory::{read_orders_file, write_orders_file};
+use crate::storage::{OrderFilter, OrderPage, Storage};
+
+/// SQLite-backed order storage; every write is durable as soon as it returns
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// Open (creating if needed) the database at `url`, e.g. `sqlite://data/orders.db`,
+    /// and apply pending migrations
+    pub async fn connect(url: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        if let Some(parent) = options.get_filename().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let pool = SqlitePoolOptions::new().connect_with(options).await?;
+        Self::with_pool(pool).await
+    }
+
+    /// Private in-memory database, mainly for tests and one-shot tooling
+    pub async fn in_memory() -> Result<Self> {
+        // Every connection to `:memory:` is a separate database, so keep exactly one alive
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await?;
+        Self::with_pool(pool).await
+    }
+
+    async fn with_pool(pool: SqlitePool) -> Result<Self> {
+        sqlx::migrate!("./migrations").run(&pool).await?;
+        Ok(Self { pool })
+    }
+
+    /// Copy the orders in a JSON persistence file into this database, keeping
+    /// orders already stored here; returns how many were imported
+    pub async fn import_json_file(&self, file_path: &Path) -> Result<usize> {
+        let Some(orders) = read_orders_file(file_path).await? else {
+            anyhow::bail!("Persistence file {} does not exist", file_path.display());
+        };
+
+        let mut tx = self.pool.begin().await?;
+        for order in &orders {
+            upsert(&mut *tx, order).await?;
+        }
+        tx.commit().await?;
+        Ok(orders.len())
+    }
+
+    async fn fetch_orders<'q>(&self, query: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>) -> Result<Vec<Order>> {
+        let rows = query.fetch_all(&self.pool).await?;
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(row.try_get::<&str, _>("data")?)?))
+            .collect()
+    }
+}
+
+async fn upsert<'e, E: sqlx::SqliteExecutor<'e>>(executor: E, order: &Order) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO orders (id, status, user_address, nonce, origin_chain_id, data, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+            status = excluded.status,
+            data = excluded.data,
+            updated_at = excluded.updated_at",
+    )
+    .bind(order.id.to_string())
+    .bind(status_column(&order.status))
+    .bind(order.standard_order.user.to_string())
+    .bind(order.standard_order.nonce.to_string())
+    .bind(order.standard_order.origin_chain_id.to_string())
+    .bind(serde_json::to_string(order)?)
+    .bind(order.created_at.to_rfc3339())
+    .bind(order.updated_at.to_rfc3339())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// `WHERE` clause (empty when nothing is filtered) and its bind values for `filter`
+fn filter_clause(filter: &OrderFilter) -> (String, Vec<String>) {
+    let mut conditions = Vec::new();
+    let mut binds = Vec::new();
+    if let Some(status) = &filter.status {
+        conditions.push("status = ?");
+        binds.push(status_column(status));
+    }
+    if let Some(user) = filter.user {
+        conditions.push("user_address = ?");
+        binds.push(user.to_string());
+    }
+    if let Some(chain_id) = filter.origin_chain_id {
+        conditions.push("origin_chain_id = ?");
+        binds.push(chain_id.to_string());
+    }
+
+    if conditions.is_empty() {
+        (String::new(), binds)
+    } else {
+        (format!(" WHERE {}", conditions.join(" AND ")), binds)
+    }
+}
+
+fn status_column(status: &OrderStatus) -> String {
+    format!("{:?}", status)
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn create_order(&self, order: Order) -> Result<()> {
+        upsert(&self.pool, &order).await
+    }
+
+    async fn get_order(&self, id: Uuid) -> Result<Option<Order>> {
+        let orders = self
+            .fetch_orders(sqlx::query("SELECT data FROM orders WHERE id = ?").bind(id.to_string()))
+            .await?;
+        Ok(orders.into_iter().next())
+    }
+
+    async fn update_order(&self, order: Order) -> Result<()> {
+        upsert(&self.pool, &order).await
+    }
+
+    async fn update_order_if_unchanged(&self, order: Order, read_at: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query("UPDATE orders SET status = ?, data = ?, updated_at = ? WHERE id = ? AND updated_at = ?")
+            .bind(status_column(&order.status))
+            .bind(serde_json::to_string(&order)?)
+            .bind(order.updated_at.to_rfc3339())
+            .bind(order.id.to_string())
+            .bind(read_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_orders_by_status(&self, status: OrderStatus) -> Result<Vec<Order>> {
+        self.fetch_orders(
+            sqlx::query("SELECT data FROM orders WHERE status = ? ORDER BY created_at").bind(status_column(&status)),
+        )
+        .await
+    }
+
+    async fn get_all_orders(&self) -> Result<Vec<Order>> {
+        self.fetch_orders(sqlx::query("SELECT data FROM orders ORDER BY created_at")).await
+    }
+
+    async fn delete_order(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM orders WHERE id = ?").bind(id.to_string()).execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_orders_by_user_nonce(&self, user: Address, nonce: u64, origin_chain_id: u64) -> Result<Vec<Order>> {
+        self.fetch_orders(
+            sqlx::query("SELECT data FROM orders WHERE user_address = ? AND nonce = ? AND origin_chain_id = ? ORDER BY created_at")
+                .bind(user.to_string())
+                .bind(nonce.to_string())
+                .bind(origin_chain_id.to_string()),
+        )
+        .await
+    }
+
+    async fn search_orders(&self, filter: &OrderFilter, limit: usize, offset: usize) -> Result<OrderPage> {
+        let (clause, binds) = filter_clause(filter);
+
+        let count_sql = format!("SELECT COUNT(*) FROM orders{}", clause);
+        let mut count = sqlx::query_scalar::<_, i64>(&count_sql);
+        for value in &binds {
+            count = count.bind(value);
+        }
+        let total = count.fetch_one(&self.pool).await? as usize;
+
+        let sql = format!("SELECT data FROM orders{} ORDER BY created_at, id LIMIT ? OFFSET ?", clause);
+        let mut query = sqlx::query(&sql);
+        for value in &binds {
+            query = query.bind(value);
+        }
+        let orders = self
+            .fetch_orders(query.bind(limit.min(i64::MAX as usize) as i64).bind(offset.min(i64::MAX as usize) as i64))
+            .await?;
+
+        Ok(OrderPage { orders, total })
+    }
+
+    async fn count(&self) -> usize {
+        match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM orders").fetch_one(&self.pool).await {
+            Ok(count) => count as usize,
+            Err(e) => {
+                tracing::error!("Failed to count orders: {}", e);
+                0
+            }
+        }
+    }
+
+    /// Export all orders to a JSON file in the same format as `MemoryStorage`
+    async fn save_to_file(&self, file_path: &Path) -> Result<()> {
+        let orders = self.get_all_orders().await?;
+        write_orders_file(file_path, &orders).await?;
+
+        tracing::info!("Exported {} orders to file", orders.len());
+        Ok(())
+    }
+
+    /// Replace the table contents with the orders in a JSON file
+    async fn load_from_file(&self, file_path: &Path) -> Result<()> {
+        let Some(orders) = read_orders_file(file_path).await? else {
+            tracing::info!("Persistence file does not exist, nothing to import");
+            return Ok(());
+        };
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM orders").execute(&mut *tx).await?;
+        for order in &orders {
+            upsert(&mut *tx, order).await?;
+        }
+        tx.commit().await?;
+
+        tracing::info!("Imported {} orders from file", orders.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MandateOutput, StandardOrder};
+    use alloy::primitives::U256;
+
+    fn create_test_order(nonce: u64) -> Order {
+        let standard_order = StandardOrder {
+            user: Address::from([0x11; 20]),
+            nonce,
+            origin_chain_id: 31337,
+            expires: 4294967295,
+            fill_deadline: 4294967295,
+            local_oracle: Address::from([0x22; 20]),
+            inputs: vec![(U256::from(1), U256::from(100))],
+            outputs: vec![MandateOutput::new(
+                Address::from([0x33; 20]),
+                Address::from([0x44; 20]),
+                31338,
+                Address::from([0x55; 20]),
+                U256::from(99),
+                Address::from([0x66; 20]),
+            )],
+        };
+        Order::new(standard_order, "0x".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_order_round_trip() {
+        let storage = SqliteStorage::in_memory().await.unwrap();
+        let mut order = create_test_order(1);
+        storage.create_order(order.clone()).await.unwrap();
+
+        let loaded = storage.get_order(order.id).await.unwrap().expect("order should exist");
+        assert_eq!(loaded.id, order.id);
+        assert_eq!(loaded.standard_order.nonce, 1);
+        assert_eq!(loaded.standard_order.outputs[0].amount, U256::from(99));
+        assert_eq!(loaded.status, OrderStatus::Pending);
+
+        // Updates replace the stored document and its status column
+        order.update_status(OrderStatus::Filled);
+        order.fill_tx_hash = Some("0xabc".to_string());
+        storage.update_order(order.clone()).await.unwrap();
+
+        let loaded = storage.get_order(order.id).await.unwrap().unwrap();
+        assert_eq!(loaded.fill_tx_hash.as_deref(), Some("0xabc"));
+        assert_eq!(storage.get_orders_by_status(OrderStatus::Filled).await.unwrap().len(), 1);
+        assert!(storage.get_pending_orders().await.unwrap().is_empty());
+
+        let found = storage.get_orders_by_user_nonce(Address::from([0x11; 20]), 1, 31337).await.unwrap();
+        assert_eq!(found.iter().map(|o| o.id).collect::<Vec<_>>(), vec![order.id]);
+        assert_eq!(storage.count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_conditional_update_refuses_stale_writes() {
+        use crate::storage::MemoryStorage;
+
+        let sqlite = SqliteStorage::in_memory().await.unwrap();
+        let memory = MemoryStorage::new();
+        for storage in [&sqlite as &dyn Storage, &memory] {
+            let order = create_test_order(1);
+            storage.create_order(order.clone()).await.unwrap();
+
+            // Two writers read the same copy; only the first one's write lands
+            let read_at = order.updated_at;
+            let mut first = order.clone();
+            first.update_status(OrderStatus::Processing);
+            assert!(storage.update_order_if_unchanged(first, read_at).await.unwrap());
+            let mut second = order.clone();
+            second.update_status(OrderStatus::Cancelled);
+            assert!(!storage.update_order_if_unchanged(second, read_at).await.unwrap());
+            assert_eq!(storage.get_order(order.id).await.unwrap().unwrap().status, OrderStatus::Processing);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_orders_matches_memory_storage() {
+        use crate::storage::MemoryStorage;
+
+        let sqlite = SqliteStorage::in_memory().await.unwrap();
+        let memory = MemoryStorage::new();
+        let other_user = Address::from([0x99; 20]);
+        let start = chrono::Utc::now();
+        for nonce in 1..=5u64 {
+            let mut order = create_test_order(nonce);
+            order.created_at = start + chrono::Duration::seconds(nonce as i64);
+            if nonce % 2 == 0 {
+                order.standard_order.user = other_user;
+            }
+            sqlite.create_order(order.clone()).await.unwrap();
+            memory.create_order(order).await.unwrap();
+        }
+
+        let by_user = OrderFilter { user: Some(Address::from([0x11; 20])), ..Default::default() };
+        for storage in [&sqlite as &dyn Storage, &memory] {
+            let page = storage.search_orders(&by_user, 2, 0).await.unwrap();
+            assert_eq!(page.total, 3);
+            assert_eq!(page.orders.iter().map(|o| o.standard_order.nonce).collect::<Vec<_>>(), vec![1, 3]);
+
+            // The last page is short, and one past it is empty but still counts the matches
+            let page = storage.search_orders(&by_user, 2, 2).await.unwrap();
+            assert_eq!(page.orders.iter().map(|o| o.standard_order.nonce).collect::<Vec<_>>(), vec![5]);
+            let page = storage.search_orders(&by_user, 2, 3).await.unwrap();
+            assert!(page.orders.is_empty());
+            assert_eq!(page.total, 3);
+
+            let filter = OrderFilter { status: Some(OrderStatus::Pending), origin_chain_id: Some(31337), user: Some(other_user) };
+            assert_eq!(storage.search_orders(&filter, 10, 0).await.unwrap().total, 2);
+            let filter = OrderFilter { origin_chain_id: Some(1), ..Default::default() };
+            assert_eq!(storage.search_orders(&filter, 10, 0).await.unwrap().total, 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_json_file() {
+        use crate::storage::MemoryStorage;
+
+        // A JSON persistence file as written by the default backend
+        let data_file = std::env::temp_dir()
+            .join(format!("oif-migrate-test-{}", Uuid::new_v4()))
+            .join("orders.json");
+        let memory = MemoryStorage::new();
+        let mut filled = create_test_order(2);
+        filled.update_status(OrderStatus::Filled);
+        filled.set_fill_tx("0xfill".to_string());
+        let orders = vec![create_test_order(1), filled, create_test_order(3)];
+        for order in &orders {
+            memory.create_order(order.clone()).await.unwrap();
+        }
+        memory.save_to_file(&data_file).await.unwrap();
+
+        let sqlite = SqliteStorage::in_memory().await.unwrap();
+        assert_eq!(sqlite.import_json_file(&data_file).await.unwrap(), 3);
+        assert_eq!(sqlite.count().await, 3);
+
+        for order in &orders {
+            let migrated = sqlite.get_order(order.id).await.unwrap().expect("order should be migrated");
+            assert_eq!(serde_json::to_value(&migrated).unwrap(), serde_json::to_value(order).unwrap());
+        }
+        assert_eq!(sqlite.get_orders_by_status(OrderStatus::Filled).await.unwrap().len(), 1);
+        assert_eq!(sqlite.get_pending_orders().await.unwrap().len(), 2);
+
+        // A missing source file is an error rather than a silent no-op
+        assert!(sqlite.import_json_file(&data_file.with_file_name("missing.json")).await.is_err());
+
+        std::fs::remove_dir_all(data_file.parent().unwrap()).unwrap();
+    }
+}